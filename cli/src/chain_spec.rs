@@ -14,7 +14,7 @@ use sc_service::{ChainType, Properties};
 
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_consensus_babe::AuthorityId as BabeId;
-use sp_core::{crypto::UncheckedInto, sr25519, Pair, Public};
+use sp_core::{crypto::UncheckedInto, sr25519, Pair, Public, H160};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
@@ -25,11 +25,11 @@ use chainx_primitives::{AccountId, AssetId, Balance, ReferralId, Signature};
 use chainx_runtime::constants::{currency::DOLLARS, time::DAYS};
 use xp_assets_registrar::Chain;
 use xp_protocol::{NetworkType, PCX, PCX_DECIMALS, X_BTC};
-use xpallet_gateway_bitcoin::{BtcParams, BtcTxVerifier};
+use xpallet_gateway_bitcoin::BtcTxVerifier;
 use xpallet_gateway_common::types::TrusteeInfoConfig;
 
 use crate::genesis::assets::{genesis_assets, init_assets, pcx, AssetParams};
-use crate::genesis::bitcoin::{btc_genesis_params, BtcGenesisParams, BtcTrusteeParams};
+use crate::genesis::bitcoin::{btc_genesis_params, btc_params, BtcGenesisParams, BtcTrusteeParams};
 
 use chainx_runtime as chainx;
 use dev_runtime as dev;
@@ -72,6 +72,25 @@ pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Pu
         .public()
 }
 
+/// Returns the EVM genesis accounts with the dummy `code` at the precompile `addresses`.
+fn precompile_accounts(
+    addresses: Vec<H160>,
+    code: &[u8],
+) -> BTreeMap<H160, chainx::GenesisAccount> {
+    addresses
+        .into_iter()
+        .map(|address| {
+            let account = chainx::GenesisAccount {
+                nonce: Default::default(),
+                balance: Default::default(),
+                storage: Default::default(),
+                code: code.to_vec(),
+            };
+            (address, account)
+        })
+        .collect()
+}
+
 /// Helper function to generate an account ID from seed
 pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
 where
@@ -499,6 +518,9 @@ fn mainnet_genesis(
         x_system: chainx::XSystemConfig {
             network_props: NetworkType::Mainnet,
         },
+        x_transaction_fee: chainx::XTransactionFeeConfig {
+            extra_fees: chainx::default_extra_fees(),
+        },
         x_assets_registrar: chainx::XAssetsRegistrarConfig { assets },
         x_assets: chainx::XAssetsConfig {
             assets_restrictions,
@@ -511,14 +533,7 @@ fn mainnet_genesis(
             confirmation_number: bitcoin.confirmation_number,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
-            params_info: BtcParams::new(
-                // for bitcoin mainnet
-                486604799,            // max_bits
-                2 * 60 * 60,          // block_max_future
-                2 * 7 * 24 * 60 * 60, // target_timespan_seconds
-                10 * 60,              // target_spacing_seconds
-                4,                    // retargeting_factor
-            ), // retargeting_factor
+            params_info: btc_params(bitcoin.network),
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
@@ -547,14 +562,19 @@ fn mainnet_genesis(
                 .collect(),
         },
         ethereum_chain_id: chainx::EthereumChainIdConfig { chain_id: 1501u64 },
-        evm: Default::default(),
+        evm: chainx::EvmConfig {
+            accounts: precompile_accounts(
+                chainx::ChainXPrecompiles::<chainx::Runtime>::used_addresses(),
+                &chainx::DUMMY_PRECOMPILE_CODE,
+            ),
+        },
         ethereum: Default::default(),
         base_fee: chainx::BaseFeeConfig::new(
             chainx::DefaultBaseFeePerGas::get(),
             false,
             sp_runtime::Permill::from_parts(125_000),
         ),
-        x_assets_bridge: chainx::XAssetsBridgeConfig { admin_key: None },
+        x_assets_bridge: chainx::XAssetsBridgeConfig { roles: vec![] },
     }
 }
 
@@ -752,6 +772,9 @@ fn malan_genesis(
         x_system: malan::XSystemConfig {
             network_props: NetworkType::Testnet,
         },
+        x_transaction_fee: malan::XTransactionFeeConfig {
+            extra_fees: malan::default_extra_fees(),
+        },
         x_assets_registrar: malan::XAssetsRegistrarConfig { assets },
         x_assets: malan::XAssetsConfig {
             assets_restrictions,
@@ -764,14 +787,7 @@ fn malan_genesis(
             confirmation_number: bitcoin.confirmation_number,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
-            params_info: BtcParams::new(
-                // for signet and regtest
-                545259519,            // max_bits
-                2 * 60 * 60,          // block_max_future
-                2 * 7 * 24 * 60 * 60, // target_timespan_seconds
-                10 * 60,              // target_spacing_seconds
-                4,                    // retargeting_factor
-            ), // retargeting_factor
+            params_info: btc_params(bitcoin.network),
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
@@ -801,14 +817,19 @@ fn malan_genesis(
                 .collect(),
         },
         ethereum_chain_id: malan::EthereumChainIdConfig { chain_id: 1502u64 },
-        evm: Default::default(),
+        evm: malan::EvmConfig {
+            accounts: precompile_accounts(
+                malan::ChainXPrecompiles::<malan::Runtime>::used_addresses(),
+                &malan::DUMMY_PRECOMPILE_CODE,
+            ),
+        },
         ethereum: Default::default(),
         base_fee: malan::BaseFeeConfig::new(
             malan::DefaultBaseFeePerGas::get(),
             false,
             sp_runtime::Permill::from_parts(125_000),
         ),
-        x_assets_bridge: malan::XAssetsBridgeConfig { admin_key: None },
+        x_assets_bridge: malan::XAssetsBridgeConfig { roles: vec![] },
     }
 }
 
@@ -930,6 +951,9 @@ fn build_dev_genesis(
         x_system: dev::XSystemConfig {
             network_props: NetworkType::Testnet,
         },
+        x_transaction_fee: dev::XTransactionFeeConfig {
+            extra_fees: dev::default_extra_fees(),
+        },
         x_assets_registrar: dev::XAssetsRegistrarConfig { assets },
         x_assets: dev::XAssetsConfig {
             assets_restrictions,
@@ -942,14 +966,7 @@ fn build_dev_genesis(
             confirmation_number: bitcoin.confirmation_number,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
-            params_info: BtcParams::new(
-                // for signet and regtest
-                545259519,            // max_bits
-                2 * 60 * 60,          // block_max_future
-                2 * 7 * 24 * 60 * 60, // target_timespan_seconds
-                10 * 60,              // target_spacing_seconds
-                4,                    // retargeting_factor
-            ), // retargeting_factor
+            params_info: btc_params(bitcoin.network),
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
@@ -978,13 +995,18 @@ fn build_dev_genesis(
                 .collect(),
         },
         ethereum_chain_id: dev::EthereumChainIdConfig { chain_id: 1503u64 },
-        evm: Default::default(),
+        evm: dev::EvmConfig {
+            accounts: precompile_accounts(
+                dev::ChainXPrecompiles::<dev::Runtime>::used_addresses(),
+                &dev::DUMMY_PRECOMPILE_CODE,
+            ),
+        },
         ethereum: Default::default(),
         base_fee: dev::BaseFeeConfig::new(
             dev::DefaultBaseFeePerGas::get(),
             false,
             sp_runtime::Permill::from_parts(125_000),
         ),
-        x_assets_bridge: dev::XAssetsBridgeConfig { admin_key: None },
+        x_assets_bridge: dev::XAssetsBridgeConfig { roles: vec![] },
     }
 }
@@ -300,9 +300,18 @@ pub fn run() -> sc_cli::Result<()> {
     match &cli.subcommand {
         None => {
             let runner = cli.create_runner(&cli.run.base)?;
+            let sealing = cli.run.sealing;
+            let btc_relay_url = cli.run.btc_relay_url.clone();
+            let indexer = cli.run.indexer;
 
             runner.run_node_until_exit(|config| async move {
-                service::build_full(config).map_err(sc_cli::Error::Service)
+                match sealing {
+                    Some(sealing) => {
+                        service::build_dev(config, sealing, btc_relay_url.as_deref(), indexer)
+                    }
+                    None => service::build_full(config, btc_relay_url.as_deref(), indexer),
+                }
+                .map_err(sc_cli::Error::Service)
             })
         }
         Some(Subcommand::Benchmark(cmd)) => {
@@ -324,6 +333,7 @@ pub fn run() -> sc_cli::Result<()> {
         Some(Subcommand::Sign(cmd)) => cmd.run(),
         Some(Subcommand::Verify(cmd)) => cmd.run(),
         Some(Subcommand::Vanity(cmd)) => cmd.run(),
+        Some(Subcommand::BtcGenesis(cmd)) => cmd.run(),
         Some(Subcommand::BuildSpec(cmd)) => {
             let runner = cli.create_runner(cmd)?;
 
@@ -344,6 +354,11 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok(cmd.run(components.client, config.chain_spec))
             })
         }
+        Some(Subcommand::ExportRegenesis(cmd)) => {
+            construct_async_run!(|components, cli, cmd, config| {
+                Ok(async move { cmd.run(components.client) })
+            })
+        }
         Some(Subcommand::ImportBlocks(cmd)) => {
             construct_async_run!(|components, cli, cmd, config| {
                 Ok(cmd.run(components.client, components.import_queue))
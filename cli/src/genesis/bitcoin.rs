@@ -1,22 +1,24 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::convert::TryFrom;
+use std::{collections::BTreeMap, convert::TryFrom, path::PathBuf};
 
+use codec::Decode;
 use hex_literal::hex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use sp_core::sr25519;
 
 use chainx_primitives::AccountId;
 
 use chainx_runtime::{
-    h256_rev, trustees, BtcHeader, BtcNetwork, Chain, Compact as BtcCompact, TrusteeInfoConfig,
-    H256 as BtcHash,
+    h256_rev, hash_rev, trustees, BtcHeader, BtcNetwork, BtcParams, Chain, Compact as BtcCompact,
+    TrusteeInfoConfig, H256 as BtcHash,
 };
+use xpallet_gateway_bitcoin::is_valid_proof_of_work;
 
 use crate::chain_spec::get_account_id_from_seed;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct BtcGenesisParams {
     pub network: BtcNetwork,
     pub confirmation_number: u32,
@@ -31,6 +33,22 @@ pub struct BtcGenesisParams {
 }
 
 impl BtcGenesisParams {
+    fn new(network: BtcNetwork, confirmation_number: u32, height: u32, header: &BtcHeader) -> Self {
+        let rev_hex = |hash: BtcHash| hex::encode(hash_rev(hash));
+        Self {
+            network,
+            confirmation_number,
+            height,
+            hash: rev_hex(header.hash()),
+            version: header.version,
+            previous_header_hash: rev_hex(header.previous_header_hash),
+            merkle_root_hash: rev_hex(header.merkle_root_hash),
+            time: header.time,
+            bits: header.bits,
+            nonce: header.nonce,
+        }
+    }
+
     /// Return the block hash.
     ///
     /// Indicating user-visible serializations of this hash should be backward.
@@ -60,6 +78,206 @@ pub fn btc_genesis_params(res: &str) -> BtcGenesisParams {
     params
 }
 
+/// Returns the params of the Bitcoin light client for the given network.
+pub fn btc_params(network: BtcNetwork) -> BtcParams {
+    let max_bits = match network {
+        // for bitcoin mainnet
+        BtcNetwork::Mainnet => 486604799,
+        // for signet and regtest
+        BtcNetwork::Testnet => 545259519,
+    };
+    BtcParams::new(
+        max_bits,
+        2 * 60 * 60,          // block_max_future
+        2 * 7 * 24 * 60 * 60, // target_timespan_seconds
+        10 * 60,              // target_spacing_seconds
+        4,                    // retargeting_factor
+    )
+}
+
+/// The genesis params written by `chainx btc-genesis`.
+#[derive(Debug, Serialize)]
+struct BtcGenesisOutput {
+    #[serde(flatten)]
+    genesis: BtcGenesisParams,
+    trustee_info_config: BtcTrusteeInfoConfig,
+}
+
+/// The snake_case counterpart of `TrusteeInfoConfig`, like the rest of the genesis params.
+#[derive(Debug, Serialize)]
+struct BtcTrusteeInfoConfig {
+    min_trustee_count: u32,
+    max_trustee_count: u32,
+}
+
+fn parse_network(s: &str) -> Result<BtcNetwork, String> {
+    match s.to_lowercase().as_str() {
+        "mainnet" => Ok(BtcNetwork::Mainnet),
+        "testnet" | "signet" | "regtest" => Ok(BtcNetwork::Testnet),
+        _ => Err(format!("unknown bitcoin network `{}`", s)),
+    }
+}
+
+/// The `btc-genesis` command used to build the Bitcoin genesis params from the raw headers.
+#[derive(Debug, clap::Parser)]
+pub struct BtcGenesisCmd {
+    /// The SCALE encoded `BTreeMap<u32, BtcHeader>` of the consecutive headers, e.g.
+    /// `headers-63290-63310.raw`.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    pub headers: PathBuf,
+
+    /// The height of the genesis header, the first retarget boundary in the headers by default.
+    #[clap(long)]
+    pub height: Option<u32>,
+
+    /// The bitcoin network, `mainnet` or `testnet` (signet and regtest).
+    #[clap(long, default_value = "mainnet", parse(try_from_str = parse_network))]
+    pub network: BtcNetwork,
+
+    /// The confirmation number of the bitcoin deposits and withdrawals.
+    #[clap(long, default_value = "4")]
+    pub confirmation_number: u32,
+
+    /// The minimum number of the bitcoin trustees.
+    #[clap(long, default_value = "3")]
+    pub min_trustee_count: u32,
+
+    /// The maximum number of the bitcoin trustees.
+    #[clap(long, default_value = "15")]
+    pub max_trustee_count: u32,
+
+    /// Accept a genesis header which is not on a retarget boundary.
+    ///
+    /// The difficulty of the first retarget after such a genesis can not be checked by the
+    /// light client.
+    #[clap(long)]
+    pub allow_unaligned: bool,
+
+    /// Write the genesis params to the given file instead of stdout.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    pub output: Option<PathBuf>,
+}
+
+impl BtcGenesisCmd {
+    /// Run the btc-genesis command.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let json = self.genesis_json()?;
+        match &self.output {
+            Some(path) => std::fs::write(path, json + "\n")?,
+            None => println!("{}", json),
+        }
+        Ok(())
+    }
+
+    /// Returns the genesis params built from the headers as JSON.
+    fn genesis_json(&self) -> sc_cli::Result<String> {
+        let bytes = std::fs::read(&self.headers)?;
+        let headers = BTreeMap::<u32, BtcHeader>::decode(&mut bytes.as_slice())
+            .map_err(|e| format!("invalid raw headers: {}", e))?;
+        let params = btc_params(self.network);
+        self.verify_headers(&headers, &params)?;
+
+        let height = self.genesis_height(&headers, params.retargeting_interval())?;
+        if self.min_trustee_count > self.max_trustee_count {
+            return Err("--min-trustee-count is greater than --max-trustee-count".into());
+        }
+
+        let output = BtcGenesisOutput {
+            genesis: BtcGenesisParams::new(
+                self.network,
+                self.confirmation_number,
+                height,
+                &headers[&height],
+            ),
+            trustee_info_config: BtcTrusteeInfoConfig {
+                min_trustee_count: self.min_trustee_count,
+                max_trustee_count: self.max_trustee_count,
+            },
+        };
+        let json = serde_json::to_string_pretty(&output)
+            .map_err(|e| format!("failed to serialize the genesis params: {}", e))?;
+        // The same check as the chain spec does when loading the params.
+        btc_genesis_params(&json);
+        Ok(json)
+    }
+
+    /// Returns the height of the genesis header, which is in the headers and on a retarget
+    /// boundary unless `--allow-unaligned` is given.
+    fn genesis_height(
+        &self,
+        headers: &BTreeMap<u32, BtcHeader>,
+        interval: u32,
+    ) -> sc_cli::Result<u32> {
+        let height = match self.height {
+            Some(height) => height,
+            None => headers
+                .keys()
+                .copied()
+                .find(|height| height % interval == 0)
+                .ok_or_else(|| {
+                    format!(
+                        "no retarget boundary (a multiple of {}) in the headers, use --height",
+                        interval
+                    )
+                })?,
+        };
+        if !headers.contains_key(&height) {
+            return Err(format!("header #{} is not in the headers", height).into());
+        }
+        if height % interval != 0 && !self.allow_unaligned {
+            return Err(format!(
+                "header #{} is not on a retarget boundary (a multiple of {}), \
+                 the next boundary is #{}, use --allow-unaligned to accept it anyway",
+                height,
+                interval,
+                (height / interval + 1) * interval
+            )
+            .into());
+        }
+        Ok(height)
+    }
+
+    /// Checks that the headers are linked, with valid proof of work and difficulty.
+    fn verify_headers(
+        &self,
+        headers: &BTreeMap<u32, BtcHeader>,
+        params: &BtcParams,
+    ) -> sc_cli::Result<()> {
+        if headers.is_empty() {
+            return Err("no header in the headers".into());
+        }
+        for (height, header) in headers {
+            if !is_valid_proof_of_work(params.max_bits(), header.bits, header.hash()) {
+                return Err(format!("header #{} has an invalid proof of work", height).into());
+            }
+        }
+        for ((parent_height, parent), (height, header)) in
+            headers.iter().zip(headers.iter().skip(1))
+        {
+            if *height != parent_height + 1 || header.previous_header_hash != parent.hash() {
+                return Err(format!(
+                    "header #{} is not the child of header #{}",
+                    height, parent_height
+                )
+                .into());
+            }
+            // The light client only checks the difficulty on mainnet, see `HeaderWork`.
+            let is_retarget_height = height % params.retargeting_interval() == 0;
+            if self.network == BtcNetwork::Mainnet
+                && !is_retarget_height
+                && header.bits != parent.bits
+            {
+                return Err(format!(
+                    "header #{} changes the difficulty out of a retarget boundary",
+                    height
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
 // (account_id, about, hot_key, cold_key)
 pub type BtcTrusteeParams = (AccountId, Vec<u8>, Vec<u8>, Vec<u8>);
 
@@ -194,3 +412,107 @@ pub fn mainnet_trustees() -> Vec<(Chain, TrusteeInfoConfig, Vec<BtcTrusteeParams
 
     vec![(Chain::Bitcoin, btc_config, btc_trustees)]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(height: Option<u32>, allow_unaligned: bool) -> BtcGenesisCmd {
+        BtcGenesisCmd {
+            headers: concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../xpallets/gateway/bitcoin/src/res/headers-63290-63310.raw"
+            )
+            .into(),
+            height,
+            network: BtcNetwork::Testnet,
+            confirmation_number: 4,
+            min_trustee_count: 3,
+            max_trustee_count: 15,
+            allow_unaligned,
+            output: None,
+        }
+    }
+
+    fn headers() -> BTreeMap<u32, BtcHeader> {
+        let bytes =
+            include_bytes!("../../../xpallets/gateway/bitcoin/src/res/headers-63290-63310.raw");
+        BTreeMap::decode(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn verify_raw_headers() {
+        let cmd = cmd(None, false);
+        let params = btc_params(BtcNetwork::Testnet);
+        let headers = headers();
+        assert_eq!(headers.len(), 21);
+        assert!(cmd.verify_headers(&headers, &params).is_ok());
+        assert!(cmd.verify_headers(&BTreeMap::new(), &params).is_err());
+
+        // A missing header breaks the chain.
+        let mut gap = headers.clone();
+        gap.remove(&63300);
+        assert!(cmd.verify_headers(&gap, &params).is_err());
+
+        // So does a header of a different branch.
+        let mut fork = headers.clone();
+        fork.get_mut(&63300).unwrap().previous_header_hash = Default::default();
+        assert!(cmd.verify_headers(&fork, &params).is_err());
+
+        // A tampered header no longer meets the target.
+        let mut tampered = headers;
+        tampered.get_mut(&63300).unwrap().nonce += 1;
+        assert!(cmd.verify_headers(&tampered, &params).is_err());
+    }
+
+    #[test]
+    fn genesis_height_is_on_retarget_boundary() {
+        let interval = btc_params(BtcNetwork::Testnet).retargeting_interval();
+        let headers = headers();
+        // The headers 63290-63310 cross no boundary.
+        assert!(cmd(None, false).genesis_height(&headers, interval).is_err());
+        assert!(cmd(Some(63290), false)
+            .genesis_height(&headers, interval)
+            .is_err());
+        assert_eq!(
+            cmd(Some(63290), true)
+                .genesis_height(&headers, interval)
+                .unwrap(),
+            63290
+        );
+        assert!(cmd(Some(63311), true)
+            .genesis_height(&headers, interval)
+            .is_err());
+
+        // The first boundary is picked by default.
+        let boundary = (63290 / interval + 1) * interval;
+        let shifted = headers
+            .into_values()
+            .enumerate()
+            .map(|(i, header)| (boundary - 10 + i as u32, header))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            cmd(None, false).genesis_height(&shifted, interval).unwrap(),
+            boundary
+        );
+        assert_eq!(
+            cmd(Some(boundary), false)
+                .genesis_height(&shifted, interval)
+                .unwrap(),
+            boundary
+        );
+    }
+
+    #[test]
+    fn genesis_json_is_snake_case() {
+        let json = cmd(Some(63290), true).genesis_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["height"], 63290);
+        assert_eq!(value["confirmation_number"], 4);
+        assert_eq!(value["trustee_info_config"]["min_trustee_count"], 3);
+        assert_eq!(value["trustee_info_config"]["max_trustee_count"], 15);
+
+        let params = btc_genesis_params(&json);
+        assert_eq!(params.header(), headers()[&63290]);
+    }
+}
@@ -0,0 +1,594 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Export the live state of a local database as the regenesis params consumed by
+//! `xpallet-genesis-builder`.
+//!
+//! The mining weights are settled at the exported block, the open orders of the spot DEX
+//! are dropped and their reserved balances are exported as free balances.
+
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use codec::Decode;
+use serde::Serialize;
+
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::generic::BlockId;
+
+use chainx_primitives::{AccountId, AssetId, Balance, Block, BlockNumber, Hash, Index};
+use xp_genesis_builder::{
+    AddressBindingInfo, AllParams, AssetLedgerInfo, BtcDepositInfo, BtcPendingDepositInfo,
+    FreeBalanceInfo, MinerLedgerInfo, Nomination, NominatorInfo, TrusteeSessionParams,
+    ValidatorInfo, WithdrawalInfo, XGatewayParams, XMiningAssetParams, XStakingParams,
+};
+use xp_protocol::X_BTC;
+use xpallet_assets::{AssetType, Chain};
+use xpallet_gateway_bitcoin::BtcDepositCache;
+use xpallet_gateway_common::types::GenericTrusteeSessionInfo;
+use xpallet_gateway_records::{WithdrawalRecord, WithdrawalState};
+use xpallet_mining_asset::{AssetLedger, MinerLedger, MiningWeight};
+use xpallet_mining_staking::{NominatorLedger, ValidatorLedger, ValidatorProfile, VoteWeight};
+
+type AccountInfo = frame_system::AccountInfo<Index, pallet_balances::AccountData<Balance>>;
+
+/// The length of the hash in the key hashed by `Blake2_128Concat`.
+const BLAKE2_128_CONCAT_LEN: usize = 16;
+/// The length of the hash in the key hashed by `Twox64Concat`.
+const TWOX_64_CONCAT_LEN: usize = 8;
+
+/// The sums of the on-chain totals and the exported entries, which must be equal.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksums {
+    block_number: BlockNumber,
+    block_hash: Hash,
+    /// `Balances::TotalIssuance`.
+    pcx_total_issuance: Balance,
+    /// The sum of the exported PCX balances.
+    pcx_exported: Balance,
+    /// The sum of all the asset types in `XAssets::TotalAssetBalance` of X-BTC.
+    xbtc_total_issuance: Balance,
+    /// The sum of the exported X-BTC balances.
+    xbtc_exported: Balance,
+    /// The sum of `total_nomination` of all the validators.
+    total_stake: Balance,
+    /// The sum of the exported nominations.
+    stake_exported: Balance,
+}
+
+impl Checksums {
+    fn mismatches(&self) -> Vec<String> {
+        [
+            ("PCX", self.pcx_total_issuance, self.pcx_exported),
+            ("X-BTC", self.xbtc_total_issuance, self.xbtc_exported),
+            ("stake", self.total_stake, self.stake_exported),
+        ]
+        .iter()
+        .filter(|(_, total, exported)| total != exported)
+        .map(|(name, total, exported)| format!("{}: total {}, exported {}", name, total, exported))
+        .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RegenesisExport {
+    #[serde(flatten)]
+    params: AllParams<AccountId, Balance, Balance, Balance>,
+    checksums: Checksums,
+}
+
+/// The `export-regenesis` command used to snapshot the state of a local database as the
+/// regenesis params.
+#[derive(Debug, clap::Parser)]
+pub struct ExportRegenesisCmd {
+    /// Export the state at the given block, the best block by default.
+    #[clap(long, value_name = "HASH or NUMBER")]
+    pub at: Option<BlockNumberOrHash>,
+
+    /// Write the regenesis params to the given file.
+    #[clap(long, short, value_name = "PATH", parse(from_os_str))]
+    pub output: PathBuf,
+
+    /// Write the regenesis params even if the checksums do not match.
+    #[clap(long)]
+    pub allow_mismatch: bool,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for ExportRegenesisCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+impl ExportRegenesisCmd {
+    /// Run the export-regenesis command.
+    pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+    where
+        C: StorageProvider<Block, BE> + HeaderBackend<Block>,
+        BE: Backend<Block>,
+    {
+        let at = match &self.at {
+            Some(at) => at.parse::<Block>()?,
+            None => BlockId::Hash(client.info().best_hash),
+        };
+        let block_hash = client
+            .block_hash_from_id(&at)?
+            .ok_or_else(|| format!("block {} not found", at))?;
+        let block_number = client
+            .block_number_from_id(&at)?
+            .ok_or_else(|| format!("block {} not found", at))?;
+        let at = BlockId::Hash(block_hash);
+        let mut checksums = Checksums {
+            block_number,
+            block_hash,
+            ..Default::default()
+        };
+
+        // PCX, the reserved balances are released by the regenesis.
+        let mut balances = Vec::new();
+        for (key, info) in storage_entries::<_, _, AccountInfo>(&*client, &at, "System", "Account")?
+        {
+            let who: AccountId = decode_key(&mut key.as_slice(), BLAKE2_128_CONCAT_LEN)?;
+            let free = info.data.free + info.data.reserved;
+            if free > 0 {
+                checksums.pcx_exported += free;
+                balances.push(FreeBalanceInfo { who, free });
+            }
+        }
+        checksums.pcx_total_issuance =
+            storage_value(&*client, &at, "Balances", "TotalIssuance")?.unwrap_or_default();
+
+        // X-BTC, all the asset types are merged into the free balance.
+        let mut xassets = Vec::new();
+        let mut asset_balances = BTreeMap::new();
+        let mut total_asset_balances = BTreeMap::new();
+        for (key, asset_balance) in storage_entries::<_, _, BTreeMap<AssetType, Balance>>(
+            &*client,
+            &at,
+            "XAssets",
+            "AssetBalance",
+        )? {
+            let mut key = key.as_slice();
+            let who: AccountId = decode_key(&mut key, BLAKE2_128_CONCAT_LEN)?;
+            let asset_id: AssetId = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+            let free: Balance = asset_balance.values().sum();
+            asset_balances.insert((who.clone(), asset_id), free);
+            if asset_id == X_BTC && free > 0 {
+                checksums.xbtc_exported += free;
+                xassets.push(FreeBalanceInfo { who, free });
+            }
+        }
+        for (key, total_asset_balance) in storage_entries::<_, _, BTreeMap<AssetType, Balance>>(
+            &*client,
+            &at,
+            "XAssets",
+            "TotalAssetBalance",
+        )? {
+            let asset_id: AssetId = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+            let total: Balance = total_asset_balance.values().sum();
+            total_asset_balances.insert(asset_id, total);
+            if asset_id == X_BTC {
+                checksums.xbtc_total_issuance = total;
+            }
+        }
+
+        // Staking.
+        let mut total_nominations = BTreeMap::new();
+        for (key, ledger) in storage_entries::<
+            _,
+            _,
+            ValidatorLedger<Balance, VoteWeight, BlockNumber>,
+        >(&*client, &at, "XStaking", "ValidatorLedgers")?
+        {
+            let who: AccountId = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+            checksums.total_stake += ledger.total_nomination;
+            total_nominations.insert(who, ledger.total_nomination);
+        }
+        let mut validators = Vec::new();
+        for (key, profile) in storage_entries::<_, _, ValidatorProfile<BlockNumber>>(
+            &*client,
+            &at,
+            "XStaking",
+            "Validators",
+        )? {
+            let who: AccountId = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+            validators.push(ValidatorInfo {
+                total_nomination: total_nominations.get(&who).copied().unwrap_or_default(),
+                referral_id: profile.referral_id,
+                who,
+            });
+        }
+        let mut nominations = BTreeMap::<AccountId, Vec<_>>::new();
+        for (key, ledger) in storage_entries::<
+            _,
+            _,
+            NominatorLedger<Balance, VoteWeight, BlockNumber>,
+        >(&*client, &at, "XStaking", "Nominations")?
+        {
+            let mut key = key.as_slice();
+            let nominator: AccountId = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+            let nominee: AccountId = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+            if ledger.nomination > 0 {
+                checksums.stake_exported += ledger.nomination;
+                nominations.entry(nominator).or_default().push(Nomination {
+                    nominee,
+                    nomination: ledger.nomination,
+                });
+            }
+        }
+        let nominators = nominations
+            .into_iter()
+            .map(|(nominator, nominations)| NominatorInfo {
+                nominator,
+                nominations,
+            })
+            .collect();
+
+        let xgateway = export_gateway(&*client, &at)?;
+        let xmining_asset = export_mining_asset(
+            &*client,
+            &at,
+            block_number,
+            &asset_balances,
+            &total_asset_balances,
+        )?;
+
+        self.check(&checksums)?;
+
+        let export = RegenesisExport {
+            params: AllParams {
+                balances,
+                xassets,
+                xstaking: XStakingParams {
+                    validators,
+                    nominators,
+                },
+                xgateway,
+                xmining_asset,
+            },
+            checksums,
+        };
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("failed to serialize the regenesis params: {}", e))?;
+        std::fs::write(&self.output, json)?;
+        log::info!(
+            "Exported the regenesis params at #{} ({:?}) to {}",
+            block_number,
+            block_hash,
+            self.output.display()
+        );
+
+        Ok(())
+    }
+
+    /// Fails on the checksum mismatches unless `--allow-mismatch` is given.
+    fn check(&self, checksums: &Checksums) -> sc_cli::Result<()> {
+        let mismatches = checksums.mismatches();
+        if !mismatches.is_empty() {
+            let msg = format!("checksum mismatch, {}", mismatches.join("; "));
+            if !self.allow_mismatch {
+                return Err(msg.into());
+            }
+            log::warn!("{}", msg);
+        }
+        Ok(())
+    }
+}
+
+/// Exports the pending withdrawals, trustee sessions, address bindings and unclaimed deposits.
+fn export_gateway<C, BE>(
+    client: &C,
+    at: &BlockId<Block>,
+) -> sc_cli::Result<XGatewayParams<AccountId, Balance>>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+{
+    let mut states = BTreeMap::new();
+    for (key, state) in storage_entries::<_, _, WithdrawalState>(
+        client,
+        at,
+        "XGatewayRecords",
+        "WithdrawalStateOf",
+    )? {
+        let id: u32 = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+        states.insert(id, state);
+    }
+    let mut pending_withdrawals = Vec::new();
+    for (key, record) in storage_entries::<_, _, WithdrawalRecord<AccountId, Balance, BlockNumber>>(
+        client,
+        at,
+        "XGatewayRecords",
+        "PendingWithdrawals",
+    )? {
+        let id: u32 = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+        let processing = match states.get(&id) {
+            Some(WithdrawalState::Applying) | None => false,
+            Some(WithdrawalState::Processing) => true,
+            // The withdrawal is settled, the record will be removed soon.
+            Some(_) => continue,
+        };
+        pending_withdrawals.push(WithdrawalInfo {
+            id,
+            asset_id: record.asset_id(),
+            applicant: record.applicant().clone(),
+            balance: record.balance(),
+            addr: record.addr().clone(),
+            ext: record.ext().as_ref().to_vec(),
+            processing,
+        });
+    }
+
+    let mut trustee_sessions = Vec::new();
+    for (key, session_info) in storage_entries::<
+        _,
+        _,
+        GenericTrusteeSessionInfo<AccountId, BlockNumber>,
+    >(client, at, "XGatewayCommon", "TrusteeSessionInfoOf")?
+    {
+        let mut key = key.as_slice();
+        let chain: Chain = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+        let session_number: u32 = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+        let info = session_info.0;
+        trustee_sessions.push(TrusteeSessionParams {
+            chain,
+            session_number,
+            trustee_list: info.trustee_list,
+            threshold: info.threshold,
+            hot_address: info.hot_address,
+            cold_address: info.cold_address,
+            multi_account: info.multi_account,
+        });
+    }
+
+    let mut address_bindings = Vec::new();
+    for (key, who) in
+        storage_entries::<_, _, AccountId>(client, at, "XGatewayCommon", "AddressBindingOf")?
+    {
+        let mut key = key.as_slice();
+        let chain: Chain = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+        let address: Vec<u8> = decode_key(&mut key, BLAKE2_128_CONCAT_LEN)?;
+        address_bindings.push(AddressBindingInfo {
+            chain,
+            address,
+            who,
+        });
+    }
+
+    let mut btc_pending_deposits = Vec::new();
+    for (key, deposits) in storage_entries::<_, _, Vec<BtcDepositCache>>(
+        client,
+        at,
+        "XGatewayBitcoin",
+        "PendingDeposits",
+    )? {
+        let address: Vec<u8> = decode_key(&mut key.as_slice(), BLAKE2_128_CONCAT_LEN)?;
+        btc_pending_deposits.push(BtcPendingDepositInfo {
+            address,
+            deposits: deposits
+                .into_iter()
+                .map(|deposit| BtcDepositInfo {
+                    txid: deposit.txid,
+                    balance: deposit.balance,
+                })
+                .collect(),
+        });
+    }
+
+    Ok(XGatewayParams {
+        pending_withdrawals,
+        trustee_sessions,
+        address_bindings,
+        btc_pending_deposits,
+    })
+}
+
+/// Exports the mining weights settled at the given block.
+fn export_mining_asset<C, BE>(
+    client: &C,
+    at: &BlockId<Block>,
+    block_number: BlockNumber,
+    asset_balances: &BTreeMap<(AccountId, AssetId), Balance>,
+    total_asset_balances: &BTreeMap<AssetId, Balance>,
+) -> sc_cli::Result<XMiningAssetParams<AccountId>>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+{
+    let settle = |last_weight: MiningWeight, amount: Balance, last_update: BlockNumber| {
+        let duration = block_number.saturating_sub(last_update);
+        last_weight.saturating_add(amount.saturating_mul(duration.into()))
+    };
+
+    let mut miner_ledgers = Vec::new();
+    for (key, ledger) in storage_entries::<_, _, MinerLedger<MiningWeight, BlockNumber>>(
+        client,
+        at,
+        "XMiningAsset",
+        "MinerLedgers",
+    )? {
+        let mut key = key.as_slice();
+        let miner: AccountId = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+        let asset_id: AssetId = decode_key(&mut key, TWOX_64_CONCAT_LEN)?;
+        let balance = asset_balances
+            .get(&(miner.clone(), asset_id))
+            .copied()
+            .unwrap_or_default();
+        miner_ledgers.push(MinerLedgerInfo {
+            miner,
+            asset_id,
+            mining_weight: settle(
+                ledger.last_mining_weight,
+                balance,
+                ledger.last_mining_weight_update,
+            ),
+        });
+    }
+
+    let mut asset_ledgers = Vec::new();
+    for (key, ledger) in storage_entries::<_, _, AssetLedger<MiningWeight, BlockNumber>>(
+        client,
+        at,
+        "XMiningAsset",
+        "AssetLedgers",
+    )? {
+        let asset_id: AssetId = decode_key(&mut key.as_slice(), TWOX_64_CONCAT_LEN)?;
+        let total = total_asset_balances
+            .get(&asset_id)
+            .copied()
+            .unwrap_or_default();
+        asset_ledgers.push(AssetLedgerInfo {
+            asset_id,
+            total_mining_weight: settle(
+                ledger.last_total_mining_weight,
+                total,
+                ledger.last_total_mining_weight_update,
+            ),
+        });
+    }
+
+    Ok(XMiningAssetParams {
+        miner_ledgers,
+        asset_ledgers,
+    })
+}
+
+/// Returns all the entries of the storage map, with the key after the storage prefix.
+fn storage_entries<C, BE, V>(
+    client: &C,
+    at: &BlockId<Block>,
+    pallet: &str,
+    item: &str,
+) -> sc_cli::Result<Vec<(Vec<u8>, V)>>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+    V: Decode,
+{
+    let prefix = frame_support::storage::storage_prefix(pallet.as_bytes(), item.as_bytes());
+    client
+        .storage_pairs(at, &StorageKey(prefix.to_vec()))?
+        .into_iter()
+        .map(|(key, value)| -> sc_cli::Result<_> {
+            let value = V::decode(&mut value.0.as_slice())
+                .map_err(|e| format!("failed to decode {}::{}: {}", pallet, item, e))?;
+            Ok((key.0[prefix.len()..].to_vec(), value))
+        })
+        .collect()
+}
+
+/// Returns the storage value.
+fn storage_value<C, BE, V>(
+    client: &C,
+    at: &BlockId<Block>,
+    pallet: &str,
+    item: &str,
+) -> sc_cli::Result<Option<V>>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+    V: Decode,
+{
+    let key = frame_support::storage::storage_prefix(pallet.as_bytes(), item.as_bytes());
+    client
+        .storage(at, &StorageKey(key.to_vec()))?
+        .map(|value| {
+            V::decode(&mut value.0.as_slice())
+                .map_err(|e| format!("failed to decode {}::{}: {}", pallet, item, e).into())
+        })
+        .transpose()
+}
+
+/// Skips the hash of a `*Concat` hashed key and decodes the key itself.
+fn decode_key<K: Decode>(key: &mut &[u8], hash_len: usize) -> sc_cli::Result<K> {
+    *key = key.get(hash_len..).ok_or("the storage key is too short")?;
+    K::decode(key).map_err(|e| format!("failed to decode the storage key: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use codec::Encode;
+    use frame_support::{Blake2_128Concat, StorageHasher, Twox64Concat};
+
+    #[test]
+    fn decode_hashed_keys() {
+        let who = AccountId::new([1; 32]);
+        let asset_id: AssetId = X_BTC;
+        let mut key = Blake2_128Concat::hash(&who.encode());
+        key.extend(Twox64Concat::hash(&asset_id.encode()));
+
+        let mut cursor = key.as_slice();
+        assert_eq!(
+            decode_key::<AccountId>(&mut cursor, BLAKE2_128_CONCAT_LEN).unwrap(),
+            who
+        );
+        assert_eq!(
+            decode_key::<AssetId>(&mut cursor, TWOX_64_CONCAT_LEN).unwrap(),
+            asset_id
+        );
+        assert!(cursor.is_empty());
+
+        // The key is shorter than the hash.
+        assert!(decode_key::<AccountId>(&mut &key[..8], BLAKE2_128_CONCAT_LEN).is_err());
+        // The key is truncated after the hash.
+        assert!(decode_key::<AccountId>(&mut &key[..20], BLAKE2_128_CONCAT_LEN).is_err());
+    }
+
+    #[test]
+    fn checksums_must_match() {
+        let mut checksums = Checksums {
+            pcx_total_issuance: 100,
+            pcx_exported: 100,
+            xbtc_total_issuance: 10,
+            xbtc_exported: 10,
+            total_stake: 50,
+            stake_exported: 50,
+            ..Default::default()
+        };
+        assert!(checksums.mismatches().is_empty());
+
+        checksums.pcx_exported = 99;
+        checksums.stake_exported = 51;
+        assert_eq!(
+            checksums.mismatches(),
+            vec![
+                "PCX: total 100, exported 99".to_string(),
+                "stake: total 50, exported 51".to_string(),
+            ]
+        );
+
+        let cmd = ExportRegenesisCmd::parse_from(["export-regenesis", "--output", "out.json"]);
+        assert!(cmd.check(&checksums).is_err());
+        let cmd = ExportRegenesisCmd::parse_from([
+            "export-regenesis",
+            "--output",
+            "out.json",
+            "--allow-mismatch",
+        ]);
+        assert!(cmd.check(&checksums).is_ok());
+    }
+}
@@ -1,23 +1,109 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use log::{LevelFilter, ParseLevelError};
+use chrono::SecondsFormat;
+use log::{LevelFilter, ParseLevelError, Record};
 use log4rs::{
     append::{
         console::ConsoleAppender,
         rolling_file::{
             policy::{
                 self,
-                compound::{roll, trigger::size::SizeTrigger},
+                compound::{roll, trigger::Trigger},
             },
-            RollingFileAppender,
+            LogFile, RollingFileAppender,
         },
     },
     config,
-    encode::pattern::PatternEncoder,
+    encode::{self, pattern::PatternEncoder, Encode},
 };
 
+/// The format of the log messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "invalid log format `{}`, expected `text` or `json`",
+                s
+            )),
+        }
+    }
+}
+
+/// The interval of the time-based log rotation, aligned to UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotateInterval {
+    Hourly,
+    Daily,
+}
+
+impl RotateInterval {
+    fn as_secs(self) -> u64 {
+        match self {
+            Self::Hourly => 60 * 60,
+            Self::Daily => 24 * 60 * 60,
+        }
+    }
+}
+
+impl FromStr for RotateInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            _ => Err(format!(
+                "invalid log rotate interval `{}`, expected `hourly` or `daily`",
+                s
+            )),
+        }
+    }
+}
+
+/// Routes the log messages of a target into its own file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRoute {
+    pub target: String,
+    pub filename: String,
+}
+
+impl FromStr for LogRoute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((target, filename)) if !target.is_empty() && !filename.is_empty() => Ok(Self {
+                target: target.into(),
+                filename: filename.into(),
+            }),
+            _ => Err(format!(
+                "invalid log route `{}`, expected `TARGET=FILENAME`",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug, clap::Parser)]
 pub struct LoggerParams {
     /// Disable the log rotation.
@@ -55,9 +141,154 @@ pub struct LoggerParams {
 
     /// Compress the old log file to save some disk space.
     ///
-    /// The compressed log file would be like `chainx.log.gz.0` by default.
+    /// The compressed log file would be like `chainx.log.0.gz` by default.
     #[clap(long)]
     pub log_compression: bool,
+
+    /// Also rotate the log file every hour or day (UTC), aside from the size limit.
+    #[clap(long, value_name = "hourly|daily")]
+    pub log_rotate_interval: Option<RotateInterval>,
+
+    /// The format of the log messages, `text` or `json`.
+    ///
+    /// Each line of the `json` format is an object with the timestamp, level, target,
+    /// best block number, message and the span fields kept in the mapped diagnostic
+    /// context of the thread, e.g. the block being imported.
+    #[clap(long, value_name = "text|json", default_value = "text")]
+    pub log_format: LogFormat,
+
+    /// Write the log messages of the target into its own file in the log directory.
+    ///
+    /// e.g. `--log-route runtime::bitcoin=bitcoin.log`. The routed messages are not
+    /// written into the main log file. This option can be given multiple times.
+    #[clap(long, value_name = "TARGET=FILENAME")]
+    pub log_route: Vec<LogRoute>,
+}
+
+/// Encodes the log record as a line of JSON.
+#[derive(Debug)]
+struct JsonEncoder;
+
+impl Encode for JsonEncoder {
+    fn encode(
+        &self,
+        w: &mut dyn encode::Write,
+        record: &Record,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let mut span = serde_json::Map::new();
+        log_mdc::iter(|key, value| {
+            span.insert(key.into(), value.into());
+        });
+        let line = serde_json::json!({
+            "ts": chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "block": chainx_service::best_block_number(),
+            "thread": std::thread::current().name(),
+            "message": record.args().to_string(),
+            "span": span,
+        });
+        serde_json::to_writer(&mut *w, &line)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Rolls the log file once it exceeds the size limit or crosses the interval boundary.
+#[derive(Debug)]
+struct RotationTrigger {
+    limit: u64,
+    interval: Option<u64>,
+    /// The next interval boundary in seconds since the UNIX epoch.
+    next_rotation: AtomicU64,
+}
+
+impl RotationTrigger {
+    fn new(limit: u64, interval: Option<RotateInterval>) -> Self {
+        let interval = interval.map(RotateInterval::as_secs);
+        let next_rotation = interval
+            .map(|i| next_boundary(now(), i))
+            .unwrap_or(u64::MAX);
+        Self {
+            limit,
+            interval,
+            next_rotation: AtomicU64::new(next_rotation),
+        }
+    }
+}
+
+impl Trigger for RotationTrigger {
+    fn trigger(&self, file: &LogFile) -> Result<bool, Box<dyn Error + Sync + Send>> {
+        if file.len() > self.limit {
+            return Ok(true);
+        }
+        if let Some(interval) = self.interval {
+            let now = now();
+            if now >= self.next_rotation.load(Ordering::Relaxed) {
+                self.next_rotation
+                    .store(next_boundary(now, interval), Ordering::Relaxed);
+                return Ok(file.len() > 0);
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Returns the first multiple of `interval` after `now`.
+fn next_boundary(now: u64, interval: u64) -> u64 {
+    (now / interval + 1) * interval
+}
+
+fn encoder(format: LogFormat, pattern: &str) -> Box<dyn Encode> {
+    match format {
+        LogFormat::Text => Box::new(PatternEncoder::new(pattern)),
+        LogFormat::Json => Box::new(JsonEncoder),
+    }
+}
+
+/// Builds the rolling file appender writing into the given file of the log directory.
+fn rolling_file_appender(
+    params: &LoggerParams,
+    filename: &str,
+    pattern: &str,
+) -> Result<RollingFileAppender, String> {
+    let full_log_filename = format!(
+        "{}{}{}",
+        params.log_dir,
+        std::path::MAIN_SEPARATOR,
+        filename
+    );
+
+    // The roller compresses the rotated files if the pattern ends with `.gz`.
+    let roller_pattern = if params.log_compression {
+        format!("{}.{{}}.gz", full_log_filename)
+    } else {
+        format!("{}.{{}}", full_log_filename)
+    };
+
+    let roller = roll::fixed_window::FixedWindowRoller::builder()
+        .build(&roller_pattern, params.log_roll_count)
+        .map_err(|e| format!("log rotate file:{:?}", e))?;
+
+    let policy = policy::compound::CompoundPolicy::new(
+        Box::new(RotationTrigger::new(
+            params.log_size * 1024 * 1024, // log_size MB
+            params.log_rotate_interval,
+        )),
+        Box::new(roller),
+    );
+
+    RollingFileAppender::builder()
+        .encoder(encoder(params.log_format, pattern))
+        .build(full_log_filename, Box::new(policy))
+        .map_err(|e| format!("{}", e))
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -127,36 +358,11 @@ pub fn init(log_filters: &str, params: &LoggerParams) -> Result<(), String> {
         )
     };
 
-    let full_log_filename = format!(
-        "{}{}{}",
-        params.log_dir,
-        std::path::MAIN_SEPARATOR,
-        &params.log_filename
-    );
-
-    let roller_pattern = if params.log_compression {
-        full_log_filename.clone() + ".gz"
-    } else {
-        full_log_filename.clone()
-    };
-
-    let roller = roll::fixed_window::FixedWindowRoller::builder()
-        .build(&format!("{}.{{}}", roller_pattern), params.log_roll_count)
-        .map_err(|e| format!("log rotate file:{:?}", e))?;
-
-    let policy = policy::compound::CompoundPolicy::new(
-        Box::new(SizeTrigger::new(params.log_size * 1024 * 1024)), // log_size MB
-        Box::new(roller),
-    );
-
-    let roll_file = RollingFileAppender::builder()
-        .encoder(Box::new(PatternEncoder::new(log_file_pattern)))
-        .build(full_log_filename, Box::new(policy))
-        .map_err(|e| format!("{}", e))?;
+    let roll_file = rolling_file_appender(params, &params.log_filename, log_file_pattern)?;
 
     let mut config_builder = if params.enable_console_log {
         let console = ConsoleAppender::builder()
-            .encoder(Box::new(PatternEncoder::new(console_pattern)))
+            .encoder(encoder(params.log_format, console_pattern))
             .build();
         config::Config::builder()
             .appender(config::Appender::builder().build("console", Box::new(console)))
@@ -166,10 +372,27 @@ pub fn init(log_filters: &str, params: &LoggerParams) -> Result<(), String> {
             .appender(config::Appender::builder().build("roll", Box::new(roll_file)))
     };
 
-    for d in directives {
-        if let Some(name) = d.name {
-            config_builder = config_builder.logger(config::Logger::builder().build(name, d.level));
+    let mut levels = directives
+        .into_iter()
+        .filter_map(|d| d.name.map(|name| (name, d.level)))
+        .collect::<BTreeMap<_, _>>();
+
+    for (index, route) in params.log_route.iter().enumerate() {
+        let appender = format!("route{}", index);
+        let route_file = rolling_file_appender(params, &route.filename, log_file_pattern)?;
+        config_builder = config_builder
+            .appender(config::Appender::builder().build(&appender, Box::new(route_file)));
+
+        let level = levels.remove(&route.target).unwrap_or(global_level);
+        let mut logger = config::Logger::builder().appender(appender).additive(false);
+        if params.enable_console_log {
+            logger = logger.appender("console");
         }
+        config_builder = config_builder.logger(logger.build(&route.target, level));
+    }
+
+    for (name, level) in levels {
+        config_builder = config_builder.logger(config::Logger::builder().build(name, level));
     }
 
     let root = if params.enable_console_log {
@@ -192,6 +415,55 @@ pub fn init(log_filters: &str, params: &LoggerParams) -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+fn test_log_route() {
+    assert_eq!(
+        "runtime::bitcoin=bitcoin.log".parse::<LogRoute>(),
+        Ok(LogRoute {
+            target: "runtime::bitcoin".into(),
+            filename: "bitcoin.log".into(),
+        })
+    );
+    assert!("runtime::bitcoin".parse::<LogRoute>().is_err());
+    assert!("=bitcoin.log".parse::<LogRoute>().is_err());
+}
+
+#[test]
+fn test_json_span() {
+    let encode = || {
+        let mut w = encode::writer::simple::SimpleWriter(Vec::new());
+        JsonEncoder
+            .encode(
+                &mut w,
+                &Record::builder()
+                    .args(format_args!("Imported"))
+                    .level(log::Level::Info)
+                    .target("sync")
+                    .build(),
+            )
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&w.0).unwrap()
+    };
+
+    {
+        let _span = chainx_service::LogSpan::enter(vec![("import", "#1 (0x01)".into())]);
+        let line = encode();
+        assert_eq!(line["message"], "Imported");
+        assert_eq!(line["target"], "sync");
+        assert_eq!(line["span"], serde_json::json!({ "import": "#1 (0x01)" }));
+    }
+    // The span is left once the guard is dropped.
+    assert_eq!(encode()["span"], serde_json::json!({}));
+}
+
+#[test]
+fn test_next_boundary() {
+    assert_eq!(next_boundary(0, 3600), 3600);
+    assert_eq!(next_boundary(3599, 3600), 3600);
+    assert_eq!(next_boundary(3600, 3600), 7200);
+    assert_eq!(next_boundary(90_000, 86_400), 172_800);
+}
+
 #[test]
 fn test_directive() {
     assert_eq!(
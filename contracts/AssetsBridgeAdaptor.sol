@@ -24,6 +24,33 @@ interface IAssetsBridge {
     function burn_from(address account, uint256 amount) external returns (bool);
 }
 
+/**
+ * @dev Interface of the ChainX AssetsBridge precompile.
+ */
+interface IAssetsBridgePrecompile {
+    /*
+     * @dev withdraw the burnt token into substrate assets, only for the registered erc20 contracts.
+     * @param from The owner of the burnt token.
+     * @param target The substrate account, zero for the account linked with `from`.
+     * @param amount The amount of token.
+     */
+    function withdrawTo(address from, bytes32 target, uint256 amount) external;
+
+    /*
+     * @dev teleport the native currency of caller into substrate account.
+     * @param target The substrate account, zero for the account linked with caller.
+     * @param amount The amount of native currency.
+     */
+    function teleportTo(bytes32 target, uint256 amount) external;
+
+    /*
+     * @dev the substrate account linked with `account`, zero if not linked.
+     */
+    function linkedAccount(address account) external view returns (bytes32);
+}
+
+address constant ASSETS_BRIDGE_PRECOMPILE = 0x0000000000000000000000000000000000000803;
+
 abstract contract AssetsBridgeAdmin is Context {
     address public constant admin = 0x1111111111111111111111111111111111111111;
 
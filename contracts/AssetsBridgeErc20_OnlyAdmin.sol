@@ -258,6 +258,18 @@ contract AssetsBridgeErc20 is Context, IERC20, IERC20Metadata, Pausable, Ownable
         return true;
     }
 
+    /*
+     * @dev burn the token of caller and withdraw into substrate assets.
+     * @param target The substrate account, zero for the account linked with caller.
+     * @param amount The amount of token.
+     */
+    function withdraw_to_substrate(bytes32 target, uint256 amount) external virtual returns (bool) {
+        _burn(_msgSender(), amount);
+        IAssetsBridgePrecompile(ASSETS_BRIDGE_PRECOMPILE).withdrawTo(_msgSender(), target, amount);
+
+        return true;
+    }
+
     function pause() external whenNotPaused {
         require(_msgSender() == owner(), "ERC20: require called by the contract owner");
         _pause();
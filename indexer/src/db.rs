@@ -0,0 +1,454 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The key-value database of the indexer.
+//!
+//! The records of an account are keyed by the account followed by the bitwise NOT of their
+//! position in the chain, so that iterating the prefix of the account yields the latest
+//! records first.
+
+use std::{collections::BTreeMap, io, path::Path, sync::Arc};
+
+use codec::{Decode, Encode};
+use kvdb::{DBTransaction, KeyValueDB};
+use serde::Serialize;
+
+use chainx_primitives::{AccountId, AssetId, BlockNumber, Hash};
+use xpallet_gateway_records::WithdrawalRecordId;
+
+use crate::event::{BlockEvents, EventEntry, IndexedEvent};
+
+const NUM_COLUMNS: u32 = 5;
+
+mod columns {
+    /// The indexer metadata.
+    pub const META: u32 = 0;
+    /// account ++ !block_number ++ !event_index => EventEntry
+    pub const EVENTS: u32 = 1;
+    /// account ++ asset_id ++ !block_number ++ !event_index => ()
+    pub const ASSET_EVENTS: u32 = 2;
+    /// signer ++ !block_number ++ !extrinsic_index => ExtrinsicEntry
+    pub const EXTRINSICS: u32 = 3;
+    /// withdrawal_id => (applicant, asset_id)
+    pub const WITHDRAWALS: u32 = 4;
+}
+
+/// The number of the last indexed block.
+const INDEXED_BLOCK_KEY: &[u8] = b"indexed_block";
+/// skipped_block ++ block_number => SkippedBlock
+const SKIPPED_BLOCK_PREFIX: &[u8] = b"skipped_block";
+
+/// A signed extrinsic along with its position in the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicEntry {
+    pub block_number: BlockNumber,
+    pub block_hash: Hash,
+    pub extrinsic_index: u32,
+    pub extrinsic_hash: Hash,
+    pub success: bool,
+}
+
+/// A block whose events could not be decoded, none of its records are indexed.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedBlock {
+    pub block_number: BlockNumber,
+    pub block_hash: Hash,
+    pub reason: String,
+}
+
+fn position(block_number: BlockNumber, index: u32) -> [u8; 8] {
+    let mut position = [0u8; 8];
+    position[..4].copy_from_slice(&(!block_number).to_be_bytes());
+    position[4..].copy_from_slice(&(!index).to_be_bytes());
+    position
+}
+
+fn account_key(who: &AccountId) -> &[u8] {
+    who.as_ref()
+}
+
+fn invalid_data(column: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("corrupted indexer column {}", column),
+    )
+}
+
+pub struct Database {
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl Database {
+    /// Opens the RocksDB database at the path, creates it if missing.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(path)
+            .map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+        let config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&config, path)
+            .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+        Ok(Self::new(Arc::new(db)))
+    }
+
+    pub(crate) fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self { db }
+    }
+
+    /// Returns the number of the last indexed block.
+    pub fn indexed_block(&self) -> io::Result<Option<BlockNumber>> {
+        self.db
+            .get(columns::META, INDEXED_BLOCK_KEY)?
+            .map(|value| BlockNumber::decode(&mut &value[..]).map_err(|_| invalid_data("META")))
+            .transpose()
+    }
+
+    fn withdrawal(&self, id: WithdrawalRecordId) -> io::Result<Option<(AccountId, AssetId)>> {
+        self.db
+            .get(columns::WITHDRAWALS, &id.to_be_bytes())?
+            .map(|value| Decode::decode(&mut &value[..]).map_err(|_| invalid_data("WITHDRAWALS")))
+            .transpose()
+    }
+
+    /// Writes the records of a block along with its number atomically.
+    ///
+    /// `extrinsics` are the (index, signer, hash) of the signed extrinsics in the block.
+    pub fn write_block(
+        &self,
+        block_number: BlockNumber,
+        block_hash: Hash,
+        block_events: BlockEvents,
+        extrinsics: Vec<(u32, AccountId, Hash)>,
+    ) -> io::Result<()> {
+        let mut tx = DBTransaction::new();
+        let mut new_withdrawals = BTreeMap::new();
+        for (event_index, extrinsic_index, mut event) in block_events.events {
+            match &mut event {
+                IndexedEvent::WithdrawalCreated {
+                    withdrawal_id,
+                    applicant,
+                    asset_id,
+                    ..
+                } => {
+                    let withdrawal = (applicant.clone(), *asset_id);
+                    tx.put_vec(
+                        columns::WITHDRAWALS,
+                        &withdrawal_id.to_be_bytes(),
+                        withdrawal.encode(),
+                    );
+                    new_withdrawals.insert(*withdrawal_id, withdrawal);
+                }
+                IndexedEvent::WithdrawalStateChanged {
+                    withdrawal_id,
+                    applicant,
+                    asset_id,
+                    ..
+                } => {
+                    let withdrawal = match new_withdrawals.get(withdrawal_id) {
+                        Some(withdrawal) => Some(withdrawal.clone()),
+                        None => self.withdrawal(*withdrawal_id)?,
+                    };
+                    if let Some((who, id)) = withdrawal {
+                        *applicant = Some(who);
+                        *asset_id = Some(id);
+                    }
+                }
+                _ => {}
+            }
+
+            let position = position(block_number, event_index);
+            let accounts = event.accounts().into_iter().cloned().collect::<Vec<_>>();
+            let asset_id = event.asset_id();
+            let entry = EventEntry {
+                block_number,
+                block_hash,
+                event_index,
+                extrinsic_index,
+                event,
+            }
+            .encode();
+            for who in accounts {
+                tx.put(
+                    columns::EVENTS,
+                    &[account_key(&who), &position[..]].concat(),
+                    &entry,
+                );
+                if let Some(asset_id) = asset_id {
+                    tx.put(
+                        columns::ASSET_EVENTS,
+                        &[account_key(&who), &asset_id.to_be_bytes(), &position[..]].concat(),
+                        &[],
+                    );
+                }
+            }
+        }
+
+        for (extrinsic_index, signer, extrinsic_hash) in extrinsics {
+            let entry = ExtrinsicEntry {
+                block_number,
+                block_hash,
+                extrinsic_index,
+                extrinsic_hash,
+                success: !block_events.failed_extrinsics.contains(&extrinsic_index),
+            };
+            tx.put_vec(
+                columns::EXTRINSICS,
+                &[
+                    account_key(&signer),
+                    &position(block_number, extrinsic_index)[..],
+                ]
+                .concat(),
+                entry.encode(),
+            );
+        }
+
+        tx.put_vec(columns::META, INDEXED_BLOCK_KEY, block_number.encode());
+        self.db.write(tx)
+    }
+
+    /// Records the block as skipped along with its number, so that the indexing goes on with
+    /// the next block.
+    pub fn skip_block(
+        &self,
+        block_number: BlockNumber,
+        block_hash: Hash,
+        reason: String,
+    ) -> io::Result<()> {
+        let mut tx = DBTransaction::new();
+        let skipped = SkippedBlock {
+            block_number,
+            block_hash,
+            reason,
+        };
+        tx.put_vec(
+            columns::META,
+            &[SKIPPED_BLOCK_PREFIX, &block_number.to_be_bytes()].concat(),
+            skipped.encode(),
+        );
+        tx.put_vec(columns::META, INDEXED_BLOCK_KEY, block_number.encode());
+        self.db.write(tx)
+    }
+
+    /// Returns a page of the skipped blocks, oldest first.
+    pub fn skipped_blocks(&self, page_index: u32, page_size: u32) -> io::Result<Vec<SkippedBlock>> {
+        self.db
+            .iter_with_prefix(columns::META, SKIPPED_BLOCK_PREFIX)
+            .skip(page_index as usize * page_size as usize)
+            .take(page_size as usize)
+            .map(|(_, value)| {
+                SkippedBlock::decode(&mut &value[..]).map_err(|_| invalid_data("META"))
+            })
+            .collect()
+    }
+
+    /// Returns a page of the events of the account, optionally of an asset only, latest first.
+    pub fn events_of(
+        &self,
+        who: &AccountId,
+        asset_id: Option<AssetId>,
+        page_index: u32,
+        page_size: u32,
+    ) -> io::Result<Vec<EventEntry>> {
+        let skip = page_index as usize * page_size as usize;
+        let take = page_size as usize;
+        let decode =
+            |value: &[u8]| EventEntry::decode(&mut &value[..]).map_err(|_| invalid_data("EVENTS"));
+        match asset_id {
+            None => self
+                .db
+                .iter_with_prefix(columns::EVENTS, account_key(who))
+                .skip(skip)
+                .take(take)
+                .map(|(_, value)| decode(&value))
+                .collect(),
+            Some(asset_id) => {
+                let prefix = [account_key(who), &asset_id.to_be_bytes()].concat();
+                self.db
+                    .iter_with_prefix(columns::ASSET_EVENTS, &prefix)
+                    .skip(skip)
+                    .take(take)
+                    .map(|(key, _)| {
+                        let position = &key[prefix.len()..];
+                        let value = self
+                            .db
+                            .get(columns::EVENTS, &[account_key(who), position].concat())?
+                            .ok_or_else(|| invalid_data("ASSET_EVENTS"))?;
+                        decode(&value)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns a page of the signed extrinsics of the account, latest first.
+    pub fn extrinsics_of(
+        &self,
+        who: &AccountId,
+        page_index: u32,
+        page_size: u32,
+    ) -> io::Result<Vec<ExtrinsicEntry>> {
+        self.db
+            .iter_with_prefix(columns::EXTRINSICS, account_key(who))
+            .skip(page_index as usize * page_size as usize)
+            .take(page_size as usize)
+            .map(|(_, value)| {
+                ExtrinsicEntry::decode(&mut &value[..]).map_err(|_| invalid_data("EXTRINSICS"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xpallet_gateway_records::WithdrawalState;
+
+    fn database() -> Database {
+        Database::new(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)))
+    }
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::new([seed; 32])
+    }
+
+    fn deposited(who: u8, asset_id: AssetId) -> IndexedEvent {
+        IndexedEvent::Deposited {
+            who: account(who),
+            asset_id,
+            amount: 100,
+        }
+    }
+
+    #[test]
+    fn test_events_latest_first() {
+        let db = database();
+        assert_eq!(db.indexed_block().unwrap(), None);
+        for block_number in 1..=3 {
+            let block_events = BlockEvents {
+                events: vec![
+                    (0, None, deposited(1, 1)),
+                    (1, Some(1), deposited(1, 2)),
+                    (2, Some(1), deposited(2, 1)),
+                ],
+                failed_extrinsics: vec![],
+            };
+            db.write_block(block_number, Hash::zero(), block_events, vec![])
+                .unwrap();
+        }
+        assert_eq!(db.indexed_block().unwrap(), Some(3));
+
+        let positions = |entries: Vec<EventEntry>| {
+            entries
+                .into_iter()
+                .map(|e| (e.block_number, e.event_index))
+                .collect::<Vec<_>>()
+        };
+        let events = db.events_of(&account(1), None, 0, 4).unwrap();
+        assert_eq!(positions(events), vec![(3, 1), (3, 0), (2, 1), (2, 0)]);
+        let events = db.events_of(&account(1), None, 1, 4).unwrap();
+        assert_eq!(positions(events), vec![(1, 1), (1, 0)]);
+        let events = db.events_of(&account(1), Some(2), 0, 10).unwrap();
+        assert_eq!(positions(events), vec![(3, 1), (2, 1), (1, 1)]);
+        let events = db.events_of(&account(2), Some(2), 0, 10).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_skip_block() {
+        let db = database();
+        let block_events = BlockEvents {
+            events: vec![(0, None, deposited(1, 1))],
+            failed_extrinsics: vec![],
+        };
+        db.write_block(1, Hash::zero(), block_events.clone(), vec![])
+            .unwrap();
+        db.skip_block(2, Hash::repeat_byte(2), "spec version 27".into())
+            .unwrap();
+        db.write_block(3, Hash::zero(), block_events, vec![])
+            .unwrap();
+        db.skip_block(256, Hash::repeat_byte(1), "no events".into())
+            .unwrap();
+        assert_eq!(db.indexed_block().unwrap(), Some(256));
+
+        let events = db.events_of(&account(1), None, 0, 10).unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.block_number).collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+        let skipped = db.skipped_blocks(0, 10).unwrap();
+        assert_eq!(
+            skipped,
+            vec![
+                SkippedBlock {
+                    block_number: 2,
+                    block_hash: Hash::repeat_byte(2),
+                    reason: "spec version 27".into(),
+                },
+                SkippedBlock {
+                    block_number: 256,
+                    block_hash: Hash::repeat_byte(1),
+                    reason: "no events".into(),
+                },
+            ]
+        );
+        assert_eq!(db.skipped_blocks(1, 1).unwrap(), vec![skipped[1].clone()]);
+    }
+
+    #[test]
+    fn test_withdrawal_state_resolved() {
+        let db = database();
+        let created = IndexedEvent::WithdrawalCreated {
+            withdrawal_id: 7,
+            applicant: account(1),
+            asset_id: 1,
+            amount: 100,
+            addr: b"addr".to_vec(),
+        };
+        let processed = IndexedEvent::WithdrawalStateChanged {
+            withdrawal_id: 7,
+            applicant: None,
+            asset_id: None,
+            state: WithdrawalState::Processing,
+        };
+        let block_events = BlockEvents {
+            events: vec![(0, Some(1), created)],
+            failed_extrinsics: vec![],
+        };
+        db.write_block(
+            1,
+            Hash::zero(),
+            block_events,
+            vec![(1, account(1), Hash::zero())],
+        )
+        .unwrap();
+        let block_events = BlockEvents {
+            events: vec![(0, None, processed)],
+            failed_extrinsics: vec![1],
+        };
+        db.write_block(
+            2,
+            Hash::zero(),
+            block_events,
+            vec![(1, account(1), Hash::zero())],
+        )
+        .unwrap();
+
+        let events = db.events_of(&account(1), Some(1), 0, 10).unwrap();
+        assert_eq!(
+            events[0].event,
+            IndexedEvent::WithdrawalStateChanged {
+                withdrawal_id: 7,
+                applicant: Some(account(1)),
+                asset_id: Some(1),
+                state: WithdrawalState::Processing,
+            }
+        );
+        let extrinsics = db.extrinsics_of(&account(1), 0, 10).unwrap();
+        assert_eq!(
+            extrinsics
+                .iter()
+                .map(|e| (e.block_number, e.success))
+                .collect::<Vec<_>>(),
+            vec![(2, false), (1, true)]
+        );
+    }
+}
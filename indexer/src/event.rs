@@ -0,0 +1,559 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The indexed ChainX pallet events, decoded from the `System::Events` of a block.
+
+use codec::{Decode, Encode};
+use serde::Serialize;
+
+use chainx_primitives::{AccountId, AddrStr, AssetId, Balance, Hash};
+use xpallet_assets::AssetType;
+use xpallet_dex_spot::{OrderId, Side, TradingPairId};
+use xpallet_gateway_records::{WithdrawalRecordId, WithdrawalState};
+
+/// A ChainX pallet event involving some accounts.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum IndexedEvent {
+    /// XAssets::Moved
+    #[serde(rename_all = "camelCase")]
+    AssetMoved {
+        asset_id: AssetId,
+        from: AccountId,
+        from_type: AssetType,
+        to: AccountId,
+        to_type: AssetType,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XAssets::Issued
+    #[serde(rename_all = "camelCase")]
+    AssetIssued {
+        asset_id: AssetId,
+        who: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XAssets::Destroyed
+    #[serde(rename_all = "camelCase")]
+    AssetDestroyed {
+        asset_id: AssetId,
+        who: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XAssets::BalanceSet
+    #[serde(rename_all = "camelCase")]
+    AssetBalanceSet {
+        asset_id: AssetId,
+        who: AccountId,
+        asset_type: AssetType,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XAssets::VestingScheduleAdded
+    #[serde(rename_all = "camelCase")]
+    VestingScheduleAdded {
+        asset_id: AssetId,
+        from: AccountId,
+        to: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        locked: Balance,
+    },
+    /// XAssets::Vested
+    #[serde(rename_all = "camelCase")]
+    Vested {
+        asset_id: AssetId,
+        who: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        still_locked: Balance,
+    },
+    /// XGatewayRecords::Deposited
+    #[serde(rename_all = "camelCase")]
+    Deposited {
+        who: AccountId,
+        asset_id: AssetId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XGatewayRecords::WithdrawalCreated
+    #[serde(rename_all = "camelCase")]
+    WithdrawalCreated {
+        withdrawal_id: WithdrawalRecordId,
+        applicant: AccountId,
+        asset_id: AssetId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+        #[serde(with = "xp_rpc::serde_text")]
+        addr: AddrStr,
+    },
+    /// XGatewayRecords::WithdrawalProcessed, WithdrawalRecovered, WithdrawalCanceled and
+    /// WithdrawalFinished.
+    ///
+    /// The events only carry the withdrawal id, the applicant and the asset are filled in
+    /// from the `WithdrawalCreated` indexed before.
+    #[serde(rename_all = "camelCase")]
+    WithdrawalStateChanged {
+        withdrawal_id: WithdrawalRecordId,
+        applicant: Option<AccountId>,
+        asset_id: Option<AssetId>,
+        state: WithdrawalState,
+    },
+    /// XSpot::NewOrder
+    #[serde(rename_all = "camelCase")]
+    OrderCreated {
+        order_id: OrderId,
+        pair_id: TradingPairId,
+        submitter: AccountId,
+        side: Side,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        price: Balance,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XSpot::CanceledOrderUpdated
+    #[serde(rename_all = "camelCase")]
+    OrderCanceled {
+        order_id: OrderId,
+        pair_id: TradingPairId,
+        submitter: AccountId,
+    },
+    /// XSpot::OrderExecuted
+    #[serde(rename_all = "camelCase")]
+    OrderExecuted {
+        pair_id: TradingPairId,
+        maker: AccountId,
+        maker_order_id: OrderId,
+        taker: AccountId,
+        taker_order_id: OrderId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        price: Balance,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        turnover: Balance,
+    },
+    /// XStaking::Bonded
+    #[serde(rename_all = "camelCase")]
+    Bonded {
+        nominator: AccountId,
+        validator: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XStaking::Rebonded
+    #[serde(rename_all = "camelCase")]
+    Rebonded {
+        nominator: AccountId,
+        from: AccountId,
+        to: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XStaking::Unbonded
+    #[serde(rename_all = "camelCase")]
+    Unbonded {
+        nominator: AccountId,
+        validator: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XStaking::Claimed
+    #[serde(rename_all = "camelCase")]
+    StakingClaimed {
+        nominator: AccountId,
+        validator: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        dividend: Balance,
+    },
+    /// XStaking::Withdrawn
+    #[serde(rename_all = "camelCase")]
+    Withdrawn {
+        nominator: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XStaking::NominatorSlashed
+    #[serde(rename_all = "camelCase")]
+    NominatorSlashed {
+        nominator: AccountId,
+        validator: AccountId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+    /// XMiningAsset::Claimed
+    #[serde(rename_all = "camelCase")]
+    MiningClaimed {
+        claimer: AccountId,
+        asset_id: AssetId,
+        #[serde(with = "xp_rpc::serde_num_str")]
+        amount: Balance,
+    },
+}
+
+impl IndexedEvent {
+    /// Returns the accounts the event is indexed by, without duplicates.
+    pub fn accounts(&self) -> Vec<&AccountId> {
+        let mut accounts = match self {
+            Self::AssetMoved { from, to, .. } | Self::VestingScheduleAdded { from, to, .. } => {
+                vec![from, to]
+            }
+            Self::AssetIssued { who, .. }
+            | Self::AssetDestroyed { who, .. }
+            | Self::AssetBalanceSet { who, .. }
+            | Self::Vested { who, .. }
+            | Self::Deposited { who, .. } => vec![who],
+            Self::WithdrawalCreated { applicant, .. } => vec![applicant],
+            Self::WithdrawalStateChanged { applicant, .. } => applicant.iter().collect(),
+            Self::OrderCreated { submitter, .. } | Self::OrderCanceled { submitter, .. } => {
+                vec![submitter]
+            }
+            Self::OrderExecuted { maker, taker, .. } => vec![maker, taker],
+            Self::Bonded {
+                nominator,
+                validator,
+                ..
+            }
+            | Self::Unbonded {
+                nominator,
+                validator,
+                ..
+            }
+            | Self::StakingClaimed {
+                nominator,
+                validator,
+                ..
+            }
+            | Self::NominatorSlashed {
+                nominator,
+                validator,
+                ..
+            } => vec![nominator, validator],
+            Self::Rebonded {
+                nominator,
+                from,
+                to,
+                ..
+            } => vec![nominator, from, to],
+            Self::Withdrawn { nominator, .. } => vec![nominator],
+            Self::MiningClaimed { claimer, .. } => vec![claimer],
+        };
+        accounts.sort();
+        accounts.dedup();
+        accounts
+    }
+
+    /// Returns the asset of the event, if any.
+    pub fn asset_id(&self) -> Option<AssetId> {
+        match self {
+            Self::AssetMoved { asset_id, .. }
+            | Self::AssetIssued { asset_id, .. }
+            | Self::AssetDestroyed { asset_id, .. }
+            | Self::AssetBalanceSet { asset_id, .. }
+            | Self::VestingScheduleAdded { asset_id, .. }
+            | Self::Vested { asset_id, .. }
+            | Self::Deposited { asset_id, .. }
+            | Self::WithdrawalCreated { asset_id, .. }
+            | Self::MiningClaimed { asset_id, .. } => Some(*asset_id),
+            Self::WithdrawalStateChanged { asset_id, .. } => *asset_id,
+            _ => None,
+        }
+    }
+}
+
+/// An indexed event along with its position in the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventEntry {
+    pub block_number: u32,
+    pub block_hash: Hash,
+    /// The index of the event in the block.
+    pub event_index: u32,
+    /// The index of the extrinsic emitting the event, `None` for the events emitted on the
+    /// initialization and the finalization of the block.
+    pub extrinsic_index: Option<u32>,
+    #[serde(flatten)]
+    pub event: IndexedEvent,
+}
+
+/// The indexed events of a block and the extrinsics which failed in it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockEvents {
+    /// (event_index, extrinsic_index, event)
+    pub events: Vec<(u32, Option<u32>, IndexedEvent)>,
+    pub failed_extrinsics: Vec<u32>,
+}
+
+/// Decodes the encoded `System::Events` of a block.
+///
+/// The outer event differs among the runtimes, see [`decode_events`].
+pub type EventsDecoder = fn(&[u8]) -> Option<BlockEvents>;
+
+/// The events decoder of a runtime along with its spec version.
+///
+/// The outer event of the runtime only matches the layout of the events emitted by the
+/// same spec version, the blocks executed by the other versions are skipped.
+#[derive(Clone, Copy)]
+pub struct RuntimeDecoder {
+    pub spec_version: u32,
+    pub decode: EventsDecoder,
+}
+
+impl RuntimeDecoder {
+    pub fn new(spec_version: u32, decode: EventsDecoder) -> Self {
+        Self {
+            spec_version,
+            decode,
+        }
+    }
+}
+
+/// Decodes the encoded `System::Events` into the ChainX pallet events of interest, `R` is
+/// the runtime and `E` its outer event.
+pub fn decode_events<R, E>(mut encoded: &[u8]) -> Option<BlockEvents>
+where
+    R: frame_system::Config<AccountId = AccountId>
+        + xpallet_assets::Config
+        + xpallet_gateway_records::Config
+        + xpallet_dex_spot::Config
+        + xpallet_mining_staking::Config
+        + xpallet_mining_asset::Config,
+    xpallet_assets::BalanceOf<R>: Into<Balance>,
+    xpallet_dex_spot::BalanceOf<R>: Into<Balance>,
+    xpallet_mining_staking::BalanceOf<R>: Into<Balance>,
+    <R as xpallet_dex_spot::Config>::Price: Into<Balance>,
+    E: Decode
+        + Clone
+        + TryInto<frame_system::Event<R>>
+        + TryInto<xpallet_assets::Event<R>>
+        + TryInto<xpallet_gateway_records::Event<R>>
+        + TryInto<xpallet_dex_spot::Event<R>>
+        + TryInto<xpallet_mining_staking::Event<R>>
+        + TryInto<xpallet_mining_asset::Event<R>>,
+{
+    let records = Vec::<frame_system::EventRecord<E, Hash>>::decode(&mut encoded).ok()?;
+    // The trailing bytes are left by a layout mismatch.
+    if !encoded.is_empty() {
+        return None;
+    }
+    let mut block_events = BlockEvents::default();
+    for (event_index, record) in records.into_iter().enumerate() {
+        let extrinsic_index = match record.phase {
+            frame_system::Phase::ApplyExtrinsic(index) => Some(index),
+            _ => None,
+        };
+        if let Ok(frame_system::Event::<R>::ExtrinsicFailed(..)) = record.event.clone().try_into() {
+            block_events.failed_extrinsics.extend(extrinsic_index);
+            continue;
+        }
+        if let Some(event) = decode_event::<R, E>(record.event) {
+            block_events
+                .events
+                .push((event_index as u32, extrinsic_index, event));
+        }
+    }
+    Some(block_events)
+}
+
+fn decode_event<R, E>(event: E) -> Option<IndexedEvent>
+where
+    R: frame_system::Config<AccountId = AccountId>
+        + xpallet_assets::Config
+        + xpallet_gateway_records::Config
+        + xpallet_dex_spot::Config
+        + xpallet_mining_staking::Config
+        + xpallet_mining_asset::Config,
+    xpallet_assets::BalanceOf<R>: Into<Balance>,
+    xpallet_dex_spot::BalanceOf<R>: Into<Balance>,
+    xpallet_mining_staking::BalanceOf<R>: Into<Balance>,
+    <R as xpallet_dex_spot::Config>::Price: Into<Balance>,
+    E: Clone
+        + TryInto<xpallet_assets::Event<R>>
+        + TryInto<xpallet_gateway_records::Event<R>>
+        + TryInto<xpallet_dex_spot::Event<R>>
+        + TryInto<xpallet_mining_staking::Event<R>>
+        + TryInto<xpallet_mining_asset::Event<R>>,
+{
+    use xpallet_assets::Event as Assets;
+    use xpallet_dex_spot::Event as Spot;
+    use xpallet_gateway_records::Event as Records;
+    use xpallet_mining_asset::Event as MiningAsset;
+    use xpallet_mining_staking::Event as Staking;
+
+    if let Ok(event) = TryInto::<Assets<R>>::try_into(event.clone()) {
+        return match event {
+            Assets::Moved(asset_id, from, from_type, to, to_type, amount) => {
+                Some(IndexedEvent::AssetMoved {
+                    asset_id,
+                    from,
+                    from_type,
+                    to,
+                    to_type,
+                    amount: amount.into(),
+                })
+            }
+            Assets::Issued(asset_id, who, amount) => Some(IndexedEvent::AssetIssued {
+                asset_id,
+                who,
+                amount: amount.into(),
+            }),
+            Assets::Destroyed(asset_id, who, amount) => Some(IndexedEvent::AssetDestroyed {
+                asset_id,
+                who,
+                amount: amount.into(),
+            }),
+            Assets::BalanceSet(asset_id, who, asset_type, amount) => {
+                Some(IndexedEvent::AssetBalanceSet {
+                    asset_id,
+                    who,
+                    asset_type,
+                    amount: amount.into(),
+                })
+            }
+            Assets::VestingScheduleAdded(asset_id, from, to, locked) => {
+                Some(IndexedEvent::VestingScheduleAdded {
+                    asset_id,
+                    from,
+                    to,
+                    locked: locked.into(),
+                })
+            }
+            Assets::Vested(asset_id, who, still_locked) => Some(IndexedEvent::Vested {
+                asset_id,
+                who,
+                still_locked: still_locked.into(),
+            }),
+            _ => None,
+        };
+    }
+
+    if let Ok(event) = TryInto::<Records<R>>::try_into(event.clone()) {
+        let state_changed = |withdrawal_id, state| IndexedEvent::WithdrawalStateChanged {
+            withdrawal_id,
+            applicant: None,
+            asset_id: None,
+            state,
+        };
+        return match event {
+            Records::Deposited(who, asset_id, amount) => Some(IndexedEvent::Deposited {
+                who,
+                asset_id,
+                amount: amount.into(),
+            }),
+            Records::WithdrawalCreated(withdrawal_id, record) => {
+                Some(IndexedEvent::WithdrawalCreated {
+                    withdrawal_id,
+                    applicant: record.applicant().clone(),
+                    asset_id: record.asset_id(),
+                    amount: record.balance().into(),
+                    addr: record.addr().clone(),
+                })
+            }
+            Records::WithdrawalProcessed(id) => {
+                Some(state_changed(id, WithdrawalState::Processing))
+            }
+            Records::WithdrawalRecovered(id) => Some(state_changed(id, WithdrawalState::Applying)),
+            Records::WithdrawalCanceled(id, state) | Records::WithdrawalFinished(id, state) => {
+                Some(state_changed(id, state))
+            }
+            _ => None,
+        };
+    }
+
+    if let Ok(event) = TryInto::<Spot<R>>::try_into(event.clone()) {
+        return match event {
+            Spot::NewOrder(order) => Some(IndexedEvent::OrderCreated {
+                order_id: order.id(),
+                pair_id: order.props.pair_id,
+                submitter: order.submitter(),
+                side: order.props.side,
+                price: order.props.price.into(),
+                amount: order.props.amount.into(),
+            }),
+            Spot::CanceledOrderUpdated(order) => Some(IndexedEvent::OrderCanceled {
+                order_id: order.id(),
+                pair_id: order.props.pair_id,
+                submitter: order.submitter(),
+            }),
+            Spot::OrderExecuted(info) => {
+                let (maker, maker_order_id) = info.maker();
+                let (taker, taker_order_id) = info.taker();
+                Some(IndexedEvent::OrderExecuted {
+                    pair_id: info.pair_id(),
+                    maker,
+                    maker_order_id,
+                    taker,
+                    taker_order_id,
+                    price: info.price().into(),
+                    turnover: info.turnover().into(),
+                })
+            }
+            _ => None,
+        };
+    }
+
+    if let Ok(event) = TryInto::<Staking<R>>::try_into(event.clone()) {
+        return match event {
+            Staking::Bonded(nominator, validator, amount) => Some(IndexedEvent::Bonded {
+                nominator,
+                validator,
+                amount: amount.into(),
+            }),
+            Staking::Rebonded(nominator, from, to, amount) => Some(IndexedEvent::Rebonded {
+                nominator,
+                from,
+                to,
+                amount: amount.into(),
+            }),
+            Staking::Unbonded(nominator, validator, amount) => Some(IndexedEvent::Unbonded {
+                nominator,
+                validator,
+                amount: amount.into(),
+            }),
+            Staking::Claimed(nominator, validator, dividend) => {
+                Some(IndexedEvent::StakingClaimed {
+                    nominator,
+                    validator,
+                    dividend: dividend.into(),
+                })
+            }
+            Staking::Withdrawn(nominator, amount) => Some(IndexedEvent::Withdrawn {
+                nominator,
+                amount: amount.into(),
+            }),
+            Staking::NominatorSlashed(nominator, validator, amount) => {
+                Some(IndexedEvent::NominatorSlashed {
+                    nominator,
+                    validator,
+                    amount: amount.into(),
+                })
+            }
+            _ => None,
+        };
+    }
+
+    match TryInto::<MiningAsset<R>>::try_into(event) {
+        Ok(MiningAsset::Claimed(claimer, asset_id, amount)) => Some(IndexedEvent::MiningClaimed {
+            claimer,
+            asset_id,
+            amount: amount.into(),
+        }),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_event_json() {
+    let who = AccountId::new([1; 32]);
+    let event = IndexedEvent::Deposited {
+        who: who.clone(),
+        asset_id: 1,
+        amount: 100,
+    };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "event": "deposited",
+            "who": who.to_string(),
+            "assetId": 1,
+            "amount": "100",
+        })
+    );
+}
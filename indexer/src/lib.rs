@@ -0,0 +1,26 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Optional node-side indexer of ChainX.
+//!
+//! It records the events of the ChainX pallets by the involved accounts and assets, and the
+//! signed extrinsics by their signers, of every finalized block into a RocksDB database of
+//! its own, and serves them through the paginated `xindexer_*` RPC methods. Enabling it on a
+//! synced node backfills the blocks finalized before, which requires the state of them, i.e.
+//! an archive node.
+//!
+//! The events are only decoded for the blocks executed by the spec version of the native
+//! runtime, the other blocks are skipped and recorded, see `xindexer_skippedBlocks`.
+
+mod db;
+mod event;
+mod rpc;
+mod worker;
+
+pub use self::db::{Database, ExtrinsicEntry, SkippedBlock};
+pub use self::event::{
+    decode_events, BlockEvents, EventEntry, EventsDecoder, IndexedEvent, RuntimeDecoder,
+};
+pub use self::rpc::{Page, XIndexer, XIndexerApi};
+pub use self::worker::run;
+
+const LOG_TARGET: &str = "indexer";
@@ -0,0 +1,175 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! RPC interface of the indexer.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+
+use chainx_primitives::{AccountId, AssetId, BlockNumber};
+
+use crate::{
+    db::{Database, ExtrinsicEntry, SkippedBlock},
+    event::EventEntry,
+};
+
+/// The max size of a page.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// The base error code of the indexer.
+const INDEXER_ERROR: i64 = 1700;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub page_index: u32,
+    pub page_size: u32,
+    pub data: T,
+}
+
+/// XIndexer RPC methods.
+#[rpc]
+pub trait XIndexerApi {
+    /// Get the number of the last indexed block, the records are complete up to it except
+    /// for the skipped blocks.
+    #[rpc(name = "xindexer_indexedBlock")]
+    fn indexed_block(&self) -> Result<Option<BlockNumber>>;
+
+    /// Get the blocks whose events could not be decoded, oldest first.
+    #[rpc(name = "xindexer_skippedBlocks")]
+    fn skipped_blocks(&self, page_index: u32, page_size: u32) -> Result<Page<Vec<SkippedBlock>>>;
+
+    /// Get the ChainX pallet events involving the account, latest first.
+    #[rpc(name = "xindexer_getEventsByAccount")]
+    fn events(
+        &self,
+        who: AccountId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<EventEntry>>>;
+
+    /// Get the ChainX pallet events of the asset involving the account, latest first.
+    #[rpc(name = "xindexer_getAssetEventsByAccount")]
+    fn asset_events(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<EventEntry>>>;
+
+    /// Get the extrinsics signed by the account, latest first.
+    #[rpc(name = "xindexer_getExtrinsicsByAccount")]
+    fn extrinsics(
+        &self,
+        who: AccountId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<ExtrinsicEntry>>>;
+}
+
+/// Provides the indexer RPC methods.
+pub struct XIndexer {
+    db: Arc<Database>,
+}
+
+impl XIndexer {
+    /// Creates a new instance of the XIndexer RPC handler.
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+fn check_page_size(page_size: u32) -> Result<()> {
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(Error::invalid_params(format!(
+            "page size must be in 1..={}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    Ok(())
+}
+
+fn db_error_into_rpc_err(err: std::io::Error) -> Error {
+    Error {
+        code: ErrorCode::ServerError(INDEXER_ERROR),
+        message: "Indexer database error".into(),
+        data: Some(err.to_string().into()),
+    }
+}
+
+impl XIndexerApi for XIndexer {
+    fn indexed_block(&self) -> Result<Option<BlockNumber>> {
+        self.db.indexed_block().map_err(db_error_into_rpc_err)
+    }
+
+    fn skipped_blocks(&self, page_index: u32, page_size: u32) -> Result<Page<Vec<SkippedBlock>>> {
+        check_page_size(page_size)?;
+        let data = self
+            .db
+            .skipped_blocks(page_index, page_size)
+            .map_err(db_error_into_rpc_err)?;
+        Ok(Page {
+            page_index,
+            page_size,
+            data,
+        })
+    }
+
+    fn events(
+        &self,
+        who: AccountId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<EventEntry>>> {
+        check_page_size(page_size)?;
+        let data = self
+            .db
+            .events_of(&who, None, page_index, page_size)
+            .map_err(db_error_into_rpc_err)?;
+        Ok(Page {
+            page_index,
+            page_size,
+            data,
+        })
+    }
+
+    fn asset_events(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<EventEntry>>> {
+        check_page_size(page_size)?;
+        let data = self
+            .db
+            .events_of(&who, Some(asset_id), page_index, page_size)
+            .map_err(db_error_into_rpc_err)?;
+        Ok(Page {
+            page_index,
+            page_size,
+            data,
+        })
+    }
+
+    fn extrinsics(
+        &self,
+        who: AccountId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<Page<Vec<ExtrinsicEntry>>> {
+        check_page_size(page_size)?;
+        let data = self
+            .db
+            .extrinsics_of(&who, page_index, page_size)
+            .map_err(db_error_into_rpc_err)?;
+        Ok(Page {
+            page_index,
+            page_size,
+            data,
+        })
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Indexes the finalized blocks, starting with the backfill of the blocks finalized before
+//! the indexer was enabled.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{BlakeTwo256, Hash as HashT},
+    MultiAddress, OpaqueExtrinsic,
+};
+
+use chainx_primitives::{AccountId, AccountIndex, Block, BlockNumber, Hash};
+
+use crate::{db::Database, event::RuntimeDecoder, LOG_TARGET};
+
+/// The interval of the progress logs during the backfill.
+const BACKFILL_LOG_INTERVAL: BlockNumber = 10_000;
+
+/// Returns the signer of the extrinsic if it is signed by an account id.
+fn signer(extrinsic: &OpaqueExtrinsic) -> Option<AccountId> {
+    // The opaque extrinsic is encoded as a byte vector, see `UncheckedExtrinsic`.
+    let encoded = extrinsic.encode();
+    let payload = Vec::<u8>::decode(&mut &encoded[..]).ok()?;
+    let (version, mut rest) = payload.split_first()?;
+    if version & 0b1000_0000 == 0 {
+        return None;
+    }
+    match MultiAddress::<AccountId, AccountIndex>::decode(&mut rest).ok()? {
+        MultiAddress::Id(who) => Some(who),
+        _ => None,
+    }
+}
+
+/// Returns the spec version of the runtime which executed the block, i.e. the one recorded
+/// by the last runtime upgrade up to the block.
+fn spec_version<B, C>(client: &C, at: &BlockId<Block>) -> Result<Option<u32>, String>
+where
+    B: Backend<Block>,
+    C: StorageProvider<Block, B>,
+{
+    let key = [twox_128(b"System"), twox_128(b"LastRuntimeUpgrade")].concat();
+    client
+        .storage(at, &StorageKey(key))
+        .map_err(|e| e.to_string())?
+        .map(|upgrade| {
+            frame_system::LastRuntimeUpgradeInfo::decode(&mut &upgrade.0[..])
+                .map(|upgrade| upgrade.spec_version.0)
+                .map_err(|e| format!("failed to decode the last runtime upgrade: {}", e))
+        })
+        .transpose()
+}
+
+fn index_block<B, C>(
+    client: &C,
+    db: &Database,
+    decoder: RuntimeDecoder,
+    number: BlockNumber,
+) -> Result<(), String>
+where
+    B: Backend<Block>,
+    C: HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, B>,
+{
+    let hash = client
+        .hash(number)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("block #{} not found", number))?;
+    let at = BlockId::Hash(hash);
+
+    let events_key = [twox_128(b"System"), twox_128(b"Events")].concat();
+    let block_events = match client.storage(&at, &StorageKey(events_key)) {
+        Ok(Some(events)) => {
+            let spec_version = spec_version(client, &at)?;
+            let block_events = match spec_version {
+                Some(spec_version) if spec_version == decoder.spec_version => {
+                    (decoder.decode)(&events.0)
+                }
+                _ => None,
+            };
+            match block_events {
+                Some(block_events) => block_events,
+                None => {
+                    let reason = match spec_version {
+                        Some(spec_version) if spec_version != decoder.spec_version => format!(
+                            "executed by spec version {}, the native runtime is {}",
+                            spec_version, decoder.spec_version
+                        ),
+                        Some(_) => "failed to decode the events".into(),
+                        None => "unknown spec version".into(),
+                    };
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "Skipped block #{} ({:?}): {}",
+                        number,
+                        hash,
+                        reason
+                    );
+                    return db
+                        .skip_block(number, hash, reason)
+                        .map_err(|e| format!("failed to skip block #{}: {}", number, e));
+                }
+            }
+        }
+        Ok(None) => Default::default(),
+        Err(e) => {
+            return Err(format!(
+                "the state of block #{} is unavailable, the indexer needs an archive node \
+                 (--pruning archive) to backfill the pruned blocks: {}",
+                number, e
+            ))
+        }
+    };
+    let extrinsics = client
+        .block_body(&at)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("the body of block #{} not found", number))?
+        .iter()
+        .enumerate()
+        .filter_map(|(index, extrinsic)| {
+            signer(extrinsic).map(|who| {
+                let hash = BlakeTwo256::hash(&extrinsic.encode());
+                (index as u32, who, hash)
+            })
+        })
+        .collect();
+
+    db.write_block(number, hash, block_events, extrinsics)
+        .map_err(|e| format!("failed to write block #{}: {}", number, e))
+}
+
+fn index_finalized<B, C>(client: &C, db: &Database, decoder: RuntimeDecoder) -> Result<(), String>
+where
+    B: Backend<Block>,
+    C: HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, B>,
+{
+    let finalized = client.info().finalized_number;
+    let from = match db.indexed_block().map_err(|e| e.to_string())? {
+        Some(indexed) => indexed + 1,
+        None => 0,
+    };
+    if finalized > from {
+        log::info!(
+            target: LOG_TARGET,
+            "Indexing blocks #{}..#{}",
+            from,
+            finalized
+        );
+    }
+    for number in from..=finalized {
+        index_block(client, db, decoder, number)?;
+        if number % BACKFILL_LOG_INTERVAL == 0 && number != finalized {
+            log::info!(target: LOG_TARGET, "Indexed block #{}", number);
+        }
+    }
+    Ok(())
+}
+
+/// Indexes the finalized blocks not indexed yet, then every newly finalized block.
+///
+/// A failed indexing is retried on the next finalized block. The indexing is blocking, the
+/// task should be spawned as blocking.
+pub async fn run<B, C>(client: Arc<C>, db: Arc<Database>, decoder: RuntimeDecoder)
+where
+    B: Backend<Block>,
+    C: HeaderBackend<Block>
+        + BlockBackend<Block>
+        + StorageProvider<Block, B>
+        + BlockchainEvents<Block>,
+{
+    let mut finality_notifications = client.finality_notification_stream();
+    loop {
+        if let Err(e) = index_finalized(&*client, &db, decoder) {
+            log::error!(
+                target: LOG_TARGET,
+                "Indexing failed, retrying on the next finalized block: {}",
+                e
+            );
+        }
+        if finality_notifications.next().await.is_none() {
+            return;
+        }
+    }
+}
+
+#[test]
+fn test_signer() {
+    // A signed extrinsic: the compact length, the version, the `MultiAddress::Id` and the
+    // rest which is never decoded.
+    let who = AccountId::new([7; 32]);
+    let mut payload = vec![0b1000_0100, 0];
+    payload.extend_from_slice(&who.encode());
+    payload.extend_from_slice(&[0; 70]);
+    let extrinsic = OpaqueExtrinsic::from_bytes(&payload.encode()).unwrap();
+    assert_eq!(signer(&extrinsic), Some(who));
+
+    payload[0] = 0b0000_0100;
+    let extrinsic = OpaqueExtrinsic::from_bytes(&payload.encode()).unwrap();
+    assert_eq!(signer(&extrinsic), None);
+}
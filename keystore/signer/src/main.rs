@@ -0,0 +1,368 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Reference remote signer of the ChainX node.
+//!
+//! It serves the keys of a local keystore directory over HTTP or a Unix socket, the node
+//! uses it with `--keystore-uri http://127.0.0.1:9955` or `--keystore-uri unix://<path>`.
+//! It is meant for testing the remote keystore, a production signer should keep the keys
+//! in an HSM instead.
+//!
+//! The HTTP server only listens on the loopback interface and requires the token of
+//! `--token-file` as a bearer token, which the node reads from `CHAINX_SIGNER_TOKEN`.
+//! The Unix socket is only accessible by its owner.
+
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::Parser;
+use jsonrpc_core::{Error as RpcError, IoHandler, Result};
+use jsonrpc_http_server::{
+    hyper::{header, Body, Request, Response, StatusCode},
+    RequestMiddlewareAction, Server,
+};
+
+use sc_keystore::LocalKeystore;
+use sp_core::{
+    crypto::{CryptoTypePublicPair, KeyTypeId},
+    ecdsa, sr25519, Bytes, H256,
+};
+use sp_keystore::{
+    vrf::{VRFTranscriptData, VRFTranscriptValue},
+    SyncCryptoStore,
+};
+
+use chainx_keystore::protocol::{
+    id_to_string, string_to_key_type, RemoteSignerApi, RpcPublicKey, RpcTranscript,
+    RpcTranscriptValue, RpcVrfSignature,
+};
+
+const LOG_TARGET: &str = "signer";
+
+#[derive(Debug, clap::Parser)]
+#[clap(
+    name = "chainx-signer",
+    about = "Reference remote signer of the ChainX node."
+)]
+struct Cli {
+    /// The keystore directory.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    keystore_path: PathBuf,
+
+    /// The password of the keystore.
+    #[clap(long)]
+    password: Option<String>,
+
+    /// Listen on the given loopback HTTP address, e.g. `127.0.0.1:9955`.
+    #[clap(long, value_name = "ADDR", conflicts_with = "ipc")]
+    http: Option<SocketAddr>,
+
+    /// The file holding the token of the HTTP requests, required with `--http`.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    token_file: Option<PathBuf>,
+
+    /// Listen on the given Unix socket.
+    #[clap(long, value_name = "PATH", required_unless_present = "http")]
+    ipc: Option<String>,
+}
+
+/// The labels of the VRF transcripts signed by the node, i.e. the BABE slot claims.
+///
+/// The transcript labels have to be `'static`, so only the known ones are accepted.
+const TRANSCRIPT_LABELS: &[&str] = &["BABE", "slot number", "current epoch", "chain randomness"];
+
+struct Signer {
+    keystore: LocalKeystore,
+}
+
+fn transcript_label(label: &str) -> Result<&'static str> {
+    TRANSCRIPT_LABELS
+        .iter()
+        .find(|known| **known == label)
+        .copied()
+        .ok_or_else(|| RpcError::invalid_params(format!("unknown transcript label {}", label)))
+}
+
+fn key_type(s: &str) -> Result<KeyTypeId> {
+    string_to_key_type(s).ok_or_else(|| RpcError::invalid_params(format!("invalid key type {}", s)))
+}
+
+fn keystore_error(e: sp_keystore::Error) -> RpcError {
+    log::warn!(target: LOG_TARGET, "Keystore error: {}", e);
+    RpcError {
+        code: jsonrpc_core::ErrorCode::ServerError(1),
+        message: e.to_string(),
+        data: None,
+    }
+}
+
+impl RemoteSignerApi for Signer {
+    fn keys(&self, key_type_str: String) -> Result<Vec<RpcPublicKey>> {
+        let keys = SyncCryptoStore::keys(&self.keystore, key_type(&key_type_str)?)
+            .map_err(keystore_error)?;
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    fn generate_new(
+        &self,
+        key_type_str: String,
+        crypto_type: String,
+        seed: Option<String>,
+    ) -> Result<Bytes> {
+        let id = key_type(&key_type_str)?;
+        let seed = seed.as_deref();
+        let public = match crypto_type.as_bytes() {
+            b"sr25" => SyncCryptoStore::sr25519_generate_new(&self.keystore, id, seed)
+                .map(|public| public.0.to_vec()),
+            b"ed25" => SyncCryptoStore::ed25519_generate_new(&self.keystore, id, seed)
+                .map(|public| public.0.to_vec()),
+            b"ecds" => SyncCryptoStore::ecdsa_generate_new(&self.keystore, id, seed)
+                .map(|public| public.0.to_vec()),
+            _ => {
+                return Err(RpcError::invalid_params(format!(
+                    "invalid crypto type {}",
+                    crypto_type
+                )))
+            }
+        }
+        .map_err(keystore_error)?;
+        log::info!(
+            target: LOG_TARGET,
+            "Generated {} key 0x{} for {}",
+            crypto_type,
+            sp_core::hexdisplay::HexDisplay::from(&public),
+            key_type_str
+        );
+        Ok(public.into())
+    }
+
+    fn insert_unknown(&self, key_type_str: String, suri: String, public: Bytes) -> Result<()> {
+        SyncCryptoStore::insert_unknown(&self.keystore, key_type(&key_type_str)?, &suri, &public)
+            .map_err(|_| RpcError::invalid_params("failed to insert the key"))
+    }
+
+    fn has_keys(&self, keys: Vec<(Bytes, String)>) -> Result<bool> {
+        let keys = keys
+            .into_iter()
+            .map(|(public, id)| Ok((public.0, key_type(&id)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SyncCryptoStore::has_keys(&self.keystore, &keys))
+    }
+
+    fn sign_with(
+        &self,
+        key_type_str: String,
+        key: RpcPublicKey,
+        msg: Bytes,
+    ) -> Result<Option<Bytes>> {
+        let id = key_type(&key_type_str)?;
+        let key: CryptoTypePublicPair = key
+            .to_pair()
+            .ok_or_else(|| RpcError::invalid_params("invalid crypto type"))?;
+        log::debug!(
+            target: LOG_TARGET,
+            "Signing with {} key of {}",
+            id_to_string(&key.0 .0),
+            key_type_str
+        );
+        let signature =
+            SyncCryptoStore::sign_with(&self.keystore, id, &key, &msg).map_err(keystore_error)?;
+        Ok(signature.map(Into::into))
+    }
+
+    fn sr25519_vrf_sign(
+        &self,
+        key_type_str: String,
+        public: Bytes,
+        transcript: RpcTranscript,
+    ) -> Result<Option<RpcVrfSignature>> {
+        let id = key_type(&key_type_str)?;
+        let public = sr25519::Public::try_from(&public[..])
+            .map_err(|_| RpcError::invalid_params("invalid sr25519 public key"))?;
+        let transcript_data = VRFTranscriptData {
+            label: transcript_label(&transcript.label)?.as_bytes(),
+            items: transcript
+                .items
+                .into_iter()
+                .map(|(label, value)| {
+                    let value = match value {
+                        RpcTranscriptValue::Bytes(bytes) => VRFTranscriptValue::Bytes(bytes.0),
+                        RpcTranscriptValue::U64(v) => VRFTranscriptValue::U64(v),
+                    };
+                    Ok((transcript_label(&label)?, value))
+                })
+                .collect::<Result<_>>()?,
+        };
+        let signature =
+            SyncCryptoStore::sr25519_vrf_sign(&self.keystore, id, &public, transcript_data)
+                .map_err(keystore_error)?;
+        Ok(signature.map(|signature| RpcVrfSignature {
+            output: signature.output.to_bytes().to_vec().into(),
+            proof: signature.proof.to_bytes().to_vec().into(),
+        }))
+    }
+
+    fn ecdsa_sign_prehashed(
+        &self,
+        key_type_str: String,
+        public: Bytes,
+        msg: H256,
+    ) -> Result<Option<Bytes>> {
+        let id = key_type(&key_type_str)?;
+        let public = ecdsa::Public::try_from(&public[..])
+            .map_err(|_| RpcError::invalid_params("invalid ecdsa public key"))?;
+        let signature = SyncCryptoStore::ecdsa_sign_prehashed(&self.keystore, id, &public, &msg.0)
+            .map_err(keystore_error)?;
+        Ok(signature.map(|signature| signature.0.to_vec().into()))
+    }
+}
+
+/// Compares the tokens in constant time.
+fn token_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn is_authorized(request: &Request<Body>, token: &str) -> bool {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map_or(false, |given| token_eq(given.as_bytes(), token.as_bytes()))
+}
+
+fn start_http(signer: Signer, addr: &SocketAddr, token: String) -> std::io::Result<Server> {
+    let mut io = IoHandler::default();
+    io.extend_with(signer.to_delegate());
+    jsonrpc_http_server::ServerBuilder::new(io)
+        .request_middleware(move |request: Request<Body>| -> RequestMiddlewareAction {
+            if is_authorized(&request, &token) {
+                request.into()
+            } else {
+                log::warn!(target: LOG_TARGET, "Rejected an unauthorized request");
+                Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .body(Body::empty())
+                    .expect("the response is well-formed; qed")
+                    .into()
+            }
+        })
+        .start_http(addr)
+}
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let cli = Cli::parse();
+    let keystore = LocalKeystore::open(
+        &cli.keystore_path,
+        cli.password.map(sp_core::crypto::SecretString::new),
+    )?;
+    log::info!(
+        target: LOG_TARGET,
+        "Opened the keystore at {}",
+        cli.keystore_path.display()
+    );
+    let signer = Signer { keystore };
+
+    if let Some(addr) = cli.http {
+        if !addr.ip().is_loopback() {
+            return Err(format!("refusing to listen on the non-loopback address {}", addr).into());
+        }
+        let token_file = cli
+            .token_file
+            .ok_or("--token-file is required with --http")?;
+        let token = std::fs::read_to_string(&token_file)?.trim().to_string();
+        if token.is_empty() {
+            return Err(format!("the token file {} is empty", token_file.display()).into());
+        }
+        let server = start_http(signer, &addr, token)
+            .map_err(|e| format!("failed to listen on {}: {}", addr, e))?;
+        log::info!(target: LOG_TARGET, "Signer listening on http://{}", addr);
+        server.wait();
+    } else if let Some(path) = cli.ipc {
+        let mut io = IoHandler::default();
+        io.extend_with(signer.to_delegate());
+        let server = jsonrpc_ipc_server::ServerBuilder::new(io)
+            .start(&path)
+            .map_err(|e| format!("failed to listen on {}: {}", path, e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        log::info!(target: LOG_TARGET, "Signer listening on unix://{}", path);
+        server.wait();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chainx_keystore::RemoteKeystore;
+    use sp_core::Pair;
+    use sp_keystore::CryptoStore;
+
+    const TOKEN: &str = "signer-test-token";
+    const BABE: KeyTypeId = KeyTypeId(*b"babe");
+
+    fn start_signer() -> (Server, String) {
+        let signer = Signer {
+            keystore: LocalKeystore::in_memory(),
+        };
+        let server = start_http(signer, &"127.0.0.1:0".parse().unwrap(), TOKEN.into()).unwrap();
+        let uri = format!("http://{}", server.address());
+        (server, uri)
+    }
+
+    #[test]
+    fn sign_through_remote_keystore() {
+        let (_server, uri) = start_signer();
+        let keystore = RemoteKeystore::open(&uri, Some(TOKEN.into())).unwrap();
+
+        let public = SyncCryptoStore::sr25519_generate_new(&keystore, BABE, None).unwrap();
+        assert_eq!(
+            SyncCryptoStore::sr25519_public_keys(&keystore, BABE),
+            vec![public]
+        );
+
+        let msg = b"chainx";
+        let signature = SyncCryptoStore::sign_with(&keystore, BABE, &public.into(), msg)
+            .unwrap()
+            .unwrap();
+        let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+        assert!(sr25519::Pair::verify(&signature, msg, &public));
+
+        // The async methods go through the blocking thread pool.
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let signature = runtime
+            .block_on(CryptoStore::sign_with(&keystore, BABE, &public.into(), msg))
+            .unwrap()
+            .unwrap();
+        let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+        assert!(sr25519::Pair::verify(&signature, msg, &public));
+
+        let transcript = || VRFTranscriptData {
+            label: b"BABE",
+            items: vec![("slot number", VRFTranscriptValue::U64(1))],
+        };
+        assert!(
+            SyncCryptoStore::sr25519_vrf_sign(&keystore, BABE, &public, transcript())
+                .unwrap()
+                .is_some()
+        );
+        let unknown = VRFTranscriptData {
+            label: b"UNKNOWN",
+            ..transcript()
+        };
+        assert!(SyncCryptoStore::sr25519_vrf_sign(&keystore, BABE, &public, unknown).is_err());
+    }
+
+    #[test]
+    fn reject_unauthorized_requests() {
+        let (_server, uri) = start_signer();
+        assert!(RemoteKeystore::open(&uri, None).is_err());
+        assert!(RemoteKeystore::open(&uri, Some("wrong-token".into())).is_err());
+        assert!(RemoteKeystore::open(&uri, Some(TOKEN.into())).is_ok());
+    }
+}
@@ -0,0 +1,548 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Keystore which keeps the private keys in a remote signer, used by `--keystore-uri`.
+//!
+//! The signer is reached over a local connection, either `http://<host>:<port>` or
+//! `unix://<path of the socket>`, and speaks the JSON-RPC protocol defined in [`protocol`].
+//! The reference signer lives in `keystore/signer`.
+//!
+//! The HTTP requests carry the token of [`TOKEN_ENV`] as a bearer token, the Unix socket
+//! is guarded by its file permissions instead.
+//!
+//! NOTE: the requests are blocking, the async `CryptoStore` methods run them on the
+//! blocking thread pool of tokio.
+
+pub mod protocol;
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use sp_core::{
+    crypto::{CryptoTypePublicPair, KeyTypeId},
+    ecdsa, ed25519, sr25519, H256,
+};
+use sp_keystore::{
+    vrf::{VRFSignature, VRFTranscriptData, VRFTranscriptValue},
+    CryptoStore, Error, SyncCryptoStore,
+};
+
+use self::protocol::{
+    key_type_to_string, RpcPublicKey, RpcTranscript, RpcTranscriptValue, RpcVrfSignature,
+};
+
+const LOG_TARGET: &str = "keystore";
+
+/// The environment variable holding the token of the HTTP signer.
+pub const TOKEN_ENV: &str = "CHAINX_SIGNER_TOKEN";
+
+/// The timeout of a single request to the signer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The connection to the remote signer.
+#[derive(Debug, Clone)]
+enum Transport {
+    Http {
+        authority: String,
+        path: String,
+    },
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+impl Transport {
+    fn parse(uri: &str) -> Result<Self, String> {
+        if let Some(rest) = uri.strip_prefix("http://") {
+            let (authority, path) = match rest.find('/') {
+                Some(pos) => (&rest[..pos], &rest[pos..]),
+                None => (rest, "/"),
+            };
+            if authority.is_empty() {
+                return Err(format!("missing the signer address in `{}`", uri));
+            }
+            return Ok(Self::Http {
+                authority: authority.into(),
+                path: path.into(),
+            });
+        }
+        #[cfg(unix)]
+        if let Some(path) = uri.strip_prefix("unix://") {
+            return Ok(Self::Unix(path.into()));
+        }
+        Err(format!(
+            "unsupported keystore uri `{}`, expected `http://<host>:<port>` or `unix://<path>`",
+            uri
+        ))
+    }
+
+    fn request(&self, body: &[u8], token: Option<&str>) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Http { authority, path } => {
+                let mut stream = TcpStream::connect(authority)?;
+                stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+                stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+                let authorization = token
+                    .map(|token| format!("Authorization: Bearer {}\r\n", token))
+                    .unwrap_or_default();
+                write!(
+                    stream,
+                    "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+                     {}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    path,
+                    authority,
+                    authorization,
+                    body.len()
+                )?;
+                stream.write_all(body)?;
+
+                let mut response = Vec::new();
+                stream.read_to_end(&mut response)?;
+                let header_end = response
+                    .windows(4)
+                    .position(|w| w == b"\r\n\r\n")
+                    .ok_or_else(|| invalid_data("incomplete http response"))?;
+                let status_line = response
+                    .split(|b| *b == b'\n')
+                    .next()
+                    .map(|line| String::from_utf8_lossy(line).trim().to_string())
+                    .unwrap_or_default();
+                if status_line.split_whitespace().nth(1) != Some("200") {
+                    return Err(invalid_data(&status_line));
+                }
+                Ok(response.split_off(header_end + 4))
+            }
+            #[cfg(unix)]
+            Self::Unix(path) => {
+                let mut stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+                stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+                stream.write_all(body)?;
+                stream.write_all(b"\n")?;
+
+                let mut response = Vec::new();
+                BufReader::new(stream).read_until(b'\n', &mut response)?;
+                Ok(response)
+            }
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// A keystore which forwards every operation to the remote signer.
+///
+/// It is cheap to clone, the clones share the same connection settings.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+    transport: Transport,
+    token: Option<Arc<str>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl RemoteKeystore {
+    /// Connects to the signer at the given uri and checks that it is reachable.
+    ///
+    /// The `token` authenticates the requests to an HTTP signer.
+    pub fn open(uri: &str, token: Option<String>) -> Result<Self, String> {
+        let keystore = Self {
+            transport: Transport::parse(uri)?,
+            token: token.map(Into::into),
+            next_id: Arc::new(AtomicU64::new(0)),
+        };
+        keystore
+            .call::<Vec<RpcPublicKey>>("signer_keys", json!(["babe"]))
+            .map_err(|e| format!("remote signer at {} is unavailable: {}", uri, e))?;
+        Ok(keystore)
+    }
+
+    fn call<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R, Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        let body = serde_json::to_vec(&request).expect("JSON serialization never fails; qed");
+
+        let response = self
+            .transport
+            .request(&body, self.token.as_deref())
+            .map_err(|e| {
+                log::error!(
+                    target: LOG_TARGET,
+                    "[{}] Failed to reach the signer: {}",
+                    method,
+                    e
+                );
+                Error::Unavailable
+            })?;
+        let mut response: Value = serde_json::from_slice(&response)
+            .map_err(|e| Error::Other(format!("invalid response of {}: {}", method, e)))?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Other(format!("{} failed: {}", method, error)));
+        }
+        serde_json::from_value(response["result"].take())
+            .map_err(|e| Error::Other(format!("invalid result of {}: {}", method, e)))
+    }
+
+    /// Runs the blocking requests of `f` on the blocking thread pool.
+    async fn spawn_blocking<R, F>(&self, f: F) -> Result<R, Error>
+    where
+        R: Send + 'static,
+        F: FnOnce(&Self) -> R + Send + 'static,
+    {
+        let keystore = self.clone();
+        tokio::task::spawn_blocking(move || f(&keystore))
+            .await
+            .map_err(|e| Error::Other(format!("the signer request was aborted: {}", e)))
+    }
+
+    fn public_keys<Public>(
+        &self,
+        id: KeyTypeId,
+        crypto_id: sp_core::crypto::CryptoTypeId,
+    ) -> Vec<Public>
+    where
+        Public: for<'a> TryFrom<&'a [u8]>,
+    {
+        SyncCryptoStore::keys(self, id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pair| pair.0 == crypto_id)
+            .filter_map(|pair| Public::try_from(pair.1.as_slice()).ok())
+            .collect()
+    }
+
+    fn generate_new<Public>(
+        &self,
+        id: KeyTypeId,
+        crypto_id: sp_core::crypto::CryptoTypeId,
+        seed: Option<&str>,
+    ) -> Result<Public, Error>
+    where
+        Public: for<'a> TryFrom<&'a [u8]>,
+    {
+        let public: sp_core::Bytes = self.call(
+            "signer_generateNew",
+            json!([
+                key_type_to_string(id),
+                protocol::id_to_string(&crypto_id.0),
+                seed
+            ]),
+        )?;
+        Public::try_from(&public[..])
+            .map_err(|_| Error::ValidationError("invalid public key from the signer".into()))
+    }
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+    fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+        self.public_keys(id, sr25519::CRYPTO_ID)
+    }
+
+    fn sr25519_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<sr25519::Public, Error> {
+        self.generate_new(id, sr25519::CRYPTO_ID, seed)
+    }
+
+    fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+        self.public_keys(id, ed25519::CRYPTO_ID)
+    }
+
+    fn ed25519_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<ed25519::Public, Error> {
+        self.generate_new(id, ed25519::CRYPTO_ID, seed)
+    }
+
+    fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+        self.public_keys(id, ecdsa::CRYPTO_ID)
+    }
+
+    fn ecdsa_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<ecdsa::Public, Error> {
+        self.generate_new(id, ecdsa::CRYPTO_ID, seed)
+    }
+
+    fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+        self.call(
+            "signer_insertUnknown",
+            json!([
+                key_type_to_string(key_type),
+                suri,
+                sp_core::Bytes(public.to_vec())
+            ]),
+        )
+        .map_err(|e| {
+            log::error!(target: LOG_TARGET, "Failed to insert the key: {}", e);
+        })
+    }
+
+    fn supported_keys(
+        &self,
+        id: KeyTypeId,
+        keys: Vec<CryptoTypePublicPair>,
+    ) -> Result<Vec<CryptoTypePublicPair>, Error> {
+        let all_keys = SyncCryptoStore::keys(self, id)?;
+        Ok(keys
+            .into_iter()
+            .filter(|key| all_keys.contains(key))
+            .collect())
+    }
+
+    fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+        let keys: Vec<RpcPublicKey> = self.call("signer_keys", json!([key_type_to_string(id)]))?;
+        Ok(keys.iter().filter_map(RpcPublicKey::to_pair).collect())
+    }
+
+    fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+        let keys = public_keys
+            .iter()
+            .map(|(public, id)| (sp_core::Bytes(public.clone()), key_type_to_string(*id)))
+            .collect::<Vec<_>>();
+        self.call("signer_hasKeys", json!([keys]))
+            .unwrap_or_else(|e| {
+                log::error!(target: LOG_TARGET, "Failed to check the keys: {}", e);
+                false
+            })
+    }
+
+    fn sign_with(
+        &self,
+        id: KeyTypeId,
+        key: &CryptoTypePublicPair,
+        msg: &[u8],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let signature: Option<sp_core::Bytes> = self.call(
+            "signer_signWith",
+            json!([
+                key_type_to_string(id),
+                RpcPublicKey::from(key.clone()),
+                sp_core::Bytes(msg.to_vec())
+            ]),
+        )?;
+        Ok(signature.map(|signature| signature.0))
+    }
+
+    fn sr25519_vrf_sign(
+        &self,
+        key_type: KeyTypeId,
+        public: &sr25519::Public,
+        transcript_data: VRFTranscriptData,
+    ) -> Result<Option<VRFSignature>, Error> {
+        let transcript = RpcTranscript {
+            label: String::from_utf8_lossy(transcript_data.label).into_owned(),
+            items: transcript_data
+                .items
+                .into_iter()
+                .map(|(label, value)| {
+                    let value = match value {
+                        VRFTranscriptValue::Bytes(bytes) => RpcTranscriptValue::Bytes(bytes.into()),
+                        VRFTranscriptValue::U64(v) => RpcTranscriptValue::U64(v),
+                    };
+                    (label.to_string(), value)
+                })
+                .collect(),
+        };
+        let signature: Option<RpcVrfSignature> = self.call(
+            "signer_sr25519VrfSign",
+            json!([
+                key_type_to_string(key_type),
+                sp_core::Bytes(public.0.to_vec()),
+                transcript
+            ]),
+        )?;
+        signature
+            .map(|signature| {
+                let invalid = |_| Error::ValidationError("invalid VRF signature".into());
+                Ok(VRFSignature {
+                    output: schnorrkel::vrf::VRFOutput::from_bytes(&signature.output)
+                        .map_err(invalid)?,
+                    proof: schnorrkel::vrf::VRFProof::from_bytes(&signature.proof)
+                        .map_err(invalid)?,
+                })
+            })
+            .transpose()
+    }
+
+    fn ecdsa_sign_prehashed(
+        &self,
+        id: KeyTypeId,
+        public: &ecdsa::Public,
+        msg: &[u8; 32],
+    ) -> Result<Option<ecdsa::Signature>, Error> {
+        let signature: Option<sp_core::Bytes> = self.call(
+            "signer_ecdsaSignPrehashed",
+            json!([
+                key_type_to_string(id),
+                sp_core::Bytes(public.0.to_vec()),
+                H256(*msg)
+            ]),
+        )?;
+        signature
+            .map(|signature| {
+                ecdsa::Signature::try_from(&signature[..])
+                    .map_err(|_| Error::ValidationError("invalid ecdsa signature".into()))
+            })
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+    async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+        self.spawn_blocking(move |k| SyncCryptoStore::sr25519_public_keys(k, id))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn sr25519_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<sr25519::Public, Error> {
+        let seed = seed.map(ToOwned::to_owned);
+        self.spawn_blocking(move |k| SyncCryptoStore::sr25519_generate_new(k, id, seed.as_deref()))
+            .await?
+    }
+
+    async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+        self.spawn_blocking(move |k| SyncCryptoStore::ed25519_public_keys(k, id))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn ed25519_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<ed25519::Public, Error> {
+        let seed = seed.map(ToOwned::to_owned);
+        self.spawn_blocking(move |k| SyncCryptoStore::ed25519_generate_new(k, id, seed.as_deref()))
+            .await?
+    }
+
+    async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+        self.spawn_blocking(move |k| SyncCryptoStore::ecdsa_public_keys(k, id))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn ecdsa_generate_new(
+        &self,
+        id: KeyTypeId,
+        seed: Option<&str>,
+    ) -> Result<ecdsa::Public, Error> {
+        let seed = seed.map(ToOwned::to_owned);
+        self.spawn_blocking(move |k| SyncCryptoStore::ecdsa_generate_new(k, id, seed.as_deref()))
+            .await?
+    }
+
+    async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+        let (suri, public) = (suri.to_owned(), public.to_vec());
+        self.spawn_blocking(move |k| SyncCryptoStore::insert_unknown(k, id, &suri, &public))
+            .await
+            .map_err(|_| ())?
+    }
+
+    async fn supported_keys(
+        &self,
+        id: KeyTypeId,
+        keys: Vec<CryptoTypePublicPair>,
+    ) -> Result<Vec<CryptoTypePublicPair>, Error> {
+        self.spawn_blocking(move |k| SyncCryptoStore::supported_keys(k, id, keys))
+            .await?
+    }
+
+    async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+        self.spawn_blocking(move |k| SyncCryptoStore::keys(k, id))
+            .await?
+    }
+
+    async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+        let public_keys = public_keys.to_vec();
+        self.spawn_blocking(move |k| SyncCryptoStore::has_keys(k, &public_keys))
+            .await
+            .unwrap_or(false)
+    }
+
+    async fn sign_with(
+        &self,
+        id: KeyTypeId,
+        key: &CryptoTypePublicPair,
+        msg: &[u8],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let (key, msg) = (key.clone(), msg.to_vec());
+        self.spawn_blocking(move |k| SyncCryptoStore::sign_with(k, id, &key, &msg))
+            .await?
+    }
+
+    async fn sr25519_vrf_sign(
+        &self,
+        key_type: KeyTypeId,
+        public: &sr25519::Public,
+        transcript_data: VRFTranscriptData,
+    ) -> Result<Option<VRFSignature>, Error> {
+        let public = *public;
+        self.spawn_blocking(move |k| {
+            SyncCryptoStore::sr25519_vrf_sign(k, key_type, &public, transcript_data)
+        })
+        .await?
+    }
+
+    async fn ecdsa_sign_prehashed(
+        &self,
+        id: KeyTypeId,
+        public: &ecdsa::Public,
+        msg: &[u8; 32],
+    ) -> Result<Option<ecdsa::Signature>, Error> {
+        let (public, msg) = (*public, *msg);
+        self.spawn_blocking(move |k| SyncCryptoStore::ecdsa_sign_prehashed(k, id, &public, &msg))
+            .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keystore_uri() {
+        assert!(matches!(
+            Transport::parse("http://127.0.0.1:9955"),
+            Ok(Transport::Http { authority, path }) if authority == "127.0.0.1:9955" && path == "/"
+        ));
+        assert!(matches!(
+            Transport::parse("http://localhost:9955/signer"),
+            Ok(Transport::Http { authority, path }) if authority == "localhost:9955" && path == "/signer"
+        ));
+        #[cfg(unix)]
+        assert!(matches!(
+            Transport::parse("unix:///tmp/signer.sock"),
+            Ok(Transport::Unix(path)) if path == std::path::Path::new("/tmp/signer.sock")
+        ));
+        assert!(Transport::parse("http://").is_err());
+        assert!(Transport::parse("ws://127.0.0.1:9955").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The JSON-RPC protocol spoken between the node and the remote signer.
+//!
+//! The key types (e.g. `babe`) and the crypto types (e.g. `sr25`) are passed as their
+//! 4 bytes ASCII representation, the keys, messages and signatures are hex encoded.
+
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+
+use sp_core::{
+    crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+    Bytes, H256,
+};
+
+/// A public key together with its crypto type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPublicKey {
+    pub crypto_type: String,
+    pub public: Bytes,
+}
+
+impl From<CryptoTypePublicPair> for RpcPublicKey {
+    fn from(pair: CryptoTypePublicPair) -> Self {
+        Self {
+            crypto_type: id_to_string(&pair.0 .0),
+            public: pair.1.into(),
+        }
+    }
+}
+
+impl RpcPublicKey {
+    pub fn to_pair(&self) -> Option<CryptoTypePublicPair> {
+        let id = string_to_id(&self.crypto_type)?;
+        Some(CryptoTypePublicPair(CryptoTypeId(id), self.public.to_vec()))
+    }
+}
+
+/// A value of the VRF transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTranscriptValue {
+    Bytes(Bytes),
+    U64(u64),
+}
+
+/// The VRF transcript to sign.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTranscript {
+    pub label: String,
+    pub items: Vec<(String, RpcTranscriptValue)>,
+}
+
+/// The VRF output and proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVrfSignature {
+    pub output: Bytes,
+    pub proof: Bytes,
+}
+
+/// The API of the remote signer.
+#[rpc(server)]
+pub trait RemoteSignerApi {
+    /// Return the public keys of the given key type.
+    #[rpc(name = "signer_keys")]
+    fn keys(&self, key_type: String) -> jsonrpc_core::Result<Vec<RpcPublicKey>>;
+
+    /// Generate a new key of the given key type and crypto type, return its public key.
+    #[rpc(name = "signer_generateNew")]
+    fn generate_new(
+        &self,
+        key_type: String,
+        crypto_type: String,
+        seed: Option<String>,
+    ) -> jsonrpc_core::Result<Bytes>;
+
+    /// Insert a key of unknown crypto type with its secret uri.
+    #[rpc(name = "signer_insertUnknown")]
+    fn insert_unknown(
+        &self,
+        key_type: String,
+        suri: String,
+        public: Bytes,
+    ) -> jsonrpc_core::Result<()>;
+
+    /// Return whether the signer has the private keys of all the given public keys.
+    #[rpc(name = "signer_hasKeys")]
+    fn has_keys(&self, keys: Vec<(Bytes, String)>) -> jsonrpc_core::Result<bool>;
+
+    /// Sign the message with the given key, return `None` if the key is unknown.
+    #[rpc(name = "signer_signWith")]
+    fn sign_with(
+        &self,
+        key_type: String,
+        key: RpcPublicKey,
+        msg: Bytes,
+    ) -> jsonrpc_core::Result<Option<Bytes>>;
+
+    /// Sign the VRF transcript with the given sr25519 key, return `None` if the key is unknown.
+    #[rpc(name = "signer_sr25519VrfSign")]
+    fn sr25519_vrf_sign(
+        &self,
+        key_type: String,
+        public: Bytes,
+        transcript: RpcTranscript,
+    ) -> jsonrpc_core::Result<Option<RpcVrfSignature>>;
+
+    /// Sign the prehashed message with the given ecdsa key, return `None` if the key is unknown.
+    #[rpc(name = "signer_ecdsaSignPrehashed")]
+    fn ecdsa_sign_prehashed(
+        &self,
+        key_type: String,
+        public: Bytes,
+        msg: H256,
+    ) -> jsonrpc_core::Result<Option<Bytes>>;
+}
+
+/// Returns the ASCII representation of a key type or crypto type id.
+pub fn id_to_string(id: &[u8; 4]) -> String {
+    String::from_utf8_lossy(id).into_owned()
+}
+
+/// Parses a key type or crypto type id from its ASCII representation.
+pub fn string_to_id(s: &str) -> Option<[u8; 4]> {
+    s.as_bytes().try_into().ok()
+}
+
+pub fn key_type_to_string(key_type: KeyTypeId) -> String {
+    id_to_string(&key_type.0)
+}
+
+pub fn string_to_key_type(s: &str) -> Option<KeyTypeId> {
+    string_to_id(s).map(KeyTypeId)
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use chainx_primitives::{AssetId, Hash};
+use xp_assets_registrar::Chain;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FreeBalanceInfo<AccountId, Balance> {
     pub free: Balance,
@@ -32,11 +35,119 @@ pub struct XStakingParams<AccountId, Balance> {
     pub nominators: Vec<NominatorInfo<AccountId, Balance>>,
 }
 
+/// A pending withdrawal, the balance is locked again on the regenesis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalInfo<AccountId, Balance> {
+    pub id: u32,
+    pub asset_id: AssetId,
+    pub applicant: AccountId,
+    pub balance: Balance,
+    #[serde(with = "xp_rpc::serde_text")]
+    pub addr: Vec<u8>,
+    #[serde(with = "xp_rpc::serde_hex")]
+    pub ext: Vec<u8>,
+    /// The withdrawal has been taken by the trustees, otherwise it's still applying.
+    pub processing: bool,
+}
+
+/// A trustee session, the heights of the previous chain are not carried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrusteeSessionParams<AccountId> {
+    pub chain: Chain,
+    pub session_number: u32,
+    pub trustee_list: Vec<(AccountId, u64)>,
+    pub threshold: u16,
+    #[serde(with = "xp_rpc::serde_hex")]
+    pub hot_address: Vec<u8>,
+    #[serde(with = "xp_rpc::serde_hex")]
+    pub cold_address: Vec<u8>,
+    pub multi_account: Option<AccountId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBindingInfo<AccountId> {
+    pub chain: Chain,
+    #[serde(with = "xp_rpc::serde_text")]
+    pub address: Vec<u8>,
+    pub who: AccountId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BtcDepositInfo {
+    pub txid: Hash,
+    pub balance: u64,
+}
+
+/// The unclaimed deposits of a Bitcoin address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BtcPendingDepositInfo {
+    #[serde(with = "xp_rpc::serde_text")]
+    pub address: Vec<u8>,
+    pub deposits: Vec<BtcDepositInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XGatewayParams<AccountId, Balance> {
+    pub pending_withdrawals: Vec<WithdrawalInfo<AccountId, Balance>>,
+    pub trustee_sessions: Vec<TrusteeSessionParams<AccountId>>,
+    pub address_bindings: Vec<AddressBindingInfo<AccountId>>,
+    pub btc_pending_deposits: Vec<BtcPendingDepositInfo>,
+}
+
+impl<AccountId, Balance> Default for XGatewayParams<AccountId, Balance> {
+    fn default() -> Self {
+        XGatewayParams {
+            pending_withdrawals: vec![],
+            trustee_sessions: vec![],
+            address_bindings: vec![],
+            btc_pending_deposits: vec![],
+        }
+    }
+}
+
+/// The mining weight of a miner, settled at the exported block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinerLedgerInfo<AccountId> {
+    pub miner: AccountId,
+    pub asset_id: AssetId,
+    pub mining_weight: u128,
+}
+
+/// The total mining weight of an asset, settled at the exported block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetLedgerInfo {
+    pub asset_id: AssetId,
+    pub total_mining_weight: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XMiningAssetParams<AccountId> {
+    pub miner_ledgers: Vec<MinerLedgerInfo<AccountId>>,
+    pub asset_ledgers: Vec<AssetLedgerInfo>,
+}
+
+impl<AccountId> Default for XMiningAssetParams<AccountId> {
+    fn default() -> Self {
+        XMiningAssetParams {
+            miner_ledgers: vec![],
+            asset_ledgers: vec![],
+        }
+    }
+}
+
+/// The params of the regenesis.
+///
+/// The open orders of the spot DEX are not carried, their reserved balances are
+/// released into the free balances of `xassets`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllParams<AccountId, Balance, AssetBalanceOf, StakingBalanceOf> {
     pub balances: Vec<FreeBalanceInfo<AccountId, Balance>>,
     pub xassets: Vec<FreeBalanceInfo<AccountId, AssetBalanceOf>>,
     pub xstaking: XStakingParams<AccountId, StakingBalanceOf>,
+    #[serde(default)]
+    pub xgateway: XGatewayParams<AccountId, AssetBalanceOf>,
+    #[serde(default)]
+    pub xmining_asset: XMiningAssetParams<AccountId>,
 }
 
 impl<AccountId, Balance, AssetBalanceOf, StakingBalanceOf> Default
@@ -50,6 +161,112 @@ impl<AccountId, Balance, AssetBalanceOf, StakingBalanceOf> Default
                 validators: vec![],
                 nominators: vec![],
             },
+            xgateway: Default::default(),
+            xmining_asset: Default::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Params = AllParams<u64, u128, u128, u128>;
+
+    fn params() -> Params {
+        AllParams {
+            balances: vec![FreeBalanceInfo { free: 100, who: 1 }],
+            xassets: vec![FreeBalanceInfo { free: 50, who: 2 }],
+            xstaking: XStakingParams {
+                validators: vec![ValidatorInfo {
+                    who: 3,
+                    referral_id: b"Alice".to_vec(),
+                    total_nomination: 10,
+                }],
+                nominators: vec![NominatorInfo {
+                    nominator: 1,
+                    nominations: vec![Nomination {
+                        nominee: 3,
+                        nomination: 10,
+                    }],
+                }],
+            },
+            xgateway: XGatewayParams {
+                pending_withdrawals: vec![WithdrawalInfo {
+                    id: 7,
+                    asset_id: 1,
+                    applicant: 2,
+                    balance: 20,
+                    addr: b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec(),
+                    ext: vec![0xff, 0x00],
+                    processing: true,
+                }],
+                trustee_sessions: vec![TrusteeSessionParams {
+                    chain: Chain::Bitcoin,
+                    session_number: 2,
+                    trustee_list: vec![(1, 5), (2, 3)],
+                    threshold: 2,
+                    hot_address: vec![1, 2, 3],
+                    cold_address: vec![4, 5, 6],
+                    multi_account: Some(9),
+                }],
+                address_bindings: vec![AddressBindingInfo {
+                    chain: Chain::Bitcoin,
+                    address: b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec(),
+                    who: 2,
+                }],
+                btc_pending_deposits: vec![BtcPendingDepositInfo {
+                    address: b"1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_vec(),
+                    deposits: vec![BtcDepositInfo {
+                        txid: Hash::repeat_byte(1),
+                        balance: 30,
+                    }],
+                }],
+            },
+            xmining_asset: XMiningAssetParams {
+                miner_ledgers: vec![MinerLedgerInfo {
+                    miner: 2,
+                    asset_id: 1,
+                    mining_weight: u128::max_value(),
+                }],
+                asset_ledgers: vec![AssetLedgerInfo {
+                    asset_id: 1,
+                    total_mining_weight: u128::max_value(),
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_regenesis_params_round_trip() {
+        let ser = serde_json::to_string(&params()).unwrap();
+        let de = serde_json::from_str::<Params>(&ser).unwrap();
+        assert_eq!(serde_json::to_string(&de).unwrap(), ser);
+
+        let withdrawal = &de.xgateway.pending_withdrawals[0];
+        assert_eq!(withdrawal.ext, vec![0xff, 0x00]);
+        assert!(withdrawal.processing);
+        assert_eq!(
+            de.xgateway.btc_pending_deposits[0].deposits[0].txid,
+            Hash::repeat_byte(1)
+        );
+        assert_eq!(
+            de.xmining_asset.miner_ledgers[0].mining_weight,
+            u128::max_value()
+        );
+    }
+
+    #[test]
+    fn test_regenesis_params_without_gateway_and_mining_asset() {
+        let json = r#"{
+            "balances": [{ "free": 100, "who": 1 }],
+            "xassets": [{ "free": 50, "who": 2 }],
+            "xstaking": { "validators": [], "nominators": [] }
+        }"#;
+        let de = serde_json::from_str::<Params>(json).unwrap();
+        assert_eq!(de.balances.len(), 1);
+        assert!(de.xgateway.pending_withdrawals.is_empty());
+        assert!(de.xgateway.trustee_sessions.is_empty());
+        assert!(de.xmining_asset.miner_ledgers.is_empty());
+    }
+}
@@ -40,7 +40,7 @@
 //! All asset miners split the reward of asset's reward pot according to the proportion of asset mining weight.
 //!
 
-use sp_arithmetic::traits::{BaseArithmetic, SaturatedConversion};
+use sp_arithmetic::traits::{BaseArithmetic, SaturatedConversion, Zero};
 use sp_runtime::RuntimeDebug;
 
 /// Type for calculating the mining weight.
@@ -228,6 +228,96 @@ pub fn compute_dividend<AccountId, Balance: BaseArithmetic>(
     }
 }
 
+/// A detached copy of the mining weight state, used for the dry-run dividend simulations.
+///
+/// The simulation goes through the same weight calculation as the on-chain operations,
+/// so that the estimates match what the chain will actually pay.
+#[derive(Clone, Copy, RuntimeDebug)]
+pub struct SimulatedMiningWeight<Balance, BlockNumber> {
+    pub amount: Balance,
+    pub last_acum_weight: WeightType,
+    pub last_acum_weight_update: BlockNumber,
+}
+
+impl<Balance, BlockNumber> BaseMiningWeight<Balance, BlockNumber>
+    for SimulatedMiningWeight<Balance, BlockNumber>
+where
+    Balance: Copy,
+    BlockNumber: Copy,
+{
+    fn amount(&self) -> Balance {
+        self.amount
+    }
+
+    fn set_amount(&mut self, new: Balance) {
+        self.amount = new;
+    }
+
+    fn last_acum_weight(&self) -> WeightType {
+        self.last_acum_weight
+    }
+
+    fn set_last_acum_weight(&mut self, s: WeightType) {
+        self.last_acum_weight = s;
+    }
+
+    fn last_acum_weight_update(&self) -> BlockNumber {
+        self.last_acum_weight_update
+    }
+
+    fn set_last_acum_weight_update(&mut self, num: BlockNumber) {
+        self.last_acum_weight_update = num;
+    }
+}
+
+impl<Balance, BlockNumber> SimulatedMiningWeight<Balance, BlockNumber>
+where
+    Balance: BaseArithmetic + Copy,
+    BlockNumber: BaseArithmetic + Copy,
+{
+    /// Takes a snapshot of the mining weight state of `w`.
+    pub fn from_weight<W: BaseMiningWeight<Balance, BlockNumber>>(w: &W) -> Self {
+        Self {
+            amount: w.amount(),
+            last_acum_weight: w.last_acum_weight(),
+            last_acum_weight_update: w.last_acum_weight_update(),
+        }
+    }
+
+    /// Returns the latest mining weight at `block_number`.
+    pub fn weight_at(&self, block_number: BlockNumber) -> WeightType {
+        let (last_acum_weight, amount, duration) =
+            generic_weight_factors::<Balance, BlockNumber, _>(*self, block_number);
+        last_acum_weight + amount * duration
+    }
+
+    /// Settles the mining weight at `current_block` and applies the amount change `delta`,
+    /// in the same way as `bond`, `unbond` and `rebond` do.
+    pub fn apply(&mut self, current_block: BlockNumber, delta: &Delta<Balance>) {
+        if let Delta::Zero = delta {
+            return;
+        }
+        let latest_acum_weight = self.weight_at(current_block);
+        self.set_state(latest_acum_weight, current_block, delta);
+    }
+
+    /// Returns the dividend at `block_number` given the reward pot balance at that time,
+    /// where `self` is the claimer and `claimee` is the one that holds the reward pot.
+    pub fn dividend_at(
+        &self,
+        claimee: &Self,
+        block_number: BlockNumber,
+        reward_pot_balance: Balance,
+    ) -> Balance {
+        let source_weight = self.weight_at(block_number);
+        let target_weight = claimee.weight_at(block_number);
+        if source_weight == 0 || target_weight == 0 {
+            return Balance::zero();
+        }
+        compute_dividend::<(), Balance>(source_weight, target_weight, reward_pot_balance)
+    }
+}
+
 /// Claims the reward for participating in the mining.
 pub trait Claim<AccountId> {
     /// Entity of holder of individual miners.
@@ -89,6 +89,13 @@ impl<T: Display + FromStr> From<T> for RpcU128<T> {
     }
 }
 
+impl<T: Display + FromStr> RpcU128<T> {
+    /// Returns the inner number, used when the number is a parameter of RPC.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
 /// Number string serialization/deserialization
 pub mod serde_num_str {
     use super::*;
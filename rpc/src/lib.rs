@@ -5,6 +5,10 @@ use std::sync::Arc;
 use sc_client_api::AuxStore;
 use sc_consensus_babe::Epoch;
 use sc_consensus_babe_rpc::BabeRpcHandler;
+use sc_consensus_manual_seal::{
+    rpc::{ManualSeal, ManualSealApi},
+    EngineCommand,
+};
 use sc_finality_grandpa::{
     FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
 };
@@ -107,6 +111,10 @@ pub struct FullDeps<C, P, SC, B, A: sc_transaction_pool::ChainApi> {
     pub grandpa: GrandpaDeps<B>,
     /// Frontier specific dependencies.
     pub frontier: FrontierDeps<A>,
+    /// Manual seal command sink, only available on the development node.
+    pub command_sink: Option<futures::channel::mpsc::Sender<EngineCommand<Hash>>>,
+    /// The database of the indexer, only available if it is enabled.
+    pub indexer: Option<Arc<chainx_indexer::Database>>,
 }
 
 pub fn overrides_handle<C, B>(client: Arc<C>) -> Arc<OverrideHandle<Block>>
@@ -196,6 +204,7 @@ where
         MiningWeight,
         BlockNumber,
     >,
+    C::Api: xpallet_system_rpc_runtime_api::XSystemApi<Block, AccountId, BlockNumber>,
     C::Api: xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>,
     C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
     C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
@@ -214,6 +223,7 @@ where
     use xpallet_gateway_records_rpc::{XGatewayRecords, XGatewayRecordsApi};
     use xpallet_mining_asset_rpc::{XMiningAsset, XMiningAssetApi};
     use xpallet_mining_staking_rpc::{XStaking, XStakingApi};
+    use xpallet_system_rpc::{XSystem, XSystemApi};
     use xpallet_transaction_fee_rpc::{XTransactionFee, XTransactionFeeApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
@@ -226,6 +236,8 @@ where
         grandpa,
         babe,
         frontier,
+        command_sink,
+        indexer,
     } = deps;
 
     let BabeDeps {
@@ -295,6 +307,17 @@ where
     io.extend_with(XGatewayCommonApi::to_delegate(XGatewayCommon::new(
         client.clone(),
     )));
+    io.extend_with(XSystemApi::to_delegate(XSystem::new(client.clone())));
+
+    if let Some(indexer) = indexer {
+        use chainx_indexer::{XIndexer, XIndexerApi};
+        io.extend_with(XIndexerApi::to_delegate(XIndexer::new(indexer)));
+    }
+
+    if let Some(command_sink) = command_sink {
+        // `engine_createBlock` and `engine_finalizeBlock`
+        io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(command_sink)));
+    }
 
     // EVM
     {
@@ -5,26 +5,34 @@
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
-    traits::{DispatchInfoOf, SignedExtension},
+    helpers_128bit::multiply_by_rational,
+    traits::{DispatchInfoOf, PostDispatchInfoOf, Saturating, SignedExtension, Zero},
     transaction_validity::{
         InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
     },
-    FixedPointNumber, Perquintill, RuntimeDebug,
+    DispatchResult, FixedPointNumber, Permill, Perquintill, RuntimeDebug,
 };
 
+use sp_std::prelude::*;
+
 use frame_support::{
+    dispatch::GetCallMetadata,
     parameter_types,
-    traits::{Currency, ExistenceRequirement, Imbalance, OnUnbalanced, WithdrawReasons},
+    traits::{
+        fungibles::Transfer, Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced,
+        WithdrawReasons,
+    },
 };
 
-use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
-
-use xpallet_gateway_common::Call as XGatewayCommonCall;
-use xpallet_mining_staking::Call as XStakingCall;
+use pallet_transaction_payment::{ChargeTransactionPayment, Multiplier, TargetedFeeAdjustment};
 
-use chainx_primitives::{AccountId, Balance};
+use chainx_primitives::{AccountId, AssetId, Balance};
+use xp_protocol::PCX;
 
-use crate::{Authorship, Balances, Call, Runtime};
+use crate::{
+    Authorship, Balances, Call, ExistentialDeposit, Runtime, TransactionPayment, Treasury, XAssets,
+    XSpot, XTransactionFee,
+};
 
 type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
 
@@ -38,25 +46,40 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 }
 
 pub struct DealWithFees;
-impl OnUnbalanced<NegativeImbalance> for DealWithFees {
-    fn on_nonzero_unbalanced(fees: NegativeImbalance) {
+impl DealWithFees {
+    /// Distributes the `fees` to the block author and its reward pot, returns
+    /// (author, author_fee, reward_pot, reward_pot_fee) if the author exists.
+    fn distribute(fees: NegativeImbalance) -> Option<(AccountId, Balance, AccountId, Balance)> {
         // for fees, 90% to the reward pot of author, 10% to author
         let (to_reward_pot, to_author) = fees.ration(90, 10);
 
         let to_author_numeric_amount = to_author.peek();
         let to_reward_pot_numeric_amount = to_reward_pot.peek();
 
-        if let Some(author) = <pallet_authorship::Pallet<Runtime>>::author() {
-            let reward_pot = <xpallet_mining_staking::Pallet<Runtime>>::reward_pot_for(&author);
+        let author = <pallet_authorship::Pallet<Runtime>>::author()?;
+        let reward_pot = <xpallet_mining_staking::Pallet<Runtime>>::reward_pot_for(&author);
+
+        <pallet_balances::Pallet<Runtime>>::resolve_creating(&author, to_author);
+        <pallet_balances::Pallet<Runtime>>::resolve_creating(&reward_pot, to_reward_pot);
 
-            <pallet_balances::Pallet<Runtime>>::resolve_creating(&author, to_author);
-            <pallet_balances::Pallet<Runtime>>::resolve_creating(&reward_pot, to_reward_pot);
+        Some((
+            author,
+            to_author_numeric_amount,
+            reward_pot,
+            to_reward_pot_numeric_amount,
+        ))
+    }
+}
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+    fn on_nonzero_unbalanced(fees: NegativeImbalance) {
+        if let Some((author, author_fee, reward_pot, reward_pot_fee)) = Self::distribute(fees) {
             <frame_system::Pallet<Runtime>>::deposit_event(
                 xpallet_transaction_fee::Event::<Runtime>::FeePaid(
                     author,
-                    to_author_numeric_amount,
+                    author_fee,
                     reward_pot,
-                    to_reward_pot_numeric_amount,
+                    reward_pot_fee,
                 ),
             );
         }
@@ -72,28 +95,36 @@ parameter_types! {
 pub type SlowAdjustingFeeUpdate<R> =
     TargetedFeeAdjustment<R, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
 
+/// The extra fees of the special calls before they are moved into `XTransactionFee`,
+/// (pallet, call, fee).
+pub fn default_extra_fees() -> Vec<(Vec<u8>, Vec<u8>, Balance)> {
+    // 1 PCX
+    const BASE_EXTRA_FEE: Balance = 100_000_000;
+
+    vec![
+        (
+            b"XGatewayCommon".to_vec(),
+            b"setup_trustee".to_vec(),
+            BASE_EXTRA_FEE,
+        ),
+        (
+            b"XStaking".to_vec(),
+            b"register".to_vec(),
+            10 * BASE_EXTRA_FEE,
+        ),
+        (b"XStaking".to_vec(), b"validate".to_vec(), BASE_EXTRA_FEE),
+        (b"XStaking".to_vec(), b"rebond".to_vec(), BASE_EXTRA_FEE),
+    ]
+}
+
 /// A struct for charging additional fee for some special calls.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct ChargeExtraFee;
 
 impl ChargeExtraFee {
-    /// Returns the optional extra fee for the given `call`.
+    /// Returns the optional extra fee for the given `call`, see `XTransactionFee::extra_fees`.
     pub fn has_extra_fee(call: &Call) -> Option<Balance> {
-        // 1 PCX
-        const BASE_EXTRA_FEE: Balance = 100_000_000;
-
-        let extra_cofficient: Option<u32> = match call {
-            Call::XGatewayCommon(XGatewayCommonCall::setup_trustee { .. }) => Some(1),
-            Call::XStaking(xstaking) => match xstaking {
-                XStakingCall::register { .. } => Some(10),
-                XStakingCall::validate { .. } => Some(1),
-                XStakingCall::rebond { .. } => Some(1),
-                _ => None,
-            },
-            _ => None,
-        };
-
-        extra_cofficient.map(|cofficient| Balance::from(cofficient) * BASE_EXTRA_FEE)
+        XTransactionFee::extra_fee(call.get_call_metadata())
     }
 
     /// Actually withdraws the extra `fee` from account `who`.
@@ -105,7 +136,19 @@ impl ChargeExtraFee {
             ExistenceRequirement::KeepAlive,
         ) {
             Ok(fee) => {
-                DealWithFees::on_nonzero_unbalanced(fee);
+                if let Some((author, author_fee, reward_pot, reward_pot_fee)) =
+                    DealWithFees::distribute(fee)
+                {
+                    <frame_system::Pallet<Runtime>>::deposit_event(
+                        xpallet_transaction_fee::Event::<Runtime>::ExtraFeePaid(
+                            who.clone(),
+                            author,
+                            author_fee,
+                            reward_pot,
+                            reward_pot_fee,
+                        ),
+                    );
+                }
                 Ok(ValidTransaction::default())
             }
             Err(_) => Err(InvalidTransaction::Payment.into()),
@@ -148,3 +191,477 @@ impl SignedExtension for ChargeExtraFee {
         Ok(ValidTransaction::default())
     }
 }
+
+parameter_types! {
+    /// The maximum deviation of the lowest ask from the average price for the fee asset.
+    pub const MaxFeePriceDeviation: Permill = Permill::from_percent(10);
+}
+
+/// Prices the fee assets at the time-weighted average price of the XSpot trading pair
+/// `PCX/asset`, which is refused if the lowest ask deviates too much from it.
+pub struct SpotAssetPrice;
+impl xpallet_transaction_fee::AssetPrice<Balance> for SpotAssetPrice {
+    fn native_to_asset(asset_id: AssetId, amount: Balance) -> Option<Balance> {
+        XSpot::convert_at_average_price(PCX, asset_id, amount, MaxFeePriceDeviation::get())
+    }
+}
+
+/// The payment withdrawn in `pre_dispatch` of `ChargeAssetTxPayment`.
+pub enum InitialPayment {
+    /// The fee was paid in PCX by `ChargeTransactionPayment`.
+    Native(<ChargeTransactionPayment<Runtime> as SignedExtension>::Pre),
+    /// The fee was paid in `asset_fee` of the asset, which has been exchanged with the
+    /// treasury for the `native_fee`.
+    Asset {
+        who: AccountId,
+        tip: Balance,
+        asset_id: AssetId,
+        asset_fee: Balance,
+        native_fee: NegativeImbalance,
+    },
+}
+
+/// Charges the transaction fee in PCX, or in one of the assets accepted by
+/// `XTransactionFee` if `asset_id` is specified.
+///
+/// The asset fee is paid to the treasury, which pays the PCX fee to the fee recipients
+/// in exchange, the unused part of both is refunded after the dispatch.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ChargeAssetTxPayment {
+    #[codec(compact)]
+    tip: Balance,
+    asset_id: Option<AssetId>,
+}
+
+impl ChargeAssetTxPayment {
+    pub fn from(tip: Balance, asset_id: Option<AssetId>) -> Self {
+        Self { tip, asset_id }
+    }
+
+    /// Returns the asset in which the fee is paid, None for PCX.
+    pub fn asset_id(&self) -> Option<AssetId> {
+        self.asset_id
+    }
+
+    /// Returns the native fee and the equivalent amount of the fee asset.
+    fn asset_fee(
+        &self,
+        asset_id: AssetId,
+        who: &AccountId,
+        info: &DispatchInfoOf<Call>,
+        len: usize,
+    ) -> Result<(Balance, Balance), TransactionValidityError> {
+        XAssets::ensure_not_paused(&asset_id).map_err(|_| InvalidTransaction::Payment)?;
+        let fee = TransactionPayment::compute_fee(len as u32, info, self.tip);
+        let asset_fee = XTransactionFee::native_to_asset_fee(asset_id, fee)
+            .ok_or(InvalidTransaction::Payment)?;
+
+        if XAssets::usable_balance(who, &asset_id) < asset_fee
+            || Balances::free_balance(&Treasury::account_id())
+                < fee.saturating_add(ExistentialDeposit::get())
+        {
+            return Err(InvalidTransaction::Payment.into());
+        }
+        Ok((fee, asset_fee))
+    }
+}
+
+impl SignedExtension for ChargeAssetTxPayment {
+    const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+    type AccountId = AccountId;
+    type Call = Call;
+    type AdditionalSigned = ();
+    type Pre = InitialPayment;
+
+    fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match self.asset_id {
+            None => {
+                ChargeTransactionPayment::<Runtime>::from(self.tip).validate(who, call, info, len)
+            }
+            Some(asset_id) => {
+                let (fee, _) = self.asset_fee(asset_id, who, info, len)?;
+                Ok(ValidTransaction {
+                    priority: ChargeTransactionPayment::<Runtime>::get_priority(
+                        info, len, self.tip, fee,
+                    ),
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        let asset_id = match self.asset_id {
+            None => {
+                return ChargeTransactionPayment::<Runtime>::from(self.tip)
+                    .pre_dispatch(who, call, info, len)
+                    .map(InitialPayment::Native)
+            }
+            Some(asset_id) => asset_id,
+        };
+
+        let (fee, asset_fee) = self.asset_fee(asset_id, who, info, len)?;
+        let treasury = Treasury::account_id();
+        let native_fee = Balances::withdraw(
+            &treasury,
+            fee,
+            WithdrawReasons::TRANSACTION_PAYMENT,
+            ExistenceRequirement::KeepAlive,
+        )
+        .map_err(|_| InvalidTransaction::Payment)?;
+        if <XAssets as Transfer<AccountId>>::transfer(asset_id, who, &treasury, asset_fee, false)
+            .is_err()
+        {
+            Balances::resolve_creating(&treasury, native_fee);
+            return Err(InvalidTransaction::Payment.into());
+        }
+
+        Ok(InitialPayment::Asset {
+            who: who.clone(),
+            tip: self.tip,
+            asset_id,
+            asset_fee,
+            native_fee,
+        })
+    }
+
+    fn post_dispatch(
+        pre: Self::Pre,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        let (who, tip, asset_id, asset_fee, native_fee) = match pre {
+            InitialPayment::Native(pre) => {
+                return ChargeTransactionPayment::<Runtime>::post_dispatch(
+                    pre, info, post_info, len, result,
+                )
+            }
+            InitialPayment::Asset {
+                who,
+                tip,
+                asset_id,
+                asset_fee,
+                native_fee,
+            } => (who, tip, asset_id, asset_fee, native_fee),
+        };
+
+        let treasury = Treasury::account_id();
+        let actual_fee = TransactionPayment::compute_actual_fee(len as u32, info, post_info, tip);
+        let withdrawn = native_fee.peek();
+        let (paid, unused) = native_fee.split(actual_fee);
+
+        // Refund the unused part of the asset fee in proportion, rounding down.
+        let mut asset_refund = if unused.peek().is_zero() {
+            Zero::zero()
+        } else {
+            multiply_by_rational(asset_fee, unused.peek(), withdrawn).unwrap_or_default()
+        };
+        if asset_refund.is_zero()
+            || <XAssets as Transfer<AccountId>>::transfer(
+                asset_id,
+                &treasury,
+                &who,
+                asset_refund,
+                false,
+            )
+            .is_ok()
+        {
+            Balances::resolve_creating(&treasury, unused);
+        } else {
+            // The asset can not be refunded, e.g. it's paused, refund the unused PCX instead.
+            asset_refund = Zero::zero();
+            Balances::resolve_creating(&who, unused);
+        }
+
+        let native_paid = paid.peek();
+        DealWithFees::on_unbalanced(paid);
+        <frame_system::Pallet<Runtime>>::deposit_event(
+            xpallet_transaction_fee::Event::<Runtime>::AssetFeePaid(
+                who,
+                asset_id,
+                asset_fee.saturating_sub(asset_refund),
+                native_paid,
+            ),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use frame_support::{
+        assert_ok,
+        traits::GenesisBuild,
+        weights::{DispatchClass, DispatchInfo, Pays, PostDispatchInfo},
+    };
+    use sp_runtime::FixedU128;
+
+    use xp_protocol::X_BTC;
+    use xpallet_assets::{AssetInfo, AssetRestrictions, Chain};
+    use xpallet_system::{AccountRestrictions, PauseScope};
+    use xpallet_transaction_fee::FeeRate;
+
+    use crate::{constants::currency::PCXS, Origin, System, XSystem};
+
+    const ALICE: AccountId = AccountId::new([1u8; 32]);
+    const BOB: AccountId = AccountId::new([2u8; 32]);
+    const ASSET_BALANCE: Balance = 1_000 * PCXS;
+    const LEN: usize = 100;
+    const INFO: DispatchInfo = DispatchInfo {
+        weight: 1_000_000_000,
+        class: DispatchClass::Normal,
+        pays_fee: Pays::Yes,
+    };
+    const POST_INFO: PostDispatchInfo = PostDispatchInfo {
+        actual_weight: Some(INFO.weight / 2),
+        pays_fee: Pays::Yes,
+    };
+
+    fn call() -> Call {
+        Call::System(frame_system::Call::remark { remark: vec![] })
+    }
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(Treasury::account_id(), 1_000 * PCXS), (ALICE, PCXS)],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        let btc = AssetInfo::new::<Runtime>(
+            b"X-BTC".to_vec(),
+            b"X-BTC".to_vec(),
+            Chain::Bitcoin,
+            8,
+            b"ChainX's cross-chain Bitcoin".to_vec(),
+        )
+        .unwrap();
+        GenesisBuild::<Runtime>::assimilate_storage(
+            &xpallet_assets_registrar::GenesisConfig {
+                assets: vec![(X_BTC, btc, true, true)],
+            },
+            &mut t,
+        )
+        .unwrap();
+        xpallet_assets::GenesisConfig::<Runtime> {
+            assets_restrictions: vec![],
+            endowed: vec![(X_BTC, vec![(ALICE, ASSET_BALANCE)])]
+                .into_iter()
+                .collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| {
+            System::set_block_number(1);
+            // 2 X-BTC for 1 PCX.
+            assert_ok!(XTransactionFee::set_fee_asset(
+                Origin::root(),
+                X_BTC,
+                Some(FeeRate::Fixed(FixedU128::saturating_from_integer(2)))
+            ));
+        });
+        ext
+    }
+
+    #[test]
+    fn native_to_asset_fee_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(XTransactionFee::native_to_asset_fee(X_BTC, 100), Some(200));
+            assert_eq!(XTransactionFee::native_to_asset_fee(X_BTC, 0), Some(0));
+            // unaccepted asset
+            assert_eq!(XTransactionFee::native_to_asset_fee(PCX, 100), None);
+
+            assert_ok!(XTransactionFee::set_asset_fee_margin(
+                Origin::root(),
+                Permill::from_percent(10)
+            ));
+            assert_eq!(XTransactionFee::native_to_asset_fee(X_BTC, 100), Some(220));
+        })
+    }
+
+    #[test]
+    fn asset_fee_should_be_refunded_in_asset() {
+        new_test_ext().execute_with(|| {
+            let treasury = Treasury::account_id();
+            let treasury_pcx = Balances::free_balance(&treasury);
+            let fee = TransactionPayment::compute_fee(LEN as u32, &INFO, 0);
+            let asset_fee = XTransactionFee::native_to_asset_fee(X_BTC, fee).unwrap();
+            assert_eq!(asset_fee, 2 * fee);
+
+            let pre = ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .unwrap();
+            assert_eq!(
+                XAssets::usable_balance(&ALICE, &X_BTC),
+                ASSET_BALANCE - asset_fee
+            );
+            assert_eq!(XAssets::usable_balance(&treasury, &X_BTC), asset_fee);
+            assert_eq!(Balances::free_balance(&treasury), treasury_pcx - fee);
+
+            assert_ok!(ChargeAssetTxPayment::post_dispatch(
+                pre,
+                &INFO,
+                &POST_INFO,
+                LEN,
+                &Ok(())
+            ));
+            let actual_fee =
+                TransactionPayment::compute_actual_fee(LEN as u32, &INFO, &POST_INFO, 0);
+            let refund = multiply_by_rational(asset_fee, fee - actual_fee, fee).unwrap();
+            assert!(refund > 0);
+            assert_eq!(
+                XAssets::usable_balance(&ALICE, &X_BTC),
+                ASSET_BALANCE - asset_fee + refund
+            );
+            assert_eq!(
+                XAssets::usable_balance(&treasury, &X_BTC),
+                asset_fee - refund
+            );
+            // The treasury only pays the actual fee in PCX.
+            assert_eq!(Balances::free_balance(&treasury), treasury_pcx - actual_fee);
+            assert_eq!(Balances::free_balance(&ALICE), PCXS);
+        })
+    }
+
+    #[test]
+    fn unused_pcx_should_be_refunded_if_asset_refund_fails() {
+        new_test_ext().execute_with(|| {
+            let treasury = Treasury::account_id();
+            let treasury_pcx = Balances::free_balance(&treasury);
+            let fee = TransactionPayment::compute_fee(LEN as u32, &INFO, 0);
+            let asset_fee = XTransactionFee::native_to_asset_fee(X_BTC, fee).unwrap();
+
+            let pre = ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .unwrap();
+            // The asset is not transferable any more during the dispatch.
+            assert_ok!(XAssets::set_asset_limit(
+                Origin::root(),
+                X_BTC,
+                AssetRestrictions::TRANSFER
+            ));
+
+            assert_ok!(ChargeAssetTxPayment::post_dispatch(
+                pre,
+                &INFO,
+                &POST_INFO,
+                LEN,
+                &Ok(())
+            ));
+            let actual_fee =
+                TransactionPayment::compute_actual_fee(LEN as u32, &INFO, &POST_INFO, 0);
+            assert_eq!(
+                XAssets::usable_balance(&ALICE, &X_BTC),
+                ASSET_BALANCE - asset_fee
+            );
+            assert_eq!(Balances::free_balance(&ALICE), PCXS + fee - actual_fee);
+            assert_eq!(Balances::free_balance(&treasury), treasury_pcx - fee);
+        })
+    }
+
+    #[test]
+    fn invalid_asset_fee_should_be_rejected() {
+        new_test_ext().execute_with(|| {
+            // unaccepted asset
+            assert!(ChargeAssetTxPayment::from(0, Some(PCX))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .is_err());
+            // not enough asset
+            assert!(ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .validate(&BOB, &call(), &INFO, LEN)
+                .is_err());
+            assert!(ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&BOB, &call(), &INFO, LEN)
+                .is_err());
+            // the treasury can not pay the PCX fee
+            let treasury = Treasury::account_id();
+            let _ = Balances::slash(&treasury, Balances::free_balance(&treasury));
+            assert!(ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .is_err());
+            assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), ASSET_BALANCE);
+        })
+    }
+
+    #[test]
+    fn restricted_payer_can_not_pay_asset_fee() {
+        new_test_ext().execute_with(|| {
+            let treasury = Treasury::account_id();
+            let treasury_pcx = Balances::free_balance(&treasury);
+            assert_ok!(XSystem::set_restriction(
+                Origin::root(),
+                ALICE.into(),
+                AccountRestrictions::MOVE_ASSET,
+                b"frozen".to_vec(),
+                None
+            ));
+            assert!(ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .is_err());
+            assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), ASSET_BALANCE);
+            assert_eq!(Balances::free_balance(&treasury), treasury_pcx);
+            // The fee can still be paid in PCX.
+            assert_ok!(ChargeAssetTxPayment::from(0, None).pre_dispatch(
+                &ALICE,
+                &call(),
+                &INFO,
+                LEN
+            ));
+        })
+    }
+
+    #[test]
+    fn paused_asset_can_not_pay_fee() {
+        new_test_ext().execute_with(|| {
+            let treasury = Treasury::account_id();
+            let treasury_pcx = Balances::free_balance(&treasury);
+            assert_ok!(XSystem::emergency_pause(
+                Origin::root(),
+                PauseScope::Asset(X_BTC),
+                10
+            ));
+            assert!(ChargeAssetTxPayment::from(0, Some(X_BTC))
+                .pre_dispatch(&ALICE, &call(), &INFO, LEN)
+                .is_err());
+            assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), ASSET_BALANCE);
+            assert_eq!(Balances::free_balance(&treasury), treasury_pcx);
+
+            assert_ok!(XSystem::emergency_unpause(
+                Origin::root(),
+                PauseScope::Asset(X_BTC)
+            ));
+            assert_ok!(ChargeAssetTxPayment::from(0, Some(X_BTC)).pre_dispatch(
+                &ALICE,
+                &call(),
+                &INFO,
+                LEN
+            ));
+        })
+    }
+}
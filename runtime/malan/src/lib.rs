@@ -46,8 +46,13 @@ use sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots;
 
 use chainx_runtime_common::{BlockLength, BlockWeights, BASE_FEE};
 use xpallet_dex_spot::{Depth, FullPairInfo, RpcOrder, TradingPairId};
-use xpallet_mining_asset::{MinerLedger, MiningAssetInfo, MiningDividendInfo};
-use xpallet_mining_staking::{NominatorInfo, NominatorLedger, ValidatorInfo};
+use xpallet_mining_asset::{
+    AssetBalanceChange, MinerLedger, MiningAssetInfo, MiningDividendEstimate, MiningDividendInfo,
+};
+use xpallet_mining_staking::{
+    EraHistory, EraIndex, NominationChange, NominatorInfo, NominatorLedger,
+    StakingDividendEstimate, UnappliedSlash, ValidatorInfo,
+};
 use xpallet_support::traits::MultisigAddressFor;
 
 // A few exports that help ease life for downstream crates.
@@ -101,7 +106,10 @@ pub mod impls;
 mod migrations;
 
 use self::constants::{currency::*, time::*};
-use self::impls::{ChargeExtraFee, DealWithFees, SlowAdjustingFeeUpdate};
+pub use self::impls::default_extra_fees;
+use self::impls::{
+    ChargeAssetTxPayment, ChargeExtraFee, DealWithFees, SlowAdjustingFeeUpdate, SpotAssetPrice,
+};
 
 // EVM
 use chainx_runtime_common::NORMAL_DISPATCH_RATIO;
@@ -116,17 +124,17 @@ use pallet_evm::{
 use sp_core::{H160, U256};
 use sp_runtime::traits::{Dispatchable, PostDispatchInfoOf};
 mod precompiles;
-pub use precompiles::ChainXPrecompiles;
+pub use precompiles::{ChainXPrecompiles, DUMMY_PRECOMPILE_CODE};
 
 /// This runtime version.
 pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("chainx"),
     impl_name: create_runtime_str!("chainx-malan"),
     authoring_version: 1,
-    spec_version: 27,
+    spec_version: 28,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 5,
+    transaction_version: 6,
     state_version: 0,
 };
 
@@ -162,6 +170,42 @@ impl Contains<Call> for BaseFilter {
 
         let metadata = call.get_call_metadata();
         !XSystem::is_paused(metadata)
+            && !call_assets(call).into_iter().any(XSystem::is_asset_paused)
+    }
+}
+
+/// Returns the assets moved by the user calls, which are paused by `PauseScope::Asset`.
+fn call_assets(call: &Call) -> Vec<AssetId> {
+    match call {
+        Call::XAssets(xpallet_assets::Call::transfer { id, .. })
+        | Call::XAssets(xpallet_assets::Call::vested_transfer { id, .. }) => vec![*id],
+        Call::XGatewayCommon(xpallet_gateway_common::Call::withdraw { asset_id, .. })
+        | Call::XAssetsBridge(xpallet_assets_bridge::Call::deposit { asset_id, .. })
+        | Call::XAssetsBridge(xpallet_assets_bridge::Call::withdraw { asset_id, .. }) => {
+            vec![*asset_id]
+        }
+        Call::XSpot(xpallet_dex_spot::Call::put_order { pair_id, .. }) => {
+            XSpot::trading_pair_of(pair_id)
+                .map(|pair| vec![pair.currency_pair.base, pair.currency_pair.quote])
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the account restrictions which forbid the user call besides `TRANSACT`.
+fn call_restrictions(call: &Call) -> xpallet_system::AccountRestrictions {
+    use xpallet_system::AccountRestrictions;
+    match call {
+        Call::XAssets(xpallet_assets::Call::transfer { .. })
+        | Call::XAssets(xpallet_assets::Call::vested_transfer { .. }) => {
+            AccountRestrictions::MOVE_ASSET
+        }
+        Call::XGatewayCommon(xpallet_gateway_common::Call::withdraw { .. }) => {
+            AccountRestrictions::WITHDRAW
+        }
+        Call::XSpot(xpallet_dex_spot::Call::put_order { .. }) => AccountRestrictions::TRADE,
+        _ => AccountRestrictions::empty(),
     }
 }
 
@@ -198,7 +242,9 @@ impl SignedExtension for BaseFilter {
         if !Self::contains(call) {
             return Err(InvalidTransaction::Custom(FORBIDDEN_CALL).into());
         }
-        if XSystem::blacklist(who) {
+        if XSystem::restrictions_of(who)
+            .intersects(xpallet_system::AccountRestrictions::TRANSACT | call_restrictions(call))
+        {
             return Err(InvalidTransaction::Custom(FORBIDDEN_ACCOUNT).into());
         }
         Ok(ValidTransaction::default())
@@ -447,6 +493,9 @@ impl pallet_transaction_payment::Config for Runtime {
 
 impl xpallet_transaction_fee::Config for Runtime {
     type Event = Event;
+    type UpdateOrigin = EnsureRootOrHalfCouncil;
+    type AssetPrice = SpotAssetPrice;
+    type WeightInfo = xpallet_transaction_fee::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -531,7 +580,7 @@ where
             frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+            ChargeAssetTxPayment::from(tip, None),
             BaseFilter,
             ChargeExtraFee,
         );
@@ -986,28 +1035,56 @@ impl pallet_proxy::Config for Runtime {
 ///////////////////////////////////////////
 // Chainx pallets
 ///////////////////////////////////////////
+parameter_types! {
+    pub const MaxPauseDuration: BlockNumber = 3 * DAYS;
+}
+
 impl xpallet_system::Config for Runtime {
     type Event = Event;
     type Currency = Balances;
+    // Half of the technical committee can pause in emergency.
+    type EmergencyOrigin = EnsureOneOf<
+        EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 2>,
+    >;
+    type MaxPauseDuration = MaxPauseDuration;
 }
 
 parameter_types! {
     pub const ChainXAssetId: AssetId = xp_protocol::PCX;
 }
 
+parameter_types! {
+    pub const ListingDeposit: Balance = 1000 * DOLLARS;
+    pub const RejectionSlash: Perbill = Perbill::from_percent(10);
+}
+
 impl xpallet_assets_registrar::Config for Runtime {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
-    type RegistrarHandler = XMiningAsset;
+    type RegistrarHandler = (XMiningAsset, ChainXPrecompiles<Runtime>);
+    type Currency = Balances;
+    type ListingOrigin = EnsureRootOrHalfCouncil;
+    type ListingDeposit = ListingDeposit;
+    type RejectionSlash = RejectionSlash;
+    type OnSlash = Treasury;
     type WeightInfo = xpallet_assets_registrar::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    // 0.0001 X-BTC, in case of filling the vesting schedules with dust.
+    pub const MinVestedTransfer: Balance = 10_000;
+}
+
 impl xpallet_assets::Config for Runtime {
     type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Runtime>;
     type OnAssetChanged = XMiningAsset;
+    type AccountRestriction = XSystem;
+    type AssetPause = XSystem;
+    type MinVestedTransfer = MinVestedTransfer;
     type WeightInfo = xpallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1047,6 +1124,7 @@ impl xpallet_gateway_bitcoin::Config for Runtime {
     type TrusteeInfoUpdate = XGatewayCommon;
     type ReferralBinding = XGatewayCommon;
     type AddressBinding = XGatewayCommon;
+    type AuthorityId = xpallet_gateway_bitcoin::relay::crypto::RelayerAuthId;
     type WeightInfo = xpallet_gateway_bitcoin::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1083,6 +1161,11 @@ impl xpallet_mining_staking::Config for Runtime {
     type DetermineRewardPotAccount =
         xpallet_mining_staking::SimpleValidatorRewardPotAccountDeterminer<Runtime>;
     type ValidatorRegistration = Session;
+    /// A super-majority of the council can cancel the deferred nominator slash.
+    type SlashCancelOrigin = EnsureOneOf<
+        EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 3, 4>,
+    >;
     type WeightInfo = xpallet_mining_staking::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1197,11 +1280,20 @@ parameter_types! {
     // 0x1111111111111111111111111111111111111111
     pub EvmCaller: H160 = H160::from_slice(&[17u8;20][..]);
     pub ClaimBond: Balance = PCXS;
+    pub const RegistrationDelay: BlockNumber = DAYS;
 }
 impl xpallet_assets_bridge::Config for Runtime {
     type Event = Event;
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
+    // Half of the technical committee can pause the bridge in emergency.
+    type PauserOrigin = EnsureOneOf<
+        EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 2>,
+    >;
+    type RegistrarOrigin = EnsureRootOrHalfCouncil;
+    type BackForeignOrigin = EnsureRootOrHalfCouncil;
+    type RegistrationDelay = RegistrationDelay;
 }
 
 construct_runtime!(
@@ -1265,7 +1357,7 @@ construct_runtime!(
 
         // It might be possible to merge this module into pallet_transaction_payment in future, thus
         // we put it at the end for keeping the extrinsic ordering.
-        XTransactionFee: xpallet_transaction_fee::{Pallet, Event<T>} = 35,
+        XTransactionFee: xpallet_transaction_fee::{Pallet, Call, Storage, Config<T>, Event<T>} = 35,
 
         Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 36,
 
@@ -1305,7 +1397,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    ChargeAssetTxPayment,
     BaseFilter,
     ChargeExtraFee,
 );
@@ -1329,6 +1421,10 @@ pub type Executive = frame_executive::Executive<
         EthereumChainIdMigration,
         BaseFeeMigration,
         XAssetsBridgeMigration,
+        XTransactionFeeMigration,
+        XSystemMigration,
+        XStakingMigration,
+        PrecompileCodeMigration,
     ),
 >;
 
@@ -1367,17 +1463,68 @@ pub struct XAssetsBridgeMigration;
 impl OnRuntimeUpgrade for XAssetsBridgeMigration {
     fn on_runtime_upgrade() -> Weight {
         frame_support::log::info!("🔍️ XAssetsBridgeMigration start");
-        let admin = [
-            166u8, 42, 221, 26, 243, 188, 249, 37, 106, 162, 222, 240, 254, 161, 185, 100, 140,
-            183, 37, 23, 204, 238, 146, 168, 145, 220, 41, 3, 169, 9, 62, 82,
-        ];
+        // Grant the pauser role to the admin account and remove the admin key.
+        let w = XAssetsBridge::migrate_admin_to_roles();
+        frame_support::log::info!("🚀 XAssetsBridgeMigration end with roles");
+        w
+    }
+}
 
-        let w = XAssetsBridge::set_admin_inner(admin.into());
-        frame_support::log::info!("🚀 XAssetsBridgeMigration end with initialization");
+pub struct XTransactionFeeMigration;
+impl OnRuntimeUpgrade for XTransactionFeeMigration {
+    fn on_runtime_upgrade() -> Weight {
+        frame_support::log::info!("🔍️ XTransactionFeeMigration start");
+        let w = XTransactionFee::migrate_to_v1(default_extra_fees());
+        frame_support::log::info!("🚀 XTransactionFeeMigration end");
         w
     }
 }
 
+pub struct XSystemMigration;
+impl OnRuntimeUpgrade for XSystemMigration {
+    fn on_runtime_upgrade() -> Weight {
+        frame_support::log::info!("🔍️ XSystemMigration start");
+        // Move the blacklist into the account restrictions.
+        let w = XSystem::migrate_to_v1();
+        frame_support::log::info!("🚀 XSystemMigration end");
+        w
+    }
+}
+
+pub struct XStakingMigration;
+impl OnRuntimeUpgrade for XStakingMigration {
+    fn on_runtime_upgrade() -> Weight {
+        frame_support::log::info!("🔍️ XStakingMigration start");
+        // Index the nominations by the validator for the nominator slashing.
+        let w = XStaking::migrate_to_v1();
+        frame_support::log::info!("🚀 XStakingMigration end");
+        w
+    }
+}
+
+pub struct PrecompileCodeMigration;
+impl OnRuntimeUpgrade for PrecompileCodeMigration {
+    fn on_runtime_upgrade() -> Weight {
+        frame_support::log::info!("🔍️ PrecompileCodeMigration start");
+        // Put the dummy code at the precompile addresses which have no code yet,
+        // the ERC-20 precompiles only for the registered assets.
+        let addresses = ChainXPrecompiles::<Runtime>::used_addresses()
+            .into_iter()
+            .chain(
+                XAssetsRegistrar::asset_ids()
+                    .filter_map(xpallet_assets_bridge::precompile::asset_precompile_address),
+            )
+            .collect::<Vec<_>>();
+        let writes = addresses
+            .iter()
+            .filter(|address| ChainXPrecompiles::<Runtime>::put_dummy_code(**address))
+            .count() as Weight;
+        frame_support::log::info!("🚀 PrecompileCodeMigration end with {} codes", writes);
+        let chains = xpallet_assets_registrar::Chain::iter().len() as Weight;
+        RocksDbWeight::get().reads_writes(chains + addresses.len() as Weight, writes)
+    }
+}
+
 pub struct TransactionConverter;
 impl fp_rpc::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
     fn convert_transaction(&self, transaction: pallet_ethereum::Transaction) -> UncheckedExtrinsic {
@@ -1640,8 +1787,24 @@ impl_runtime_apis! {
             len: u32,
         ) -> xpallet_transaction_fee::FeeDetails<Balance> {
             let maybe_extra = ChargeExtraFee::has_extra_fee(&uxt.0.function);
+            let maybe_asset_id = uxt
+                .0
+                .signature
+                .as_ref()
+                .and_then(|(_, _, extra)| extra.7.asset_id());
             let base = TransactionPayment::query_fee_details(uxt, len);
-            xpallet_transaction_fee::FeeDetails::new(base, maybe_extra)
+            // The extra fee is always paid in PCX.
+            let asset_fee = maybe_asset_id.and_then(|asset_id| {
+                XTransactionFee::native_to_asset_fee(asset_id, base.final_fee())
+                    .map(|amount| xpallet_transaction_fee::AssetFee { asset_id, amount })
+            });
+            xpallet_transaction_fee::FeeDetails::new(base, maybe_extra).with_asset_fee(asset_fee)
+        }
+    }
+
+    impl xpallet_system_rpc_runtime_api::XSystemApi<Block, AccountId, BlockNumber> for Runtime {
+        fn restricted_accounts() -> Vec<(AccountId, xpallet_system::Restriction<BlockNumber>)> {
+            XSystem::restricted_accounts()
         }
     }
 
@@ -1671,6 +1834,19 @@ impl_runtime_apis! {
         fn nominator_info_of(who: AccountId) -> NominatorInfo<BlockNumber> {
             XStaking::nominator_info_of(who)
         }
+        fn unapplied_slashes() -> BTreeMap<EraIndex, Vec<UnappliedSlash<AccountId>>> {
+            XStaking::pending_slashes()
+        }
+        fn era_history(era: EraIndex) -> Option<EraHistory<AccountId, Balance>> {
+            XStaking::era_history(era)
+        }
+        fn simulate_staking_dividend(
+            who: AccountId,
+            change: NominationChange<AccountId, Balance>,
+            horizon: BlockNumber,
+        ) -> BTreeMap<AccountId, StakingDividendEstimate<Balance>> {
+            XStaking::simulate_staking_dividend(who, change, horizon)
+        }
     }
 
     impl xpallet_dex_spot_rpc_runtime_api::XSpotApi<Block, AccountId, Balance, BlockNumber, Balance> for Runtime {
@@ -1699,6 +1875,15 @@ impl_runtime_apis! {
         fn miner_ledger(who: AccountId) -> BTreeMap<AssetId, MinerLedger<MiningWeight, BlockNumber>> {
             XMiningAsset::miner_ledger(who)
         }
+
+        fn simulate_mining_dividend(
+            who: AccountId,
+            asset_id: AssetId,
+            change: AssetBalanceChange<Balance>,
+            horizon: BlockNumber,
+        ) -> MiningDividendEstimate<Balance, BlockNumber> {
+            XMiningAsset::simulate_mining_dividend(who, asset_id, change, horizon)
+        }
     }
 
     impl xpallet_gateway_records_rpc_runtime_api::XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
@@ -1975,6 +2160,7 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, xpallet_gateway_common, XGatewayCommon);
             list_benchmark!(list, extra, xpallet_gateway_bitcoin, XGatewayBitcoin);
             list_benchmark!(list, extra, xpallet_dex_spot, XSpot);
+            list_benchmark!(list, extra, xpallet_transaction_fee, XTransactionFee);
 
             let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -2035,5 +2221,6 @@ mod benches {
         [xpallet_gateway_common,  XGatewayCommon]
         [xpallet_gateway_bitcoin, XGatewayBitcoin]
         [xpallet_dex_spot, XSpot]
+        [xpallet_transaction_fee, XTransactionFee]
     );
 }
@@ -7,11 +7,31 @@ use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 use sp_core::H160;
+use sp_runtime::DispatchResult;
 use sp_std::marker::PhantomData;
 
+use chainx_primitives::AssetId;
+use xpallet_assets_bridge::precompile::{
+    asset_precompile_address, AssetsBridgePrecompile, Erc20AssetsPrecompile,
+};
+use xpallet_assets_registrar::RegistrarHandler;
+use xpallet_gateway_common_precompile::WithdrawalPrecompile;
+use xpallet_mining_staking_precompile::StakingPrecompile;
+
+/// The indexes of the precompiles except the ERC-20 precompile range.
+const FIXED_PRECOMPILES: [u64; 15] = [
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 2049, 2050, 2051,
+];
+
+/// The code stored at the precompile addresses, i.e. `PUSH1 0 PUSH1 0 REVERT`, so that
+/// the contracts checking `extcodesize` before calling them don't fail.
+pub const DUMMY_PRECOMPILE_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
 /// We include the nine Istanbul precompiles
 /// (https://github.com/ethereum/go-ethereum/blob/3c46f557/core/vm/contracts.go#L69)
-/// as well as a special precompile for dispatching Substrate extrinsics
+/// as well as a special precompile for dispatching Substrate extrinsics,
+/// the ERC-20 precompiles of the assets in XAssets, the staking precompile,
+/// the X-BTC withdrawal precompile and the assets bridge precompile.
 pub struct ChainXPrecompiles<R>(PhantomData<R>);
 
 impl<R> ChainXPrecompiles<R>
@@ -22,13 +42,30 @@ where
     pub fn new() -> Self {
         Self(PhantomData::<R>)
     }
-    /// Return all addresses that contain precompiles. This can be used to populate dummy code
-    /// under the precompile.
+    /// Return all addresses that contain precompiles except the ERC-20 precompiles.
+    /// This can be used to populate dummy code under the precompile.
+    ///
+    /// The ERC-20 precompile of an asset gets its dummy code when the asset is registered.
     pub fn used_addresses() -> sp_std::vec::Vec<H160> {
-        sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026]
-            .into_iter()
-            .map(hash)
-            .collect()
+        FIXED_PRECOMPILES.into_iter().map(hash).collect()
+    }
+
+    /// Puts the dummy code at `address` if it has no code yet, returns true if written.
+    pub fn put_dummy_code(address: H160) -> bool {
+        if pallet_evm::AccountCodes::<R>::contains_key(address) {
+            return false;
+        }
+        pallet_evm::AccountCodes::<R>::insert(address, DUMMY_PRECOMPILE_CODE.to_vec());
+        true
+    }
+}
+
+impl<R: pallet_evm::Config> RegistrarHandler for ChainXPrecompiles<R> {
+    fn on_register(asset_id: &AssetId, _has_mining_rights: bool) -> DispatchResult {
+        if let Some(address) = asset_precompile_address(*asset_id) {
+            Self::put_dummy_code(address);
+        }
+        Ok(())
     }
 }
 
@@ -36,9 +73,17 @@ where
 /// 0-1023: Ethereum Mainnet Precompiles
 /// 1024-2047 Precompiles that are not in Ethereum Mainnet but are neither ChainX specific
 /// 2048-4095 ChainX specific precompiles
+///
+/// The staking precompile is at 2049, the X-BTC withdrawal precompile is at 2050,
+/// the assets bridge precompile is at 2051, and the ERC-20 precompile of each asset
+/// is at `3072 + AssetId`, i.e. 3072-4095 is reserved for them, see
+/// `xpallet_assets_bridge::precompile::asset_precompile_address`.
 impl<R> PrecompileSet for ChainXPrecompiles<R>
 where
-    R: pallet_evm::Config,
+    R: pallet_evm::Config
+        + xpallet_assets_bridge::Config
+        + xpallet_mining_staking::Config
+        + xpallet_gateway_common::Config,
     Dispatch<R>: Precompile,
 {
     fn execute(
@@ -70,11 +115,27 @@ where
             a if a == hash(1026) => Some(ECRecoverPublicKey::execute(
                 input, target_gas, context, is_static,
             )),
+            // ChainX specific precompiles :
+            a if a == hash(2049) => Some(StakingPrecompile::<R>::execute(
+                a, input, target_gas, context, is_static,
+            )),
+            a if a == hash(2050) => Some(WithdrawalPrecompile::<R>::execute(
+                a, input, target_gas, context, is_static,
+            )),
+            a if a == hash(2051) => Some(AssetsBridgePrecompile::<R>::execute(
+                a, input, target_gas, context, is_static,
+            )),
+            a if Erc20AssetsPrecompile::<R>::is_precompile(a) => Some(
+                Erc20AssetsPrecompile::<R>::execute(a, input, target_gas, context, is_static),
+            ),
             _ => None,
         }
     }
     fn is_precompile(&self, address: H160) -> bool {
-        Self::used_addresses().contains(&address)
+        FIXED_PRECOMPILES
+            .iter()
+            .any(|index| hash(*index) == address)
+            || Erc20AssetsPrecompile::<R>::is_precompile(address)
     }
 }
 
@@ -34,3 +34,4 @@ bench_run xpallet_gateway_common    ./xpallets/gateway/common/src/weights.rs
 bench_run xpallet_gateway_records   ./xpallets/gateway/records/src/weights.rs
 bench_run xpallet_mining_asset      ./xpallets/mining/asset/src/weights.rs
 bench_run xpallet_mining_staking    ./xpallets/mining/staking/src/weights.rs
+bench_run xpallet_transaction_fee   ./xpallets/transaction-fee/src/weights.rs
@@ -44,7 +44,8 @@ pub trait RuntimeApiCollection:
         Balance,
         MiningWeight,
         BlockNumber,
-    > + xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>
+    > + xpallet_system_rpc_runtime_api::XSystemApi<Block, AccountId, BlockNumber>
+    + xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>
     + fp_rpc::EthereumRuntimeRPCApi<Block>
     + fp_rpc::ConvertTransactionRuntimeApi<Block>
 where
@@ -90,7 +91,8 @@ where
             Balance,
             MiningWeight,
             BlockNumber,
-        > + xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>
+        > + xpallet_system_rpc_runtime_api::XSystemApi<Block, AccountId, BlockNumber>
+        + xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>
         + fp_rpc::EthereumRuntimeRPCApi<Block>
         + fp_rpc::ConvertTransactionRuntimeApi<Block>,
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
@@ -4,6 +4,10 @@
 #![allow(clippy::type_complexity)]
 use sc_client_api::{BlockBackend, ExecutorProvider};
 use sc_consensus_babe::SlotProportion;
+use sc_consensus_manual_seal::{
+    consensus::{babe::BabeConsensusDataProvider, timestamp::SlotTimestampProvider},
+    ManualSealParams,
+};
 use sc_executor::{NativeElseWasmExecutor, NativeExecutionDispatch};
 use sc_finality_grandpa::FinalityProofProvider as GrandpaFinalityProofProvider;
 use sc_network::{Event, NetworkService};
@@ -11,13 +15,24 @@ use sc_service::{config::Configuration, error::Error as ServiceError, RpcHandler
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_api::ConstructRuntimeApi;
 use sp_runtime::traits::Block as BlockT;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
 use std::time::Duration;
 
-use chainx_primitives::Block;
+use chainx_keystore::RemoteKeystore;
+use chainx_primitives::{Block, BlockNumber};
 
+mod btc_relay;
 mod client;
+mod log_span;
+mod metrics;
+mod sealing;
 use client::RuntimeApiCollection;
+pub use log_span::LogSpan;
+use log_span::LogSpanBlockImport;
+pub use sealing::Sealing;
 
 // EVM
 use fc_consensus::FrontierBlockImport;
@@ -27,7 +42,6 @@ use fc_rpc_core::types::{FeeHistoryCache, FilterPool};
 use futures::StreamExt;
 use maplit::hashmap;
 use sc_client_api::BlockchainEvents;
-use sc_keystore::LocalKeystore;
 use sc_service::config::PrometheusConfig;
 use sc_service::BasePath;
 use std::{collections::BTreeMap, sync::Mutex};
@@ -47,6 +61,20 @@ type FullGrandpaBlockImport<RuntimeApi, Executor> = sc_finality_grandpa::Grandpa
 
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
+type FullBabeBlockImport<RuntimeApi, Executor> = sc_consensus_babe::BabeBlockImport<
+    Block,
+    FullClient<RuntimeApi, Executor>,
+    FullGrandpaBlockImport<RuntimeApi, Executor>,
+>;
+
+type FullFrontierBlockImport<RuntimeApi, Executor> = LogSpanBlockImport<
+    FrontierBlockImport<
+        Block,
+        FullBabeBlockImport<RuntimeApi, Executor>,
+        FullClient<RuntimeApi, Executor>,
+    >,
+>;
+
 pub type ConsensusResult<RuntimeApi, Executor> = (
     sc_finality_grandpa::GrandpaBlockImport<
         FullBackend,
@@ -57,6 +85,15 @@ pub type ConsensusResult<RuntimeApi, Executor> = (
     sc_finality_grandpa::LinkHalf<Block, FullClient<RuntimeApi, Executor>, FullSelectChain>,
 );
 
+/// The number of the best block imported by the node.
+static BEST_BLOCK_NUMBER: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the number of the best block imported by the node, which is attached to the
+/// structured log records.
+pub fn best_block_number() -> BlockNumber {
+    BEST_BLOCK_NUMBER.load(Ordering::Relaxed)
+}
+
 pub fn frontier_database_dir(config: &Configuration) -> std::path::PathBuf {
     let config_dir = config
         .base_path
@@ -68,6 +105,17 @@ pub fn frontier_database_dir(config: &Configuration) -> std::path::PathBuf {
     config_dir.join("frontier").join("db")
 }
 
+pub fn indexer_database_dir(config: &Configuration) -> std::path::PathBuf {
+    let config_dir = config
+        .base_path
+        .as_ref()
+        .map(|base_path| base_path.config_dir(config.chain_spec.id()))
+        .unwrap_or_else(|| {
+            BasePath::from_project("", "", "chainx").config_dir(config.chain_spec.id())
+        });
+    config_dir.join("indexer").join("db")
+}
+
 pub fn open_frontier_backend(config: &Configuration) -> Result<Arc<fc_db::Backend<Block>>, String> {
     Ok(Arc::new(fc_db::Backend::<Block>::new(
         &fc_db::DatabaseSettings {
@@ -91,39 +139,31 @@ fn set_prometheus_registry(config: &mut Configuration) -> Result<(), ServiceErro
     Ok(())
 }
 
-pub fn new_partial<RuntimeApi, Executor>(
-    config: &mut Configuration,
-) -> Result<
-    sc_service::PartialComponents<
-        FullClient<RuntimeApi, Executor>,
-        FullBackend,
-        FullSelectChain,
-        sc_consensus::DefaultImportQueue<Block, FullClient<RuntimeApi, Executor>>,
-        sc_transaction_pool::FullPool<Block, FullClient<RuntimeApi, Executor>>,
+pub type PartialComponents<RuntimeApi, Executor> = sc_service::PartialComponents<
+    FullClient<RuntimeApi, Executor>,
+    FullBackend,
+    FullSelectChain,
+    sc_consensus::DefaultImportQueue<Block, FullClient<RuntimeApi, Executor>>,
+    sc_transaction_pool::FullPool<Block, FullClient<RuntimeApi, Executor>>,
+    (
         (
-            (
-                sc_consensus_babe::BabeBlockImport<
-                    Block,
-                    FullClient<RuntimeApi, Executor>,
-                    FullGrandpaBlockImport<RuntimeApi, Executor>,
-                >,
-                sc_finality_grandpa::LinkHalf<
-                    Block,
-                    FullClient<RuntimeApi, Executor>,
-                    FullSelectChain,
-                >,
-                sc_consensus_babe::BabeLink<Block>,
-            ),
-            Option<Telemetry>,
-            (
-                Option<FilterPool>,
-                FeeHistoryCache,
-                Arc<fc_db::Backend<Block>>,
-            ),
+            FullBabeBlockImport<RuntimeApi, Executor>,
+            FullFrontierBlockImport<RuntimeApi, Executor>,
+            sc_finality_grandpa::LinkHalf<Block, FullClient<RuntimeApi, Executor>, FullSelectChain>,
+            sc_consensus_babe::BabeLink<Block>,
         ),
-    >,
-    ServiceError,
->
+        Option<Telemetry>,
+        (
+            Option<FilterPool>,
+            FeeHistoryCache,
+            Arc<fc_db::Backend<Block>>,
+        ),
+    ),
+>;
+
+pub fn new_partial<RuntimeApi, Executor>(
+    config: &mut Configuration,
+) -> Result<PartialComponents<RuntimeApi, Executor>, ServiceError>
 where
     RuntimeApi:
         ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
@@ -131,12 +171,20 @@ where
         RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
     Executor: NativeExecutionDispatch + 'static,
 {
-    if config.keystore_remote.is_some() {
-        return Err(ServiceError::Other(
-            "Remote Keystores are not supported.".into(),
-        ));
-    }
+    new_partial_base(config, None)
+}
 
+fn new_partial_base<RuntimeApi, Executor>(
+    config: &mut Configuration,
+    sealing: Option<Sealing>,
+) -> Result<PartialComponents<RuntimeApi, Executor>, ServiceError>
+where
+    RuntimeApi:
+        ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
+    RuntimeApi::RuntimeApi:
+        RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
+    Executor: NativeExecutionDispatch + 'static,
+{
     set_prometheus_registry(config)?;
 
     let telemetry = config
@@ -200,40 +248,53 @@ where
         client.clone(),
     )?;
 
-    let frontier_block_import = FrontierBlockImport::new(
+    let frontier_block_import = LogSpanBlockImport::new(FrontierBlockImport::new(
         babe_block_import.clone(),
         client.clone(),
         frontier_backend.clone(),
-    );
+    ));
 
     let slot_duration = babe_link.config().slot_duration();
-    let import_queue = sc_consensus_babe::import_queue(
-        babe_link.clone(),
-        frontier_block_import,
-        Some(Box::new(justification_import)),
-        client.clone(),
-        select_chain.clone(),
-        move |_, ()| async move {
-            let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+    let import_queue = if sealing.is_some() {
+        sc_consensus_manual_seal::import_queue(
+            Box::new(frontier_block_import.clone()),
+            &task_manager.spawn_essential_handle(),
+            config.prometheus_registry(),
+        )
+    } else {
+        sc_consensus_babe::import_queue(
+            babe_link.clone(),
+            frontier_block_import.clone(),
+            Some(Box::new(justification_import)),
+            client.clone(),
+            select_chain.clone(),
+            move |_, ()| async move {
+                let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
-            let slot =
-                sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-                    *timestamp,
-                    slot_duration,
-                );
+                let slot =
+                    sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+                        *timestamp,
+                        slot_duration,
+                    );
 
-            let uncles =
-                sp_authorship::InherentDataProvider::<<Block as BlockT>::Header>::check_inherents();
+                let uncles =
+                    sp_authorship::InherentDataProvider::<<Block as BlockT>::Header>::check_inherents();
 
-            Ok((timestamp, slot, uncles))
-        },
-        &task_manager.spawn_essential_handle(),
-        config.prometheus_registry(),
-        sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
-        telemetry.as_ref().map(|x| x.handle()),
-    )?;
+                Ok((timestamp, slot, uncles))
+            },
+            &task_manager.spawn_essential_handle(),
+            config.prometheus_registry(),
+            sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+            telemetry.as_ref().map(|x| x.handle()),
+        )?
+    };
 
-    let import_setup = (babe_block_import, grandpa_link, babe_link);
+    let import_setup = (
+        babe_block_import,
+        frontier_block_import,
+        grandpa_link,
+        babe_link,
+    );
     let frontier_setup = (filter_pool, fee_history_cache, frontier_backend);
 
     Ok(sc_service::PartialComponents {
@@ -269,16 +330,20 @@ where
     pub rpc_handlers: RpcHandlers,
 }
 
-fn remote_keystore(_url: &str) -> Result<Arc<LocalKeystore>, &'static str> {
-    // FIXME: here would the concrete keystore be built,
-    //        must return a concrete type (NOT `LocalKeystore`) that
-    //        implements `CryptoStore` and `SyncCryptoStore`
-    Err("Remote Keystore not supported.")
+fn remote_keystore(url: &str) -> Result<Arc<RemoteKeystore>, String> {
+    RemoteKeystore::open(url, std::env::var(chainx_keystore::TOKEN_ENV).ok()).map(Arc::new)
 }
 
 /// Creates a full service from the configuration.
+///
+/// The blocks are sealed by manual seal instead of BABE and GRANDPA if `sealing` is given,
+/// the Bitcoin relay offchain worker reads from bitcoind if `btc_relay_url` is given, and
+/// the finalized blocks are indexed by account if `indexer` is set.
 pub fn new_full_base<RuntimeApi, Executor>(
     mut config: Configuration,
+    sealing: Option<Sealing>,
+    btc_relay_url: Option<&str>,
+    indexer: bool,
 ) -> Result<NewFullBase<RuntimeApi, Executor>, ServiceError>
 where
     RuntimeApi:
@@ -296,7 +361,7 @@ where
         select_chain,
         transaction_pool,
         other: (import_setup, mut telemetry, frontier_setup),
-    } = new_partial(&mut config)?;
+    } = new_partial_base(&mut config, sealing)?;
 
     if let Some(url) = &config.keystore_remote {
         match remote_keystore(url) {
@@ -310,7 +375,7 @@ where
         };
     }
 
-    let (babe_block_import, grandpa_link, babe_link) = import_setup;
+    let (babe_block_import, frontier_block_import, grandpa_link, babe_link) = import_setup;
     let (filter_pool, fee_history_cache, frontier_backend) = frontier_setup;
 
     let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
@@ -348,6 +413,7 @@ where
         })?;
 
     if config.offchain_worker.enabled {
+        btc_relay::set_endpoint(&*backend, btc_relay_url).map_err(ServiceError::Other)?;
         sc_service::build_offchain_workers(
             &config,
             task_manager.spawn_handle(),
@@ -365,6 +431,38 @@ where
     let name = config.network.node_name.clone();
     let enable_grandpa = !config.disable_grandpa;
     let prometheus_registry = config.prometheus_registry().cloned();
+    // The outer event to decode the `FeePaid` with is the only runtime specific part of the
+    // metrics.
+    let fee_paid_decoder: metrics::FeePaidDecoder = if config.chain_spec.is_chainx() {
+        metrics::fee_paid::<chainx_runtime::Runtime, chainx_runtime::Event>
+    } else if config.chain_spec.is_malan() {
+        metrics::fee_paid::<malan_runtime::Runtime, malan_runtime::Event>
+    } else {
+        metrics::fee_paid::<dev_runtime::Runtime, dev_runtime::Event>
+    };
+    let indexer = if indexer {
+        let db = chainx_indexer::Database::open(&indexer_database_dir(&config))
+            .map_err(ServiceError::Other)?;
+        let decoder = if config.chain_spec.is_chainx() {
+            chainx_indexer::RuntimeDecoder::new(
+                chainx_runtime::VERSION.spec_version,
+                chainx_indexer::decode_events::<chainx_runtime::Runtime, chainx_runtime::Event>,
+            )
+        } else if config.chain_spec.is_malan() {
+            chainx_indexer::RuntimeDecoder::new(
+                malan_runtime::VERSION.spec_version,
+                chainx_indexer::decode_events::<malan_runtime::Runtime, malan_runtime::Event>,
+            )
+        } else {
+            chainx_indexer::RuntimeDecoder::new(
+                dev_runtime::VERSION.spec_version,
+                chainx_indexer::decode_events::<dev_runtime::Runtime, dev_runtime::Event>,
+            )
+        };
+        Some((Arc::new(db), decoder))
+    } else {
+        None
+    };
 
     // EVM
     let subscription_task_executor =
@@ -379,6 +477,9 @@ where
         prometheus_registry.clone(),
     ));
 
+    let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+    let command_sink = sealing.map(|_| command_sink);
+
     let rpc_extensions_builder = {
         let justification_stream = grandpa_link.justification_stream();
         let shared_authority_set = grandpa_link.shared_authority_set().clone();
@@ -405,6 +506,8 @@ where
         let fee_history_cache = fee_history_cache.clone();
         let is_authority = false;
         let max_past_logs = 10000;
+        let command_sink = command_sink.clone();
+        let indexer = indexer.as_ref().map(|(db, _)| db.clone());
 
         Box::new(move |deny_unsafe, subscription_executor| {
             let deps = chainx_rpc::FullDeps {
@@ -437,6 +540,8 @@ where
                     overrides: overrides.clone(),
                     block_data_cache: block_data_cache.clone(),
                 },
+                command_sink: command_sink.clone(),
+                indexer: indexer.clone(),
             };
 
             chainx_rpc::create_full(deps, subscription_task_executor.clone()).map_err(Into::into)
@@ -501,7 +606,94 @@ where
         EthTask::ethereum_schema_cache_task(Arc::clone(&client), Arc::clone(&frontier_backend)),
     );
 
-    if let sc_service::config::Role::Authority { .. } = &role {
+    BEST_BLOCK_NUMBER.store(client.chain_info().best_number, Ordering::Relaxed);
+    task_manager.spawn_handle().spawn(
+        "best-block-number",
+        None,
+        client
+            .import_notification_stream()
+            .for_each(|notification| {
+                if notification.is_new_best {
+                    BEST_BLOCK_NUMBER.store(notification.header.number, Ordering::Relaxed);
+                }
+                futures::future::ready(())
+            }),
+    );
+
+    if let Some((db, decoder)) = indexer {
+        task_manager.spawn_handle().spawn_blocking(
+            "chainx-indexer",
+            None,
+            chainx_indexer::run(client.clone(), db, decoder),
+        );
+    }
+
+    if let Some(registry) = prometheus_registry.as_ref() {
+        task_manager.spawn_handle().spawn(
+            "chainx-metrics",
+            None,
+            metrics::task(client.clone(), registry, fee_paid_decoder)?,
+        );
+    }
+
+    if let Some(sealing) = sealing {
+        let proposer = sc_basic_authorship::ProposerFactory::new(
+            task_manager.spawn_handle(),
+            client.clone(),
+            transaction_pool.clone(),
+            prometheus_registry.as_ref(),
+            telemetry.as_ref().map(|x| x.handle()),
+        );
+
+        let commands_stream = sealing::commands_stream(
+            sealing,
+            rpc_commands.boxed(),
+            transaction_pool
+                .pool()
+                .validated_pool()
+                .import_notification_stream(),
+        );
+
+        // Claim the BABE slots on behalf of the genesis authorities, so that the runtime
+        // keeps working as it does under BABE.
+        let consensus_data_provider = BabeConsensusDataProvider::new(
+            client.clone(),
+            keystore_container.sync_keystore(),
+            babe_link.epoch_changes().clone(),
+            babe_link.config().genesis_authorities.clone(),
+        )
+        .map_err(|e| ServiceError::Other(format!("{:?}", e)))?;
+
+        let client_clone = client.clone();
+        // Import the sealed blocks through the same Frontier import as the import queue.
+        let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+            block_import: frontier_block_import,
+            env: proposer,
+            client: client.clone(),
+            pool: transaction_pool.clone(),
+            commands_stream,
+            select_chain,
+            consensus_data_provider: Some(Box::new(consensus_data_provider)),
+            create_inherent_data_providers: move |_, ()| {
+                let client_clone = client_clone.clone();
+                async move {
+                    let timestamp = SlotTimestampProvider::new_babe(client_clone)
+                        .map_err(|e| format!("{:?}", e))?;
+
+                    let slot = sp_consensus_babe::inherents::InherentDataProvider::new(
+                        timestamp.slot().into(),
+                    );
+
+                    Ok((timestamp, slot))
+                }
+            },
+        });
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "manual-seal",
+            Some("block-authoring"),
+            manual_seal,
+        );
+    } else if let sc_service::config::Role::Authority { .. } = &role {
         let proposer = sc_basic_authorship::ProposerFactory::new(
             task_manager.spawn_handle(),
             client.clone(),
@@ -611,7 +803,7 @@ where
         protocol_name: grandpa_protocol_name,
     };
 
-    if enable_grandpa {
+    if enable_grandpa && sealing.is_none() {
         // start the full GRANDPA voter
         // NOTE: non-authorities could run the GRANDPA observer protocol, but at
         // this point the full voter should provide better guarantees of block
@@ -649,7 +841,11 @@ where
 }
 
 /// Builds a new service for a full client.
-pub fn new_full<RuntimeApi, Executor>(config: Configuration) -> Result<TaskManager, ServiceError>
+pub fn new_full<RuntimeApi, Executor>(
+    config: Configuration,
+    btc_relay_url: Option<&str>,
+    indexer: bool,
+) -> Result<TaskManager, ServiceError>
 where
     RuntimeApi:
         ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
@@ -657,7 +853,8 @@ where
         RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
     Executor: NativeExecutionDispatch + 'static,
 {
-    new_full_base(config).map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
+    new_full_base(config, None, btc_relay_url, indexer)
+        .map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
 }
 
 /// Can be called for a `Configuration` to check if it is a configuration for the `ChainX` network.
@@ -684,12 +881,49 @@ impl IdentifyVariant for Box<dyn sc_service::ChainSpec> {
     }
 }
 
-pub fn build_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn build_full(
+    config: Configuration,
+    btc_relay_url: Option<&str>,
+    indexer: bool,
+) -> Result<TaskManager, ServiceError> {
     if config.chain_spec.is_chainx() {
-        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(config)
+        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(
+            config,
+            btc_relay_url,
+            indexer,
+        )
     } else if config.chain_spec.is_malan() {
-        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(config)
+        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(
+            config,
+            btc_relay_url,
+            indexer,
+        )
     } else {
-        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(config)
+        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(
+            config,
+            btc_relay_url,
+            indexer,
+        )
+    }
+}
+
+/// Builds a development node which seals the blocks on demand.
+pub fn build_dev(
+    config: Configuration,
+    sealing: Sealing,
+    btc_relay_url: Option<&str>,
+    indexer: bool,
+) -> Result<TaskManager, ServiceError> {
+    if !config.chain_spec.is_dev() {
+        return Err(ServiceError::Other(
+            "`--sealing` is only supported by the dev chain".into(),
+        ));
     }
+    new_full_base::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(
+        config,
+        Some(sealing),
+        btc_relay_url,
+        indexer,
+    )
+    .map(|base| base.task_manager)
 }
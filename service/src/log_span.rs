@@ -0,0 +1,77 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Fills the mapped diagnostic context of the logger, which is written as the `span` of the
+//! JSON log records, while importing the blocks.
+
+use std::collections::HashMap;
+
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sp_consensus::CacheKeyId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+
+/// Keeps the fields in the mapped diagnostic context of the current thread until dropped.
+pub struct LogSpan(Vec<&'static str>);
+
+impl LogSpan {
+    pub fn enter(fields: Vec<(&'static str, String)>) -> Self {
+        let keys = fields.iter().map(|(key, _)| *key).collect();
+        for (key, value) in fields {
+            log_mdc::insert(key, value);
+        }
+        Self(keys)
+    }
+}
+
+impl Drop for LogSpan {
+    fn drop(&mut self) {
+        for key in &self.0 {
+            log_mdc::remove(key);
+        }
+    }
+}
+
+/// Wraps the block import so that the log records emitted during the import carry the
+/// number, hash and origin of the block.
+///
+/// The import queue drives the import on its own blocking thread, so the thread local
+/// context is not shared with the other tasks.
+#[derive(Clone)]
+pub struct LogSpanBlockImport<I>(I);
+
+impl<I> LogSpanBlockImport<I> {
+    pub fn new(inner: I) -> Self {
+        Self(inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl<B, I> BlockImport<B> for LogSpanBlockImport<I>
+where
+    B: BlockT,
+    I: BlockImport<B> + Send,
+{
+    type Error = I::Error;
+    type Transaction = I::Transaction;
+
+    async fn check_block(
+        &mut self,
+        block: BlockCheckParams<B>,
+    ) -> Result<ImportResult, Self::Error> {
+        self.0.check_block(block).await
+    }
+
+    async fn import_block(
+        &mut self,
+        block: BlockImportParams<B, Self::Transaction>,
+        cache: HashMap<CacheKeyId, Vec<u8>>,
+    ) -> Result<ImportResult, Self::Error> {
+        let _span = LogSpan::enter(vec![
+            (
+                "import",
+                format!("#{} ({:?})", block.header.number(), block.post_hash()),
+            ),
+            ("origin", format!("{:?}", block.origin)),
+        ]);
+        self.0.import_block(block, cache).await
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Prometheus gauges of the gateway, DEX and staking state, refreshed on every new best block
+//! so that the operators can alert on a lagging BTC relay, a growing withdrawal queue or a
+//! stuck trustee transition.
+
+use std::sync::Arc;
+
+use codec::Decode;
+use futures::StreamExt;
+
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{
+    hashing::{twox_128, twox_64},
+    storage::StorageKey,
+};
+use sp_runtime::generic::BlockId;
+use substrate_prometheus_endpoint::{
+    register, Counter, GaugeVec, Opts, PrometheusError, Registry, F64, U64,
+};
+
+use chainx_primitives::{AccountId, Balance, Block, BlockNumber, Hash};
+use xpallet_dex_spot_rpc_runtime_api::XSpotApi;
+use xpallet_gateway_bitcoin::{types::BtcHeaderIndex, BtcDepositCache};
+use xpallet_gateway_records_rpc_runtime_api::{Chain, WithdrawalState, XGatewayRecordsApi};
+use xpallet_mining_staking_rpc_runtime_api::{VoteWeight, XStakingApi};
+
+const LOG_TARGET: &str = "metrics";
+
+/// Decodes the `System::Events` of a block into the total of its `FeePaid`.
+///
+/// The outer event differs among the runtimes, see [`fee_paid`].
+pub type FeePaidDecoder = fn(&[u8]) -> Option<Balance>;
+
+/// Sums the author and the reward pot parts of every `FeePaid` in the encoded events.
+pub fn fee_paid<R, E>(mut events: &[u8]) -> Option<Balance>
+where
+    R: xpallet_transaction_fee::Config,
+    xpallet_transaction_fee::BalanceOf<R>: Into<Balance>,
+    E: Decode + TryInto<xpallet_transaction_fee::Event<R>>,
+{
+    let records = Vec::<frame_system::EventRecord<E, Hash>>::decode(&mut events).ok()?;
+    Some(
+        records
+            .into_iter()
+            .filter_map(|record| record.event.try_into().ok())
+            .map(|event| match event {
+                xpallet_transaction_fee::Event::FeePaid(_, author_fee, _, reward_pot_fee) => {
+                    author_fee.into().saturating_add(reward_pot_fee.into())
+                }
+                _ => 0,
+            })
+            .fold(0, Balance::saturating_add),
+    )
+}
+
+fn storage_prefix(pallet: &str, storage: &str) -> Vec<u8> {
+    let mut key = twox_128(pallet.as_bytes()).to_vec();
+    key.extend(twox_128(storage.as_bytes()));
+    key
+}
+
+struct Metrics {
+    btc_header_height: GaugeVec<U64>,
+    pending_withdrawals: GaugeVec<U64>,
+    pending_withdrawal_balance: GaugeVec<F64>,
+    btc_pending_deposits: GaugeVec<U64>,
+    trustee_transition: GaugeVec<U64>,
+    active_validators: GaugeVec<U64>,
+    spot_spread: GaugeVec<F64>,
+    fee_paid: Counter<F64>,
+}
+
+impl Metrics {
+    fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            btc_header_height: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_btc_header_height",
+                        "Height of the best and the confirmed Bitcoin headers on chain",
+                    ),
+                    &["index"],
+                )?,
+                registry,
+            )?,
+            pending_withdrawals: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_pending_withdrawals",
+                        "Number of the unfinished withdrawals per chain",
+                    ),
+                    &["chain", "state"],
+                )?,
+                registry,
+            )?,
+            pending_withdrawal_balance: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_pending_withdrawal_balance",
+                        "Balance of the unfinished withdrawals per chain, in the asset units",
+                    ),
+                    &["chain"],
+                )?,
+                registry,
+            )?,
+            btc_pending_deposits: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_btc_pending_deposits",
+                        "Number and satoshi of the BTC deposits without a bound account",
+                    ),
+                    &["unit"],
+                )?,
+                registry,
+            )?,
+            trustee_transition: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_trustee_transition",
+                        "Whether the trustee transition of the chain is in progress",
+                    ),
+                    &["chain"],
+                )?,
+                registry,
+            )?,
+            active_validators: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_validators",
+                        "Number of the validating and the registered validators",
+                    ),
+                    &["status"],
+                )?,
+                registry,
+            )?,
+            spot_spread: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_spot_spread",
+                        "Lowest ask minus highest bid of the trading pair, in the price units",
+                    ),
+                    &["pair"],
+                )?,
+                registry,
+            )?,
+            fee_paid: register(
+                Counter::new(
+                    "chainx_fee_paid_total",
+                    "Transaction fee paid in PCX by the new best blocks, in the PCX units",
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    fn update<B, C>(
+        &self,
+        client: &C,
+        hash: Hash,
+        fee_paid_decoder: FeePaidDecoder,
+    ) -> Result<(), String>
+    where
+        B: Backend<Block>,
+        C: ProvideRuntimeApi<Block> + StorageProvider<Block, B>,
+        C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+            + XStakingApi<Block, AccountId, Balance, VoteWeight, BlockNumber>
+            + XSpotApi<Block, AccountId, Balance, BlockNumber, Balance>,
+    {
+        let at = BlockId::Hash(hash);
+        let storage = |key: Vec<u8>| {
+            client
+                .storage(&at, &StorageKey(key))
+                .map_err(|e| e.to_string())
+                .map(|data| data.map(|data| data.0))
+        };
+        let decode_err = |name: &str| format!("failed to decode {}", name);
+
+        for name in ["BestIndex", "ConfirmedIndex"] {
+            if let Some(data) = storage(storage_prefix("XGatewayBitcoin", name))? {
+                let index = BtcHeaderIndex::decode(&mut &data[..]).map_err(|_| decode_err(name))?;
+                self.btc_header_height
+                    .with_label_values(&[name])
+                    .set(index.height.into());
+            }
+        }
+
+        let api = client.runtime_api();
+        for chain in Chain::iter() {
+            let label = format!("{:?}", chain);
+            let withdrawals = api
+                .withdrawal_list_by_chain(&at, *chain)
+                .map_err(|e| e.to_string())?;
+            for state in [WithdrawalState::Applying, WithdrawalState::Processing] {
+                let count = withdrawals.values().filter(|w| w.state == state).count();
+                self.pending_withdrawals
+                    .with_label_values(&[&label, &format!("{:?}", state)])
+                    .set(count as u64);
+            }
+            let balance = withdrawals
+                .values()
+                .map(|w| w.balance)
+                .fold(0, Balance::saturating_add);
+            self.pending_withdrawal_balance
+                .with_label_values(&[&label])
+                .set(balance as f64);
+
+            let mut key = storage_prefix("XGatewayCommon", "TrusteeTransitionStatus");
+            let encoded_chain = codec::Encode::encode(chain);
+            key.extend(twox_64(&encoded_chain));
+            key.extend(encoded_chain);
+            let in_transition = match storage(key)? {
+                Some(data) => bool::decode(&mut &data[..])
+                    .map_err(|_| decode_err("TrusteeTransitionStatus"))?,
+                None => false,
+            };
+            self.trustee_transition
+                .with_label_values(&[&label])
+                .set(in_transition.into());
+        }
+
+        let (mut deposits, mut satoshi) = (0u64, 0u64);
+        let prefix = StorageKey(storage_prefix("XGatewayBitcoin", "PendingDeposits"));
+        for (_, data) in client
+            .storage_pairs(&at, &prefix)
+            .map_err(|e| e.to_string())?
+        {
+            let caches = Vec::<BtcDepositCache>::decode(&mut &data.0[..])
+                .map_err(|_| decode_err("PendingDeposits"))?;
+            deposits += caches.len() as u64;
+            satoshi = caches
+                .iter()
+                .map(|cache| cache.balance)
+                .fold(satoshi, u64::saturating_add);
+        }
+        self.btc_pending_deposits
+            .with_label_values(&["count"])
+            .set(deposits);
+        self.btc_pending_deposits
+            .with_label_values(&["satoshi"])
+            .set(satoshi);
+
+        let validators = api.validators(&at).map_err(|e| e.to_string())?;
+        let validating = validators.iter().filter(|v| v.is_validating).count();
+        self.active_validators
+            .with_label_values(&["validating"])
+            .set(validating as u64);
+        self.active_validators
+            .with_label_values(&["registered"])
+            .set(validators.len() as u64);
+
+        for pair in api.trading_pairs(&at).map_err(|e| e.to_string())? {
+            let label = format!(
+                "{}/{}",
+                pair.profile.currency_pair.base, pair.profile.currency_pair.quote
+            );
+            let handicap = pair.handicap;
+            // Either side of an empty order book is zero.
+            if handicap.highest_bid == 0 || handicap.lowest_ask == 0 {
+                let _ = self.spot_spread.remove_label_values(&[&label]);
+            } else {
+                let spread = handicap.lowest_ask as f64 - handicap.highest_bid as f64;
+                self.spot_spread.with_label_values(&[&label]).set(spread);
+            }
+        }
+
+        if let Some(events) = storage(storage_prefix("System", "Events"))? {
+            let fee = fee_paid_decoder(&events).ok_or_else(|| decode_err("System::Events"))?;
+            self.fee_paid.inc_by(fee as f64);
+        }
+
+        Ok(())
+    }
+}
+
+/// Registers the gauges and refreshes them on every new best block.
+pub(crate) fn task<B, C>(
+    client: Arc<C>,
+    registry: &Registry,
+    fee_paid_decoder: FeePaidDecoder,
+) -> Result<impl std::future::Future<Output = ()>, PrometheusError>
+where
+    B: Backend<Block>,
+    C: ProvideRuntimeApi<Block> + StorageProvider<Block, B> + BlockchainEvents<Block>,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XStakingApi<Block, AccountId, Balance, VoteWeight, BlockNumber>
+        + XSpotApi<Block, AccountId, Balance, BlockNumber, Balance>,
+{
+    let metrics = Metrics::register(registry)?;
+    Ok(client
+        .import_notification_stream()
+        .for_each(move |notification| {
+            if notification.is_new_best {
+                if let Err(e) = metrics.update(&*client, notification.hash, fee_paid_decoder) {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "Failed to update the metrics at {}: {}",
+                        notification.hash,
+                        e
+                    );
+                }
+            }
+            futures::future::ready(())
+        }))
+}
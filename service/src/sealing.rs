@@ -0,0 +1,78 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use std::{str::FromStr, time::Duration};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use sc_consensus_manual_seal::EngineCommand;
+
+use chainx_primitives::Hash;
+
+/// The block production mode of a development node running on manual seal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+    /// Seal a new block as soon as a transaction enters the pool.
+    Instant,
+    /// Seal the blocks only on `engine_createBlock`.
+    Manual,
+    /// Seal a new block every given milliseconds.
+    Interval(u64),
+}
+
+impl FromStr for Sealing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "instant" => Ok(Self::Instant),
+            "manual" => Ok(Self::Manual),
+            millis => millis
+                .parse::<u64>()
+                .ok()
+                .filter(|millis| *millis > 0)
+                .map(Self::Interval)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid sealing `{}`, expected `instant`, `manual` or an interval in milliseconds",
+                        millis
+                    )
+                }),
+        }
+    }
+}
+
+/// The command to seal and finalize a new block on top of the best block.
+fn seal_new_block() -> EngineCommand<Hash> {
+    EngineCommand::SealNewBlock {
+        create_empty: true,
+        finalize: true,
+        parent_hash: None,
+        sender: None,
+    }
+}
+
+/// Merges the commands from the `engine_*` RPC with the ones implied by the sealing mode.
+pub(crate) fn commands_stream<TxNotifications>(
+    sealing: Sealing,
+    rpc_commands: BoxStream<'static, EngineCommand<Hash>>,
+    tx_notifications: TxNotifications,
+) -> BoxStream<'static, EngineCommand<Hash>>
+where
+    TxNotifications: stream::Stream + Send + 'static,
+{
+    match sealing {
+        Sealing::Manual => rpc_commands,
+        Sealing::Instant => {
+            stream::select(rpc_commands, tx_notifications.map(|_| seal_new_block())).boxed()
+        }
+        Sealing::Interval(millis) => {
+            let ticks = stream::unfold((), move |()| async move {
+                futures_timer::Delay::new(Duration::from_millis(millis)).await;
+                Some((seal_new_block(), ()))
+            });
+            stream::select(rpc_commands, ticks).boxed()
+        }
+    }
+}
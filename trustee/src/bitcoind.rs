@@ -0,0 +1,141 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The queries to bitcoind, none of them needs a wallet.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    rpc::{Client, Error, Result},
+    tx::Utxo,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressInfo {
+    isvalid: bool,
+    script_pub_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Unspent {
+    txid: String,
+    vout: u32,
+    script_pub_key: String,
+    amount: f64,
+}
+
+#[derive(Deserialize)]
+struct ScanResult {
+    success: bool,
+    unspents: Vec<Unspent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TxOut {
+    value: f64,
+    script_pub_key: ScriptPubKey,
+}
+
+#[derive(Deserialize)]
+struct ScriptPubKey {
+    hex: String,
+}
+
+fn to_satoshi(btc: f64) -> u64 {
+    (btc * 100_000_000.0).round() as u64
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>> {
+    hex::decode(s).map_err(|_| Error::Other(format!("invalid hex `{}` from bitcoind", s)))
+}
+
+/// Converts the txid displayed by bitcoind to the internal byte order.
+pub fn parse_txid(txid: &str) -> Result<[u8; 32]> {
+    let mut bytes = decode_hex(txid)?;
+    if bytes.len() != 32 {
+        return Err(Error::Other(format!("invalid txid `{}`", txid)));
+    }
+    bytes.reverse();
+    let mut txid = [0u8; 32];
+    txid.copy_from_slice(&bytes);
+    Ok(txid)
+}
+
+/// Converts the txid in the internal byte order to the one displayed by bitcoind.
+pub fn display_txid(txid: &[u8; 32]) -> String {
+    let mut bytes = *txid;
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+pub struct Bitcoind(Client);
+
+impl Bitcoind {
+    pub fn new(url: &str) -> Result<Self> {
+        Client::new(url).map(Self)
+    }
+
+    /// Returns the output script of the address.
+    pub fn script_pubkey(&self, addr: &str) -> Result<Vec<u8>> {
+        let info: AddressInfo = self.0.call("validateaddress", json!([addr]))?;
+        match info.script_pub_key {
+            Some(script) if info.isvalid => decode_hex(&script),
+            _ => Err(Error::Other(format!("invalid bitcoin address {}", addr))),
+        }
+    }
+
+    /// Returns the confirmed unspent outputs of the address.
+    pub fn utxos(&self, addr: &str) -> Result<Vec<Utxo>> {
+        let result: ScanResult = self.0.call(
+            "scantxoutset",
+            json!(["start", [format!("addr({})", addr)]]),
+        )?;
+        if !result.success {
+            return Err("scantxoutset was aborted".into());
+        }
+        result
+            .unspents
+            .into_iter()
+            .map(|unspent| {
+                Ok(Utxo {
+                    txid: parse_txid(&unspent.txid)?,
+                    vout: unspent.vout,
+                    value: to_satoshi(unspent.amount),
+                    script_pubkey: decode_hex(&unspent.script_pub_key)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the output if it is still unspent, including by the transactions in mempool.
+    pub fn utxo(&self, txid: &str, vout: u32) -> Result<Option<Utxo>> {
+        let out: Option<TxOut> = self.0.call("gettxout", json!([txid, vout]))?;
+        out.map(|out| {
+            Ok(Utxo {
+                txid: parse_txid(txid)?,
+                vout,
+                value: to_satoshi(out.value),
+                script_pubkey: decode_hex(&out.script_pub_key.hex)?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Broadcasts the transaction, returns its txid.
+    pub fn send_raw_transaction(&self, tx: &[u8]) -> Result<String> {
+        self.0.call("sendrawtransaction", json!([hex::encode(tx)]))
+    }
+}
+
+#[test]
+fn test_txid_order() {
+    let displayed = "4ac0b02e3c2a2e8fd64bfcfa6d3d1de1d57f26e47b39c8e5cb0d82d0a1f5e7a4";
+    let txid = parse_txid(displayed).unwrap();
+    assert_eq!(txid[0], 0xa4);
+    assert_eq!(display_txid(&txid), displayed);
+    assert_eq!(to_satoshi(0.1), 10_000_000);
+    assert_eq!(to_satoshi(21.00000001), 2_100_000_001);
+}
@@ -0,0 +1,295 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Turns the pending withdrawals into a signed withdrawal proposal.
+//!
+//! The coordinator picks the first leaf of the hot address whose signers are all online,
+//! builds the transaction, runs the two MuSig2 rounds with them and submits the signed
+//! transaction with `create_taproot_withdraw_tx`. The proposal is only broadcast to bitcoin
+//! once it is on chain, see [`broadcast_proposal`].
+
+use std::collections::BTreeMap;
+
+use rand::RngCore;
+use serde_json::json;
+
+use sp_core::{sr25519, Bytes, H256};
+
+use crate::{
+    bitcoind::display_txid,
+    cosigner::Trustee,
+    musig::{self, PubNonce},
+    protocol::{CosignerApi, RpcInput, RpcOutput, SigningRequest},
+    rpc::{Client, Error, Result},
+    session::HotSession,
+    tx::{self, leaf_script, Output},
+    LOG_TARGET,
+};
+
+/// A signer of the leaf, either this trustee or a peer.
+enum Cosigner<'a> {
+    Local(&'a Trustee),
+    Remote(&'a Client),
+}
+
+impl Cosigner<'_> {
+    fn nonces(&self, request: &SigningRequest) -> Result<Vec<PubNonce>> {
+        let nonces: Vec<Bytes> = match self {
+            Self::Local(trustee) => trustee
+                .nonces(request.clone())
+                .map_err(|e| Error::Rpc(e.message))?,
+            Self::Remote(client) => client.call("trustee_nonces", json!([request]))?,
+        };
+        nonces.iter().map(|n| PubNonce::from_bytes(n)).collect()
+    }
+
+    fn partial_sign(&self, session_id: H256, agg_nonces: &[PubNonce]) -> Result<Vec<[u8; 32]>> {
+        let agg_nonces = agg_nonces
+            .iter()
+            .map(|n| Bytes(n.to_bytes()))
+            .collect::<Vec<_>>();
+        let partials: Vec<Bytes> = match self {
+            Self::Local(trustee) => trustee
+                .partial_sign(session_id, agg_nonces)
+                .map_err(|e| Error::Rpc(e.message))?,
+            Self::Remote(client) => {
+                client.call("trustee_partialSign", json!([session_id, agg_nonces]))?
+            }
+        };
+        partials
+            .iter()
+            .map(|s| {
+                s[..]
+                    .try_into()
+                    .map_err(|_| Error::from("invalid partial signature"))
+            })
+            .collect()
+    }
+}
+
+pub struct Coordinator {
+    pub trustee: Trustee,
+    pub peers: Vec<Client>,
+    /// The account submitting `create_taproot_withdraw_tx`.
+    pub signer: sr25519::Pair,
+    /// In satoshi per virtual byte.
+    pub fee_rate: u64,
+}
+
+impl Coordinator {
+    /// Returns the trustee indices of the peers which are online.
+    fn online_cosigners(&self, session: &HotSession) -> BTreeMap<usize, Cosigner<'_>> {
+        let mut cosigners = BTreeMap::new();
+        if let Some(index) = session.trustee_index(&self.trustee.public) {
+            cosigners.insert(index, Cosigner::Local(&self.trustee));
+        }
+        for peer in &self.peers {
+            let key = peer
+                .call::<Bytes>("trustee_hotPubkey", json!([]))
+                .and_then(|key| {
+                    secp256k1::PublicKey::from_slice(&key)
+                        .map_err(|_| Error::from("invalid hot key"))
+                });
+            match key {
+                Ok(key) => match session.trustee_index(&key) {
+                    Some(index) => {
+                        cosigners.insert(index, Cosigner::Remote(peer));
+                    }
+                    None => log::warn!(
+                        target: LOG_TARGET,
+                        "Peer {} is not a trustee of the current session",
+                        peer.url()
+                    ),
+                },
+                Err(e) => log::warn!(target: LOG_TARGET, "Peer {} is offline: {}", peer.url(), e),
+            }
+        }
+        cosigners
+    }
+
+    /// Proposes the pending withdrawals, returns the hash of the submitted extrinsic or
+    /// `None` if there is nothing to do.
+    pub fn propose(&self) -> Result<Option<H256>> {
+        let node = &self.trustee.node;
+        let bitcoind = &self.trustee.bitcoind;
+        let secp = &self.trustee.secp;
+
+        let pending = node.pending_withdrawals()?;
+        if pending.is_empty() {
+            return Ok(None);
+        }
+        let max_count = node.max_withdrawal_count()? as usize;
+        let fee = node.withdrawal_fee()?;
+        let mut withdrawal_id_list = Vec::new();
+        let mut withdrawals = Vec::new();
+        for (id, withdrawal) in pending.into_iter().take(max_count) {
+            let balance: u64 = withdrawal
+                .balance
+                .parse()
+                .map_err(|_| Error::Other(format!("invalid balance of withdrawal {}", id)))?;
+            let value = match balance.checked_sub(fee) {
+                Some(value) => value,
+                None => {
+                    log::warn!(target: LOG_TARGET, "Withdrawal {} is below the fee", id);
+                    continue;
+                }
+            };
+            withdrawal_id_list.push(id);
+            withdrawals.push(Output {
+                value,
+                script_pubkey: bitcoind.script_pubkey(&withdrawal.addr)?,
+            });
+        }
+        if withdrawal_id_list.is_empty() {
+            return Ok(None);
+        }
+
+        let session = HotSession::load(node, bitcoind)?;
+        let cosigners = self.online_cosigners(&session);
+        let leaf = session
+            .leaves
+            .iter()
+            .find(|leaf| leaf.signers.iter().all(|i| cosigners.contains_key(i)))
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "not enough trustees online, {} of {}",
+                    cosigners.len(),
+                    session.trustees.len()
+                ))
+            })?;
+        let key_agg = session.key_agg(secp, leaf)?;
+        let control_block = session.control_block(secp, leaf)?;
+
+        let tx = tx::build(
+            bitcoind.utxos(&session.hot_address)?,
+            withdrawals,
+            session.hot_script_pubkey.clone(),
+            control_block.len(),
+            self.fee_rate,
+        )?;
+        if !node.verify_tx_valid(&tx.serialize(None), &withdrawal_id_list)? {
+            return Err("the withdrawal transaction is refused by the chain".into());
+        }
+
+        let mut session_id = H256::zero();
+        rand::rngs::OsRng.fill_bytes(session_id.as_bytes_mut());
+        let request = SigningRequest {
+            session_id,
+            withdrawal_id_list: withdrawal_id_list.clone(),
+            inputs: tx
+                .inputs
+                .iter()
+                .map(|i| RpcInput {
+                    txid: display_txid(&i.txid),
+                    vout: i.vout,
+                })
+                .collect(),
+            outputs: tx
+                .outputs
+                .iter()
+                .map(|o| RpcOutput {
+                    value: o.value,
+                    script_pubkey: o.script_pubkey.clone().into(),
+                })
+                .collect(),
+            agg_pubkey: leaf.agg_pubkey.to_vec().into(),
+        };
+        let signers = leaf
+            .signers
+            .iter()
+            .map(|i| &cosigners[i])
+            .collect::<Vec<_>>();
+        log::info!(
+            target: LOG_TARGET,
+            "Signing withdrawals {:?} with trustees {:?}",
+            withdrawal_id_list,
+            leaf.signers
+        );
+
+        // Round 1: the nonces of every signer for every input.
+        let nonces = signers
+            .iter()
+            .map(|signer| signer.nonces(&request))
+            .collect::<Result<Vec<_>>>()?;
+        let agg_nonces = (0..tx.inputs.len())
+            .map(|input| {
+                let input_nonces = nonces
+                    .iter()
+                    .map(|n| n.get(input).copied().ok_or("missing nonce"))
+                    .collect::<Result<Vec<_>, _>>()?;
+                musig::nonce_agg(&input_nonces)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Round 2: the partial signatures.
+        let partials = signers
+            .iter()
+            .map(|signer| signer.partial_sign(session_id, &agg_nonces))
+            .collect::<Result<Vec<_>>>()?;
+        let script = leaf_script(&leaf.agg_pubkey);
+        let witnesses = (0..tx.inputs.len())
+            .map(|input| {
+                let input_partials = partials
+                    .iter()
+                    .map(|p| p.get(input).copied().ok_or("missing partial signature"))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let sighash = tx.sighash(input, &script);
+                for (j, trustee) in leaf.signers.iter().enumerate() {
+                    if !musig::partial_verify(
+                        secp,
+                        &input_partials[j],
+                        &nonces[j][input],
+                        &session.keys[*trustee],
+                        &key_agg,
+                        &agg_nonces[input],
+                        &sighash,
+                    ) {
+                        return Err(Error::Other(format!(
+                            "invalid partial signature of trustee {} for input {}",
+                            trustee, input
+                        )));
+                    }
+                }
+                let signature = musig::partial_sig_agg(
+                    secp,
+                    &input_partials,
+                    &key_agg,
+                    &agg_nonces[input],
+                    &sighash,
+                )?;
+                if !musig::verify(secp, &signature, &leaf.agg_pubkey, &sighash) {
+                    return Err(Error::from("invalid aggregated signature"));
+                }
+                Ok(vec![
+                    signature.to_vec(),
+                    script.clone(),
+                    control_block.clone(),
+                ])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let raw = tx.serialize(Some(&witnesses));
+        node.submit_withdraw_tx(&self.signer, withdrawal_id_list, raw)
+            .map(Some)
+    }
+}
+
+/// Broadcasts the transaction of the withdrawal proposal on chain, if any, returns whether
+/// there is a proposal.
+pub fn broadcast_proposal(trustee: &Trustee) -> Result<bool> {
+    let proposal = match trustee.node.withdrawal_proposal()? {
+        Some(proposal) => proposal,
+        None => return Ok(false),
+    };
+    let raw = light_bitcoin::serialization::serialize(&proposal.tx);
+    match trustee.bitcoind.send_raw_transaction(&raw) {
+        Ok(txid) => log::info!(
+            target: LOG_TARGET,
+            "Broadcast withdrawal transaction {} of {:?}",
+            txid,
+            proposal.withdrawal_id_list
+        ),
+        // Most likely it is already in the mempool or in a block.
+        Err(e) => log::debug!(target: LOG_TARGET, "Withdrawal transaction not sent: {}", e),
+    }
+    Ok(true)
+}
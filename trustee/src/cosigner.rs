@@ -0,0 +1,246 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The trustee side of a signing session, it signs nothing it has not checked against the
+//! chain and bitcoind on its own.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+
+use sp_core::{Bytes, H256};
+
+use crate::{
+    bitcoind::Bitcoind,
+    musig::{self, KeyAgg, PubNonce, SecNonce},
+    node::Node,
+    protocol::{CosignerApi, RpcOutput, SigningRequest},
+    rpc::{Error, Result},
+    session::HotSession,
+    tx::{leaf_script, Output, WithdrawalTx},
+    LOG_TARGET,
+};
+
+/// The secret nonces of an unfinished session are dropped after it.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct PendingSession {
+    key_agg: KeyAgg,
+    sighashes: Vec<[u8; 32]>,
+    secnonces: Vec<SecNonce>,
+    started: Instant,
+}
+
+pub struct Inner {
+    pub secp: Secp256k1<All>,
+    pub node: Node,
+    pub bitcoind: Bitcoind,
+    pub secret: SecretKey,
+    pub public: PublicKey,
+    /// The max fee in satoshi of a withdrawal transaction this trustee signs.
+    pub max_fee: u64,
+    sessions: Mutex<HashMap<H256, PendingSession>>,
+}
+
+/// The trustee, shared by the cosigner server and the coordinator.
+#[derive(Clone)]
+pub struct Trustee(Arc<Inner>);
+
+impl std::ops::Deref for Trustee {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.0
+    }
+}
+
+fn rpc_error(e: Error) -> RpcError {
+    log::warn!(target: LOG_TARGET, "Refused to sign: {}", e);
+    RpcError {
+        code: ErrorCode::ServerError(1),
+        message: e.to_string(),
+        data: None,
+    }
+}
+
+impl Trustee {
+    pub fn new(node: Node, bitcoind: Bitcoind, secret: SecretKey, max_fee: u64) -> Self {
+        let secp = Secp256k1::new();
+        let public = PublicKey::from_secret_key(&secp, &secret);
+        Self(Arc::new(Inner {
+            secp,
+            node,
+            bitcoind,
+            secret,
+            public,
+            max_fee,
+            sessions: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Rebuilds the transaction of the request and checks it can be signed.
+    fn check_request(&self, request: &SigningRequest) -> Result<(KeyAgg, WithdrawalTx)> {
+        let session = HotSession::load(&self.node, &self.bitcoind)?;
+        let leaf = session
+            .leaf(&request.agg_pubkey)
+            .ok_or("the leaf is not one of the hot address")?;
+        let index = session
+            .trustee_index(&self.public)
+            .ok_or("this trustee is not in the current session")?;
+        if !leaf.signers.contains(&index) {
+            return Err("this trustee is not a signer of the leaf".into());
+        }
+        let key_agg = session.key_agg(&self.secp, leaf)?;
+
+        let inputs = request
+            .inputs
+            .iter()
+            .map(|input| {
+                let utxo = self
+                    .bitcoind
+                    .utxo(&input.txid, input.vout)?
+                    .ok_or_else(|| {
+                        Error::Other(format!("{}:{} is spent", input.txid, input.vout))
+                    })?;
+                if utxo.script_pubkey != session.hot_script_pubkey {
+                    return Err(Error::Other(format!(
+                        "{}:{} is not an output of the hot address",
+                        input.txid, input.vout
+                    )));
+                }
+                Ok(utxo)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let tx = WithdrawalTx {
+            inputs,
+            outputs: request
+                .outputs
+                .iter()
+                .map(
+                    |RpcOutput {
+                         value,
+                         script_pubkey,
+                     }| Output {
+                        value: *value,
+                        script_pubkey: script_pubkey.to_vec(),
+                    },
+                )
+                .collect(),
+        };
+
+        match tx.fee() {
+            Some(fee) if fee <= self.max_fee => {}
+            fee => {
+                return Err(Error::Other(format!(
+                    "the fee {:?} exceeds the max fee {}",
+                    fee, self.max_fee
+                )))
+            }
+        }
+        let withdrawals = tx
+            .outputs
+            .iter()
+            .filter(|o| o.script_pubkey != session.hot_script_pubkey)
+            .count();
+        if withdrawals == 0 || withdrawals != request.withdrawal_id_list.len() {
+            return Err("the outputs do not match the withdrawal list".into());
+        }
+        if !self
+            .node
+            .verify_tx_valid(&tx.serialize(None), &request.withdrawal_id_list)?
+        {
+            return Err("the transaction is refused by the chain".into());
+        }
+        Ok((key_agg, tx))
+    }
+
+    fn start_session(&self, request: SigningRequest) -> Result<Vec<PubNonce>> {
+        let (key_agg, tx) = self.check_request(&request)?;
+        let script = leaf_script(&key_agg.x_only());
+        let sighashes = (0..tx.inputs.len())
+            .map(|i| tx.sighash(i, &script))
+            .collect::<Vec<_>>();
+        let (secnonces, pubnonces): (Vec<SecNonce>, Vec<PubNonce>) = sighashes
+            .iter()
+            .map(|sighash| musig::nonce_gen(&self.secp, &self.secret, &key_agg.x_only(), sighash))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        let mut sessions = self
+            .sessions
+            .lock()
+            .expect("the lock is never poisoned; qed");
+        sessions.retain(|_, session| session.started.elapsed() < SESSION_TIMEOUT);
+        if sessions.contains_key(&request.session_id) {
+            return Err("the session exists".into());
+        }
+        log::info!(
+            target: LOG_TARGET,
+            "Started session {:?} for withdrawals {:?}",
+            request.session_id,
+            request.withdrawal_id_list
+        );
+        sessions.insert(
+            request.session_id,
+            PendingSession {
+                key_agg,
+                sighashes,
+                secnonces,
+                started: Instant::now(),
+            },
+        );
+        Ok(pubnonces)
+    }
+
+    fn finish_session(&self, session_id: H256, agg_nonces: Vec<Bytes>) -> Result<Vec<[u8; 32]>> {
+        // The secret nonces are removed whatever happens next.
+        let session = self
+            .sessions
+            .lock()
+            .expect("the lock is never poisoned; qed")
+            .remove(&session_id)
+            .ok_or("unknown session")?;
+        if agg_nonces.len() != session.sighashes.len() {
+            return Err("the number of nonces differs from the inputs".into());
+        }
+        session
+            .secnonces
+            .into_iter()
+            .zip(&session.sighashes)
+            .zip(agg_nonces)
+            .map(|((secnonce, sighash), agg_nonce)| {
+                musig::partial_sign(
+                    &self.secp,
+                    secnonce,
+                    &self.secret,
+                    &session.key_agg,
+                    &PubNonce::from_bytes(&agg_nonce)?,
+                    sighash,
+                )
+            })
+            .collect()
+    }
+}
+
+impl CosignerApi for Trustee {
+    fn hot_pubkey(&self) -> RpcResult<Bytes> {
+        Ok(self.public.serialize().to_vec().into())
+    }
+
+    fn nonces(&self, request: SigningRequest) -> RpcResult<Vec<Bytes>> {
+        self.start_session(request)
+            .map(|nonces| nonces.iter().map(|n| n.to_bytes().into()).collect())
+            .map_err(rpc_error)
+    }
+
+    fn partial_sign(&self, session_id: H256, agg_nonces: Vec<Bytes>) -> RpcResult<Vec<Bytes>> {
+        self.finish_session(session_id, agg_nonces)
+            .map(|partials| partials.iter().map(|s| s.to_vec().into()).collect())
+            .map_err(rpc_error)
+    }
+}
@@ -0,0 +1,438 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! MuSig2 multi-signatures over secp256k1, without tweaks.
+//!
+//! The keys are aggregated by the MuSig2 of light-bitcoin, which `Mast` uses for the leaves
+//! of the hot address, the signing rounds follow BIP-327.
+//!
+//! The scalars are kept in `SecretKey`s so that the arithmetic is done by libsecp256k1,
+//! the only cost is that a zero scalar is refused, which never happens in practice.
+
+use light_bitcoin::{
+    keys::Public,
+    mast::{key::PublicKey as MastPublicKey, musig2::KeyAgg as MastKeyAgg},
+};
+use rand::RngCore;
+use secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+use crate::rpc::{Error, Result};
+
+/// Returns `sha256(sha256(tag) || sha256(tag) || data)`.
+pub fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(&tag);
+    hasher.update(&tag);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+fn scalar(bytes: &[u8]) -> Result<SecretKey> {
+    SecretKey::from_slice(bytes).map_err(|_| Error::from("invalid scalar"))
+}
+
+fn scalar_bytes(s: &SecretKey) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&s[..]);
+    bytes
+}
+
+fn add(a: &SecretKey, b: &SecretKey) -> Result<SecretKey> {
+    let mut sum = *a;
+    sum.add_assign(&b[..])
+        .map_err(|_| Error::from("zero scalar"))?;
+    Ok(sum)
+}
+
+fn mul(a: &SecretKey, b: &SecretKey) -> Result<SecretKey> {
+    let mut product = *a;
+    product
+        .mul_assign(&b[..])
+        .map_err(|_| Error::from("zero scalar"))?;
+    Ok(product)
+}
+
+fn point_mul(secp: &Secp256k1<All>, p: &PublicKey, s: &SecretKey) -> Result<PublicKey> {
+    let mut product = *p;
+    product
+        .mul_assign(secp, &s[..])
+        .map_err(|_| Error::from("invalid point multiplication"))?;
+    Ok(product)
+}
+
+fn combine(a: &PublicKey, b: &PublicKey) -> Result<PublicKey> {
+    a.combine(b)
+        .map_err(|_| Error::from("the sum of the points is infinity"))
+}
+
+/// Returns the x coordinate of the point.
+pub fn x_only(p: &PublicKey) -> [u8; 32] {
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&p.serialize()[1..]);
+    x
+}
+
+fn has_even_y(p: &PublicKey) -> bool {
+    p.serialize()[0] == 0x02
+}
+
+/// Returns the point with the x coordinate and an even y.
+pub fn lift_x(x: &[u8; 32]) -> Result<PublicKey> {
+    let mut compressed = [0x02u8; 33];
+    compressed[1..].copy_from_slice(x);
+    PublicKey::from_slice(&compressed).map_err(|_| Error::from("invalid x-only public key"))
+}
+
+/// The aggregated key of the signers.
+pub struct KeyAgg {
+    keys: Vec<PublicKey>,
+    coefficients: Vec<SecretKey>,
+    agg: PublicKey,
+}
+
+impl KeyAgg {
+    /// Aggregates the keys in the given order, i.e. the order of the signers in the leaf.
+    pub fn new(secp: &Secp256k1<All>, keys: Vec<PublicKey>) -> Result<Self> {
+        if keys.is_empty() {
+            return Err("no key to aggregate".into());
+        }
+        let mast_keys = keys
+            .iter()
+            .map(|key| {
+                Public::from_slice(&key.serialize())
+                    .ok()
+                    .and_then(|key| MastPublicKey::try_from(key).ok())
+                    .ok_or_else(|| Error::from("invalid key to aggregate"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mast_agg = MastKeyAgg::key_aggregation_n(&mast_keys)
+            .map_err(|_| Error::from("failed to aggregate the keys"))?;
+        let coefficients = mast_agg
+            .a_coefficients
+            .iter()
+            .map(|a| scalar(&a.b32()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut agg: Option<PublicKey> = None;
+        for (key, coefficient) in keys.iter().zip(&coefficients) {
+            let term = point_mul(secp, key, coefficient)?;
+            agg = Some(match agg {
+                Some(agg) => combine(&agg, &term)?,
+                None => term,
+            });
+        }
+        let agg = agg.ok_or("no key to aggregate")?;
+        if x_only(&agg) != mast_agg.x_tilde.x_coor() {
+            return Err("the key coefficients do not add up to the aggregated key".into());
+        }
+        Ok(Self {
+            keys,
+            coefficients,
+            agg,
+        })
+    }
+
+    /// The x-only aggregated key, which is pushed into the tapscript leaf.
+    pub fn x_only(&self) -> [u8; 32] {
+        x_only(&self.agg)
+    }
+
+    fn coefficient(&self, key: &PublicKey) -> Option<&SecretKey> {
+        self.keys
+            .iter()
+            .position(|k| k == key)
+            .map(|i| &self.coefficients[i])
+    }
+}
+
+/// The secret nonce of a signing session, it is consumed by [`partial_sign`] so that it
+/// can never be used twice.
+pub struct SecNonce([SecretKey; 2]);
+
+/// The public nonce, serialized as the two compressed points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PubNonce([PublicKey; 2]);
+
+impl PubNonce {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.0[0].serialize(), self.0[1].serialize()].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 66 {
+            return Err("invalid public nonce length".into());
+        }
+        let point =
+            |bytes: &[u8]| PublicKey::from_slice(bytes).map_err(|_| Error::from("invalid nonce"));
+        Ok(Self([point(&bytes[..33])?, point(&bytes[33..])?]))
+    }
+}
+
+/// Generates a fresh nonce pair, the secret key and the message only add entropy on top of
+/// the system randomness.
+pub fn nonce_gen(
+    secp: &Secp256k1<All>,
+    sk: &SecretKey,
+    agg_x: &[u8; 32],
+    msg: &[u8; 32],
+) -> Result<(SecNonce, PubNonce)> {
+    let mut rand = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut rand);
+    let k = |i: u8| {
+        scalar(&tagged_hash(
+            "MuSig/nonce",
+            &[&rand, &sk[..], agg_x, msg, &[i]],
+        ))
+    };
+    let sec = [k(0)?, k(1)?];
+    let public = [
+        PublicKey::from_secret_key(secp, &sec[0]),
+        PublicKey::from_secret_key(secp, &sec[1]),
+    ];
+    Ok((SecNonce(sec), PubNonce(public)))
+}
+
+/// Sums the public nonces of all the signers.
+pub fn nonce_agg(nonces: &[PubNonce]) -> Result<PubNonce> {
+    let (first, rest) = nonces.split_first().ok_or("no nonce to aggregate")?;
+    rest.iter().try_fold(*first, |agg, nonce| {
+        Ok(PubNonce([
+            combine(&agg.0[0], &nonce.0[0])?,
+            combine(&agg.0[1], &nonce.0[1])?,
+        ]))
+    })
+}
+
+/// The values shared by all the signers of a session.
+struct Session {
+    b: SecretKey,
+    r: PublicKey,
+    e: SecretKey,
+}
+
+impl Session {
+    fn new(
+        secp: &Secp256k1<All>,
+        key_agg: &KeyAgg,
+        aggnonce: &PubNonce,
+        msg: &[u8; 32],
+    ) -> Result<Self> {
+        let agg_x = key_agg.x_only();
+        let b = scalar(&tagged_hash(
+            "MuSig/noncecoef",
+            &[&aggnonce.to_bytes(), &agg_x, msg],
+        ))?;
+        let r = combine(&aggnonce.0[0], &point_mul(secp, &aggnonce.0[1], &b)?)?;
+        let e = scalar(&tagged_hash(
+            "BIP0340/challenge",
+            &[&x_only(&r), &agg_x, msg],
+        ))?;
+        Ok(Self { b, r, e })
+    }
+}
+
+/// Returns the partial signature of `sk`, whose public key must be one of the aggregated.
+pub fn partial_sign(
+    secp: &Secp256k1<All>,
+    secnonce: SecNonce,
+    sk: &SecretKey,
+    key_agg: &KeyAgg,
+    aggnonce: &PubNonce,
+    msg: &[u8; 32],
+) -> Result<[u8; 32]> {
+    let session = Session::new(secp, key_agg, aggnonce, msg)?;
+    let SecNonce([mut k1, mut k2]) = secnonce;
+    if !has_even_y(&session.r) {
+        k1.negate_assign();
+        k2.negate_assign();
+    }
+    let a = key_agg
+        .coefficient(&PublicKey::from_secret_key(secp, sk))
+        .ok_or("the key is not one of the aggregated keys")?;
+    let mut d = *sk;
+    if !has_even_y(&key_agg.agg) {
+        d.negate_assign();
+    }
+    let s = add(
+        &add(&k1, &mul(&session.b, &k2)?)?,
+        &mul(&mul(&session.e, a)?, &d)?,
+    )?;
+    Ok(scalar_bytes(&s))
+}
+
+/// Verifies the partial signature of the signer with the public key `key` and the public
+/// nonce `pubnonce`, so that a bad signer is caught before the aggregation.
+pub fn partial_verify(
+    secp: &Secp256k1<All>,
+    partial: &[u8; 32],
+    pubnonce: &PubNonce,
+    key: &PublicKey,
+    key_agg: &KeyAgg,
+    aggnonce: &PubNonce,
+    msg: &[u8; 32],
+) -> bool {
+    let check = || -> Result<bool> {
+        let session = Session::new(secp, key_agg, aggnonce, msg)?;
+        // s⋅G = R' + e⋅a⋅P, with the signs of `partial_sign`.
+        let mut r = combine(
+            &pubnonce.0[0],
+            &point_mul(secp, &pubnonce.0[1], &session.b)?,
+        )?;
+        if !has_even_y(&session.r) {
+            r.negate_assign(secp);
+        }
+        let a = key_agg
+            .coefficient(key)
+            .ok_or("the key is not one of the aggregated keys")?;
+        let mut p = *key;
+        if !has_even_y(&key_agg.agg) {
+            p.negate_assign(secp);
+        }
+        let expected = combine(&r, &point_mul(secp, &p, &mul(&session.e, a)?)?)?;
+        Ok(PublicKey::from_secret_key(secp, &scalar(partial)?) == expected)
+    };
+    check().unwrap_or(false)
+}
+
+/// Sums the partial signatures into the BIP-340 signature of the aggregated key.
+pub fn partial_sig_agg(
+    secp: &Secp256k1<All>,
+    partials: &[[u8; 32]],
+    key_agg: &KeyAgg,
+    aggnonce: &PubNonce,
+    msg: &[u8; 32],
+) -> Result<[u8; 64]> {
+    let session = Session::new(secp, key_agg, aggnonce, msg)?;
+    let (first, rest) = partials.split_first().ok_or("no partial signature")?;
+    let s = rest
+        .iter()
+        .try_fold(scalar(first)?, |s, partial| add(&s, &scalar(partial)?))?;
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&x_only(&session.r));
+    signature[32..].copy_from_slice(&s[..]);
+    Ok(signature)
+}
+
+/// Verifies the BIP-340 signature of the x-only public key.
+pub fn verify(secp: &Secp256k1<All>, signature: &[u8; 64], x: &[u8; 32], msg: &[u8; 32]) -> bool {
+    let check = || -> Result<bool> {
+        let p = lift_x(x)?;
+        let e = scalar(&tagged_hash(
+            "BIP0340/challenge",
+            &[&signature[..32], x, msg],
+        ))?;
+        // R = s⋅G - e⋅P
+        let mut minus_ep = point_mul(secp, &p, &e)?;
+        minus_ep.negate_assign(secp);
+        let r = combine(
+            &PublicKey::from_secret_key(secp, &scalar(&signature[32..])?),
+            &minus_ep,
+        )?;
+        Ok(has_even_y(&r) && x_only(&r)[..] == signature[..32])
+    };
+    check().unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_key(secp: &Secp256k1<All>) -> (SecretKey, PublicKey) {
+        let mut bytes = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut bytes);
+        let sk = SecretKey::from_slice(&bytes).unwrap();
+        (sk, PublicKey::from_secret_key(secp, &sk))
+    }
+
+    fn sign(n: usize, msg: &[u8; 32]) -> (KeyAgg, [u8; 64]) {
+        let secp = Secp256k1::new();
+        let signers = (0..n).map(|_| random_key(&secp)).collect::<Vec<_>>();
+        let key_agg = KeyAgg::new(&secp, signers.iter().map(|(_, pk)| *pk).collect()).unwrap();
+        let agg_x = key_agg.x_only();
+
+        let nonces = signers
+            .iter()
+            .map(|(sk, _)| nonce_gen(&secp, sk, &agg_x, msg).unwrap())
+            .collect::<Vec<_>>();
+        let pubnonces = nonces.iter().map(|(_, p)| *p).collect::<Vec<_>>();
+        let aggnonce = nonce_agg(&pubnonces).unwrap();
+        let partials = signers
+            .iter()
+            .zip(nonces)
+            .map(|((sk, _), (secnonce, _))| {
+                partial_sign(&secp, secnonce, sk, &key_agg, &aggnonce, msg).unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (((_, pk), pubnonce), partial) in signers.iter().zip(&pubnonces).zip(&partials) {
+            assert!(partial_verify(
+                &secp, partial, pubnonce, pk, &key_agg, &aggnonce, msg
+            ));
+            let mut tampered = *partial;
+            tampered[31] ^= 1;
+            assert!(!partial_verify(
+                &secp, &tampered, pubnonce, pk, &key_agg, &aggnonce, msg
+            ));
+        }
+        if n > 1 {
+            // The partial signature of a signer does not pass as the one of another.
+            assert!(!partial_verify(
+                &secp,
+                &partials[0],
+                &pubnonces[1],
+                &signers[1].1,
+                &key_agg,
+                &aggnonce,
+                msg
+            ));
+        }
+        let signature = partial_sig_agg(&secp, &partials, &key_agg, &aggnonce, msg).unwrap();
+        (key_agg, signature)
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let secp = Secp256k1::new();
+        let msg = tagged_hash("test", &[b"withdrawal"]);
+        // Run it several times to hit both parities of the aggregated key and nonce.
+        for n in 1..=5 {
+            let (key_agg, signature) = sign(n, &msg);
+            assert!(verify(&secp, &signature, &key_agg.x_only(), &msg));
+
+            let mut tampered = signature;
+            tampered[63] ^= 1;
+            assert!(!verify(&secp, &tampered, &key_agg.x_only(), &msg));
+            assert!(!verify(&secp, &signature, &key_agg.x_only(), &[0u8; 32]));
+        }
+    }
+
+    #[test]
+    fn test_pub_nonce_codec() {
+        let secp = Secp256k1::new();
+        let (sk, _) = random_key(&secp);
+        let (_, public) = nonce_gen(&secp, &sk, &[1u8; 32], &[2u8; 32]).unwrap();
+        assert_eq!(PubNonce::from_bytes(&public.to_bytes()).unwrap(), public);
+        assert!(PubNonce::from_bytes(&public.to_bytes()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_key_agg_matches_mast() {
+        let secp = Secp256k1::new();
+        let keys = (0..5).map(|_| random_key(&secp).1).collect::<Vec<_>>();
+        let mast_keys = keys
+            .iter()
+            .map(|k| MastPublicKey::try_from(Public::from_slice(&k.serialize()).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        let mast = light_bitcoin::mast::Mast::new(mast_keys, 3).unwrap();
+        assert_eq!(mast.pubkeys.len(), 10);
+        for (pubkey, indexs) in mast.pubkeys.iter().zip(&mast.indexs) {
+            let leaf_keys = indexs.iter().map(|i| keys[*i as usize - 1]).collect();
+            assert_eq!(
+                KeyAgg::new(&secp, leaf_keys).unwrap().x_only(),
+                pubkey.x_coor()
+            );
+        }
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The queries to the ChainX node and the submission of `create_taproot_withdraw_tx`.
+
+use std::collections::BTreeMap;
+
+use codec::{Decode, Encode};
+use serde::Deserialize;
+use serde_json::json;
+
+use sp_core::{hashing::twox_128, sr25519, Bytes, Pair, H256};
+use sp_runtime::{generic::Era, MultiSignature};
+
+use chainx_primitives::{AccountId, BlockNumber};
+use xpallet_gateway_bitcoin::BtcWithdrawalProposal;
+use xpallet_gateway_common::trustees::bitcoin::{BtcTrusteeIntentionProps, BtcTrusteeSessionInfo};
+
+use crate::rpc::{Client, Error, Result};
+
+/// A withdrawal waiting for a proposal, as returned by `pendingWithdrawalListByChain`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingWithdrawal {
+    pub balance: String,
+    pub addr: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeVersion {
+    impl_name: String,
+    spec_version: u32,
+    transaction_version: u32,
+}
+
+#[derive(Deserialize)]
+struct WithdrawalLimit {
+    fee: String,
+}
+
+/// Signs `create_taproot_withdraw_tx` with the `SignedExtra` of the given runtime, returns
+/// the encoded extrinsic. The runtimes share the same extra but not the same types.
+macro_rules! signed_extrinsic {
+    ($runtime:ident, $pair:expr, $account:expr, $nonce:expr, $genesis_hash:expr, $version:expr, $withdrawal_id_list:expr, $tx:expr) => {{
+        use $runtime::{
+            impls::{ChargeAssetTxPayment, ChargeExtraFee},
+            BaseFilter, Runtime,
+        };
+
+        let extra: $runtime::SignedExtra = (
+            frame_system::CheckNonZeroSender::<Runtime>::new(),
+            frame_system::CheckSpecVersion::<Runtime>::new(),
+            frame_system::CheckTxVersion::<Runtime>::new(),
+            frame_system::CheckGenesis::<Runtime>::new(),
+            frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+            frame_system::CheckNonce::<Runtime>::from($nonce),
+            frame_system::CheckWeight::<Runtime>::new(),
+            ChargeAssetTxPayment::from(0, None),
+            BaseFilter,
+            ChargeExtraFee,
+        );
+        let payload = $runtime::SignedPayload::from_raw(
+            $runtime::Call::XGatewayBitcoin(
+                xpallet_gateway_bitcoin::Call::create_taproot_withdraw_tx {
+                    withdrawal_id_list: $withdrawal_id_list,
+                    tx: $tx,
+                },
+            ),
+            extra,
+            (
+                (),
+                $version.spec_version,
+                $version.transaction_version,
+                $genesis_hash,
+                $genesis_hash,
+                (),
+                (),
+                (),
+                (),
+                (),
+            ),
+        );
+        let signature = payload.using_encoded(|payload| $pair.sign(payload));
+        let (call, extra, _) = payload.deconstruct();
+        $runtime::UncheckedExtrinsic::new_signed(
+            call,
+            $runtime::Address::Id($account),
+            MultiSignature::Sr25519(signature),
+            extra,
+        )
+        .encode()
+    }};
+}
+
+/// The ChainX node.
+pub struct Node(Client);
+
+impl Node {
+    pub fn new(url: &str) -> Result<Self> {
+        Client::new(url).map(Self)
+    }
+
+    fn storage_value<T: Decode>(&self, pallet: &str, item: &str) -> Result<Option<T>> {
+        let key = [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat();
+        let value: Option<Bytes> = self.0.call("state_getStorage", json!([Bytes(key)]))?;
+        value
+            .map(|value| {
+                T::decode(&mut &value[..])
+                    .map_err(|e| format!("invalid {}::{}: {}", pallet, item, e).into())
+            })
+            .transpose()
+    }
+
+    /// The withdrawals of BTC in the `Applying` state.
+    pub fn pending_withdrawals(&self) -> Result<BTreeMap<u32, PendingWithdrawal>> {
+        self.0.call(
+            "xgatewayrecords_pendingWithdrawalListByChain",
+            json!(["Bitcoin"]),
+        )
+    }
+
+    /// The fee deducted from every BTC withdrawal, in satoshi.
+    pub fn withdrawal_fee(&self) -> Result<u64> {
+        let limit: WithdrawalLimit = self.0.call(
+            "xgatewaycommon_withdrawalLimit",
+            json!([xp_protocol::X_BTC]),
+        )?;
+        limit
+            .fee
+            .parse()
+            .map_err(|_| format!("invalid withdrawal fee {}", limit.fee).into())
+    }
+
+    /// The max number of withdrawals in a proposal.
+    pub fn max_withdrawal_count(&self) -> Result<u32> {
+        self.storage_value("XGatewayBitcoin", "MaxWithdrawalCount")
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn withdrawal_proposal(&self) -> Result<Option<BtcWithdrawalProposal<AccountId>>> {
+        self.0
+            .call("xgatewaybitcoin_getWithdrawalProposal", json!([]))
+    }
+
+    /// Whether the unsigned transaction passes the checks of `create_taproot_withdraw_tx`.
+    pub fn verify_tx_valid(&self, raw_tx: &[u8], withdrawal_id_list: &[u32]) -> Result<bool> {
+        self.0.call(
+            "xgatewaybitcoin_verifyTxValid",
+            json!([hex::encode(raw_tx), withdrawal_id_list, false]),
+        )
+    }
+
+    /// The current trustee session.
+    pub fn trustee_session(&self) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>> {
+        self.0
+            .call("xgatewaycommon_bitcoinTrusteeSessionInfo", json!([-1]))
+    }
+
+    pub fn trustee_properties(
+        &self,
+        who: &AccountId,
+    ) -> Result<BtcTrusteeIntentionProps<AccountId>> {
+        self.0
+            .call("xgatewaycommon_bitcoinTrusteeProperties", json!([who]))
+    }
+
+    /// Signs `create_taproot_withdraw_tx` with the account of `pair` and submits it, returns
+    /// the hash of the extrinsic.
+    pub fn submit_withdraw_tx(
+        &self,
+        pair: &sr25519::Pair,
+        withdrawal_id_list: Vec<u32>,
+        tx: Vec<u8>,
+    ) -> Result<H256> {
+        let account: AccountId = pair.public().into();
+        let nonce: u32 = self.0.call("system_accountNextIndex", json!([account]))?;
+        let genesis_hash: H256 = self.0.call("chain_getBlockHash", json!([0]))?;
+        let version: RuntimeVersion = self.0.call("state_getRuntimeVersion", json!([]))?;
+
+        let extrinsic = match version.impl_name.as_str() {
+            "chainx-net" => signed_extrinsic!(
+                chainx_runtime,
+                pair,
+                account,
+                nonce,
+                genesis_hash,
+                version,
+                withdrawal_id_list,
+                tx
+            ),
+            "chainx-malan" => signed_extrinsic!(
+                malan_runtime,
+                pair,
+                account,
+                nonce,
+                genesis_hash,
+                version,
+                withdrawal_id_list,
+                tx
+            ),
+            "chainx-dev" => signed_extrinsic!(
+                dev_runtime,
+                pair,
+                account,
+                nonce,
+                genesis_hash,
+                version,
+                withdrawal_id_list,
+                tx
+            ),
+            other => {
+                return Err(Error::Other(format!("unknown runtime {}", other)));
+            }
+        };
+        self.0
+            .call("author_submitExtrinsic", json!([Bytes(extrinsic)]))
+    }
+}
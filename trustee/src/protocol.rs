@@ -0,0 +1,57 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The JSON-RPC protocol spoken between the coordinator and the other trustees.
+//!
+//! A signing session takes two rounds: `trustee_nonces` hands out the public nonces of
+//! every input once the trustee has checked the transaction on its own, `trustee_partialSign`
+//! returns the partial signatures for the aggregated nonces and forgets the secret nonces.
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+
+use sp_core::{Bytes, H256};
+
+/// An input of the withdrawal transaction, the txid is in the order displayed by bitcoind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInput {
+    pub txid: String,
+    pub vout: u32,
+}
+
+/// An output of the withdrawal transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcOutput {
+    pub value: u64,
+    pub script_pubkey: Bytes,
+}
+
+/// The withdrawal transaction to co-sign with the leaf of `agg_pubkey`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningRequest {
+    pub session_id: H256,
+    pub withdrawal_id_list: Vec<u32>,
+    pub inputs: Vec<RpcInput>,
+    pub outputs: Vec<RpcOutput>,
+    /// The x-only aggregated key of the leaf script.
+    pub agg_pubkey: Bytes,
+}
+
+/// The cosigner API served by every trustee.
+#[rpc]
+pub trait CosignerApi {
+    /// Returns the compressed hot public key of the trustee.
+    #[rpc(name = "trustee_hotPubkey")]
+    fn hot_pubkey(&self) -> Result<Bytes>;
+
+    /// Checks the transaction and returns the public nonces of its inputs.
+    #[rpc(name = "trustee_nonces")]
+    fn nonces(&self, request: SigningRequest) -> Result<Vec<Bytes>>;
+
+    /// Returns the partial signatures of the inputs for the aggregated nonces.
+    #[rpc(name = "trustee_partialSign")]
+    fn partial_sign(&self, session_id: H256, agg_nonces: Vec<Bytes>) -> Result<Vec<Bytes>>;
+}
@@ -0,0 +1,142 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The hot address of the current trustee session, rebuilt from `TrusteeSessionInfoOf` and
+//! the hot keys of the trustees the same way `generate_trustee_session_info` does.
+
+use light_bitcoin::{
+    keys::Address,
+    mast::{key::PublicKey as MastPublicKey, Mast},
+};
+use secp256k1::{All, PublicKey, Secp256k1};
+
+use chainx_primitives::AccountId;
+
+use crate::{
+    bitcoind::Bitcoind,
+    musig::KeyAgg,
+    node::Node,
+    rpc::{Error, Result},
+    tx,
+};
+
+/// A leaf of the hot address, i.e. an entry of `AggPubkeyInfo`.
+pub struct Leaf {
+    /// The x-only aggregated key pushed into the leaf script.
+    pub agg_pubkey: [u8; 32],
+    /// The indices of the signers in the trustee list.
+    pub signers: Vec<usize>,
+}
+
+pub struct HotSession {
+    pub trustees: Vec<AccountId>,
+    /// The hot keys of the trustees.
+    pub keys: Vec<PublicKey>,
+    pub hot_address: String,
+    pub hot_script_pubkey: Vec<u8>,
+    pub leaves: Vec<Leaf>,
+    mast: Mast,
+}
+
+impl HotSession {
+    pub fn load(node: &Node, bitcoind: &Bitcoind) -> Result<Self> {
+        let info = node.trustee_session()?;
+        let trustees = info
+            .trustee_list
+            .into_iter()
+            .map(|(account, _)| account)
+            .collect::<Vec<_>>();
+        let hot_keys = trustees
+            .iter()
+            .map(|trustee| node.trustee_properties(trustee).map(|p| p.hot_entity.0))
+            .collect::<Result<Vec<_>>>()?;
+        let keys = hot_keys
+            .iter()
+            .map(|k| {
+                PublicKey::from_slice(&k.to_vec())
+                    .map_err(|_| Error::from("invalid hot key of a trustee"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mast_keys = hot_keys
+            .into_iter()
+            .map(|k| k.try_into().map_err(|_| "invalid hot key of a trustee"))
+            .collect::<Result<Vec<MastPublicKey>, _>>()?;
+
+        let hot_address = String::from_utf8_lossy(&info.hot_address.addr).into_owned();
+        let network = hot_address
+            .parse::<Address>()
+            .map_err(|_| Error::Other(format!("invalid hot address {}", hot_address)))?
+            .network;
+        let mast = Mast::new(mast_keys, info.threshold as u32)
+            .map_err(|_| "failed to build the mast of the hot keys")?;
+        let address = mast
+            .generate_address(&network.to_string())
+            .map_err(|_| "failed to build the hot address")?;
+        // The trustees may have changed their hot keys since the session started.
+        if address != hot_address {
+            return Err(Error::Other(format!(
+                "the hot address {} differs from {} built by the current hot keys",
+                hot_address, address
+            )));
+        }
+
+        let leaves = mast
+            .pubkeys
+            .iter()
+            .zip(&mast.indexs)
+            .map(|(pubkey, indexs)| Leaf {
+                agg_pubkey: pubkey.x_coor(),
+                signers: indexs.iter().map(|i| *i as usize - 1).collect(),
+            })
+            .collect();
+        Ok(Self {
+            trustees,
+            keys,
+            hot_script_pubkey: bitcoind.script_pubkey(&hot_address)?,
+            hot_address,
+            leaves,
+            mast,
+        })
+    }
+
+    pub fn leaf(&self, agg_pubkey: &[u8]) -> Option<&Leaf> {
+        self.leaves.iter().find(|l| l.agg_pubkey[..] == *agg_pubkey)
+    }
+
+    /// Returns the index of the hot key in the trustee list.
+    pub fn trustee_index(&self, key: &PublicKey) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+
+    /// Aggregates the hot keys of the leaf signers, in the order of the mast, and makes sure
+    /// the result is the key of the leaf.
+    pub fn key_agg(&self, secp: &Secp256k1<All>, leaf: &Leaf) -> Result<KeyAgg> {
+        let keys = leaf.signers.iter().map(|i| self.keys[*i]).collect();
+        let key_agg = KeyAgg::new(secp, keys)?;
+        if key_agg.x_only() == leaf.agg_pubkey {
+            return Ok(key_agg);
+        }
+        Err(Error::Other(format!(
+            "the MuSig2 aggregation of the signers does not match the leaf key {}",
+            hex::encode(leaf.agg_pubkey)
+        )))
+    }
+
+    /// Returns the control block of the leaf, checked against the hot address.
+    pub fn control_block(&self, secp: &Secp256k1<All>, leaf: &Leaf) -> Result<Vec<u8>> {
+        let pubkey = self
+            .mast
+            .pubkeys
+            .iter()
+            .find(|p| p.x_coor() == leaf.agg_pubkey)
+            .ok_or("unknown leaf")?;
+        let control_block = self
+            .mast
+            .generate_merkle_proof(pubkey)
+            .map_err(|_| "failed to build the control block")?;
+        let script = tx::leaf_script(&leaf.agg_pubkey);
+        if !tx::check_control_block(secp, &self.hot_script_pubkey, &script, &control_block) {
+            return Err("the control block does not match the hot address".into());
+        }
+        Ok(control_block)
+    }
+}
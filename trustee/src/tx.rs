@@ -0,0 +1,338 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The withdrawal transaction spending the taproot hot address through a script path.
+
+use crate::musig::tagged_hash;
+
+/// The version of the tapscript leaves.
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+/// Outputs below it are not relayed by the bitcoin nodes, a smaller change goes to the fee.
+pub const DUST_LIMIT: u64 = 546;
+
+/// An unspent output of the hot address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utxo {
+    /// In the internal byte order, i.e. reversed from the one displayed by bitcoind.
+    pub txid: [u8; 32],
+    pub vout: u32,
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Output {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+/// The withdrawal transaction, every input spends the hot address with the same leaf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawalTx {
+    pub inputs: Vec<Utxo>,
+    pub outputs: Vec<Output>,
+}
+
+fn push_compact_size(buf: &mut Vec<u8>, n: usize) {
+    match n {
+        0..=0xfc => buf.push(n as u8),
+        0xfd..=0xffff => {
+            buf.push(0xfd);
+            buf.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        _ => {
+            buf.push(0xfe);
+            buf.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+    }
+}
+
+fn push_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    push_compact_size(buf, bytes.len());
+    buf.extend_from_slice(bytes);
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data).into()
+}
+
+/// Returns the tapleaf hash of the script.
+pub fn leaf_hash(script: &[u8]) -> [u8; 32] {
+    let mut leaf = vec![TAPSCRIPT_LEAF_VERSION];
+    push_bytes(&mut leaf, script);
+    tagged_hash("TapLeaf", &[&leaf])
+}
+
+/// Returns the leaf script `<x-only key> OP_CHECKSIG`, the same as in `AggPubkeyInfo`.
+pub fn leaf_script(x_only: &[u8; 32]) -> Vec<u8> {
+    const OP_CHECKSIG: u8 = 0xac;
+    let mut script = vec![32];
+    script.extend_from_slice(x_only);
+    script.push(OP_CHECKSIG);
+    script
+}
+
+/// Checks that the control block proves the leaf script against the output key of the
+/// taproot `script_pubkey`.
+pub fn check_control_block(
+    secp: &secp256k1::Secp256k1<secp256k1::All>,
+    script_pubkey: &[u8],
+    script: &[u8],
+    control_block: &[u8],
+) -> bool {
+    // OP_1 <32 bytes>
+    if script_pubkey.len() != 34 || script_pubkey[..2] != [0x51, 0x20] {
+        return false;
+    }
+    if control_block.len() < 33 || (control_block.len() - 33) % 32 != 0 {
+        return false;
+    }
+    if control_block[0] & 0xfe != TAPSCRIPT_LEAF_VERSION {
+        return false;
+    }
+    let mut node = leaf_hash(script);
+    for sibling in control_block[33..].chunks(32) {
+        node = if node[..] <= sibling[..] {
+            tagged_hash("TapBranch", &[&node, sibling])
+        } else {
+            tagged_hash("TapBranch", &[sibling, &node])
+        };
+    }
+    let mut internal = [0u8; 32];
+    internal.copy_from_slice(&control_block[1..33]);
+    let mut output = match crate::musig::lift_x(&internal) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+    let tweak = tagged_hash("TapTweak", &[&internal, &node]);
+    if output.add_exp_assign(secp, &tweak).is_err() {
+        return false;
+    }
+    let output = output.serialize();
+    output[1..] == script_pubkey[2..] && (output[0] == 0x03) == (control_block[0] & 1 == 1)
+}
+
+impl WithdrawalTx {
+    /// Serializes the transaction, without the witnesses if `witnesses` is `None`.
+    pub fn serialize(&self, witnesses: Option<&[Vec<Vec<u8>>]>) -> Vec<u8> {
+        let mut buf = 2u32.to_le_bytes().to_vec();
+        if witnesses.is_some() {
+            buf.extend_from_slice(&[0x00, 0x01]);
+        }
+        push_compact_size(&mut buf, self.inputs.len());
+        for input in &self.inputs {
+            buf.extend_from_slice(&input.txid);
+            buf.extend_from_slice(&input.vout.to_le_bytes());
+            // empty script_sig
+            buf.push(0);
+            buf.extend_from_slice(&u32::MAX.to_le_bytes());
+        }
+        push_compact_size(&mut buf, self.outputs.len());
+        for output in &self.outputs {
+            buf.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut buf, &output.script_pubkey);
+        }
+        if let Some(witnesses) = witnesses {
+            for witness in witnesses {
+                push_compact_size(&mut buf, witness.len());
+                for item in witness {
+                    push_bytes(&mut buf, item);
+                }
+            }
+        }
+        // lock time
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf
+    }
+
+    /// Returns the BIP-341 signature hash of the input spent with the leaf script, with
+    /// `SIGHASH_DEFAULT`.
+    pub fn sighash(&self, index: usize, script: &[u8]) -> [u8; 32] {
+        let mut prevouts = Vec::new();
+        let mut amounts = Vec::new();
+        let mut script_pubkeys = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.inputs {
+            prevouts.extend_from_slice(&input.txid);
+            prevouts.extend_from_slice(&input.vout.to_le_bytes());
+            amounts.extend_from_slice(&input.value.to_le_bytes());
+            push_bytes(&mut script_pubkeys, &input.script_pubkey);
+            sequences.extend_from_slice(&u32::MAX.to_le_bytes());
+        }
+        let mut outputs = Vec::new();
+        for output in &self.outputs {
+            outputs.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut outputs, &output.script_pubkey);
+        }
+
+        // epoch and hash type
+        let mut msg = vec![0x00, 0x00];
+        msg.extend_from_slice(&2u32.to_le_bytes());
+        msg.extend_from_slice(&0u32.to_le_bytes());
+        msg.extend_from_slice(&sha256(&prevouts));
+        msg.extend_from_slice(&sha256(&amounts));
+        msg.extend_from_slice(&sha256(&script_pubkeys));
+        msg.extend_from_slice(&sha256(&sequences));
+        msg.extend_from_slice(&sha256(&outputs));
+        // spend type: script path without annex
+        msg.push(0x02);
+        msg.extend_from_slice(&(index as u32).to_le_bytes());
+        msg.extend_from_slice(&leaf_hash(script));
+        // key version and no OP_CODESEPARATOR
+        msg.push(0x00);
+        msg.extend_from_slice(&u32::MAX.to_le_bytes());
+        tagged_hash("TapSighash", &[&msg])
+    }
+
+    /// Returns the sum of the inputs minus the sum of the outputs.
+    pub fn fee(&self) -> Option<u64> {
+        let inputs = self.inputs.iter().map(|i| i.value).sum::<u64>();
+        let outputs = self.outputs.iter().map(|o| o.value).sum::<u64>();
+        inputs.checked_sub(outputs)
+    }
+}
+
+/// Returns the virtual size of the transaction once signed.
+pub fn estimate_vsize(tx: &WithdrawalTx, control_block_len: usize) -> u64 {
+    let base = tx.serialize(None).len() as u64;
+    // marker, flag, and per input: item count, signature, script and control block
+    let witness = 2 + tx.inputs.len() as u64 * (1 + 65 + 35 + 1 + control_block_len as u64);
+    base + (witness + 3) / 4
+}
+
+/// Selects the largest utxos to pay the withdrawals and the fee at `fee_rate` sat/vB, the
+/// change goes back to `change_script`.
+pub fn build(
+    mut utxos: Vec<Utxo>,
+    withdrawals: Vec<Output>,
+    change_script: Vec<u8>,
+    control_block_len: usize,
+    fee_rate: u64,
+) -> Result<WithdrawalTx, String> {
+    let amount = withdrawals.iter().map(|o| o.value).sum::<u64>();
+    utxos.sort_by(|a, b| b.value.cmp(&a.value));
+
+    let mut tx = WithdrawalTx {
+        inputs: Vec::new(),
+        outputs: withdrawals,
+    };
+    tx.outputs.push(Output {
+        value: 0,
+        script_pubkey: change_script,
+    });
+    let mut total = 0u64;
+    for utxo in utxos {
+        total += utxo.value;
+        tx.inputs.push(utxo);
+        let fee = estimate_vsize(&tx, control_block_len) * fee_rate;
+        if total < amount + fee {
+            continue;
+        }
+        let change = total - amount - fee;
+        if change >= DUST_LIMIT {
+            tx.outputs.last_mut().expect("the change output; qed").value = change;
+        } else {
+            tx.outputs.pop();
+        }
+        return Ok(tx);
+    }
+    Err(format!(
+        "insufficient balance of the hot address, {} sat for {} sat of withdrawals",
+        total, amount
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use light_bitcoin::{
+        chain::Transaction,
+        serialization::{deserialize, Reader},
+    };
+
+    fn utxo(n: u8, value: u64) -> Utxo {
+        Utxo {
+            txid: [n; 32],
+            vout: n as u32,
+            value,
+            script_pubkey: [&[0x51, 0x20][..], &[0xaa; 32]].concat(),
+        }
+    }
+
+    fn output(value: u64) -> Output {
+        Output {
+            value,
+            script_pubkey: [&[0x00, 0x14][..], &[0xbb; 20]].concat(),
+        }
+    }
+
+    #[test]
+    fn test_build_selects_largest_utxos() {
+        let utxos = vec![utxo(1, 10_000), utxo(2, 500_000), utxo(3, 200_000)];
+        let change = utxos[0].script_pubkey.clone();
+        let tx = build(utxos, vec![output(600_000)], change.clone(), 65, 2).unwrap();
+        assert_eq!(
+            tx.inputs.iter().map(|i| i.vout).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.outputs[1].script_pubkey, change);
+        assert_eq!(tx.fee(), Some(estimate_vsize(&tx, 65) * 2));
+
+        // the dust change goes to the fee
+        let tx = build(
+            vec![utxo(1, 100_400)],
+            vec![output(100_000)],
+            change.clone(),
+            65,
+            2,
+        )
+        .unwrap();
+        assert_eq!(tx.outputs.len(), 1);
+        assert_eq!(tx.fee(), Some(400));
+
+        assert!(build(vec![utxo(1, 1_000)], vec![output(1_000)], change, 65, 1).is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let tx = build(
+            vec![utxo(1, 500_000), utxo(2, 300_000)],
+            vec![output(600_000), output(100_000)],
+            utxo(0, 0).script_pubkey,
+            65,
+            1,
+        )
+        .unwrap();
+        let witnesses = vec![vec![vec![1u8; 64], leaf_script(&[2u8; 32]), vec![0xc0; 65]]; 2];
+        let raw = tx.serialize(Some(&witnesses));
+        let decoded: Transaction = deserialize(Reader::new(&raw)).unwrap();
+        assert_eq!(decoded.inputs.len(), 2);
+        assert_eq!(decoded.outputs.len(), 3);
+        assert_eq!(decoded.outputs[0].value, 600_000);
+        assert_eq!(decoded.inputs[1].script_witness.len(), 3);
+
+        let unsigned: Transaction = deserialize(Reader::new(&tx.serialize(None))).unwrap();
+        assert_eq!(unsigned.hash(), decoded.hash());
+        assert!(estimate_vsize(&tx, 65) * 4 >= raw.len() as u64);
+    }
+
+    #[test]
+    fn test_sighash_commits_to_the_input() {
+        let tx = WithdrawalTx {
+            inputs: vec![utxo(1, 500_000), utxo(2, 300_000)],
+            outputs: vec![output(700_000)],
+        };
+        let script = leaf_script(&[2u8; 32]);
+        assert_ne!(tx.sighash(0, &script), tx.sighash(1, &script));
+        assert_ne!(
+            tx.sighash(0, &script),
+            tx.sighash(0, &leaf_script(&[3u8; 32]))
+        );
+
+        let mut other = tx.clone();
+        other.inputs[1].value += 1;
+        assert_ne!(tx.sighash(0, &script), other.sighash(0, &script));
+    }
+}
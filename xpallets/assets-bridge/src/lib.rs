@@ -11,6 +11,7 @@
 pub mod abi;
 #[cfg(test)]
 mod mock;
+pub mod precompile;
 #[cfg(test)]
 mod tests;
 pub use abi::*;
@@ -24,9 +25,10 @@ use frame_support::{
     traits::{Currency, ExistenceRequirement, IsType},
     transactional,
 };
+use frame_system::ensure_signed;
 use sp_core::{ecdsa, H160, U256};
 use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
-use sp_runtime::traits::{StaticLookup, UniqueSaturatedInto, Zero};
+use sp_runtime::traits::{Saturating, StaticLookup, UniqueSaturatedInto, Zero};
 use sp_std::vec::Vec;
 
 pub use chainx_primitives::AssetId;
@@ -46,6 +48,26 @@ pub enum ActionType {
     BackForeign(AssetId),
 }
 
+/// The scoped permissions of the assets bridge.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, scale_info::TypeInfo)]
+pub enum Role {
+    /// Pause and unpause the deposit and withdraw.
+    Pauser,
+    /// Register and unregister the erc20 contracts.
+    Registrar,
+    /// Manage the assets which can back foreign chain.
+    BackForeign,
+}
+
+/// The time-locked change of the erc20 contract mapping.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, scale_info::TypeInfo)]
+pub enum RegistrationChange {
+    /// Bond the asset and the erc20 contract.
+    Register(H160),
+    /// Unbond the asset and its erc20 contract.
+    Unregister,
+}
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -69,6 +91,15 @@ pub mod pallet {
         /// How much should be locked up in order to claim account.
         #[pallet::constant]
         type ClaimBond: Get<BalanceOf<Self>>;
+        /// The origin which has the `Pauser` role besides the role accounts.
+        type PauserOrigin: EnsureOrigin<Self::Origin>;
+        /// The origin which has the `Registrar` role besides the role accounts.
+        type RegistrarOrigin: EnsureOrigin<Self::Origin>;
+        /// The origin which has the `BackForeign` role besides the role accounts.
+        type BackForeignOrigin: EnsureOrigin<Self::Origin>;
+        /// How long the register and unregister wait before taking effect.
+        #[pallet::constant]
+        type RegistrationDelay: Get<Self::BlockNumber>;
     }
 
     /// The Substrate Account for Evm Addresses
@@ -99,6 +130,21 @@ pub mod pallet {
     #[pallet::getter(fn asset_ids)]
     pub type AssetIds<T: Config> = StorageMap<_, Twox64Concat, H160, AssetId, OptionQuery>;
 
+    /// The allowances of the ERC-20 precompiles
+    ///
+    /// Allowances: double_map AssetId, (owner, spender) => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn allowances)]
+    pub type Allowances<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetId,
+        Blake2_128Concat,
+        (H160, H160),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
     /// The Assets can back foreign chain
     ///
     /// AssetIds: Vec<AssetId>
@@ -106,10 +152,35 @@ pub mod pallet {
     #[pallet::getter(fn back_foreign_assets)]
     pub type BackForeign<T: Config> = StorageValue<_, Vec<AssetId>, ValueQuery>;
 
-    /// The pallet admin key.
+    /// The accounts which have the role.
+    ///
+    /// Roles: double_map Role, AccountId => Option<()>
     #[pallet::storage]
-    #[pallet::getter(fn admin_key)]
-    pub(super) type Admin<T: Config> = StorageValue<_, T::AccountId>;
+    pub(super) type Roles<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Role, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// The time-locked registration changes and the block they take effect.
+    ///
+    /// PendingChanges: map AssetId => Option<(RegistrationChange, BlockNumber)>
+    #[pallet::storage]
+    #[pallet::getter(fn pending_changes)]
+    pub type PendingChanges<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, (RegistrationChange, T::BlockNumber), OptionQuery>;
+
+    /// The assets whose pending registration changes take effect at the block.
+    ///
+    /// PendingChangesAt: map BlockNumber => Vec<AssetId>
+    #[pallet::storage]
+    pub(super) type PendingChangesAt<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<AssetId>, ValueQuery>;
+
+    /// The assets of the erc20 contracts with a pending registration.
+    ///
+    /// PendingRegistrations: map H160 => Option<AssetId>
+    #[pallet::storage]
+    #[pallet::getter(fn pending_registrations)]
+    pub type PendingRegistrations<T: Config> =
+        StorageMap<_, Twox64Concat, H160, AssetId, OptionQuery>;
 
     /// The Assets in emergency
     #[pallet::storage]
@@ -118,15 +189,15 @@ pub mod pallet {
 
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
-        /// The `AccountId` of the admin key.
-        pub admin_key: Option<T::AccountId>,
+        /// The initial role accounts.
+        pub roles: Vec<(Role, T::AccountId)>,
     }
 
     #[cfg(feature = "std")]
     impl<T: Config> Default for GenesisConfig<T> {
         fn default() -> Self {
             Self {
-                admin_key: Default::default(),
+                roles: Default::default(),
             }
         }
     }
@@ -134,9 +205,27 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
-            if let Some(key) = &self.admin_key {
-                <Admin<T>>::put(key.clone());
+            for (role, who) in &self.roles {
+                Roles::<T>::insert(role, who, ());
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let due = PendingChangesAt::<T>::take(now);
+            let mut applied = 0u64;
+            for asset_id in &due {
+                if let Some((change, effective_at)) = PendingChanges::<T>::get(asset_id) {
+                    if effective_at <= now {
+                        Self::remove_pending_change(*asset_id, &change);
+                        Self::apply_registration_change(*asset_id, change);
+                        applied += 1;
+                    }
+                }
             }
+            T::DbWeight::get().reads_writes(1 + due.len() as u64 + 2 * applied, 1 + 5 * applied)
         }
     }
 
@@ -153,8 +242,10 @@ pub mod pallet {
         WithdrawExecuted(AssetId, T::AccountId, H160, BalanceOf<T>, H160),
         /// (account_id, amount, action)
         Teleport(T::AccountId, BalanceOf<T>, ActionType),
-        /// (account_id)
-        SetAdmin(T::AccountId),
+        /// (evm_address, account_id, amount)
+        EvmTeleport(H160, T::AccountId, BalanceOf<T>),
+        /// (role, account_id)
+        RoleGranted(Role, T::AccountId),
         /// (asset_id, erc20_contract)
         Register(AssetId, H160),
         /// (asset_id, erc20_contract)
@@ -167,6 +258,12 @@ pub mod pallet {
         UnPausedAll,
         // (asset_id, remove)
         BackForeign(AssetId, bool),
+        /// (role, account_id)
+        RoleRevoked(Role, T::AccountId),
+        /// (asset_id, change, effective_at)
+        RegistrationScheduled(AssetId, RegistrationChange, T::BlockNumber),
+        /// (asset_id, change)
+        RegistrationCancelled(AssetId, RegistrationChange),
     }
 
     /// Error for evm accounts module.
@@ -194,14 +291,18 @@ pub mod pallet {
         ContractAddressHasNotMapped,
         /// Failed Erc20 contract call
         ExecutedFailed,
-        /// Require admin authority
-        RequireAdmin,
+        /// Require the role authority
+        RequireRole,
         /// Ban deposit and withdraw when in emergency
         InEmergency,
         /// Ban back to foreign
         BanBackForeign,
         /// Zero balance
         ZeroBalance,
+        /// The asset has a pending registration change
+        PendingChangeExists,
+        /// The asset has no pending registration change
+        PendingChangeNotExists,
     }
 
     #[pallet::call]
@@ -279,7 +380,10 @@ pub mod pallet {
         ///
         /// - `asset_id`: The asset id
         /// - `amount`: Deposit amount
-        #[pallet::weight(1_000_000u64)]
+        #[pallet::weight(
+            // The locks and vesting schedules of the burnt asset are read.
+            1_000_000u64.saturating_add(T::DbWeight::get().reads(2))
+        )]
         #[transactional]
         pub fn deposit(
             origin: OriginFor<T>,
@@ -288,8 +392,11 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             ensure!(!Self::is_in_emergency(asset_id), Error::<T>::InEmergency);
+            xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id)?;
             ensure!(!amount.is_zero(), Error::<T>::ZeroBalance);
 
+            xpallet_assets::Pallet::<T>::ensure_can_move_out(&who)?;
+
             // 1. check evm account
             let evm_account = Self::evm_accounts(&who).ok_or(Error::<T>::EthAddressHasNotMapped)?;
 
@@ -328,6 +435,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             ensure!(!Self::is_in_emergency(asset_id), Error::<T>::InEmergency);
+            xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id)?;
             ensure!(!amount.is_zero(), Error::<T>::ZeroBalance);
 
             // 1. check evm account
@@ -365,7 +473,10 @@ pub mod pallet {
         ///    (3) FromEthToSub: transfer from mapped evm address to substrate account
         /// - companion with `relay`:
         ///    (4) BackForeign(asset_id): transfer assets back foreign chain
-        #[pallet::weight(1_000_000u64)]
+        #[pallet::weight(
+            // The locks and vesting schedules of the burnt asset are read.
+            1_000_000u64.saturating_add(T::DbWeight::get().reads(2))
+        )]
         #[transactional]
         pub fn teleport(
             origin: OriginFor<T>,
@@ -402,6 +513,8 @@ pub mod pallet {
                         Error::<T>::BanBackForeign
                     );
                     ensure!(!Self::is_in_emergency(asset_id), Error::<T>::InEmergency);
+                    xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id)?;
+                    xpallet_assets::Pallet::<T>::ensure_can_move_out(&who)?;
 
                     let amount: u128 = amount.unique_saturated_into();
                     // burn asset first, then relay will transfer back `who`.
@@ -416,6 +529,7 @@ pub mod pallet {
             };
 
             if !back_foreign {
+                xpallet_assets::Pallet::<T>::ensure_not_paused(&T::NativeAssetId::get())?;
                 <T as xpallet_assets::Config>::Currency::transfer(
                     &from,
                     &to,
@@ -429,8 +543,9 @@ pub mod pallet {
             Ok(Pays::No.into())
         }
 
-        /// Register substrate assets and erc20 contracts
-        /// Note: for admin
+        /// Register substrate assets and erc20 contracts,
+        /// which takes effect after `RegistrationDelay`.
+        /// Note: for registrar
         ///
         /// - `asset_id`: The asset id
         /// - `erc20`: The erc20 contract address
@@ -440,8 +555,7 @@ pub mod pallet {
             asset_id: AssetId,
             erc20: H160,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
-            ensure!(Some(who) == Self::admin_key(), Error::<T>::RequireAdmin);
+            Self::ensure_role(origin, Role::Registrar)?;
 
             // ensure asset_id and erc20 address has not been mapped
             ensure!(
@@ -452,17 +566,41 @@ pub mod pallet {
                 !AssetIds::<T>::contains_key(&erc20),
                 Error::<T>::ContractAddressHasMapped
             );
+            ensure!(
+                !PendingRegistrations::<T>::contains_key(&erc20),
+                Error::<T>::ContractAddressHasMapped
+            );
+
+            Self::schedule_registration_change(asset_id, RegistrationChange::Register(erc20))?;
+
+            Ok(Pays::No.into())
+        }
+
+        /// Cancel the pending registration change of the asset
+        /// Note: for registrar
+        ///
+        /// - `asset_id`: The asset id
+        #[pallet::weight(100_000_000u64)]
+        pub fn cancel_registration_change(
+            origin: OriginFor<T>,
+            asset_id: AssetId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_role(origin, Role::Registrar)?;
 
-            Erc20s::<T>::insert(asset_id, erc20);
-            AssetIds::<T>::insert(erc20, asset_id);
+            let (change, effective_at) =
+                PendingChanges::<T>::get(asset_id).ok_or(Error::<T>::PendingChangeNotExists)?;
+            Self::remove_pending_change(asset_id, &change);
+            PendingChangesAt::<T>::mutate(effective_at, |assets| {
+                assets.retain(|&pending| pending != asset_id)
+            });
 
-            Self::deposit_event(Event::Register(asset_id, erc20));
+            Self::deposit_event(Event::RegistrationCancelled(asset_id, change));
 
             Ok(Pays::No.into())
         }
 
         /// Pause assets bridge deposit and withdraw
-        /// Note: for admin
+        /// Note: for pauser
         ///
         /// - `asset_id`: None will pause all, Some(id) will pause the specified asset
         #[pallet::weight(100_000_000u64)]
@@ -470,8 +608,7 @@ pub mod pallet {
             origin: OriginFor<T>,
             asset_id: Option<AssetId>,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
-            ensure!(Some(who) == Self::admin_key(), Error::<T>::RequireAdmin);
+            Self::ensure_role(origin, Role::Pauser)?;
 
             Emergencies::<T>::try_mutate(|emergencies| {
                 if let Some(id) = asset_id {
@@ -499,7 +636,7 @@ pub mod pallet {
         }
 
         /// Unpause assets bridge deposit and withdraw
-        /// Note: for admin
+        /// Note: for pauser
         ///
         /// - `asset_id`: None will unpause all, Some(id) will unpause the specified asset
         #[pallet::weight(100_000_000u64)]
@@ -507,8 +644,7 @@ pub mod pallet {
             origin: OriginFor<T>,
             asset_id: Option<AssetId>,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
-            ensure!(Some(who) == Self::admin_key(), Error::<T>::RequireAdmin);
+            Self::ensure_role(origin, Role::Pauser)?;
 
             Emergencies::<T>::try_mutate(|emergencies| {
                 if let Some(id) = asset_id {
@@ -534,7 +670,7 @@ pub mod pallet {
         }
 
         /// Add assets which can back add_back_foreign chain
-        /// Note: for admin
+        /// Note: for back foreign role
         ///
         /// - `asset_id`:
         #[pallet::weight(100_000_000u64)]
@@ -543,8 +679,7 @@ pub mod pallet {
             asset_id: AssetId,
             remove: bool,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
-            ensure!(Some(who) == Self::admin_key(), Error::<T>::RequireAdmin);
+            Self::ensure_role(origin, Role::BackForeign)?;
 
             BackForeign::<T>::try_mutate(|foreigns| {
                 if remove {
@@ -561,31 +696,50 @@ pub mod pallet {
             })
         }
 
-        /// Set this pallet admin key
+        /// Grant the role to the account, e.g. a multisig account
         /// Note: for super admin
         #[pallet::weight(100_000_000u64)]
-        pub fn set_admin(
+        pub fn grant_role(
             origin: OriginFor<T>,
-            new_admin: <T::Lookup as StaticLookup>::Source,
+            role: Role,
+            who: <T::Lookup as StaticLookup>::Source,
         ) -> DispatchResultWithPostInfo {
             ensure_root(origin)?;
-            let new_admin = T::Lookup::lookup(new_admin)?;
-
-            let _ = Self::set_admin_inner(new_admin.clone());
+            let who = T::Lookup::lookup(who)?;
 
-            Self::deposit_event(Event::SetAdmin(new_admin));
+            let _ = Self::grant_role_inner(role, who);
 
             Ok(Pays::No.into())
         }
 
-        /// Force unregister substrate assets and erc20 contracts
+        /// Revoke the role from the account
         /// Note: for super admin
         #[pallet::weight(100_000_000u64)]
+        pub fn revoke_role(
+            origin: OriginFor<T>,
+            role: Role,
+            who: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+
+            ensure!(Self::has_role(role, &who), Error::<T>::RequireRole);
+            Roles::<T>::remove(role, &who);
+
+            Self::deposit_event(Event::RoleRevoked(role, who));
+
+            Ok(Pays::No.into())
+        }
+
+        /// Unregister substrate assets and erc20 contracts,
+        /// which takes effect after `RegistrationDelay`.
+        /// Note: for registrar
+        #[pallet::weight(100_000_000u64)]
         pub fn force_unregister(
             origin: OriginFor<T>,
             asset_id: AssetId,
         ) -> DispatchResultWithPostInfo {
-            ensure_root(origin)?;
+            Self::ensure_role(origin, Role::Registrar)?;
 
             let erc20 = Self::erc20s(&asset_id).ok_or(Error::<T>::AssetIdHasNotMapped)?;
 
@@ -594,17 +748,7 @@ pub mod pallet {
                 Error::<T>::ContractAddressHasMapped
             );
 
-            Erc20s::<T>::remove(&asset_id);
-            AssetIds::<T>::remove(&erc20);
-
-            // clear emergency
-            if Self::is_in_emergency(asset_id) {
-                Emergencies::<T>::mutate(|emergencies| {
-                    emergencies.retain(|&emergency| emergency != asset_id);
-                })
-            }
-
-            Self::deposit_event(Event::ForceUnRegister(asset_id, erc20));
+            Self::schedule_registration_change(asset_id, RegistrationChange::Unregister)?;
 
             Ok(Pays::No.into())
         }
@@ -612,11 +756,115 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
-    pub fn set_admin_inner(new_admin: T::AccountId) -> Weight {
-        Admin::<T>::mutate(|admin| *admin = Some(new_admin));
+    /// Returns true if `who` has the `role`.
+    pub fn has_role(role: Role, who: &T::AccountId) -> bool {
+        Roles::<T>::contains_key(role, who)
+    }
+
+    pub fn grant_role_inner(role: Role, who: T::AccountId) -> Weight {
+        Roles::<T>::insert(role, &who, ());
+        Self::deposit_event(Event::RoleGranted(role, who));
         T::DbWeight::get().write
     }
 
+    /// Grants every role to the account of the legacy `Admin` key and removes it.
+    pub fn migrate_admin_to_roles() -> Weight {
+        let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+        match frame_support::storage::migration::take_storage_value::<T::AccountId>(
+            pallet,
+            b"Admin",
+            &[],
+        ) {
+            Some(admin) => [Role::Pauser, Role::Registrar, Role::BackForeign]
+                .into_iter()
+                .fold(T::DbWeight::get().reads_writes(1, 1), |weight, role| {
+                    weight.saturating_add(Self::grant_role_inner(role, admin.clone()))
+                }),
+            None => T::DbWeight::get().reads(1),
+        }
+    }
+
+    /// Ensures the `origin` is the role origin or a signed account which has the `role`.
+    fn ensure_role(origin: T::Origin, role: Role) -> DispatchResult {
+        let origin = match role {
+            Role::Pauser => T::PauserOrigin::try_origin(origin),
+            Role::Registrar => T::RegistrarOrigin::try_origin(origin),
+            Role::BackForeign => T::BackForeignOrigin::try_origin(origin),
+        };
+        match origin {
+            Ok(_) => Ok(()),
+            Err(origin) => {
+                let who = ensure_signed(origin)?;
+                ensure!(Self::has_role(role, &who), Error::<T>::RequireRole);
+                Ok(())
+            }
+        }
+    }
+
+    fn schedule_registration_change(
+        asset_id: AssetId,
+        change: RegistrationChange,
+    ) -> DispatchResult {
+        ensure!(
+            !PendingChanges::<T>::contains_key(asset_id),
+            Error::<T>::PendingChangeExists
+        );
+
+        let delay = T::RegistrationDelay::get();
+        if delay.is_zero() {
+            Self::apply_registration_change(asset_id, change);
+            return Ok(());
+        }
+
+        let effective_at = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+        if let RegistrationChange::Register(erc20) = change {
+            PendingRegistrations::<T>::insert(erc20, asset_id);
+        }
+        PendingChanges::<T>::insert(asset_id, (change.clone(), effective_at));
+        PendingChangesAt::<T>::append(effective_at, asset_id);
+        Self::deposit_event(Event::RegistrationScheduled(asset_id, change, effective_at));
+        Ok(())
+    }
+
+    fn remove_pending_change(asset_id: AssetId, change: &RegistrationChange) {
+        PendingChanges::<T>::remove(asset_id);
+        if let RegistrationChange::Register(erc20) = change {
+            PendingRegistrations::<T>::remove(erc20);
+        }
+    }
+
+    fn apply_registration_change(asset_id: AssetId, change: RegistrationChange) {
+        match change {
+            RegistrationChange::Register(erc20) => {
+                // the mapping may have changed during the time-lock
+                if Erc20s::<T>::contains_key(asset_id) || AssetIds::<T>::contains_key(erc20) {
+                    return;
+                }
+
+                Erc20s::<T>::insert(asset_id, erc20);
+                AssetIds::<T>::insert(erc20, asset_id);
+
+                Self::deposit_event(Event::Register(asset_id, erc20));
+            }
+            RegistrationChange::Unregister => {
+                let erc20 = match Erc20s::<T>::take(asset_id) {
+                    Some(erc20) => erc20,
+                    None => return,
+                };
+                AssetIds::<T>::remove(&erc20);
+
+                // clear emergency
+                if Self::is_in_emergency(asset_id) {
+                    Emergencies::<T>::mutate(|emergencies| {
+                        emergencies.retain(|&emergency| emergency != asset_id);
+                    })
+                }
+
+                Self::deposit_event(Event::ForceUnRegister(asset_id, erc20));
+            }
+        }
+    }
+
     pub fn apply_direct_deposit(
         evm_account: H160,
         asset_id: AssetId,
@@ -632,6 +880,72 @@ impl<T: Config> Pallet<T> {
         Self::call_evm(erc20, inputs)
     }
 
+    /// Withdraw from the evm erc20 contract into substrate assets, which is initiated by
+    /// the `erc20` contract itself after it has burnt `amount` from `evm_account`.
+    ///
+    /// The assets go to `target`, or to the substrate account linked with `evm_account`
+    /// if `target` is None.
+    #[transactional]
+    pub fn withdraw_from_evm(
+        erc20: H160,
+        evm_account: H160,
+        target: Option<T::AccountId>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        let asset_id = Self::asset_ids(erc20).ok_or(Error::<T>::ContractAddressHasNotMapped)?;
+        ensure!(!Self::is_in_emergency(asset_id), Error::<T>::InEmergency);
+        xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id)?;
+        ensure!(!amount.is_zero(), Error::<T>::ZeroBalance);
+
+        let who = match target {
+            Some(target) => target,
+            None => Self::sub_accounts(evm_account).ok_or(Error::<T>::AccountIdHasNotMapped)?,
+        };
+
+        xpallet_assets::Pallet::<T>::issue(&asset_id, &who, amount)?;
+
+        Self::deposit_event(Event::WithdrawExecuted(
+            asset_id,
+            who,
+            evm_account,
+            amount,
+            erc20,
+        ));
+
+        Ok(())
+    }
+
+    /// Teleport native currency from the evm address into substrate account, which is
+    /// initiated by the evm address itself.
+    ///
+    /// The currency goes to `target`, or to the substrate account linked with `evm_account`
+    /// if `target` is None.
+    #[transactional]
+    pub fn teleport_from_evm(
+        evm_account: H160,
+        target: Option<T::AccountId>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        xpallet_assets::Pallet::<T>::ensure_not_paused(&T::NativeAssetId::get())?;
+        ensure!(!amount.is_zero(), Error::<T>::ZeroBalance);
+
+        let who = match target {
+            Some(target) => target,
+            None => Self::sub_accounts(evm_account).ok_or(Error::<T>::AccountIdHasNotMapped)?,
+        };
+
+        <T as xpallet_assets::Config>::Currency::transfer(
+            &AddressMappingOf::<T>::into_account_id(evm_account),
+            &who,
+            amount,
+            ExistenceRequirement::AllowDeath,
+        )?;
+
+        Self::deposit_event(Event::EvmTeleport(evm_account, who, amount));
+
+        Ok(())
+    }
+
     fn call_evm(erc20: H160, inputs: Vec<u8>) -> DispatchResult {
         match T::Runner::call(
             T::EvmCaller::get(),
@@ -1,7 +1,9 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
+use std::cell::RefCell;
+
 pub use crate as xassets_bridge;
-pub use xassets_bridge::{AssetId, Config, Error, Event as XAssetsBridgeEvent};
+pub use xassets_bridge::{AssetId, Config, Error, Event as XAssetsBridgeEvent, Role};
 
 use frame_support::traits::ConstU32;
 use frame_support::{parameter_types, traits::GenesisBuild};
@@ -12,6 +14,7 @@ pub use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
     AccountId32,
 };
+use xpallet_support::traits::AssetPause;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -99,6 +102,7 @@ parameter_types! {
     // 0x1111111111111111111111111111111111111111
     pub EvmCaller: H160 = H160::from_slice(&[17u8;20][..]);
     pub ClaimBond: u128 = 2;
+    pub storage RegistrationDelay: u64 = 0;
 }
 
 parameter_types! {
@@ -109,15 +113,45 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
+thread_local! {
+    static PAUSED_ASSETS: RefCell<Vec<AssetId>> = RefCell::new(Vec::new());
+}
+
+/// Pauses or unpauses the user operations moving the asset in the tests.
+pub fn set_asset_paused(id: AssetId, paused: bool) {
+    PAUSED_ASSETS.with(|assets| {
+        let mut assets = assets.borrow_mut();
+        assets.retain(|asset| *asset != id);
+        if paused {
+            assets.push(id);
+        }
+    });
+}
+
+pub struct MockAssetPause;
+impl AssetPause<AssetId> for MockAssetPause {
+    fn is_paused(asset_id: &AssetId) -> bool {
+        PAUSED_ASSETS.with(|assets| assets.borrow().contains(asset_id))
+    }
+}
+
 impl xpallet_assets::Config for Test {
     type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = ();
+    type AssetPause = MockAssetPause;
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -144,6 +178,10 @@ impl xassets_bridge::Config for Test {
     type Event = Event;
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
+    type PauserOrigin = frame_system::EnsureRoot<AccountId32>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId32>;
+    type BackForeignOrigin = frame_system::EnsureRoot<AccountId32>;
+    type RegistrationDelay = RegistrationDelay;
 }
 
 pub const ALICE: [u8; 32] = [1u8; 32];
@@ -161,7 +199,11 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .unwrap();
 
     xassets_bridge::GenesisConfig::<Test> {
-        admin_key: Some(ALICE.into()),
+        roles: vec![
+            (Role::Pauser, ALICE.into()),
+            (Role::Registrar, ALICE.into()),
+            (Role::BackForeign, ALICE.into()),
+        ],
     }
     .assimilate_storage(&mut t)
     .unwrap();
@@ -0,0 +1,611 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Precompiles of the assets bridge.
+//!
+//! ERC-20 precompile exposing the XAssets balances to the EVM.
+//!
+//! Each non-native asset registered in xpallet-assets-registrar with an id up to 1023 has
+//! a deterministic precompile address, see [`asset_precompile_address`]. The balances are
+//! the usable balances of the Substrate account mapped from the EVM address, the assets
+//! never leave xpallet-assets, so the asset restrictions, vesting and mining hooks still
+//! apply.
+//!
+//! Assets bridge precompile, see [`AssetsBridgePrecompile`], which allows the EVM side
+//! to move the ERC-20 tokens and PCX back to Substrate without a signed extrinsic.
+
+use fp_evm::{
+    Context, ExitError, ExitRevert, ExitSucceed, Log, PrecompileFailure, PrecompileOutput,
+    PrecompileResult,
+};
+use frame_support::{
+    dispatch::Weight,
+    traits::{tokens::fungibles::Transfer, Get},
+};
+use pallet_evm::GasWeightMapping;
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+    traits::{SaturatedConversion, UniqueSaturatedInto},
+    DispatchError,
+};
+use sp_std::{marker::PhantomData, vec, vec::Vec};
+use xpallet_assets::WeightInfo as AssetsWeightInfo;
+
+use super::*;
+
+/// The first ChainX specific precompile index of the XAssets ERC-20 family.
+pub const ASSETS_ERC20_PRECOMPILE_START: u64 = 3072;
+
+/// The last ChainX specific precompile index of the XAssets ERC-20 family.
+pub const ASSETS_ERC20_PRECOMPILE_END: u64 = 4095;
+
+/// keccak256("Transfer(address,address,uint256)")
+pub const TRANSFER_EVENT_TOPIC: [u8; 32] = [
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+
+/// keccak256("Approval(address,address,uint256)")
+pub const APPROVAL_EVENT_TOPIC: [u8; 32] = [
+    0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4f, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3,
+    0xdd, 0x03, 0x14, 0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25,
+];
+
+/// The ERC-20 function selectors, i.e. the first 4 bytes of keccak256(signature).
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[repr(u32)]
+enum Action {
+    /// name()
+    Name = 0x06fdde03,
+    /// symbol()
+    Symbol = 0x95d89b41,
+    /// decimals()
+    Decimals = 0x313ce567,
+    /// totalSupply()
+    TotalSupply = 0x18160ddd,
+    /// balanceOf(address)
+    BalanceOf = 0x70a08231,
+    /// allowance(address,address)
+    Allowance = 0xdd62ed3e,
+    /// transfer(address,uint256)
+    Transfer = 0xa9059cbb,
+    /// approve(address,uint256)
+    Approve = 0x095ea7b3,
+    /// transferFrom(address,address,uint256)
+    TransferFrom = 0x23b872dd,
+}
+
+impl Action {
+    fn from_selector(selector: u32) -> Option<Self> {
+        [
+            Self::Name,
+            Self::Symbol,
+            Self::Decimals,
+            Self::TotalSupply,
+            Self::BalanceOf,
+            Self::Allowance,
+            Self::Transfer,
+            Self::Approve,
+            Self::TransferFrom,
+        ]
+        .into_iter()
+        .find(|action| *action as u32 == selector)
+    }
+
+    fn is_mutating(&self) -> bool {
+        matches!(self, Self::Transfer | Self::Approve | Self::TransferFrom)
+    }
+}
+
+/// Returns the ERC-20 precompile address of asset `id`.
+///
+/// The address is the precompile index `3072 + id`, so only the assets with an id up to
+/// 1023 have one.
+pub fn asset_precompile_address(id: AssetId) -> Option<H160> {
+    let index = ASSETS_ERC20_PRECOMPILE_START + u64::from(id);
+    (index <= ASSETS_ERC20_PRECOMPILE_END).then(|| H160::from_low_u64_be(index))
+}
+
+/// Returns the `AssetId` encoded in `address` if it's an ERC-20 precompile address.
+pub fn asset_id_of_precompile(address: &H160) -> Option<AssetId> {
+    if address.as_bytes()[..12].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let index = address.to_low_u64_be();
+    (ASSETS_ERC20_PRECOMPILE_START..=ASSETS_ERC20_PRECOMPILE_END)
+        .contains(&index)
+        .then(|| (index - ASSETS_ERC20_PRECOMPILE_START) as AssetId)
+}
+
+/// Returns all the addresses of the ERC-20 precompile range, whether the asset exists or not.
+pub fn asset_precompile_addresses() -> impl Iterator<Item = H160> {
+    (ASSETS_ERC20_PRECOMPILE_START..=ASSETS_ERC20_PRECOMPILE_END).map(H160::from_low_u64_be)
+}
+
+/// Reader of the ABI encoded call data.
+pub struct EvmDataReader<'a> {
+    input: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> EvmDataReader<'a> {
+    /// Splits the 4 bytes function selector from `input`.
+    pub fn new_with_selector(input: &'a [u8]) -> Result<(Self, u32), PrecompileFailure> {
+        if input.len() < 4 {
+            return Err(revert("tried to parse selector out of bounds"));
+        }
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&input[..4]);
+        Ok((
+            Self {
+                input: &input[4..],
+                cursor: 0,
+            },
+            u32::from_be_bytes(selector),
+        ))
+    }
+
+    fn read_word(&mut self) -> Result<&'a [u8], PrecompileFailure> {
+        let word = self
+            .input
+            .get(self.cursor..self.cursor + 32)
+            .ok_or_else(|| revert("tried to parse word out of bounds"))?;
+        self.cursor += 32;
+        Ok(word)
+    }
+
+    /// Reads an `address`.
+    pub fn read_address(&mut self) -> Result<H160, PrecompileFailure> {
+        let word = self.read_word()?;
+        Ok(H160::from_slice(&word[12..]))
+    }
+
+    /// Reads an `uint256`.
+    pub fn read_u256(&mut self) -> Result<U256, PrecompileFailure> {
+        Ok(U256::from_big_endian(self.read_word()?))
+    }
+
+    /// Reads an `uint256` as an `u128`, saturating at `u128::MAX`.
+    ///
+    /// The amounts beyond `u128::MAX` can't be covered by any balance anyway, while the
+    /// `type(uint256).max` allowance is what the contracts approve for "unlimited".
+    pub fn read_u128(&mut self) -> Result<u128, PrecompileFailure> {
+        let value = self.read_u256()?;
+        Ok(value.min(U256::from(u128::MAX)).low_u128())
+    }
+
+    /// Reads an `uint32`.
+    pub fn read_u32(&mut self) -> Result<u32, PrecompileFailure> {
+        let value = self.read_u256()?;
+        if value > U256::from(u32::MAX) {
+            return Err(revert("value is too large"));
+        }
+        Ok(value.low_u32())
+    }
+
+    /// Reads a `bytes32`.
+    pub fn read_bytes32(&mut self) -> Result<H256, PrecompileFailure> {
+        Ok(H256::from_slice(self.read_word()?))
+    }
+
+    /// Reads a dynamic `bytes` or `string`.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, PrecompileFailure> {
+        let offset = self.read_u32()? as usize;
+        let len_word = self
+            .input
+            .get(offset..offset.saturating_add(32))
+            .ok_or_else(|| revert("tried to parse bytes length out of bounds"))?;
+        let len = U256::from_big_endian(len_word);
+        if len > U256::from(u32::MAX) {
+            return Err(revert("bytes length is too large"));
+        }
+        let start = offset.saturating_add(32);
+        let data = self
+            .input
+            .get(start..start.saturating_add(len.low_u32() as usize))
+            .ok_or_else(|| revert("tried to parse bytes out of bounds"))?;
+        Ok(data.to_vec())
+    }
+}
+
+/// Encodes `value` as an ABI `uint256` word.
+pub fn encode_u256(value: U256) -> Vec<u8> {
+    let mut word = vec![0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// Encodes `value` as an ABI `bool` word.
+pub fn encode_bool(value: bool) -> Vec<u8> {
+    encode_u256(if value { U256::one() } else { U256::zero() })
+}
+
+/// Encodes the `arrays` as the ABI return value of a tuple of dynamic `uint` arrays.
+pub fn encode_uint_arrays(arrays: &[Vec<U256>]) -> Vec<u8> {
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for array in arrays {
+        head.extend(encode_u256(U256::from(32 * arrays.len() + tail.len())));
+        tail.extend(encode_u256(U256::from(array.len())));
+        array
+            .iter()
+            .for_each(|value| tail.extend(encode_u256(*value)));
+    }
+    head.extend(tail);
+    head
+}
+
+/// Encodes `value` as an ABI `string`/`bytes` return value.
+pub fn encode_bytes(value: &[u8]) -> Vec<u8> {
+    let padded_len = (value.len() + 31) / 32 * 32;
+    let mut output = encode_u256(U256::from(32));
+    output.extend(encode_u256(U256::from(value.len())));
+    output.extend_from_slice(value);
+    output.resize(64 + padded_len, 0);
+    output
+}
+
+/// Returns a failure which reverts with the message of the dispatch `error`.
+pub fn revert_dispatch_error(error: DispatchError) -> PrecompileFailure {
+    let message: &'static str = error.into();
+    revert(message)
+}
+
+/// Returns a revert failure with the `message`.
+pub fn revert(message: &str) -> PrecompileFailure {
+    PrecompileFailure::Revert {
+        exit_status: ExitRevert::Reverted,
+        output: message.as_bytes().to_vec(),
+        cost: 0,
+    }
+}
+
+/// Returns the Substrate account of the EVM `address`.
+///
+/// The account claimed via `claim_account` takes precedence over the one derived
+/// from `pallet_evm::Config::AddressMapping`.
+pub fn evm_to_sub_account<T: Config>(address: H160) -> T::AccountId {
+    Pallet::<T>::sub_accounts(address)
+        .unwrap_or_else(|| AddressMappingOf::<T>::into_account_id(address))
+}
+
+/// Gas metering of a precompile call.
+pub struct Gasometer<T> {
+    used: u64,
+    limit: Option<u64>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: pallet_evm::Config> Gasometer<T> {
+    pub fn new(limit: Option<u64>) -> Self {
+        Self {
+            used: 0,
+            limit,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Records the cost of the weight, fails if it exceeds the gas limit.
+    pub fn record_weight(&mut self, weight: Weight) -> Result<(), PrecompileFailure> {
+        self.used = self
+            .used
+            .saturating_add(T::GasWeightMapping::weight_to_gas(weight));
+        match self.limit {
+            Some(limit) if self.used > limit => Err(PrecompileFailure::Error {
+                exit_status: ExitError::OutOfGas,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the cost of `reads` storage reads and `writes` storage writes.
+    pub fn record_db(&mut self, reads: u64, writes: u64) -> Result<(), PrecompileFailure> {
+        self.record_weight(T::DbWeight::get().reads_writes(reads, writes))
+    }
+
+    /// Returns the gas used so far.
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+}
+
+/// ERC-20 precompile of the assets in xpallet-assets.
+pub struct Erc20AssetsPrecompile<T>(PhantomData<T>);
+
+impl<T: Config> Erc20AssetsPrecompile<T> {
+    /// Returns true if `address` is in the ERC-20 precompile range.
+    ///
+    /// It doesn't read the storage, the calls to the precompile of an asset which is not
+    /// exposed revert.
+    pub fn is_precompile(address: H160) -> bool {
+        asset_id_of_precompile(&address).is_some()
+    }
+
+    /// Returns true if asset `id` is a valid non-native asset.
+    fn is_exposed(id: &AssetId) -> bool {
+        xpallet_assets::Pallet::<T>::ensure_not_native_asset(id).is_ok()
+            && xpallet_assets_registrar::Pallet::<T>::is_valid(id)
+    }
+
+    pub fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        let asset_id =
+            asset_id_of_precompile(&address).ok_or_else(|| revert("not an asset precompile"))?;
+        // Forbid the delegatecall and callcode, the balances of the precompile would
+        // be moved on behalf of the calling contract otherwise.
+        if context.address != address {
+            return Err(revert("cannot be called with delegatecall or callcode"));
+        }
+
+        let (mut reader, selector) = EvmDataReader::new_with_selector(input)?;
+        let action = Action::from_selector(selector).ok_or_else(|| revert("unknown selector"))?;
+        if action.is_mutating() {
+            if is_static {
+                return Err(revert("cannot modify state in static context"));
+            }
+            if !context.apparent_value.is_zero() {
+                return Err(revert("function is not payable"));
+            }
+        }
+
+        let mut gasometer = Gasometer::<T>::new(target_gas);
+        gasometer.record_db(1, 0)?;
+        if !Self::is_exposed(&asset_id) {
+            return Err(revert("asset is not exposed"));
+        }
+
+        let mut logs = Vec::new();
+        let output = match action {
+            Action::Name | Action::Symbol | Action::Decimals => {
+                gasometer.record_db(1, 0)?;
+                let info = xpallet_assets_registrar::Pallet::<T>::asset_info_of(asset_id)
+                    .ok_or_else(|| revert("asset is not registered"))?;
+                match action {
+                    Action::Name => encode_bytes(info.token_name()),
+                    Action::Symbol => encode_bytes(info.token()),
+                    _ => encode_u256(U256::from(info.decimals())),
+                }
+            }
+            Action::TotalSupply => {
+                gasometer.record_db(1, 0)?;
+                let total = xpallet_assets::Pallet::<T>::total_issuance(&asset_id);
+                encode_u256(balance_to_u256::<T>(total))
+            }
+            Action::BalanceOf => {
+                let owner = reader.read_address()?;
+                gasometer.record_db(2, 0)?;
+                let who = evm_to_sub_account::<T>(owner);
+                let balance = xpallet_assets::Pallet::<T>::usable_balance(&who, &asset_id);
+                encode_u256(balance_to_u256::<T>(balance))
+            }
+            Action::Allowance => {
+                let owner = reader.read_address()?;
+                let spender = reader.read_address()?;
+                gasometer.record_db(1, 0)?;
+                let allowance = Pallet::<T>::allowances(asset_id, (owner, spender));
+                encode_u256(balance_to_u256::<T>(allowance))
+            }
+            Action::Approve => {
+                let spender = reader.read_address()?;
+                let amount = reader.read_u128()?;
+                gasometer.record_db(0, 1)?;
+                Self::approve(asset_id, context.caller, spender, amount);
+                logs.push(Self::log(
+                    address,
+                    APPROVAL_EVENT_TOPIC,
+                    context.caller,
+                    spender,
+                    amount,
+                ));
+                encode_bool(true)
+            }
+            Action::Transfer => {
+                let to = reader.read_address()?;
+                let amount = reader.read_u128()?;
+                gasometer.record_weight(<T as xpallet_assets::Config>::WeightInfo::transfer())?;
+                Self::transfer(asset_id, context.caller, to, amount)?;
+                logs.push(Self::log(
+                    address,
+                    TRANSFER_EVENT_TOPIC,
+                    context.caller,
+                    to,
+                    amount,
+                ));
+                encode_bool(true)
+            }
+            Action::TransferFrom => {
+                let from = reader.read_address()?;
+                let to = reader.read_address()?;
+                let amount = reader.read_u128()?;
+                gasometer.record_db(1, 1)?;
+                gasometer.record_weight(<T as xpallet_assets::Config>::WeightInfo::transfer())?;
+                // The owner spends its own balances without an allowance, and an allowance
+                // of `u128::MAX` is unlimited as the ERC-20 convention.
+                let remaining = if from != context.caller {
+                    let key = (from, context.caller);
+                    let allowance: u128 =
+                        Pallet::<T>::allowances(asset_id, key).unique_saturated_into();
+                    let remaining = allowance
+                        .checked_sub(amount)
+                        .ok_or_else(|| revert("trying to spend more than allowed"))?;
+                    (allowance != u128::MAX).then(|| remaining)
+                } else {
+                    None
+                };
+                Self::transfer(asset_id, from, to, amount)?;
+                if let Some(remaining) = remaining {
+                    Self::approve(asset_id, from, context.caller, remaining);
+                }
+                logs.push(Self::log(address, TRANSFER_EVENT_TOPIC, from, to, amount));
+                encode_bool(true)
+            }
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used(),
+            output,
+            logs,
+        })
+    }
+
+    fn approve(asset_id: AssetId, owner: H160, spender: H160, amount: u128) {
+        let amount: BalanceOf<T> = amount.saturated_into();
+        if amount.is_zero() {
+            Allowances::<T>::remove(asset_id, (owner, spender));
+        } else {
+            Allowances::<T>::insert(asset_id, (owner, spender), amount);
+        }
+    }
+
+    fn transfer(
+        asset_id: AssetId,
+        from: H160,
+        to: H160,
+        amount: u128,
+    ) -> Result<(), PrecompileFailure> {
+        xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id).map_err(revert_dispatch_error)?;
+        let source = evm_to_sub_account::<T>(from);
+        let dest = evm_to_sub_account::<T>(to);
+        xpallet_assets::Pallet::<T>::ensure_can_move_out(&source).map_err(revert_dispatch_error)?;
+        <xpallet_assets::Pallet<T> as Transfer<T::AccountId>>::transfer(
+            asset_id,
+            &source,
+            &dest,
+            amount.saturated_into(),
+            false,
+        )
+        .map_err(revert_dispatch_error)?;
+        Ok(())
+    }
+
+    fn log(address: H160, topic: [u8; 32], from: H160, to: H160, amount: u128) -> Log {
+        Log {
+            address,
+            topics: vec![H256(topic), from.into(), to.into()],
+            data: encode_u256(U256::from(amount)),
+        }
+    }
+}
+
+/// The assets bridge function selectors.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[repr(u32)]
+enum BridgeAction {
+    /// withdrawTo(address,bytes32,uint256)
+    WithdrawTo = 0x84eaa2a3,
+    /// teleportTo(bytes32,uint256)
+    TeleportTo = 0x87acc285,
+    /// linkedAccount(address)
+    LinkedAccount = 0xaf67d317,
+}
+
+impl BridgeAction {
+    fn from_selector(selector: u32) -> Option<Self> {
+        [Self::WithdrawTo, Self::TeleportTo, Self::LinkedAccount]
+            .into_iter()
+            .find(|action| *action as u32 == selector)
+    }
+}
+
+/// Precompile of the EVM initiated assets bridge calls.
+///
+/// ```solidity
+/// interface IAssetsBridge {
+///     // Only for the registered erc20 contracts, which must have burnt `amount` from `from`.
+///     function withdrawTo(address from, bytes32 target, uint256 amount) external;
+///     function teleportTo(bytes32 target, uint256 amount) external;
+///     function linkedAccount(address evmAccount) external view returns (bytes32);
+/// }
+/// ```
+///
+/// The zero `target` stands for the Substrate account linked with the EVM address
+/// via `claim_account`, any other `target` is the public key of a Substrate account.
+pub struct AssetsBridgePrecompile<T>(PhantomData<T>);
+
+impl<T: Config> AssetsBridgePrecompile<T> {
+    pub fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        let (mut reader, selector) = EvmDataReader::new_with_selector(input)?;
+        let action =
+            BridgeAction::from_selector(selector).ok_or_else(|| revert("unknown selector"))?;
+        if action != BridgeAction::LinkedAccount {
+            if is_static {
+                return Err(revert("cannot modify state in static context"));
+            }
+            if !context.apparent_value.is_zero() {
+                return Err(revert("function is not payable"));
+            }
+            // Forbid the delegatecall and callcode, the calling contract could act on
+            // behalf of its caller otherwise.
+            if context.address != address {
+                return Err(revert("cannot be called with delegatecall or callcode"));
+            }
+        }
+
+        let mut gasometer = Gasometer::<T>::new(target_gas);
+        let output = match action {
+            BridgeAction::WithdrawTo => {
+                let from = reader.read_address()?;
+                let target = Self::read_target(&mut reader)?;
+                let amount: BalanceOf<T> = reader.read_u128()?.saturated_into();
+                gasometer.record_db(3, 0)?;
+                gasometer.record_weight(<T as xpallet_assets::Config>::WeightInfo::transfer())?;
+                // The caller is the erc20 contract, see `withdraw_from_evm`.
+                Pallet::<T>::withdraw_from_evm(context.caller, from, target, amount)
+                    .map_err(revert_dispatch_error)?;
+                Vec::new()
+            }
+            BridgeAction::TeleportTo => {
+                let target = Self::read_target(&mut reader)?;
+                let amount: BalanceOf<T> = reader.read_u128()?.saturated_into();
+                gasometer.record_db(3, 2)?;
+                Pallet::<T>::teleport_from_evm(context.caller, target, amount)
+                    .map_err(revert_dispatch_error)?;
+                Vec::new()
+            }
+            BridgeAction::LinkedAccount => {
+                let evm_account = reader.read_address()?;
+                gasometer.record_db(1, 0)?;
+                let mut output = vec![0u8; 32];
+                if let Some(who) = Pallet::<T>::sub_accounts(evm_account) {
+                    let encoded = who.encode();
+                    let len = encoded.len().min(32);
+                    output[..len].copy_from_slice(&encoded[..len]);
+                }
+                output
+            }
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used(),
+            output,
+            logs: Vec::new(),
+        })
+    }
+
+    /// Reads the Substrate account from a `bytes32`, the zero one means the linked account.
+    fn read_target(reader: &mut EvmDataReader) -> Result<Option<T::AccountId>, PrecompileFailure> {
+        let key = reader.read_bytes32()?;
+        if key.is_zero() {
+            return Ok(None);
+        }
+        T::AccountId::decode(&mut key.as_bytes())
+            .map(Some)
+            .map_err(|_| revert("invalid target account"))
+    }
+}
+
+fn balance_to_u256<T: Config>(balance: BalanceOf<T>) -> U256 {
+    U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
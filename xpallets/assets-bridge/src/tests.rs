@@ -1,8 +1,10 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
 use crate::mock::*;
-use crate::{to_ascii_hex, EcdsaSignature};
+use crate::precompile::*;
+use crate::{to_ascii_hex, AddressMappingOf, EcdsaSignature};
 use frame_support::{assert_noop, assert_ok};
+use pallet_evm::AddressMapping;
 use sp_core::{H160, U256};
 
 use ethabi::{Function, Param, ParamType, Token};
@@ -505,3 +507,590 @@ fn bridge_accounts_should_equal() {
 
     assert_eq!(bridge_admin1, bridge_admin2);
 }
+
+fn register_xbtc() {
+    assert_ok!(XAssetsRegistrar::register(
+        Origin::root(),
+        1,
+        xpallet_assets_registrar::AssetInfo::new::<Test>(
+            b"XBTC".to_vec(),
+            b"ChainX Bitcoin".to_vec(),
+            xpallet_assets_registrar::Chain::Bitcoin,
+            8,
+            b"ChainX's cross-chain Bitcoin".to_vec(),
+        )
+        .unwrap(),
+        true,
+        true
+    ));
+}
+
+fn call_erc20(
+    caller: H160,
+    input: Vec<u8>,
+    is_static: bool,
+) -> Result<fp_evm::PrecompileOutput, fp_evm::PrecompileFailure> {
+    let address = asset_precompile_address(1).unwrap();
+    let context = fp_evm::Context {
+        address,
+        caller,
+        apparent_value: U256::zero(),
+    };
+    Erc20AssetsPrecompile::<Test>::execute(address, &input, None, &context, is_static)
+}
+
+fn erc20_input(selector: [u8; 4], tokens: &[Token]) -> Vec<u8> {
+    let mut input = selector.to_vec();
+    input.extend(ethabi::encode(tokens));
+    input
+}
+
+#[test]
+fn asset_precompile_address_should_work() {
+    assert_eq!(
+        asset_precompile_address(1),
+        Some(H160::from_str("0000000000000000000000000000000000000c01").unwrap())
+    );
+    assert_eq!(
+        asset_precompile_address(1023),
+        Some(H160::from_low_u64_be(4095))
+    );
+    assert_eq!(asset_precompile_address(1024), None);
+    assert_eq!(asset_precompile_address(0x90000001), None);
+    assert_eq!(
+        asset_id_of_precompile(&asset_precompile_address(354).unwrap()),
+        Some(354)
+    );
+    assert_eq!(asset_id_of_precompile(&H160::from_low_u64_be(2051)), None);
+    assert_eq!(asset_id_of_precompile(&H160::from_low_u64_be(4096)), None);
+    assert_eq!(asset_id_of_precompile(&H160::from_slice(&ERC20_1)), None);
+    assert_eq!(asset_precompile_addresses().count(), 1024);
+
+    new_test_ext().execute_with(|| {
+        // The range is static, the precompile of an unregistered asset reverts.
+        assert!(Erc20AssetsPrecompile::<Test>::is_precompile(
+            asset_precompile_address(1).unwrap()
+        ));
+        assert!(call_erc20(H160::from_slice(&EVM_ADDR), hex!["313ce567"].to_vec(), true).is_err());
+        register_xbtc();
+        assert_ok!(call_erc20(
+            H160::from_slice(&EVM_ADDR),
+            hex!["313ce567"].to_vec(),
+            true
+        ));
+        // The native asset is not exposed.
+        let address = asset_precompile_address(0).unwrap();
+        let context = fp_evm::Context {
+            address,
+            caller: H160::from_slice(&EVM_ADDR),
+            apparent_value: U256::zero(),
+        };
+        assert!(Erc20AssetsPrecompile::<Test>::execute(
+            address,
+            &hex!["313ce567"],
+            None,
+            &context,
+            true
+        )
+        .is_err());
+    })
+}
+
+#[test]
+fn erc20_precompile_should_work() {
+    new_test_ext().execute_with(|| {
+        register_xbtc();
+        let alice = H160::from_slice(&EVM_ADDR);
+        let bob = H160::from_slice(&ERC20_1);
+        let charlie = H160::from_slice(&ERC20_2);
+        let alice_account = evm_to_sub_account::<Test>(alice);
+        let bob_account = evm_to_sub_account::<Test>(bob);
+        assert_ok!(XAssets::issue(&1, &alice_account, 1000));
+
+        let output = call_erc20(alice, hex!["313ce567"].to_vec(), true).unwrap();
+        assert_eq!(output.output, encode_u256(U256::from(8)));
+        let output = call_erc20(alice, hex!["95d89b41"].to_vec(), true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[Token::String("XBTC".into())])
+        );
+        let output = call_erc20(alice, hex!["18160ddd"].to_vec(), true).unwrap();
+        assert_eq!(output.output, encode_u256(U256::from(1000)));
+
+        // transfer
+        let transfer = erc20_input(
+            hex!["a9059cbb"],
+            &[Token::Address(bob), Token::Uint(U256::from(300))],
+        );
+        assert!(call_erc20(alice, transfer.clone(), true).is_err());
+        let output = call_erc20(alice, transfer, false).unwrap();
+        assert_eq!(output.output, encode_bool(true));
+        assert_eq!(output.logs.len(), 1);
+        assert_eq!(output.logs[0].topics[0].0, TRANSFER_EVENT_TOPIC);
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 700);
+        assert_eq!(XAssets::usable_balance(&bob_account, &1), 300);
+
+        let balance_of = erc20_input(hex!["70a08231"], &[Token::Address(bob)]);
+        let output = call_erc20(alice, balance_of, true).unwrap();
+        assert_eq!(output.output, encode_u256(U256::from(300)));
+
+        // approve and transferFrom
+        let approve = erc20_input(
+            hex!["095ea7b3"],
+            &[Token::Address(charlie), Token::Uint(U256::from(100))],
+        );
+        let output = call_erc20(alice, approve, false).unwrap();
+        assert_eq!(output.logs[0].topics[0].0, APPROVAL_EVENT_TOPIC);
+        assert_eq!(XAssetsBridge::allowances(1, (alice, charlie)), 100);
+
+        let transfer_from = |amount: u64| {
+            erc20_input(
+                hex!["23b872dd"],
+                &[
+                    Token::Address(alice),
+                    Token::Address(bob),
+                    Token::Uint(U256::from(amount)),
+                ],
+            )
+        };
+        assert!(call_erc20(charlie, transfer_from(101), false).is_err());
+        assert_ok!(call_erc20(charlie, transfer_from(60), false));
+        assert_eq!(XAssetsBridge::allowances(1, (alice, charlie)), 40);
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 640);
+        assert_eq!(XAssets::usable_balance(&bob_account, &1), 360);
+
+        // the unlimited approve saturates
+        let approve = erc20_input(
+            hex!["095ea7b3"],
+            &[Token::Address(charlie), Token::Uint(U256::MAX)],
+        );
+        assert_ok!(call_erc20(alice, approve, false));
+        assert_eq!(XAssetsBridge::allowances(1, (alice, charlie)), u128::MAX);
+        assert_ok!(call_erc20(charlie, transfer_from(40), false));
+        assert_eq!(XAssets::usable_balance(&bob_account, &1), 400);
+
+        // insufficient balance
+        let transfer = erc20_input(
+            hex!["a9059cbb"],
+            &[Token::Address(alice), Token::Uint(U256::from(361))],
+        );
+        assert!(call_erc20(bob, transfer, false).is_err());
+    })
+}
+
+#[test]
+fn erc20_unlimited_approval_should_work() {
+    new_test_ext().execute_with(|| {
+        register_xbtc();
+        let alice = H160::from_slice(&EVM_ADDR);
+        let bob = H160::from_slice(&ERC20_1);
+        let charlie = H160::from_slice(&ERC20_2);
+        let alice_account = evm_to_sub_account::<Test>(alice);
+        let bob_account = evm_to_sub_account::<Test>(bob);
+        assert_ok!(XAssets::issue(&1, &alice_account, 1000));
+
+        let approve = |amount: U256| {
+            erc20_input(
+                hex!["095ea7b3"],
+                &[Token::Address(charlie), Token::Uint(amount)],
+            )
+        };
+        let transfer_from = |amount: u64| {
+            erc20_input(
+                hex!["23b872dd"],
+                &[
+                    Token::Address(alice),
+                    Token::Address(bob),
+                    Token::Uint(U256::from(amount)),
+                ],
+            )
+        };
+
+        // The unlimited allowance is not spent.
+        assert_ok!(call_erc20(alice, approve(U256::from(u128::MAX)), false));
+        assert_ok!(call_erc20(charlie, transfer_from(300), false));
+        assert_ok!(call_erc20(charlie, transfer_from(200), false));
+        assert_eq!(XAssetsBridge::allowances(1, (alice, charlie)), u128::MAX);
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 500);
+        assert_eq!(XAssets::usable_balance(&bob_account, &1), 500);
+
+        // The allowance is untouched when the transfer fails.
+        assert_ok!(call_erc20(alice, approve(U256::from(800)), false));
+        assert!(call_erc20(charlie, transfer_from(600), false).is_err());
+        assert_eq!(XAssetsBridge::allowances(1, (alice, charlie)), 800);
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 500);
+    })
+}
+
+#[test]
+fn evm_data_codec_should_work() {
+    let input = erc20_input(
+        hex!["dc048cf2"],
+        &[
+            Token::Uint(U256::from(100)),
+            Token::String("1HNqrWWe9Nf4Y1HjRLWTRGvCWDzAe6Bxh8".into()),
+        ],
+    );
+    let (mut reader, selector) = EvmDataReader::new_with_selector(&input).unwrap();
+    assert_eq!(selector, 0xdc048cf2);
+    assert_eq!(reader.read_u128().unwrap(), 100);
+    let max = encode_u256(U256::MAX);
+    assert_eq!(
+        EvmDataReader::new_with_selector(&[&[0u8; 4][..], &max].concat())
+            .unwrap()
+            .0
+            .read_u128()
+            .unwrap(),
+        u128::MAX
+    );
+    assert_eq!(
+        reader.read_bytes().unwrap(),
+        b"1HNqrWWe9Nf4Y1HjRLWTRGvCWDzAe6Bxh8".to_vec()
+    );
+
+    let arrays = vec![
+        vec![U256::from(1), U256::from(2)],
+        vec![],
+        vec![U256::from(3)],
+    ];
+    assert_eq!(
+        encode_uint_arrays(&arrays),
+        ethabi::encode(&[
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::Array(vec![]),
+            Token::Array(vec![Token::Uint(3.into())]),
+        ])
+    );
+    assert_eq!(
+        encode_bytes(b"XBTC"),
+        ethabi::encode(&[Token::String("XBTC".into())])
+    );
+}
+
+fn call_bridge(
+    caller: H160,
+    input: Vec<u8>,
+    is_static: bool,
+) -> Result<fp_evm::PrecompileOutput, fp_evm::PrecompileFailure> {
+    let address = H160::from_low_u64_be(2051);
+    let context = fp_evm::Context {
+        address,
+        caller,
+        apparent_value: U256::zero(),
+    };
+    AssetsBridgePrecompile::<Test>::execute(address, &input, None, &context, is_static)
+}
+
+#[test]
+fn bridge_precompile_should_work() {
+    new_test_ext().execute_with(|| {
+        register_xbtc();
+        let erc20 = H160::from_slice(&ERC20_1);
+        let evm_account = H160::from_slice(&EVM_ADDR);
+        let sub_account = AccountId32::from_str(SUB_ACCOUNT).unwrap();
+        let bob: AccountId32 = BOB.into();
+        assert_ok!(XAssetsBridge::register(
+            Origin::signed(ALICE.into()),
+            1,
+            erc20
+        ));
+
+        let withdraw_to = |target: [u8; 32], amount: u64| {
+            erc20_input(
+                hex!["84eaa2a3"],
+                &[
+                    Token::Address(evm_account),
+                    Token::FixedBytes(target.to_vec()),
+                    Token::Uint(U256::from(amount)),
+                ],
+            )
+        };
+
+        // only the registered erc20 contract can withdraw
+        assert!(call_bridge(evm_account, withdraw_to(BOB, 100), false).is_err());
+        assert!(call_bridge(erc20, withdraw_to(BOB, 100), true).is_err());
+        assert!(call_bridge(erc20, withdraw_to(BOB, 0), false).is_err());
+        assert_ok!(call_bridge(erc20, withdraw_to(BOB, 100), false));
+        expect_event(XAssetsBridgeEvent::WithdrawExecuted(
+            1,
+            bob.clone(),
+            evm_account,
+            100,
+            erc20,
+        ));
+        assert_eq!(XAssets::usable_balance(&bob, &1), 100);
+
+        // the zero target requires the linked account
+        assert!(call_bridge(erc20, withdraw_to([0u8; 32], 50), false).is_err());
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE.into()),
+            sub_account.clone(),
+            10
+        ));
+        assert_ok!(XAssetsBridge::claim_account(
+            Origin::signed(sub_account.clone()),
+            evm_account,
+            EcdsaSignature::from_slice(&SIGNATURE).unwrap()
+        ));
+        assert_ok!(call_bridge(erc20, withdraw_to([0u8; 32], 50), false));
+        assert_eq!(XAssets::usable_balance(&sub_account, &1), 50);
+
+        let linked_account = erc20_input(hex!["af67d317"], &[Token::Address(evm_account)]);
+        let output = call_bridge(erc20, linked_account, true).unwrap();
+        assert_eq!(
+            output.output,
+            hex!["d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"].to_vec()
+        );
+
+        assert_ok!(XAssetsBridge::pause(Origin::signed(ALICE.into()), Some(1)));
+        assert!(call_bridge(erc20, withdraw_to(BOB, 100), false).is_err());
+
+        // teleport the native currency of the evm address
+        let teleport_to = |target: [u8; 32], amount: u64| {
+            erc20_input(
+                hex!["87acc285"],
+                &[
+                    Token::FixedBytes(target.to_vec()),
+                    Token::Uint(U256::from(amount)),
+                ],
+            )
+        };
+        assert!(call_bridge(evm_account, teleport_to(BOB, 200), false).is_err());
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE.into()),
+            AddressMappingOf::<Test>::into_account_id(evm_account),
+            500
+        ));
+        assert_ok!(call_bridge(evm_account, teleport_to(BOB, 200), false));
+        expect_event(XAssetsBridgeEvent::EvmTeleport(
+            evm_account,
+            bob.clone(),
+            200,
+        ));
+        assert_eq!(Balances::free_balance(&bob), 1200);
+        assert_ok!(call_bridge(evm_account, teleport_to([0u8; 32], 100), false));
+        assert_eq!(Balances::free_balance(&sub_account), 108);
+    })
+}
+
+#[test]
+fn roles_should_work() {
+    new_test_ext().execute_with(|| {
+        let bob: AccountId32 = BOB.into();
+        assert_noop!(
+            XAssetsBridge::pause(Origin::signed(BOB.into()), None),
+            Error::<Test>::RequireRole
+        );
+        assert_noop!(
+            XAssetsBridge::grant_role(Origin::signed(ALICE.into()), Role::Pauser, bob.clone()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(XAssetsBridge::grant_role(
+            Origin::root(),
+            Role::Pauser,
+            bob.clone()
+        ));
+        expect_event(XAssetsBridgeEvent::RoleGranted(Role::Pauser, bob.clone()));
+        assert_ok!(XAssetsBridge::pause(Origin::signed(BOB.into()), None));
+
+        // the roles are scoped
+        assert_noop!(
+            XAssetsBridge::register(Origin::signed(BOB.into()), 1, H160::from_slice(&ERC20_1)),
+            Error::<Test>::RequireRole
+        );
+        assert_noop!(
+            XAssetsBridge::back_foreign(Origin::signed(BOB.into()), 1, false),
+            Error::<Test>::RequireRole
+        );
+        // the role origin
+        assert_ok!(XAssetsBridge::back_foreign(Origin::root(), 1, false));
+
+        assert_ok!(XAssetsBridge::revoke_role(
+            Origin::root(),
+            Role::Pauser,
+            bob.clone()
+        ));
+        expect_event(XAssetsBridgeEvent::RoleRevoked(Role::Pauser, bob.clone()));
+        assert_noop!(
+            XAssetsBridge::unpause(Origin::signed(BOB.into()), None),
+            Error::<Test>::RequireRole
+        );
+        assert_noop!(
+            XAssetsBridge::revoke_role(Origin::root(), Role::Pauser, bob),
+            Error::<Test>::RequireRole
+        );
+    })
+}
+
+#[test]
+fn registration_time_lock_should_work() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::Hooks;
+        use xassets_bridge::RegistrationChange;
+
+        RegistrationDelay::set(&10);
+        let erc20 = H160::from_slice(&ERC20_1);
+
+        assert_ok!(XAssetsBridge::register(
+            Origin::signed(ALICE.into()),
+            1,
+            erc20
+        ));
+        expect_event(XAssetsBridgeEvent::RegistrationScheduled(
+            1,
+            RegistrationChange::Register(erc20),
+            11,
+        ));
+        assert_eq!(XAssetsBridge::erc20s(1), None);
+        assert_eq!(XAssetsBridge::pending_registrations(erc20), Some(1));
+        assert_noop!(
+            XAssetsBridge::register(Origin::signed(ALICE.into()), 1, erc20),
+            Error::<Test>::PendingChangeExists
+        );
+        assert_noop!(
+            XAssetsBridge::register(Origin::signed(ALICE.into()), 2, erc20),
+            Error::<Test>::ContractAddressHasMapped
+        );
+
+        XAssetsBridge::on_initialize(10);
+        assert_eq!(XAssetsBridge::erc20s(1), None);
+        XAssetsBridge::on_initialize(11);
+        expect_event(XAssetsBridgeEvent::Register(1, erc20));
+        assert_eq!(XAssetsBridge::erc20s(1), Some(erc20));
+        assert_eq!(XAssetsBridge::asset_ids(erc20), Some(1));
+        assert_eq!(XAssetsBridge::pending_changes(1), None);
+        assert_eq!(XAssetsBridge::pending_registrations(erc20), None);
+
+        // unregister and cancel
+        assert_ok!(XAssetsBridge::force_unregister(
+            Origin::signed(ALICE.into()),
+            1
+        ));
+        assert!(XAssetsBridge::pending_changes(1).is_some());
+        assert_noop!(
+            XAssetsBridge::cancel_registration_change(Origin::signed(BOB.into()), 1),
+            Error::<Test>::RequireRole
+        );
+        assert_ok!(XAssetsBridge::cancel_registration_change(Origin::root(), 1));
+        expect_event(XAssetsBridgeEvent::RegistrationCancelled(
+            1,
+            RegistrationChange::Unregister,
+        ));
+        XAssetsBridge::on_initialize(11);
+        XAssetsBridge::on_initialize(100);
+        assert_eq!(XAssetsBridge::erc20s(1), Some(erc20));
+
+        System::set_block_number(11);
+        assert_ok!(XAssetsBridge::force_unregister(Origin::root(), 1));
+        XAssetsBridge::on_initialize(11);
+        assert_eq!(XAssetsBridge::erc20s(1), Some(erc20));
+        XAssetsBridge::on_initialize(21);
+        expect_event(XAssetsBridgeEvent::ForceUnRegister(1, erc20));
+        assert_eq!(XAssetsBridge::erc20s(1), None);
+        assert_eq!(XAssetsBridge::asset_ids(erc20), None);
+    })
+}
+
+#[test]
+fn migrate_admin_to_roles_should_work() {
+    new_test_ext().execute_with(|| {
+        let bob: AccountId32 = BOB.into();
+        frame_support::storage::migration::put_storage_value(
+            b"XAssetsBridge",
+            b"Admin",
+            &[],
+            bob.clone(),
+        );
+
+        XAssetsBridge::migrate_admin_to_roles();
+        assert!(XAssetsBridge::has_role(Role::Pauser, &bob));
+        assert!(XAssetsBridge::has_role(Role::Registrar, &bob));
+        assert!(XAssetsBridge::has_role(Role::BackForeign, &bob));
+        assert_ok!(XAssetsBridge::register(
+            Origin::signed(bob.clone()),
+            1,
+            H160::from_slice(&ERC20_1)
+        ));
+        assert!(
+            frame_support::storage::migration::get_storage_value::<AccountId32>(
+                b"XAssetsBridge",
+                b"Admin",
+                &[]
+            )
+            .is_none()
+        );
+    })
+}
+
+#[test]
+fn asset_pause_should_work() {
+    new_test_ext().execute_with(|| {
+        register_xbtc();
+        let erc20 = H160::from_slice(&ERC20_1);
+        let alice = H160::from_slice(&EVM_ADDR);
+        let bob = H160::from_slice(&ERC20_2);
+        let alice_account = evm_to_sub_account::<Test>(alice);
+        assert_ok!(XAssets::issue(&1, &alice_account, 1000));
+        assert_ok!(XAssetsBridge::register(
+            Origin::signed(ALICE.into()),
+            1,
+            erc20
+        ));
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE.into()),
+            alice_account.clone(),
+            500
+        ));
+
+        let transfer = erc20_input(
+            hex!["a9059cbb"],
+            &[Token::Address(bob), Token::Uint(U256::from(300))],
+        );
+        let withdraw_to = erc20_input(
+            hex!["84eaa2a3"],
+            &[
+                Token::Address(alice),
+                Token::FixedBytes(BOB.to_vec()),
+                Token::Uint(U256::from(100)),
+            ],
+        );
+        let teleport_to = erc20_input(
+            hex!["87acc285"],
+            &[
+                Token::FixedBytes(BOB.to_vec()),
+                Token::Uint(U256::from(200)),
+            ],
+        );
+
+        set_asset_paused(1, true);
+        assert!(call_erc20(alice, transfer.clone(), false).is_err());
+        assert!(call_bridge(erc20, withdraw_to.clone(), false).is_err());
+        assert_noop!(
+            XAssetsBridge::deposit(Origin::signed(ALICE.into()), 1, 100),
+            xpallet_assets::Error::<Test>::AssetPaused
+        );
+        assert_noop!(
+            XAssetsBridge::withdraw(Origin::signed(ALICE.into()), 1, 100),
+            xpallet_assets::Error::<Test>::AssetPaused
+        );
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 1000);
+        // the native currency is paused on its own
+        assert_ok!(call_bridge(alice, teleport_to.clone(), false));
+
+        set_asset_paused(1, false);
+        set_asset_paused(0, true);
+        assert!(call_bridge(alice, teleport_to, false).is_err());
+        assert_noop!(
+            XAssetsBridge::teleport(
+                Origin::signed(BOB.into()),
+                100,
+                crate::ActionType::Direct(alice)
+            ),
+            xpallet_assets::Error::<Test>::AssetPaused
+        );
+        assert_ok!(call_erc20(alice, transfer, false));
+        assert_ok!(call_bridge(erc20, withdraw_to, false));
+        assert_eq!(XAssets::usable_balance(&alice_account, &1), 700);
+    })
+}
@@ -1,11 +1,13 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{Currency, Get, ReservableCurrency};
 use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
 
 use chainx_primitives::AssetId;
 
-use crate::{AssetInfo, AssetInfoOf, AssetOnline, Call, Chain, Config, Pallet};
+use crate::{AssetInfo, AssetInfoOf, AssetOnline, Call, Chain, Config, ListingProposals, Pallet};
 
 const ASSET_ID: AssetId = 8888;
 
@@ -62,6 +64,49 @@ benchmarks! {
         new_asset_info.set_desc(b"new_desc".to_vec());
         assert_eq!(AssetInfoOf::<T>::get(ASSET_ID).unwrap(), new_asset_info);
     }
+
+    propose_listing {
+        let caller = funded_proposer::<T>();
+        let asset_info = b_asset_info_test_data::<T>();
+    }: _(RawOrigin::Signed(caller), ASSET_ID, asset_info, true)
+    verify {
+        assert!(ListingProposals::<T>::contains_key(0));
+    }
+
+    approve_listing {
+        let caller = funded_proposer::<T>();
+        let asset_info = b_asset_info_test_data::<T>();
+        Pallet::<T>::propose_listing(RawOrigin::Signed(caller).into(), ASSET_ID, asset_info.clone(), true)?;
+    }: _(RawOrigin::Root, 0)
+    verify {
+        assert_eq!(AssetInfoOf::<T>::get(ASSET_ID), Some(asset_info));
+    }
+
+    reject_listing {
+        let caller = funded_proposer::<T>();
+        let asset_info = b_asset_info_test_data::<T>();
+        Pallet::<T>::propose_listing(RawOrigin::Signed(caller).into(), ASSET_ID, asset_info, true)?;
+    }: _(RawOrigin::Root, 0)
+    verify {
+        assert!(!ListingProposals::<T>::contains_key(0));
+    }
+
+    cancel_listing {
+        let caller = funded_proposer::<T>();
+        let asset_info = b_asset_info_test_data::<T>();
+        Pallet::<T>::propose_listing(RawOrigin::Signed(caller.clone()).into(), ASSET_ID, asset_info, true)?;
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert!(!ListingProposals::<T>::contains_key(0));
+        assert_eq!(T::Currency::reserved_balance(&caller), 0u32.into());
+    }
+}
+
+fn funded_proposer<T: Config>() -> T::AccountId {
+    let caller: T::AccountId = whitelisted_caller();
+    let balance = T::ListingDeposit::get().saturating_mul(10u32.into()) + 1_000u32.into();
+    T::Currency::make_free_balance_be(&caller, balance);
+    caller
 }
 
 impl_benchmark_test_suite!(
@@ -18,18 +18,32 @@ pub mod weights;
 
 use sp_std::prelude::*;
 
+use sp_runtime::Perbill;
+
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     ensure,
     log::info,
+    traits::{Currency, OnUnbalanced, ReservableCurrency},
+    transactional,
 };
 
 use chainx_primitives::{AssetId, Desc, Token};
 
-pub use self::types::AssetInfo;
+pub use self::types::{AssetInfo, ListingProposal};
 pub use self::weights::WeightInfo;
 pub use xp_assets_registrar::{Chain, RegistrarHandler};
 
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// The index of an asset listing proposal.
+pub type ListingIndex = u32;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -52,6 +66,23 @@ pub mod pallet {
         /// Handler for doing stuff after the asset is registered/deregistered.
         type RegistrarHandler: RegistrarHandler;
 
+        /// The currency for the deposit of the listing proposals.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// The origin which can approve or reject the listing proposals.
+        type ListingOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The deposit reserved for proposing to list an asset.
+        #[pallet::constant]
+        type ListingDeposit: Get<BalanceOf<Self>>;
+
+        /// The part of the deposit forfeited if the listing proposal is rejected.
+        #[pallet::constant]
+        type RejectionSlash: Get<Perbill>;
+
+        /// Handler for the forfeited deposit of the rejected listing proposals.
+        type OnSlash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -164,6 +195,118 @@ pub mod pallet {
             AssetInfoOf::<T>::insert(id, info);
             Ok(())
         }
+
+        /// Propose to list a new foreign asset, reserving `ListingDeposit` of the proposer.
+        #[pallet::weight(T::WeightInfo::propose_listing())]
+        pub fn propose_listing(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            asset: AssetInfo,
+            has_mining_rights: bool,
+        ) -> DispatchResult {
+            let proposer = ensure_signed(origin)?;
+
+            asset.is_valid::<T>()?;
+            ensure!(!Self::exists(&asset_id), Error::<T>::AssetAlreadyExists);
+            ensure!(
+                !PendingListings::<T>::contains_key(asset_id),
+                Error::<T>::ListingProposalExists
+            );
+
+            let deposit = T::ListingDeposit::get();
+            T::Currency::reserve(&proposer, deposit)
+                .map_err(|_| Error::<T>::InsufficientListingDeposit)?;
+
+            let index = Self::listing_proposal_count();
+            ListingProposalCount::<T>::put(index + 1);
+            PendingListings::<T>::insert(asset_id, index);
+            ListingProposals::<T>::insert(
+                index,
+                ListingProposal {
+                    proposer: proposer.clone(),
+                    deposit,
+                    asset_id,
+                    asset,
+                    has_mining_rights,
+                },
+            );
+
+            Self::deposit_event(Event::ListingProposed(index, proposer, asset_id));
+            Ok(())
+        }
+
+        /// Approve the listing proposal, which registers the asset and returns the deposit.
+        #[pallet::weight(T::WeightInfo::approve_listing())]
+        #[transactional]
+        pub fn approve_listing(
+            origin: OriginFor<T>,
+            #[pallet::compact] index: ListingIndex,
+        ) -> DispatchResult {
+            T::ListingOrigin::ensure_origin(origin)?;
+
+            let proposal =
+                Self::listing_proposals(index).ok_or(Error::<T>::ListingProposalNotExists)?;
+            let asset_id = proposal.asset_id;
+            ensure!(!Self::exists(&asset_id), Error::<T>::AssetAlreadyExists);
+
+            ListingProposals::<T>::remove(index);
+            PendingListings::<T>::remove(asset_id);
+            T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+
+            info!(
+                target: "runtime::assets-registrar",
+                "[approve_listing] index:{}, id:{}, info:{:?}, has_mining_rights:{}",
+                index, asset_id, proposal.asset, proposal.has_mining_rights
+            );
+
+            Self::apply_register(asset_id, proposal.asset)?;
+
+            Self::deposit_event(Event::ListingApproved(index, asset_id));
+            Self::deposit_event(Event::Registered(asset_id, proposal.has_mining_rights));
+            T::RegistrarHandler::on_register(&asset_id, proposal.has_mining_rights)?;
+            Ok(())
+        }
+
+        /// Reject the listing proposal, `RejectionSlash` of the deposit is forfeited.
+        #[pallet::weight(T::WeightInfo::reject_listing())]
+        pub fn reject_listing(
+            origin: OriginFor<T>,
+            #[pallet::compact] index: ListingIndex,
+        ) -> DispatchResult {
+            T::ListingOrigin::ensure_origin(origin)?;
+
+            let proposal =
+                ListingProposals::<T>::take(index).ok_or(Error::<T>::ListingProposalNotExists)?;
+            PendingListings::<T>::remove(proposal.asset_id);
+
+            let slash = T::RejectionSlash::get() * proposal.deposit;
+            let (imbalance, _) = T::Currency::slash_reserved(&proposal.proposer, slash);
+            T::OnSlash::on_unbalanced(imbalance);
+            T::Currency::unreserve(&proposal.proposer, proposal.deposit - slash);
+
+            Self::deposit_event(Event::ListingRejected(index, proposal.asset_id, slash));
+            Ok(())
+        }
+
+        /// Withdraw the listing proposal, the whole deposit is returned to the proposer.
+        #[pallet::weight(T::WeightInfo::cancel_listing())]
+        pub fn cancel_listing(
+            origin: OriginFor<T>,
+            #[pallet::compact] index: ListingIndex,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let proposal =
+                ListingProposals::<T>::get(index).ok_or(Error::<T>::ListingProposalNotExists)?;
+            ensure!(proposal.proposer == who, Error::<T>::NotListingProposer);
+            ListingProposals::<T>::remove(index);
+            PendingListings::<T>::remove(proposal.asset_id);
+
+            T::Currency::unreserve(&who, proposal.deposit);
+
+            Self::deposit_event(Event::ListingCancelled(index, proposal.asset_id));
+            Ok(())
+        }
     }
 
     /// Event for the XAssetRegistrar Pallet
@@ -176,6 +319,14 @@ pub mod pallet {
         Recovered(AssetId, bool),
         /// An asset was deregistered. [asset_id]
         Deregistered(AssetId),
+        /// An asset listing was proposed. [index, proposer, asset_id]
+        ListingProposed(ListingIndex, T::AccountId, AssetId),
+        /// The asset listing proposal was approved. [index, asset_id]
+        ListingApproved(ListingIndex, AssetId),
+        /// The asset listing proposal was rejected. [index, asset_id, slashed]
+        ListingRejected(ListingIndex, AssetId, BalanceOf<T>),
+        /// The asset listing proposal was cancelled by its proposer. [index, asset_id]
+        ListingCancelled(ListingIndex, AssetId),
     }
 
     /// Error for the XAssetRegistrar Pallet
@@ -199,6 +350,14 @@ pub mod pallet {
         AssetAlreadyValid,
         /// The asset is invalid (not online).
         AssetIsInvalid,
+        /// The proposer can not reserve the listing deposit.
+        InsufficientListingDeposit,
+        /// The listing proposal does not exist.
+        ListingProposalNotExists,
+        /// Only the proposer can cancel the listing proposal.
+        NotListingProposer,
+        /// The asset already has a pending listing proposal.
+        ListingProposalExists,
     }
 
     /// Asset id list for each Chain.
@@ -223,6 +382,22 @@ pub mod pallet {
     pub(super) type RegisteredAt<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, T::BlockNumber, ValueQuery>;
 
+    /// The number of the listing proposals so far.
+    #[pallet::storage]
+    #[pallet::getter(fn listing_proposal_count)]
+    pub(super) type ListingProposalCount<T: Config> = StorageValue<_, ListingIndex, ValueQuery>;
+
+    /// The pending listing proposals.
+    #[pallet::storage]
+    #[pallet::getter(fn listing_proposals)]
+    pub(super) type ListingProposals<T: Config> =
+        StorageMap<_, Twox64Concat, ListingIndex, ListingProposal<T::AccountId, BalanceOf<T>>>;
+
+    /// The index of the pending listing proposal of each asset.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_listings)]
+    pub(super) type PendingListings<T: Config> = StorageMap<_, Twox64Concat, AssetId, ListingIndex>;
+
     /// add_extra_genesis
     #[pallet::genesis_config]
     #[cfg_attr(feature = "std", derive(Default))]
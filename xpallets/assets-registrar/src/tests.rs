@@ -5,6 +5,7 @@ use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
 };
 
 use chainx_primitives::AssetId;
@@ -25,6 +26,7 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         XAssetsRegistrar: xpallet_assets_registrar::{Pallet, Call, Config, Storage, Event<T>},
     }
 );
@@ -52,7 +54,7 @@ impl frame_system::Config for Test {
     type DbWeight = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u128>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -61,14 +63,37 @@ impl frame_system::Config for Test {
     type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u128;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+}
+
 parameter_types! {
     pub const ChainXAssetId: AssetId = 0;
+    pub const ListingDeposit: u128 = 100;
+    pub const RejectionSlash: Perbill = Perbill::from_percent(20);
 }
 
 impl Config for Test {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<u64>;
+    type ListingDeposit = ListingDeposit;
+    type RejectionSlash = RejectionSlash;
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -99,6 +124,12 @@ impl ExtBuilder {
             .build_storage::<Test>()
             .unwrap();
 
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![(1, 1000), (2, 50)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
         GenesisBuild::<Test>::assimilate_storage(
             &xpallet_assets_registrar::GenesisConfig { assets },
             &mut storage,
@@ -176,3 +207,130 @@ fn test_register() {
         );
     })
 }
+
+#[test]
+fn test_listing_proposal() {
+    ExtBuilder::default().build_and_execute(|| {
+        let abc = |id: AssetId| {
+            (
+                id,
+                AssetInfo::new::<Test>(
+                    b"ABC".to_vec(),
+                    b"ABC".to_vec(),
+                    Chain::Ethereum,
+                    18,
+                    b"abc".to_vec(),
+                )
+                .unwrap(),
+            )
+        };
+
+        let (abc_id, abc_info) = abc(100);
+        assert_noop!(
+            XAssetsRegistrar::propose_listing(Origin::signed(1), X_BTC, abc_info.clone(), false),
+            Err::AssetAlreadyExists
+        );
+        assert_noop!(
+            XAssetsRegistrar::propose_listing(Origin::signed(2), abc_id, abc_info.clone(), false),
+            Err::InsufficientListingDeposit
+        );
+
+        assert_ok!(XAssetsRegistrar::propose_listing(
+            Origin::signed(1),
+            abc_id,
+            abc_info.clone(),
+            true
+        ));
+        assert_eq!(Balances::reserved_balance(1), 100);
+        assert!(!XAssetsRegistrar::exists(&abc_id));
+        assert_eq!(XAssetsRegistrar::pending_listings(abc_id), Some(0));
+
+        // Only one pending proposal for an asset.
+        assert_noop!(
+            XAssetsRegistrar::propose_listing(Origin::signed(1), abc_id, abc_info.clone(), false),
+            Err::ListingProposalExists
+        );
+
+        // Only the listing origin can approve.
+        assert_noop!(
+            XAssetsRegistrar::approve_listing(Origin::signed(1), 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XAssetsRegistrar::approve_listing(Origin::root(), 0));
+        assert_eq!(XAssetsRegistrar::get_asset_info(&abc_id), Ok(abc_info));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000);
+        assert_eq!(XAssetsRegistrar::pending_listings(abc_id), None);
+        assert_noop!(
+            XAssetsRegistrar::approve_listing(Origin::root(), 0),
+            Err::ListingProposalNotExists
+        );
+
+        // The rejected proposal forfeits 20% of the deposit.
+        let (def_id, def_info) = abc(101);
+        assert_ok!(XAssetsRegistrar::propose_listing(
+            Origin::signed(1),
+            def_id,
+            def_info,
+            false
+        ));
+        assert_eq!(XAssetsRegistrar::listing_proposal_count(), 2);
+        assert_ok!(XAssetsRegistrar::reject_listing(Origin::root(), 1));
+        assert!(!XAssetsRegistrar::exists(&def_id));
+        assert_eq!(XAssetsRegistrar::pending_listings(def_id), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 980);
+    })
+}
+
+#[test]
+fn test_cancel_listing() {
+    ExtBuilder::default().build_and_execute(|| {
+        let abc_info = AssetInfo::new::<Test>(
+            b"ABC".to_vec(),
+            b"ABC".to_vec(),
+            Chain::Ethereum,
+            18,
+            b"abc".to_vec(),
+        )
+        .unwrap();
+
+        assert_noop!(
+            XAssetsRegistrar::cancel_listing(Origin::signed(1), 0),
+            Err::ListingProposalNotExists
+        );
+
+        assert_ok!(XAssetsRegistrar::propose_listing(
+            Origin::signed(1),
+            100,
+            abc_info.clone(),
+            false
+        ));
+        assert_eq!(Balances::reserved_balance(1), 100);
+
+        // Only the proposer can cancel.
+        assert_noop!(
+            XAssetsRegistrar::cancel_listing(Origin::signed(2), 0),
+            Err::NotListingProposer
+        );
+
+        // The whole deposit is returned.
+        assert_ok!(XAssetsRegistrar::cancel_listing(Origin::signed(1), 0));
+        assert!(!XAssetsRegistrar::exists(&100));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000);
+        assert_noop!(
+            XAssetsRegistrar::approve_listing(Origin::root(), 0),
+            Err::ListingProposalNotExists
+        );
+
+        // The asset can be proposed again once the proposal is cancelled.
+        assert_ok!(XAssetsRegistrar::propose_listing(
+            Origin::signed(1),
+            100,
+            abc_info,
+            false
+        ));
+        assert_eq!(XAssetsRegistrar::pending_listings(100), Some(1));
+    })
+}
@@ -6,9 +6,10 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 use frame_support::dispatch::{DispatchError, DispatchResult};
+use sp_runtime::RuntimeDebug;
 use sp_std::fmt;
 
-use chainx_primitives::{Decimals, Desc, Token};
+use chainx_primitives::{AssetId, Decimals, Desc, Token};
 use xp_assets_registrar::Chain;
 
 use crate::verifier::*;
@@ -102,3 +103,13 @@ impl AssetInfo {
         self.token_name = token_name
     }
 }
+
+/// The proposal of listing a new asset, which reserves the `deposit` of the proposer.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ListingProposal<AccountId, Balance> {
+    pub proposer: AccountId,
+    pub deposit: Balance,
+    pub asset_id: AssetId,
+    pub asset: AssetInfo,
+    pub has_mining_rights: bool,
+}
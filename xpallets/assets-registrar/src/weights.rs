@@ -35,6 +35,10 @@ pub trait WeightInfo {
     fn deregister() -> Weight;
     fn recover() -> Weight;
     fn update_asset_info() -> Weight;
+    fn propose_listing() -> Weight;
+    fn approve_listing() -> Weight;
+    fn reject_listing() -> Weight;
+    fn cancel_listing() -> Weight;
 }
 
 /// Weights for xpallet_assets_registrar using the Substrate node and recommended hardware.
@@ -60,6 +64,26 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn propose_listing() -> Weight {
+        (41_052_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn approve_listing() -> Weight {
+        (78_394_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn reject_listing() -> Weight {
+        (46_918_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_listing() -> Weight {
+        (32_846_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -84,4 +108,24 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn propose_listing() -> Weight {
+        (41_052_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn approve_listing() -> Weight {
+        (78_394_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn reject_listing() -> Weight {
+        (46_918_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_listing() -> Weight {
+        (32_846_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
 }
@@ -54,6 +54,56 @@ benchmarks! {
         assert_eq!(XAssets::<T>::asset_balance(&user, &ASSET_ID), balances);
     }
 
+    vested_transfer {
+        // the existing schedules of the recipient that are still locked
+        let s in 0 .. MAX_VESTING_SCHEDULES - 1;
+
+        let caller = whitelisted_caller();
+        let transfer_amount: BalanceOf<T> = (100000000 * 10_u32).into(); // e.g. 10 btc
+        XAssets::<T>::issue(&ASSET_ID, &caller, transfer_amount).unwrap();
+
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let recipient_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(recipient.clone());
+        let now = frame_system::Pallet::<T>::block_number();
+        let locked = (0..s)
+            .map(|_| VestingSchedule {
+                locked: 100_u32.into(),
+                per_block: 1_u32.into(),
+                starting_block: now + 1_000_u32.into(),
+            })
+            .collect::<Vec<_>>();
+        Vesting::<T>::insert(&recipient, &ASSET_ID, locked);
+        let schedule = VestingSchedule {
+            locked: transfer_amount,
+            per_block: 100_u32.into(),
+            starting_block: now,
+        };
+    }: _(RawOrigin::Signed(caller.clone()), recipient_lookup, ASSET_ID, schedule.clone())
+    verify {
+        assert_eq!(XAssets::<T>::usable_balance(&recipient, &ASSET_ID), transfer_amount);
+        let schedules = XAssets::<T>::vesting(&recipient, &ASSET_ID);
+        assert_eq!(schedules.len() as u32, s + 1);
+        assert_eq!(schedules.last(), Some(&schedule));
+    }
+
+    vest {
+        let s in 1 .. MAX_VESTING_SCHEDULES;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let schedules = (0..s)
+            .map(|_| VestingSchedule {
+                locked: 100_u32.into(),
+                per_block: 100_u32.into(),
+                starting_block: Zero::zero(),
+            })
+            .collect::<Vec<_>>();
+        Vesting::<T>::insert(&caller, &ASSET_ID, schedules);
+        frame_system::Pallet::<T>::set_block_number(10_u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), ASSET_ID)
+    verify {
+        assert!(XAssets::<T>::vesting(&caller, &ASSET_ID).is_empty());
+    }
+
     set_asset_limit {
         let res = AssetRestrictions::DEPOSIT | AssetRestrictions::DESTROY_USABLE;
     }: set_asset_limit(RawOrigin::Root, ASSET_ID, res)
@@ -75,6 +125,8 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_force_transfer());
             assert_ok!(Pallet::<Test>::test_benchmark_set_balance());
             assert_ok!(Pallet::<Test>::test_benchmark_set_asset_limit());
+            assert_ok!(Pallet::<Test>::test_benchmark_vested_transfer());
+            assert_ok!(Pallet::<Test>::test_benchmark_vest());
         });
     }
 }
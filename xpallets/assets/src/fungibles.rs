@@ -0,0 +1,211 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Implementations of the FRAME `fungibles` traits, which allow the other pallets to
+//! use the non-native assets in a standard way.
+//!
+//! All the balance changes go through `issue`, `destroy_usable` and `move_balance`,
+//! so the asset restrictions and the `OnAssetChanged` hooks are always respected. The
+//! transfers and burns out of an account also check the asset pauses and the account
+//! restrictions like the user operations do, e.g. paying the fees in an asset.
+
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::tokens::{
+        fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
+        DepositConsequence, WithdrawConsequence,
+    },
+};
+use sp_runtime::traits::{CheckedAdd, CheckedSub, Zero};
+
+use chainx_primitives::AssetId;
+
+use crate::{AssetRestrictions, AssetType, BalanceOf, Config, Error, Pallet};
+
+impl<T: Config> Pallet<T> {
+    /// Returns true if `id` is a valid non-native asset.
+    fn is_valid_asset(id: &AssetId) -> bool {
+        Self::ensure_not_native_asset(id).is_ok()
+            && xpallet_assets_registrar::Pallet::<T>::ensure_asset_is_valid(id).is_ok()
+    }
+
+    /// Ensures the assets of `who` can be moved out by a user operation.
+    fn ensure_can_move_out_of(id: &AssetId, who: &T::AccountId) -> DispatchResult {
+        Self::ensure_not_paused(id)?;
+        Self::ensure_can_move_out(who)
+    }
+
+    /// Returns the reserved balance that can be actually moved given the `best_effort` flag.
+    fn held_amount(
+        id: &AssetId,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+        best_effort: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let on_hold = Self::asset_typed_balance(who, id, AssetType::Reserved);
+        if best_effort {
+            Ok(amount.min(on_hold))
+        } else {
+            ensure!(on_hold >= amount, Error::<T>::InsufficientBalance);
+            Ok(amount)
+        }
+    }
+}
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+    type AssetId = AssetId;
+    type Balance = BalanceOf<T>;
+
+    fn total_issuance(asset: AssetId) -> BalanceOf<T> {
+        Self::total_issuance(&asset)
+    }
+
+    fn minimum_balance(_asset: AssetId) -> BalanceOf<T> {
+        Zero::zero()
+    }
+
+    fn balance(asset: AssetId, who: &T::AccountId) -> BalanceOf<T> {
+        Self::all_type_asset_balance(who, &asset)
+    }
+
+    fn reducible_balance(asset: AssetId, who: &T::AccountId, _keep_alive: bool) -> BalanceOf<T> {
+        Self::transferable_balance(who, &asset)
+    }
+
+    fn can_deposit(asset: AssetId, who: &T::AccountId, amount: BalanceOf<T>) -> DepositConsequence {
+        if !Self::is_valid_asset(&asset) {
+            return DepositConsequence::UnknownAsset;
+        }
+        if Self::total_issuance(&asset).checked_add(&amount).is_none()
+            || Self::usable_balance(who, &asset)
+                .checked_add(&amount)
+                .is_none()
+        {
+            return DepositConsequence::Overflow;
+        }
+        DepositConsequence::Success
+    }
+
+    fn can_withdraw(
+        asset: AssetId,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> WithdrawConsequence<BalanceOf<T>> {
+        if !Self::is_valid_asset(&asset) {
+            return WithdrawConsequence::UnknownAsset;
+        }
+        if Self::total_issuance(&asset).checked_sub(&amount).is_none() {
+            return WithdrawConsequence::Underflow;
+        }
+        if Self::usable_balance(who, &asset) < amount {
+            return WithdrawConsequence::NoFunds;
+        }
+        if Self::transferable_balance(who, &asset) < amount {
+            return WithdrawConsequence::Frozen;
+        }
+        WithdrawConsequence::Success
+    }
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+    fn mint_into(asset: AssetId, who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        ensure!(
+            Self::can_do(&asset, AssetRestrictions::DEPOSIT),
+            Error::<T>::ActionNotAllowed
+        );
+        Self::issue(&asset, who, amount)
+    }
+
+    fn burn_from(
+        asset: AssetId,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::ensure_can_move_out_of(&asset, who)?;
+        Self::destroy_usable(&asset, who, amount)?;
+        Ok(amount)
+    }
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(
+        asset: AssetId,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: BalanceOf<T>,
+        _keep_alive: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::can_transfer(&asset)?;
+        Self::ensure_can_move_out_of(&asset, source)?;
+        Self::move_usable_balance(&asset, source, dest, amount)
+            .map_err::<Error<T>, _>(Into::into)?;
+        Ok(amount)
+    }
+}
+
+impl<T: Config> InspectHold<T::AccountId> for Pallet<T> {
+    fn balance_on_hold(asset: AssetId, who: &T::AccountId) -> BalanceOf<T> {
+        Self::asset_typed_balance(who, &asset, AssetType::Reserved)
+    }
+
+    fn can_hold(asset: AssetId, who: &T::AccountId, amount: BalanceOf<T>) -> bool {
+        Self::is_valid_asset(&asset)
+            && Self::can_move(&asset).is_ok()
+            && Self::transferable_balance(who, &asset) >= amount
+    }
+}
+
+impl<T: Config> MutateHold<T::AccountId> for Pallet<T> {
+    fn hold(asset: AssetId, who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        Self::move_balance(
+            &asset,
+            who,
+            AssetType::Usable,
+            who,
+            AssetType::Reserved,
+            amount,
+        )
+        .map_err::<Error<T>, _>(Into::into)?;
+        Ok(())
+    }
+
+    fn release(
+        asset: AssetId,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+        best_effort: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let actual = Self::held_amount(&asset, who, amount, best_effort)?;
+        Self::move_balance(
+            &asset,
+            who,
+            AssetType::Reserved,
+            who,
+            AssetType::Usable,
+            actual,
+        )
+        .map_err::<Error<T>, _>(Into::into)?;
+        Ok(actual)
+    }
+
+    fn transfer_held(
+        asset: AssetId,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: BalanceOf<T>,
+        best_effort: bool,
+        on_hold: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::can_transfer(&asset)?;
+        Self::ensure_can_move_out_of(&asset, source)?;
+        let actual = Self::held_amount(&asset, source, amount, best_effort)?;
+        let to_type = if on_hold {
+            AssetType::Reserved
+        } else {
+            AssetType::Usable
+        };
+        Self::move_balance(&asset, source, AssetType::Reserved, dest, to_type, actual)
+            .map_err::<Error<T>, _>(Into::into)?;
+        Ok(actual)
+    }
+}
@@ -13,6 +13,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod fungibles;
 pub mod traits;
 mod trigger;
 pub mod types;
@@ -28,7 +29,7 @@ use frame_support::{
     ensure,
     inherent::Vec,
     log::{debug, error, info},
-    traits::{Currency, Get, HandleLifetime, LockableCurrency, ReservableCurrency},
+    traits::{Currency, Get, HandleLifetime, LockIdentifier, LockableCurrency, ReservableCurrency},
 };
 
 use frame_system::{ensure_root, ensure_signed, AccountInfo};
@@ -36,11 +37,12 @@ use sp_runtime::traits::{CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero}
 
 use self::trigger::AssetChangedTrigger;
 use chainx_primitives::AssetId;
-use xpallet_support::traits::TreasuryAccount;
+use xpallet_support::traits::{AccountRestriction, AssetPause, RestrictedAction, TreasuryAccount};
 
-pub use self::traits::{ChainT, OnAssetChanged};
+pub use self::traits::{ChainT, LockableAsset, OnAssetChanged};
 pub use self::types::{
-    AssetErr, AssetRestrictions, AssetType, BalanceLock, TotalAssetInfo, WithdrawalLimit,
+    AssetErr, AssetRestrictions, AssetType, BalanceLock, TotalAssetInfo, VestingSchedule,
+    WithdrawalLimit,
 };
 pub use self::weights::WeightInfo;
 pub use xpallet_assets_registrar::{AssetInfo, Chain};
@@ -48,6 +50,12 @@ pub use xpallet_assets_registrar::{AssetInfo, Chain};
 pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+pub type VestingScheduleOf<T> =
+    VestingSchedule<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+/// The maximum number of vesting schedules of an account for each asset.
+pub const MAX_VESTING_SCHEDULES: u32 = 8;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -77,6 +85,16 @@ pub mod pallet {
         /// The hook triggered whenever the asset balance of an account is changed.
         type OnAssetChanged: OnAssetChanged<Self::AccountId, BalanceOf<Self>>;
 
+        /// The restrictions on the actions of accounts.
+        type AccountRestriction: AccountRestriction<Self::AccountId>;
+
+        /// The pauses of the user operations moving the assets.
+        type AssetPause: AssetPause<AssetId>;
+
+        /// The minimum amount transferred to create a new vesting schedule.
+        #[pallet::constant]
+        type MinVestedTransfer: Get<BalanceOf<Self>>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -89,7 +107,7 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// transfer between two accounts
-        #[pallet::weight(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::transfer())]
         pub fn transfer(
             origin: OriginFor<T>,
             dest: <T::Lookup as StaticLookup>::Source,
@@ -100,6 +118,8 @@ pub mod pallet {
             let dest = T::Lookup::lookup(dest)?;
             debug!(target: "runtime::assets", "[transfer] from:{:?}, to:{:?}, id:{}, value:{:?}", transactor, dest, id, value);
             Self::can_transfer(&id)?;
+            Self::ensure_not_paused(&id)?;
+            Self::ensure_can_move_out(&transactor)?;
 
             Self::move_usable_balance(&id, &transactor, &dest, value)
                 .map_err::<Error<T>, _>(Into::into)?;
@@ -108,7 +128,7 @@ pub mod pallet {
         }
 
         /// transfer method reserved for root(sudo)
-        #[pallet::weight(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::force_transfer())]
         pub fn force_transfer(
             origin: OriginFor<T>,
             transactor: <T::Lookup as StaticLookup>::Source,
@@ -153,6 +173,68 @@ pub mod pallet {
             ensure_root(origin)?;
             Self::set_asset_restrictions(id, restrictions)
         }
+
+        /// Transfer `schedule.locked` of the usable balance to `dest`, which will be
+        /// unlocked linearly according to the vesting schedule.
+        #[pallet::weight(<T as Config>::WeightInfo::vested_transfer(MAX_VESTING_SCHEDULES))]
+        pub fn vested_transfer(
+            origin: OriginFor<T>,
+            dest: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] id: AssetId,
+            schedule: VestingScheduleOf<T>,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            debug!(target: "runtime::assets", "[vested_transfer] from:{:?}, to:{:?}, id:{}, schedule:{:?}", transactor, dest, id, schedule);
+            ensure!(schedule.is_valid(), Error::<T>::InvalidVestingSchedule);
+            ensure!(
+                schedule.locked >= T::MinVestedTransfer::get(),
+                Error::<T>::AmountLow
+            );
+            Self::can_transfer(&id)?;
+            Self::ensure_not_paused(&id)?;
+            Self::ensure_can_move_out(&transactor)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut schedules = Self::vesting(&dest, &id);
+            schedules.retain(|s| !s.locked_at(now).is_zero());
+            ensure!(
+                (schedules.len() as u32) < MAX_VESTING_SCHEDULES,
+                Error::<T>::AtMaxVestingSchedules
+            );
+
+            Self::move_usable_balance(&id, &transactor, &dest, schedule.locked)
+                .map_err::<Error<T>, _>(Into::into)?;
+
+            let locked = schedule.locked;
+            schedules.push(schedule);
+            Vesting::<T>::insert(&dest, &id, schedules);
+            Self::deposit_event(Event::<T>::VestingScheduleAdded(
+                id, transactor, dest, locked,
+            ));
+            Ok(())
+        }
+
+        /// Remove the vesting schedules of asset `id` that have been fully unlocked.
+        #[pallet::weight(<T as Config>::WeightInfo::vest(MAX_VESTING_SCHEDULES))]
+        pub fn vest(origin: OriginFor<T>, #[pallet::compact] id: AssetId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut schedules = Self::vesting(&who, &id);
+            ensure!(!schedules.is_empty(), Error::<T>::NotVesting);
+
+            let now = frame_system::Pallet::<T>::block_number();
+            schedules.retain(|s| !s.locked_at(now).is_zero());
+            let still_locked = Self::vesting_locked(&schedules, now);
+            if schedules.is_empty() {
+                Vesting::<T>::remove(&who, &id);
+            } else {
+                Vesting::<T>::insert(&who, &id, schedules);
+            }
+
+            Self::deposit_event(Event::<T>::Vested(id, who, still_locked));
+            Ok(())
+        }
     }
 
     /// Event for the Assets Pallet
@@ -174,6 +256,10 @@ pub mod pallet {
         Destroyed(AssetId, T::AccountId, BalanceOf<T>),
         /// Set asset balance of an account by root. [asset_id, who, asset_type, amount]
         BalanceSet(AssetId, T::AccountId, AssetType, BalanceOf<T>),
+        /// Some balances of an asset were transferred with a vesting schedule. [asset_id, from, to, locked]
+        VestingScheduleAdded(AssetId, T::AccountId, T::AccountId, BalanceOf<T>),
+        /// The fully unlocked vesting schedules were removed. [asset_id, who, still_locked]
+        Vested(AssetId, T::AccountId, BalanceOf<T>),
     }
 
     /// Error for the Assets Pallet
@@ -203,6 +289,18 @@ pub mod pallet {
         /// reference exists to allow a non-zero balance of a non-self-sufficient asset, or the
         /// maximum number of consumers has been reached.
         NoProvider,
+        /// The vesting schedule can not unlock anything.
+        InvalidVestingSchedule,
+        /// The account already has the maximum number of vesting schedules.
+        AtMaxVestingSchedules,
+        /// The amount to be vested is less than `MinVestedTransfer`.
+        AmountLow,
+        /// The account has no vesting schedule for the asset.
+        NotVesting,
+        /// The account is restricted from the action.
+        AccountRestricted,
+        /// The user operations moving the asset are paused.
+        AssetPaused,
     }
 
     /// asset extend limit properties, set asset "can do", example, `CanTransfer`, `CanDestroyWithdrawal`
@@ -232,6 +330,32 @@ pub mod pallet {
     pub type TotalAssetBalance<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, BTreeMap<AssetType, BalanceOf<T>>, ValueQuery>;
 
+    /// Named locks on the usable balance of an account for each asset.
+    #[pallet::storage]
+    #[pallet::getter(fn locks)]
+    pub type Locks<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetId,
+        Vec<BalanceLock<BalanceOf<T>>>,
+        ValueQuery,
+    >;
+
+    /// Linear vesting schedules on the usable balance of an account for each asset.
+    #[pallet::storage]
+    #[pallet::getter(fn vesting)]
+    pub type Vesting<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetId,
+        Vec<VestingScheduleOf<T>>,
+        ValueQuery,
+    >;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub assets_restrictions: Vec<(AssetId, AssetRestrictions)>,
@@ -365,6 +489,28 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Ensures the user operations moving asset `id` are not paused.
+    #[inline]
+    pub fn ensure_not_paused(id: &AssetId) -> DispatchResult {
+        if T::AssetPause::is_paused(id) {
+            error!(target: "runtime::assets", "Asset is paused, id:{}", id);
+            return Err(Error::<T>::AssetPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Ensures `who` is not restricted from moving its assets to the others.
+    ///
+    /// Only the user operations check it, the internal moves such as the settlement of
+    /// the orders in XSpot are not affected.
+    pub fn ensure_can_move_out(who: &T::AccountId) -> DispatchResult {
+        ensure!(
+            !T::AccountRestriction::is_restricted(who, RestrictedAction::MoveAsset),
+            Error::<T>::AccountRestricted
+        );
+        Ok(())
+    }
+
     // Public read functions.
     /// Returns the total issuance of asset `id` by far.
     pub fn total_issuance(id: &AssetId) -> BalanceOf<T> {
@@ -409,6 +555,46 @@ impl<T: Config> Pallet<T> {
         balance_for(Reserved) + balance_for(ReservedWithdrawal) + balance_for(ReservedDexSpot)
     }
 
+    /// Returns the sum of the still locked balances of the vesting `schedules` at block `now`.
+    fn vesting_locked(schedules: &[VestingScheduleOf<T>], now: T::BlockNumber) -> BalanceOf<T> {
+        schedules.iter().fold(Zero::zero(), |acc: BalanceOf<T>, s| {
+            acc.saturating_add(s.locked_at(now))
+        })
+    }
+
+    /// Returns the usable balance of `who` for asset `id` that is frozen by the locks
+    /// and vesting schedules.
+    pub fn frozen_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
+        let now = frame_system::Pallet::<T>::block_number();
+        let max_lock = Self::locks(who, id)
+            .iter()
+            .map(|lock| lock.amount)
+            .max()
+            .unwrap_or_default();
+        max_lock.max(Self::vesting_locked(&Self::vesting(who, id), now))
+    }
+
+    /// Returns the usable balance of `who` for asset `id` that can be moved out right now.
+    pub fn transferable_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
+        Self::usable_balance(who, id).saturating_sub(Self::frozen_balance(who, id))
+    }
+
+    /// Ensures `value` of the usable balance of `who` for asset `id` can be moved out right now.
+    pub fn ensure_can_withdraw(
+        who: &T::AccountId,
+        id: &AssetId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        let new_balance = Self::usable_balance(who, id)
+            .checked_sub(&value)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+        ensure!(
+            new_balance >= Self::frozen_balance(who, id),
+            Error::<T>::LiquidityRestrictions
+        );
+        Ok(())
+    }
+
     /// Sets the free balance of `who` without sanity checks and triggering the asset changed hook.
     #[cfg(feature = "std")]
     pub fn force_set_free_balance(id: &AssetId, who: &T::AccountId, value: BalanceOf<T>) {
@@ -479,6 +665,13 @@ impl<T: Config> Pallet<T> {
             .ok_or(AssetErr::NotEnough)?;
         let new_to_balance = to_balance.checked_add(&value).ok_or(AssetErr::OverFlow)?;
 
+        if from_type == AssetType::Usable
+            && !(from == to && from_type == to_type)
+            && new_from_balance < Self::frozen_balance(from, id)
+        {
+            return Err(AssetErr::LiquidityRestrictions);
+        }
+
         // finish basic check, start self check
         if from == to && from_type == to_type {
             // same account, same type, return directly
@@ -661,6 +854,12 @@ impl<T: Config> Pallet<T> {
         let new = current
             .checked_sub(&value)
             .ok_or(Error::<T>::InsufficientBalance)?;
+        if type_ == AssetType::Usable {
+            ensure!(
+                new >= Self::frozen_balance(who, id),
+                Error::<T>::LiquidityRestrictions
+            );
+        }
 
         AssetChangedTrigger::<T>::on_destroy_pre(id, who);
 
@@ -670,3 +869,46 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 }
+
+impl<T: Config> LockableAsset<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn set_lock(id: LockIdentifier, who: &T::AccountId, asset_id: &AssetId, amount: BalanceOf<T>) {
+        if amount.is_zero() {
+            Self::remove_lock(id, who, asset_id);
+            return;
+        }
+        Locks::<T>::mutate(who, asset_id, |locks| {
+            match locks.iter_mut().find(|lock| lock.id == id) {
+                Some(lock) => lock.amount = amount,
+                None => locks.push(BalanceLock { id, amount }),
+            }
+        });
+    }
+
+    fn extend_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        asset_id: &AssetId,
+        amount: BalanceOf<T>,
+    ) {
+        if amount.is_zero() {
+            return;
+        }
+        Locks::<T>::mutate(who, asset_id, |locks| {
+            match locks.iter_mut().find(|lock| lock.id == id) {
+                Some(lock) => lock.amount = lock.amount.max(amount),
+                None => locks.push(BalanceLock { id, amount }),
+            }
+        });
+    }
+
+    fn remove_lock(id: LockIdentifier, who: &T::AccountId, asset_id: &AssetId) {
+        Locks::<T>::mutate_exists(who, asset_id, |maybe_locks| {
+            if let Some(locks) = maybe_locks {
+                locks.retain(|lock| lock.id != id);
+                if locks.is_empty() {
+                    *maybe_locks = None;
+                }
+            }
+        });
+    }
+}
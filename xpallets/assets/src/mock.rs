@@ -1,6 +1,6 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::collections::BTreeMap;
+use std::{cell::RefCell, collections::BTreeMap};
 
 use sp_core::H256;
 use sp_runtime::{
@@ -12,6 +12,7 @@ use frame_support::{parameter_types, sp_io, traits::GenesisBuild};
 
 use chainx_primitives::AssetId;
 pub use xp_protocol::X_BTC;
+use xpallet_support::traits::{AccountRestriction, AssetPause, RestrictedAction};
 
 use crate::{self as xpallet_assets, AssetInfo, AssetRestrictions, Chain, Config, Error};
 
@@ -91,18 +92,62 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const MinVestedTransfer: Balance = 10;
+}
+
 impl Config for Test {
     type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = MockRestriction;
+    type AssetPause = MockAssetPause;
+    type MinVestedTransfer = MinVestedTransfer;
     type WeightInfo = ();
 }
 
+/// The account restricted from moving assets in the tests.
+pub(crate) const RESTRICTED: AccountId = 666;
+
+pub struct MockRestriction;
+impl AccountRestriction<AccountId> for MockRestriction {
+    fn is_restricted(who: &AccountId, action: RestrictedAction) -> bool {
+        *who == RESTRICTED && action == RestrictedAction::MoveAsset
+    }
+}
+
+thread_local! {
+    static PAUSED_ASSETS: RefCell<Vec<AssetId>> = RefCell::new(Vec::new());
+}
+
+/// Pauses or unpauses the user operations moving the asset in the tests.
+pub(crate) fn set_asset_paused(id: AssetId, paused: bool) {
+    PAUSED_ASSETS.with(|assets| {
+        let mut assets = assets.borrow_mut();
+        assets.retain(|asset| *asset != id);
+        if paused {
+            assets.push(id);
+        }
+    });
+}
+
+pub struct MockAssetPause;
+impl AssetPause<AssetId> for MockAssetPause {
+    fn is_paused(asset_id: &AssetId) -> bool {
+        PAUSED_ASSETS.with(|assets| assets.borrow().contains(asset_id))
+    }
+}
+
 pub struct ExtBuilder;
 
 impl Default for ExtBuilder {
@@ -7,8 +7,11 @@ use xp_protocol::X_BTC;
 
 pub use super::mock::{ExtBuilder, Test};
 use crate::{
-    mock::{Balance, Origin, XAssets, XAssetsErr},
-    AssetBalance, AssetErr, AssetInfo, AssetRestrictions, AssetType, Chain, TotalAssetBalance,
+    mock::{
+        set_asset_paused, Balance, Origin, System, XAssets, XAssetsErr, ALICE, BOB, RESTRICTED,
+    },
+    AssetBalance, AssetErr, AssetInfo, AssetRestrictions, AssetType, Chain, LockableAsset,
+    TotalAssetBalance, VestingSchedule, MAX_VESTING_SCHEDULES,
 };
 
 #[test]
@@ -527,3 +530,388 @@ fn test_move() {
         assert_eq!(XAssets::usable_balance(&b, &token), 200 + 100 + 100);
     })
 }
+
+#[test]
+fn test_locks() {
+    ExtBuilder::default().build_and_execute(|| {
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let btc_id = X_BTC;
+
+        XAssets::set_lock(*b"lock0001", &a, &btc_id, 60);
+        XAssets::set_lock(*b"lock0002", &a, &btc_id, 30);
+        // locks overlap
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 60);
+        assert_eq!(XAssets::transferable_balance(&a, &btc_id), 40);
+
+        assert_noop!(
+            XAssets::transfer(Origin::signed(a), b, btc_id, 41),
+            XAssetsErr::LiquidityRestrictions
+        );
+        assert_noop!(
+            XAssets::move_balance(&btc_id, &a, AssetType::Usable, &a, AssetType::Locked, 41),
+            AssetErr::LiquidityRestrictions
+        );
+        assert_noop!(
+            XAssets::ensure_can_withdraw(&a, &btc_id, 41),
+            XAssetsErr::LiquidityRestrictions
+        );
+        assert_ok!(XAssets::transfer(Origin::signed(a), b, btc_id, 40));
+
+        XAssets::extend_lock(*b"lock0002", &a, &btc_id, 20);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 60);
+        XAssets::remove_lock(*b"lock0001", &a, &btc_id);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 30);
+        assert_ok!(XAssets::transfer(Origin::signed(a), b, btc_id, 30));
+
+        XAssets::remove_lock(*b"lock0002", &a, &btc_id);
+        assert!(XAssets::locks(&a, &btc_id).is_empty());
+        assert_ok!(XAssets::transfer(Origin::signed(a), b, btc_id, 30));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 0);
+    })
+}
+
+#[test]
+fn test_locks_and_vesting_overlap() {
+    ExtBuilder::default().build_and_execute(|| {
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let btc_id = X_BTC;
+
+        // unlock 10 per block since block 10
+        let schedule = VestingSchedule {
+            locked: 50,
+            per_block: 10,
+            starting_block: 10,
+        };
+        assert_ok!(XAssets::vested_transfer(
+            Origin::signed(b),
+            a,
+            btc_id,
+            schedule
+        ));
+        XAssets::set_lock(*b"lock0001", &a, &btc_id, 30);
+        // the max of the lock and the vesting schedules is frozen
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 50);
+
+        System::set_block_number(12);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 30);
+        assert_noop!(
+            XAssets::transfer(Origin::signed(a), b, btc_id, 121),
+            XAssetsErr::LiquidityRestrictions
+        );
+        assert_ok!(XAssets::transfer(Origin::signed(a), b, btc_id, 120));
+
+        // the lock outlasts the vesting schedule
+        System::set_block_number(13);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 30);
+        XAssets::remove_lock(*b"lock0001", &a, &btc_id);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 20);
+        assert_ok!(XAssets::transfer(Origin::signed(a), b, btc_id, 10));
+    })
+}
+
+#[test]
+fn test_vesting() {
+    ExtBuilder::default().build_and_execute(|| {
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let c: u64 = 3; // accountid
+        let btc_id = X_BTC;
+
+        let invalid = VestingSchedule {
+            locked: 100,
+            per_block: 0,
+            starting_block: 1,
+        };
+        assert_noop!(
+            XAssets::vested_transfer(Origin::signed(b), a, btc_id, invalid),
+            XAssetsErr::InvalidVestingSchedule
+        );
+        assert_noop!(
+            XAssets::vest(Origin::signed(a), btc_id),
+            XAssetsErr::NotVesting
+        );
+        // dust can not be vested
+        let dust = VestingSchedule {
+            locked: 9,
+            per_block: 1,
+            starting_block: 1,
+        };
+        assert_noop!(
+            XAssets::vested_transfer(Origin::signed(b), a, btc_id, dust),
+            XAssetsErr::AmountLow
+        );
+
+        // unlock 10 per block since block 10
+        let schedule = VestingSchedule {
+            locked: 100,
+            per_block: 10,
+            starting_block: 10,
+        };
+        assert_ok!(XAssets::vested_transfer(
+            Origin::signed(b),
+            a,
+            btc_id,
+            schedule.clone()
+        ));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 200);
+        assert_eq!(XAssets::usable_balance(&b, &btc_id), 100);
+        assert_eq!(XAssets::vesting(&a, &btc_id), vec![schedule.clone()]);
+
+        assert_eq!(XAssets::transferable_balance(&a, &btc_id), 100);
+        assert_noop!(
+            XAssets::transfer(Origin::signed(a), c, btc_id, 101),
+            XAssetsErr::LiquidityRestrictions
+        );
+
+        System::set_block_number(15);
+        assert_eq!(XAssets::frozen_balance(&a, &btc_id), 50);
+        assert_ok!(XAssets::transfer(Origin::signed(a), c, btc_id, 150));
+        assert_noop!(
+            XAssets::transfer(Origin::signed(a), c, btc_id, 1),
+            XAssetsErr::LiquidityRestrictions
+        );
+
+        // still locked
+        assert_ok!(XAssets::vest(Origin::signed(a), btc_id));
+        assert_eq!(XAssets::vesting(&a, &btc_id).len(), 1);
+
+        System::set_block_number(20);
+        assert_ok!(XAssets::vest(Origin::signed(a), btc_id));
+        assert!(XAssets::vesting(&a, &btc_id).is_empty());
+        assert_ok!(XAssets::transfer(Origin::signed(a), c, btc_id, 50));
+
+        // the number of schedules is limited
+        let schedule = VestingSchedule {
+            locked: 10,
+            per_block: 1,
+            starting_block: 100,
+        };
+        for _ in 0..MAX_VESTING_SCHEDULES {
+            assert_ok!(XAssets::vested_transfer(
+                Origin::signed(c),
+                a,
+                btc_id,
+                schedule.clone()
+            ));
+        }
+        assert_eq!(
+            XAssets::frozen_balance(&a, &btc_id),
+            10 * MAX_VESTING_SCHEDULES as Balance
+        );
+        assert_noop!(
+            XAssets::vested_transfer(Origin::signed(c), a, btc_id, schedule),
+            XAssetsErr::AtMaxVestingSchedules
+        );
+    })
+}
+
+#[test]
+fn test_fungibles() {
+    use frame_support::traits::tokens::{
+        fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
+        WithdrawConsequence,
+    };
+
+    ExtBuilder::default().build_and_execute(|| {
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let btc_id = X_BTC;
+
+        assert_eq!(<XAssets as Inspect<_>>::total_issuance(btc_id), 1000);
+        assert_eq!(<XAssets as Inspect<_>>::balance(btc_id, &a), 100);
+
+        assert_ok!(<XAssets as Mutate<_>>::mint_into(btc_id, &a, 50));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 150);
+        // DESTROY_USABLE is restricted for X-BTC in the mock.
+        assert_noop!(
+            <XAssets as Mutate<_>>::burn_from(btc_id, &a, 50),
+            XAssetsErr::ActionNotAllowed
+        );
+
+        assert_eq!(
+            <XAssets as Transfer<_>>::transfer(btc_id, &a, &b, 50, false),
+            Ok(50)
+        );
+        assert_eq!(XAssets::usable_balance(&b, &btc_id), 250);
+
+        // hold
+        assert_ok!(<XAssets as MutateHold<_>>::hold(btc_id, &a, 60));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 40);
+        assert_eq!(<XAssets as InspectHold<_>>::balance_on_hold(btc_id, &a), 60);
+        assert_eq!(<XAssets as Inspect<_>>::balance(btc_id, &a), 100);
+        assert!(!<XAssets as InspectHold<_>>::can_hold(btc_id, &a, 41));
+        assert_eq!(
+            <XAssets as Inspect<_>>::can_withdraw(btc_id, &a, 41),
+            WithdrawConsequence::NoFunds
+        );
+
+        // vesting schedules are respected
+        let schedule = VestingSchedule {
+            locked: 30,
+            per_block: 1,
+            starting_block: 100,
+        };
+        assert_ok!(XAssets::vested_transfer(
+            Origin::signed(b),
+            a,
+            btc_id,
+            schedule
+        ));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 70);
+        assert_eq!(
+            <XAssets as Inspect<_>>::reducible_balance(btc_id, &a, false),
+            40
+        );
+        assert_eq!(
+            <XAssets as Inspect<_>>::can_withdraw(btc_id, &a, 50),
+            WithdrawConsequence::Frozen
+        );
+        assert_noop!(
+            <XAssets as Transfer<_>>::transfer(btc_id, &a, &b, 50, false),
+            XAssetsErr::LiquidityRestrictions
+        );
+
+        assert_noop!(
+            <XAssets as MutateHold<_>>::release(btc_id, &a, 70, false),
+            XAssetsErr::InsufficientBalance
+        );
+        assert_eq!(
+            <XAssets as MutateHold<_>>::transfer_held(btc_id, &a, &b, 20, false, false),
+            Ok(20)
+        );
+        assert_eq!(XAssets::usable_balance(&b, &btc_id), 240);
+        assert_eq!(
+            <XAssets as MutateHold<_>>::release(btc_id, &a, 70, true),
+            Ok(40)
+        );
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 110);
+        assert_eq!(<XAssets as InspectHold<_>>::balance_on_hold(btc_id, &a), 0);
+    })
+}
+
+#[test]
+fn test_account_restriction() {
+    ExtBuilder::default().build_and_execute(|| {
+        let btc_id = X_BTC;
+        assert_ok!(XAssets::issue(&btc_id, &RESTRICTED, 50));
+
+        assert_noop!(
+            XAssets::transfer(Origin::signed(RESTRICTED), 1, btc_id, 10),
+            XAssetsErr::AccountRestricted
+        );
+        let schedule = VestingSchedule {
+            locked: 10,
+            per_block: 1,
+            starting_block: 1,
+        };
+        assert_noop!(
+            XAssets::vested_transfer(Origin::signed(RESTRICTED), 1, btc_id, schedule),
+            XAssetsErr::AccountRestricted
+        );
+
+        // The restricted account can still reserve its own assets and receive assets.
+        assert_ok!(XAssets::move_balance(
+            &btc_id,
+            &RESTRICTED,
+            AssetType::Usable,
+            &RESTRICTED,
+            AssetType::ReservedWithdrawal,
+            10
+        ));
+        assert_ok!(XAssets::issue(&btc_id, &1, 10));
+        assert_ok!(XAssets::transfer(Origin::signed(1), RESTRICTED, btc_id, 10));
+        assert_eq!(XAssets::usable_balance(&RESTRICTED, &btc_id), 50);
+
+        // The internal moves, e.g. the settlement of the existing orders, are not restricted.
+        assert_ok!(XAssets::move_usable_balance(&btc_id, &RESTRICTED, &1, 10));
+        assert_eq!(XAssets::usable_balance(&RESTRICTED, &btc_id), 40);
+    });
+}
+
+#[test]
+fn test_fungibles_respect_restrictions() {
+    use frame_support::traits::tokens::fungibles::{Mutate, MutateHold, Transfer};
+
+    ExtBuilder::default().build_and_execute(|| {
+        let btc_id = X_BTC;
+        assert_ok!(XAssets::issue(&btc_id, &RESTRICTED, 50));
+
+        // e.g. paying the fees in the asset
+        assert_noop!(
+            <XAssets as Transfer<_>>::transfer(btc_id, &RESTRICTED, &ALICE, 10, false),
+            XAssetsErr::AccountRestricted
+        );
+        assert_noop!(
+            <XAssets as Mutate<_>>::burn_from(btc_id, &RESTRICTED, 10),
+            XAssetsErr::AccountRestricted
+        );
+        assert_ok!(<XAssets as MutateHold<_>>::hold(btc_id, &RESTRICTED, 10));
+        assert_noop!(
+            <XAssets as MutateHold<_>>::transfer_held(
+                btc_id,
+                &RESTRICTED,
+                &ALICE,
+                10,
+                false,
+                false
+            ),
+            XAssetsErr::AccountRestricted
+        );
+
+        set_asset_paused(btc_id, true);
+        assert_ok!(<XAssets as MutateHold<_>>::hold(btc_id, &ALICE, 10));
+        assert_noop!(
+            <XAssets as Transfer<_>>::transfer(btc_id, &ALICE, &BOB, 10, false),
+            XAssetsErr::AssetPaused
+        );
+        assert_noop!(
+            <XAssets as Mutate<_>>::burn_from(btc_id, &ALICE, 10),
+            XAssetsErr::AssetPaused
+        );
+        assert_noop!(
+            <XAssets as MutateHold<_>>::transfer_held(btc_id, &ALICE, &BOB, 10, false, false),
+            XAssetsErr::AssetPaused
+        );
+
+        set_asset_paused(btc_id, false);
+        assert_eq!(
+            <XAssets as Transfer<_>>::transfer(btc_id, &ALICE, &BOB, 10, false),
+            Ok(10)
+        );
+        assert_eq!(
+            <XAssets as MutateHold<_>>::transfer_held(btc_id, &ALICE, &BOB, 10, false, false),
+            Ok(10)
+        );
+        assert_eq!(XAssets::usable_balance(&BOB, &btc_id), 220);
+    });
+}
+
+#[test]
+fn test_asset_pause() {
+    ExtBuilder::default().build_and_execute(|| {
+        let btc_id = X_BTC;
+        set_asset_paused(btc_id, true);
+
+        assert_noop!(
+            XAssets::transfer(Origin::signed(ALICE), BOB, btc_id, 10),
+            XAssetsErr::AssetPaused
+        );
+        let schedule = VestingSchedule {
+            locked: 10,
+            per_block: 1,
+            starting_block: 1,
+        };
+        assert_noop!(
+            XAssets::vested_transfer(Origin::signed(ALICE), BOB, btc_id, schedule),
+            XAssetsErr::AssetPaused
+        );
+        // Only the user operations are paused.
+        assert_ok!(XAssets::move_usable_balance(&btc_id, &ALICE, &BOB, 10));
+
+        set_asset_paused(btc_id, false);
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, btc_id, 10));
+        assert_eq!(XAssets::usable_balance(&BOB, &btc_id), 220);
+    });
+}
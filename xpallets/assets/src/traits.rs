@@ -1,6 +1,9 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
-use frame_support::dispatch::{DispatchError, DispatchResult};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    traits::LockIdentifier,
+};
 
 use chainx_primitives::AssetId;
 use xpallet_assets_registrar::Chain;
@@ -74,3 +77,19 @@ pub trait OnAssetChanged<AccountId, Balance> {
         Ok(())
     }
 }
+
+/// Named locks on the usable balance of the non-native assets, akin to `LockableCurrency`.
+///
+/// The locks of the same account and asset overlap, so that the same balance is frozen
+/// by multiple locks.
+pub trait LockableAsset<AccountId, Balance> {
+    /// Creates a new lock `id` on the usable balance of `who` for `asset_id`,
+    /// or overwrites the existing one.
+    fn set_lock(id: LockIdentifier, who: &AccountId, asset_id: &AssetId, amount: Balance);
+
+    /// Changes the lock `id` to be at least `amount`, creates a new one if it does not exist.
+    fn extend_lock(id: LockIdentifier, who: &AccountId, asset_id: &AssetId, amount: Balance);
+
+    /// Removes the lock `id` on the usable balance of `who` for `asset_id`.
+    fn remove_lock(id: LockIdentifier, who: &AccountId, asset_id: &AssetId);
+}
@@ -7,7 +7,10 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 // Substrate
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, SaturatedConversion, Saturating, Zero},
+    RuntimeDebug,
+};
 use sp_std::{collections::btree_map::BTreeMap, prelude::*, slice::Iter};
 
 // ChainX
@@ -101,6 +104,7 @@ pub enum AssetErr {
     TotalAssetOverFlow,
     InvalidAsset,
     NotAllow,
+    LiquidityRestrictions,
 }
 
 impl<T: Config> From<AssetErr> for Error<T> {
@@ -112,6 +116,7 @@ impl<T: Config> From<AssetErr> for Error<T> {
             AssetErr::TotalAssetOverFlow => Error::<T>::TotalAssetOverflow,
             AssetErr::InvalidAsset => Error::<T>::InvalidAsset,
             AssetErr::NotAllow => Error::<T>::ActionNotAllowed,
+            AssetErr::LiquidityRestrictions => Error::<T>::LiquidityRestrictions,
         }
     }
 }
@@ -128,6 +133,42 @@ pub struct BalanceLock<Balance> {
     pub amount: Balance,
 }
 
+/// Linear unlocking schedule of the usable balance of an asset.
+///
+/// `per_block` of the `locked` balance will be unlocked each block since `starting_block`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    /// Locked amount at genesis.
+    pub locked: Balance,
+    /// Amount that gets unlocked every block after `starting_block`.
+    pub per_block: Balance,
+    /// Starting block for unlocking(vesting).
+    pub starting_block: BlockNumber,
+}
+
+impl<Balance, BlockNumber> VestingSchedule<Balance, BlockNumber>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+    /// Returns true if the schedule is able to unlock anything.
+    pub fn is_valid(&self) -> bool {
+        !self.locked.is_zero() && !self.per_block.is_zero()
+    }
+
+    /// Returns the amount that is still locked at block `n`.
+    pub fn locked_at(&self, n: BlockNumber) -> Balance {
+        let vested_blocks: Balance = n
+            .saturating_sub(self.starting_block)
+            .saturated_into::<u128>()
+            .saturated_into();
+        self.locked
+            .saturating_sub(self.per_block.saturating_mul(vested_blocks))
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
@@ -35,6 +35,8 @@ pub trait WeightInfo {
     fn force_transfer() -> Weight;
     fn set_balance(n: u32) -> Weight;
     fn set_asset_limit() -> Weight;
+    fn vested_transfer(s: u32) -> Weight;
+    fn vest(s: u32) -> Weight;
 }
 
 /// Weights for xpallet_assets using the Substrate node and recommended hardware.
@@ -42,12 +44,12 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn transfer() -> Weight {
         (160_552_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
             .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn force_transfer() -> Weight {
         (158_525_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
             .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn set_balance(_n: u32) -> Weight {
@@ -60,18 +62,30 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn vested_transfer(s: u32) -> Weight {
+        (172_416_000 as Weight)
+            .saturating_add((1_158_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn vest(s: u32) -> Weight {
+        (21_904_000 as Weight)
+            .saturating_add((603_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn transfer() -> Weight {
         (160_552_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
             .saturating_add(RocksDbWeight::get().writes(6 as Weight))
     }
     fn force_transfer() -> Weight {
         (158_525_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
             .saturating_add(RocksDbWeight::get().writes(6 as Weight))
     }
     fn set_balance(_n: u32) -> Weight {
@@ -84,4 +98,16 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn vested_transfer(s: u32) -> Weight {
+        (172_416_000 as Weight)
+            .saturating_add((1_158_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn vest(s: u32) -> Weight {
+        (21_904_000 as Weight)
+            .saturating_add((603_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
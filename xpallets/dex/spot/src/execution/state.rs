@@ -183,6 +183,11 @@ impl<T: Config> Pallet<T> {
     pub(crate) fn update_latest_price(pair_index: TradingPairId, latest: T::Price) {
         let current_block = <frame_system::Pallet<T>>::block_number();
 
+        // Fold the previous latest price held until now into the average.
+        if let Some(average) = Self::time_weighted_average_price(pair_index) {
+            <AveragePriceOf<T>>::insert(pair_index, (average, current_block));
+        }
+
         <TradingPairInfoOf<T>>::insert(
             pair_index,
             TradingPairInfo {
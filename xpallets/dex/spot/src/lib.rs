@@ -19,9 +19,12 @@ mod tests;
 
 use codec::Codec;
 
-use sp_runtime::traits::{
-    AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, StaticLookup,
-    Zero,
+use sp_runtime::{
+    traits::{
+        AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, StaticLookup,
+        Zero,
+    },
+    Permill,
 };
 use sp_std::prelude::*;
 use sp_std::{cmp, fmt::Debug};
@@ -37,6 +40,7 @@ use frame_system::{ensure_root, ensure_signed};
 
 use chainx_primitives::AssetId;
 use xpallet_assets::AssetErr;
+use xpallet_support::traits::{AccountRestriction, RestrictedAction};
 
 pub use self::rpc::*;
 pub use self::types::*;
@@ -57,6 +61,9 @@ const MAX_BACKLOG_ORDER: usize = 1000;
 /// more time than the Block time to finish.
 const DEFAULT_FLUCTUATION: u32 = 100;
 
+/// The number of blocks the time-weighted average price is smoothed over, about 1 hour.
+pub const TWAP_WINDOW: u32 = 600;
+
 pub type BalanceOf<T> = <<T as xpallet_assets::Config>::Currency as Currency<
     <T as frame_system::Config>::AccountId,
 >>::Balance;
@@ -114,6 +121,10 @@ pub mod pallet {
             #[pallet::compact] price: T::Price,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(
+                !T::AccountRestriction::is_restricted(&who, RestrictedAction::Trade),
+                Error::<T>::AccountRestricted
+            );
 
             ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
             ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
@@ -123,6 +134,8 @@ pub mod pallet {
 
             ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
             ensure!(pair.is_valid_price(price), Error::<T>::InvalidPrice);
+            xpallet_assets::Pallet::<T>::ensure_not_paused(&pair.base())?;
+            xpallet_assets::Pallet::<T>::ensure_not_paused(&pair.quote())?;
 
             Self::is_valid_quote(price, side, pair_id)?;
             Self::has_too_many_backlog_orders(pair_id, price, side)?;
@@ -307,6 +320,8 @@ pub mod pallet {
         InvalidOrderId,
         /// Error from assets module.
         AssetError,
+        /// The account is restricted from trading.
+        AccountRestricted,
     }
 
     /// How many trading pairs so far.
@@ -332,6 +347,12 @@ pub mod pallet {
     pub(crate) type TradingPairInfoOf<T: Config> =
         StorageMap<_, Twox64Concat, TradingPairId, TradingPairInfo<T::Price, T::BlockNumber>>;
 
+    /// (time-weighted average price, last update height) of trading pair
+    #[pallet::storage]
+    #[pallet::getter(fn average_price_of)]
+    pub(crate) type AveragePriceOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, (T::Price, T::BlockNumber)>;
+
     /// Total transactions has been made for a trading pair.
     #[pallet::storage]
     #[pallet::getter(fn trading_history_index_of)]
@@ -443,6 +464,70 @@ impl<T: Config> Pallet<T> {
         None
     }
 
+    /// Returns the time-weighted average of the executed prices of the trading pair
+    /// over about the last `TWAP_WINDOW` blocks.
+    pub fn time_weighted_average_price(pair_id: TradingPairId) -> Option<T::Price> {
+        let latest = Self::trading_pair_info_of(pair_id)?.latest_price;
+        let now = <frame_system::Pallet<T>>::block_number();
+        Some(match Self::average_price_of(pair_id) {
+            Some((average, last_updated)) => {
+                Self::moving_average(average, latest, now.saturating_sub(last_updated))
+            }
+            None => latest,
+        })
+    }
+
+    /// Moves the `average` towards the `latest` price held for `elapsed` blocks.
+    fn moving_average(average: T::Price, latest: T::Price, elapsed: T::BlockNumber) -> T::Price {
+        let window = u128::from(TWAP_WINDOW);
+        let elapsed = elapsed.saturated_into::<u128>().min(window);
+        let average = average
+            .saturated_into::<u128>()
+            .saturating_mul(window - elapsed)
+            .saturating_add(latest.saturated_into::<u128>().saturating_mul(elapsed));
+        (average / window).saturated_into()
+    }
+
+    /// Converts the `amount` of `base` currency into the `quote` currency at the
+    /// time-weighted average price of the tradable trading pair `base/quote`, rounding up.
+    ///
+    /// Returns None if there is no such trading pair or ask price, or the lowest ask
+    /// deviates from the average price by more than `max_deviation`. Never panics on
+    /// overflow, unlike `convert_base_to_quote`.
+    pub fn convert_at_average_price(
+        base: AssetId,
+        quote: AssetId,
+        amount: BalanceOf<T>,
+        max_deviation: Permill,
+    ) -> Option<BalanceOf<T>> {
+        let pair = Self::get_trading_pair_by_currency_pair(&CurrencyPair::new(base, quote))?;
+        if !pair.tradable {
+            return None;
+        }
+        let price = Self::time_weighted_average_price(pair.id)?.saturated_into::<u128>();
+        let lowest_ask = Self::handicap_of(pair.id)
+            .lowest_ask
+            .saturated_into::<u128>();
+        if price.is_zero()
+            || lowest_ask.is_zero()
+            || cmp::max(price, lowest_ask) - cmp::min(price, lowest_ask)
+                > max_deviation.mul_ceil(price)
+        {
+            return None;
+        }
+
+        let base_p = u32::from(Self::currency_decimals_of(base)?);
+        let quote_p = u32::from(Self::currency_decimals_of(quote)?);
+        let ap = amount.saturated_into::<u128>().checked_mul(price)?;
+        let volume = if quote_p >= base_p + pair.pip_decimals {
+            ap.checked_mul(10_u128.checked_pow(quote_p - base_p - pair.pip_decimals)?)?
+        } else {
+            let exp = 10_u128.checked_pow(base_p + pair.pip_decimals - quote_p)?;
+            ap / exp + u128::from(ap % exp != 0)
+        };
+        Some(volume.saturated_into())
+    }
+
     #[inline]
     fn trading_pair(pair_id: TradingPairId) -> Result<TradingPairProfile, Error<T>> {
         TradingPairOf::<T>::get(pair_id).ok_or(Error::<T>::InvalidTradingPair)
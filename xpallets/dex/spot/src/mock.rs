@@ -20,6 +20,7 @@ use sp_runtime::{
 use chainx_primitives::{AssetId, BlockNumber};
 use xp_protocol::{BTC_DECIMALS, PCX, PCX_DECIMALS, X_BTC, X_DOT};
 use xpallet_assets::{AssetInfo, AssetRestrictions, Chain};
+use xpallet_support::traits::{AccountRestriction, RestrictedAction};
 
 use crate::{self as xpallet_dex_spot, *};
 
@@ -117,6 +118,11 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = ();
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = XSpot;
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -126,6 +132,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = MockRestriction;
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -135,6 +144,19 @@ thread_local! {
     static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
     static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
     static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
+    static RESTRICTIONS: RefCell<Vec<(AccountId, RestrictedAction)>> = RefCell::new(Vec::new());
+}
+
+/// Restricts `who` from `action` in the tests.
+pub(crate) fn restrict(who: AccountId, action: RestrictedAction) {
+    RESTRICTIONS.with(|restrictions| restrictions.borrow_mut().push((who, action)));
+}
+
+pub struct MockRestriction;
+impl AccountRestriction<AccountId> for MockRestriction {
+    fn is_restricted(who: &AccountId, action: RestrictedAction) -> bool {
+        RESTRICTIONS.with(|restrictions| restrictions.borrow().contains(&(*who, action)))
+    }
 }
 
 #[derive(Default)]
@@ -147,6 +147,90 @@ fn convert_base_to_quote_should_work() {
     })
 }
 
+#[test]
+fn time_weighted_average_price_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        // The latest price before any trade.
+        assert_eq!(XSpot::time_weighted_average_price(0), Some(100_000));
+        assert_eq!(XSpot::time_weighted_average_price(9), None);
+
+        XSpot::update_latest_price(0, 200_000);
+        assert_eq!(XSpot::time_weighted_average_price(0), Some(100_000));
+
+        // Half of the window at each price.
+        System::set_block_number(1 + u64::from(TWAP_WINDOW) / 2);
+        assert_eq!(XSpot::time_weighted_average_price(0), Some(150_000));
+
+        // The trades of the same block don't move the average.
+        XSpot::update_latest_price(0, 10_000_000);
+        XSpot::update_latest_price(0, 1_000_000);
+        assert_eq!(XSpot::time_weighted_average_price(0), Some(150_000));
+
+        System::set_block_number(1 + u64::from(TWAP_WINDOW) / 2 + u64::from(TWAP_WINDOW) * 2);
+        assert_eq!(XSpot::time_weighted_average_price(0), Some(1_000_000));
+    })
+}
+
+#[test]
+fn convert_at_average_price_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pair = t_trading_pair_of(0);
+        let max_deviation = Permill::from_percent(10);
+        // No ask price yet.
+        t_set_handicap(pair.id, 90_000, 0);
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 1000, max_deviation),
+            None
+        );
+
+        // The lowest ask deviates too much from the average price 100_000.
+        t_set_handicap(pair.id, 90_000, 111_000);
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 1000, max_deviation),
+            None
+        );
+        t_set_handicap(pair.id, 80_000, 89_000);
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 1000, max_deviation),
+            None
+        );
+
+        // Priced at the average price, instead of the lowest ask.
+        t_set_handicap(pair.id, 90_000, 110_000);
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 10_000_000, max_deviation),
+            Some(1_000)
+        );
+        // Round up.
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 1000, max_deviation),
+            Some(1)
+        );
+        // Unknown trading pair.
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.quote(), pair.base(), 1000, max_deviation),
+            None
+        );
+        // Overflow.
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), u128::MAX, max_deviation),
+            None
+        );
+
+        assert_ok!(XSpot::update_trading_pair(
+            Origin::root(),
+            pair.id,
+            pair.tick_decimals,
+            false
+        ));
+        assert_eq!(
+            XSpot::convert_at_average_price(pair.base(), pair.quote(), 1000, max_deviation),
+            None
+        );
+    })
+}
+
 #[test]
 fn put_order_reserve_should_work() {
     ExtBuilder::default().build_and_execute(|| {
@@ -507,3 +591,43 @@ fn quotations_order_should_be_preserved_when_removing_orders_and_quotations() {
         assert_eq!(XSpot::quotations_of(0, 2_000_000), [(2, 1), (5, 0), (6, 0)]);
     })
 }
+
+#[test]
+fn orders_of_restricted_account_should_be_settled() {
+    use xpallet_support::traits::RestrictedAction;
+
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        t_set_handicap(0, 1_000_000, 1_100_000);
+        t_generic_issue(trading_pair.quote(), 1, 10);
+        t_issue_pcx(2, 2000);
+
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_000));
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_000));
+        restrict(1, RestrictedAction::MoveAsset);
+        restrict(1, RestrictedAction::Trade);
+
+        // The restricted account can neither put orders nor transfer its assets,
+        assert_noop!(
+            t_put_order_buy(1, 0, 1000, 1_000_000),
+            Error::<Test>::AccountRestricted
+        );
+        assert_noop!(
+            XAssets::transfer(Origin::signed(1), 2, trading_pair.quote(), 1),
+            xpallet_assets::Error::<Test>::AccountRestricted
+        );
+        // but it can cancel its orders and its existing orders are still settled.
+        assert_ok!(t_cancel_order(1, 0, 1));
+        assert_ok!(t_put_order_sell(2, 0, 1000, 1_000_000));
+
+        let quote = t_convert_base_to_quote(1000, 1_000_000, &trading_pair);
+        assert_eq!(XSpot::order_info_of(1, 0), None);
+        assert_eq!(t_generic_free_balance(1, trading_pair.base()), 1000);
+        assert_eq!(t_generic_free_balance(1, trading_pair.quote()), 10 - quote);
+        assert_eq!(t_generic_free_balance(2, trading_pair.quote()), quote);
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &trading_pair.quote(), AssetType::ReservedDexSpot),
+            0
+        );
+    })
+}
@@ -430,4 +430,29 @@ impl<AccountId: Clone, Balance: Copy + Ord + BaseArithmetic, BlockNumber: Copy,
             taker_order_id: taker_order.id(),
         }
     }
+
+    /// Returns the trading pair of the execution.
+    pub fn pair_id(&self) -> TradingPairId {
+        self.pair_id
+    }
+
+    /// Returns the price the orders were executed at.
+    pub fn price(&self) -> Price {
+        self.price
+    }
+
+    /// Returns the maker and the id of its order.
+    pub fn maker(&self) -> (AccountId, OrderId) {
+        (self.maker.clone(), self.maker_order_id)
+    }
+
+    /// Returns the taker and the id of its order.
+    pub fn taker(&self) -> (AccountId, OrderId) {
+        (self.taker.clone(), self.taker_order_id)
+    }
+
+    /// Returns the executed amount, measured by the base currency.
+    pub fn turnover(&self) -> Balance {
+        self.turnover
+    }
 }
@@ -215,7 +215,8 @@ impl<'a> HeaderProofOfWork<'a> {
     }
 }
 
-fn is_valid_proof_of_work(max_work_bits: Compact, bits: Compact, hash: H256) -> bool {
+/// Returns whether the header hash meets its target, which is not above the maximum target.
+pub fn is_valid_proof_of_work(max_work_bits: Compact, bits: Compact, hash: H256) -> bool {
     match (max_work_bits.to_u256(), bits.to_u256()) {
         (Ok(maximum), Ok(target)) => {
             let value = U256::from(hash_rev(hash).as_bytes());
@@ -13,7 +13,7 @@ use crate::{
     Config, ConfirmedIndex, Error, MainChain, Pallet,
 };
 
-pub use self::header_proof::HeaderVerifier;
+pub use self::header_proof::{is_valid_proof_of_work, HeaderVerifier};
 
 /// Look back the headers to pick the confirmed index,
 /// return the header indexes on the look back path.
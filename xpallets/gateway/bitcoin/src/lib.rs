@@ -5,6 +5,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod header;
+pub mod relay;
 pub mod trustee;
 mod tx;
 pub mod types;
@@ -56,7 +57,8 @@ use self::{
 };
 
 pub use self::{
-    types::{BtcAddress, BtcParams, BtcTxVerifier, BtcWithdrawalProposal},
+    header::is_valid_proof_of_work,
+    types::{BtcAddress, BtcDepositCache, BtcParams, BtcTxVerifier, BtcWithdrawalProposal},
     weights::WeightInfo,
 };
 pub use pallet::*;
@@ -97,6 +99,7 @@ pub mod pallet {
         + xpallet_assets::Config
         + xpallet_gateway_records::Config
         + xpallet_assets_bridge::Config
+        + frame_system::offchain::CreateSignedTransaction<Call<Self>>
     {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
@@ -126,12 +129,19 @@ pub mod pallet {
         /// Handle address binding about pending deposit.
         type AddressBinding: AddressBinding<Self::AccountId, BtcAddress>;
 
+        /// The key signing the extrinsics of the relay offchain worker.
+        type AuthorityId: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn offchain_worker(block_number: T::BlockNumber) {
+            relay::offchain_worker::<T>(block_number);
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -894,4 +904,12 @@ pub mod pallet {
             Self::headers(txid)
         }
     }
+
+    impl<T: Config> Pallet<T> {
+        /// Restore the unclaimed deposits of the address, used by the regenesis.
+        #[cfg(feature = "std")]
+        pub fn force_set_pending_deposits(address: BtcAddress, deposits: Vec<BtcDepositCache>) {
+            PendingDeposits::<T>::insert(address, deposits);
+        }
+    }
 }
@@ -18,9 +18,9 @@ use frame_system::EnsureSigned;
 use sp_core::{blake2_256, H256};
 use sp_keyring::sr25519;
 use sp_runtime::{
-    testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
-    AccountId32, Perbill,
+    testing::{Header, TestXt},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
+    AccountId32, MultiSignature, MultiSigner, Perbill,
 };
 
 use chainx_primitives::AssetId;
@@ -166,6 +166,11 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = ();
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -186,6 +191,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = ();
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -279,6 +287,10 @@ impl xpallet_assets_bridge::Config for Test {
     type Event = ();
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
+    type PauserOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type BackForeignOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrationDelay = ();
 }
 
 impl Config for Test {
@@ -291,9 +303,39 @@ impl Config for Test {
     type TrusteeInfoUpdate = XGatewayCommon;
     type ReferralBinding = XGatewayCommon;
     type AddressBinding = XGatewayCommon;
+    type AuthorityId = crate::relay::crypto::RelayerAuthId;
     type WeightInfo = ();
 }
 
+pub(crate) type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = MultiSigner;
+    type Signature = MultiSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: MultiSigner,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (nonce, ())))
+    }
+}
+
 pub type XGatewayBitcoinErr = Error<Test>;
 
 pub(crate) fn btc() -> (AssetId, AssetInfo, AssetRestrictions) {
@@ -410,7 +452,7 @@ impl ExtBuilder {
         .assimilate_storage(&mut storage);
 
         xpallet_assets_bridge::GenesisConfig::<Test> {
-            admin_key: Some(alice()),
+            roles: vec![(xpallet_assets_bridge::Role::Registrar, alice())],
         }
         .assimilate_storage(&mut storage)
         .unwrap();
@@ -0,0 +1,413 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The offchain worker relaying the Bitcoin headers and transactions from a bitcoind node.
+//!
+//! The worker is disabled unless the JSON-RPC endpoint of bitcoind is written into the
+//! persistent offchain storage under [`BITCOIND_URL_KEY`], which the node does with
+//! `--btc-relay-url`. The headers are checked against the local light client before being
+//! pushed, and only the transactions of the confirmed blocks which touch the trustee
+//! addresses are relayed. The extrinsics are signed by the first [`KEY_TYPE`] key of the
+//! keystore.
+
+pub(crate) mod rpc;
+
+use codec::{Decode, Encode};
+use frame_support::log::{debug, info, warn};
+use frame_system::offchain::{AppCrypto, SubmitTransaction};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+    offchain::{
+        storage::StorageValueRef,
+        storage_lock::{StorageLock, Time},
+        Duration, StorageKind,
+    },
+    traits::{IdentifyAccount, One, Saturating},
+    RuntimeAppPublic, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+use light_bitcoin::{
+    chain::Transaction,
+    keys::Address,
+    primitives::{hash_rev, H256},
+    serialization::serialize,
+};
+
+use xp_gateway_bitcoin::{extract_output_addr, is_trustee_addr, BtcTxMetaType, BtcTxTypeDetector};
+use xp_gateway_common::AccountExtractor;
+
+use crate::{
+    is_valid_proof_of_work,
+    trustee::{get_current_trustee_address_pair, get_last_trustee_address_pair},
+    types::{BtcHeaderIndex, BtcRelayedTxInfo, BtcTxResult},
+    BtcHeader, Call, Config, Pallet,
+};
+
+pub use self::rpc::Bitcoind;
+
+/// The key type of the relayer keys.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"btcr");
+
+/// The key of the bitcoind JSON-RPC url in the persistent offchain storage.
+pub const BITCOIND_URL_KEY: &[u8] = b"chainx::btc-relay::url";
+
+/// The key of the `Authorization` header sent to bitcoind in the persistent offchain storage.
+pub const BITCOIND_AUTH_KEY: &[u8] = b"chainx::btc-relay::auth";
+
+/// The key of the height of the last Bitcoin block whose transactions have been relayed.
+pub const RELAYED_HEIGHT_KEY: &[u8] = b"chainx::btc-relay::relayed-height";
+
+const PENDING_NONCE_KEY: &[u8] = b"chainx::btc-relay::pending-nonce";
+const LOCK_KEY: &[u8] = b"chainx::btc-relay::lock";
+const LOCK_TIMEOUT: u64 = 60_000;
+
+/// The maximum number of headers pushed in one run.
+const MAX_HEADERS_PER_RUN: u32 = 20;
+/// The maximum number of blocks whose transactions are relayed in one run.
+const MAX_BLOCKS_PER_RUN: u32 = 5;
+/// The number of blocks after which the extrinsics still not included are given up.
+const PENDING_TIMEOUT: u32 = 10;
+
+/// The relayer crypto, i.e. sr25519 keys of [`KEY_TYPE`].
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_core::sr25519::{Public as Sr25519Public, Signature as Sr25519Signature};
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// The `AuthorityId` of the relayer.
+    pub struct RelayerAuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for RelayerAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = Sr25519Signature;
+        type GenericPublic = Sr25519Public;
+    }
+}
+
+/// The errors of the relay.
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub enum RelayError {
+    /// The url or the authorization is not valid utf8.
+    InvalidUrl,
+    /// Failed to send the request or to receive the response.
+    Http,
+    /// bitcoind returned an error.
+    Rpc,
+    /// The response is not the expected one.
+    InvalidResponse,
+    /// The header from bitcoind does not pass the local checks.
+    InvalidHeader,
+    /// The best chain of bitcoind forks beyond the confirmed header.
+    AncientFork,
+    /// No trustee session of Bitcoin.
+    NoTrustee,
+    /// No relayer key in the keystore.
+    NoKey,
+    /// Failed to sign or to submit the extrinsic.
+    Submit,
+}
+
+/// The extrinsics submitted in the previous runs and not yet included.
+#[derive(Encode, Decode)]
+struct PendingNonce<Index, BlockNumber> {
+    next_nonce: Index,
+    submitted_at: BlockNumber,
+}
+
+/// Submits the relay extrinsics signed by the relayer key with consecutive nonces.
+pub struct Relayer<T: Config> {
+    public: T::Public,
+    account: T::AccountId,
+    nonce: T::Index,
+    submitted: u32,
+}
+
+impl<T: Config> Relayer<T> {
+    /// Returns the relayer of the first key in the keystore.
+    pub fn new() -> Result<Self, RelayError> {
+        let key = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .next()
+            .ok_or(RelayError::NoKey)?;
+        let generic: <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic =
+            key.into();
+        let public: T::Public = generic.into();
+        let account = public.clone().into_account();
+        let nonce = frame_system::Pallet::<T>::account_nonce(&account);
+        Ok(Self {
+            public,
+            account,
+            nonce,
+            submitted: 0,
+        })
+    }
+
+    /// Returns the number of the extrinsics submitted by this relayer.
+    pub fn submitted(&self) -> u32 {
+        self.submitted
+    }
+
+    fn submit(&mut self, call: Call<T>) -> Result<(), RelayError> {
+        let (call, signature) = T::create_transaction::<T::AuthorityId>(
+            call.into(),
+            self.public.clone(),
+            self.account.clone(),
+            self.nonce,
+        )
+        .ok_or(RelayError::Submit)?;
+        SubmitTransaction::<T, Call<T>>::submit_transaction(call, Some(signature))
+            .map_err(|_| RelayError::Submit)?;
+        self.nonce += One::one();
+        self.submitted += 1;
+        Ok(())
+    }
+
+    /// Pushes the headers of the best chain of bitcoind which are missing on chain.
+    pub fn relay_headers(&mut self, bitcoind: &Bitcoind) -> Result<(), RelayError> {
+        let best = Pallet::<T>::best_index();
+        let confirmed = Pallet::<T>::confirmed_index().unwrap_or(best);
+        let remote_height = bitcoind.block_count()?;
+
+        // Find the last header of the best chain of bitcoind which is known on chain.
+        let mut height = best.height.min(remote_height);
+        let mut prev_hash = loop {
+            let hash = bitcoind.block_hash(height)?;
+            if Pallet::<T>::headers(&hash).is_some() {
+                break hash;
+            }
+            if height <= confirmed.height {
+                return Err(RelayError::AncientFork);
+            }
+            height -= 1;
+        };
+
+        let to = remote_height.min(height.saturating_add(MAX_HEADERS_PER_RUN));
+        let max_bits = Pallet::<T>::params_info().max_bits();
+        for height in height + 1..=to {
+            let hash = bitcoind.block_hash(height)?;
+            let header = bitcoind.block_header(&hash)?;
+            check_header(&header, &hash, &prev_hash, max_bits)?;
+            debug!(
+                target: "runtime::bitcoin",
+                "[relay_headers] Push header, height:{}, hash:{:?}",
+                height,
+                hash_rev(hash)
+            );
+            self.submit(Call::push_header {
+                header: serialize(&header).into(),
+            })?;
+            prev_hash = hash;
+        }
+        Ok(())
+    }
+
+    /// Pushes the relevant transactions of the confirmed blocks after the last relayed one.
+    pub fn relay_transactions(&mut self, bitcoind: &Bitcoind) -> Result<(), RelayError> {
+        let confirmed = match Pallet::<T>::confirmed_index() {
+            Some(confirmed) => confirmed,
+            None => return Ok(()),
+        };
+        let relayed_height = StorageValueRef::persistent(RELAYED_HEIGHT_KEY);
+        let from = match relayed_height.get::<u32>() {
+            Ok(Some(height)) => height + 1,
+            // Don't dig into the history on the first run.
+            _ => confirmed.height,
+        };
+        let to = confirmed
+            .height
+            .min(from.saturating_add(MAX_BLOCKS_PER_RUN - 1));
+
+        let current_trustee_pair =
+            get_current_trustee_address_pair::<T>().map_err(|_| RelayError::NoTrustee)?;
+        let last_trustee_pair = get_last_trustee_address_pair::<T>().ok();
+        for height in from..=to {
+            let index = main_chain_index::<T>(height).ok_or(RelayError::AncientFork)?;
+            self.relay_block_transactions(
+                bitcoind,
+                &index,
+                current_trustee_pair,
+                last_trustee_pair,
+            )?;
+            relayed_height.set(&height);
+        }
+        Ok(())
+    }
+
+    fn relay_block_transactions(
+        &mut self,
+        bitcoind: &Bitcoind,
+        index: &BtcHeaderIndex,
+        current_trustee_pair: (Address, Address),
+        last_trustee_pair: Option<(Address, Address)>,
+    ) -> Result<(), RelayError> {
+        let block = bitcoind.block(&index.hash)?;
+        if block.header().hash() != index.hash {
+            return Err(RelayError::InvalidResponse);
+        }
+
+        let network = Pallet::<T>::network_id();
+        let detector = BtcTxTypeDetector::new(network, Pallet::<T>::btc_min_deposit());
+        for tx in block.transactions() {
+            let txid = tx.hash();
+            if matches!(Pallet::<T>::tx_state(&txid), Some(state) if state.result == BtcTxResult::Success)
+            {
+                continue;
+            }
+            let is_relevant = tx.outputs.iter().any(|output| {
+                extract_output_addr(output, network).map_or(false, |addr| {
+                    is_trustee_addr(addr, current_trustee_pair)
+                        || last_trustee_pair.map_or(false, |pair| is_trustee_addr(addr, pair))
+                })
+            });
+            if !is_relevant {
+                continue;
+            }
+
+            let prev_tx = fetch_prev_tx(bitcoind, tx);
+            let meta_type = detector.detect_transaction_type::<T::AccountId, _>(
+                tx,
+                prev_tx.as_ref(),
+                T::AccountExtractor::extract_account,
+                current_trustee_pair,
+                last_trustee_pair,
+            );
+            if let BtcTxMetaType::Irrelevance = meta_type {
+                continue;
+            }
+
+            let merkle_proof = bitcoind.tx_out_proof(&txid, &index.hash)?;
+            info!(
+                target: "runtime::bitcoin",
+                "[relay_transactions] Push tx, height:{}, txid:{:?}, type:{:?}",
+                index.height,
+                hash_rev(txid),
+                meta_type.ref_into()
+            );
+            self.submit(Call::push_transaction {
+                raw_tx: serialize(tx).into(),
+                relayed_info: BtcRelayedTxInfo {
+                    block_hash: index.hash,
+                    merkle_proof,
+                }
+                .encode(),
+                prev_tx: prev_tx.map(|prev_tx| serialize(&prev_tx).into()),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the header from bitcoind before pushing it.
+pub(crate) fn check_header(
+    header: &BtcHeader,
+    hash: &H256,
+    prev_hash: &H256,
+    max_bits: light_bitcoin::primitives::Compact,
+) -> Result<(), RelayError> {
+    if header.hash() != *hash
+        || header.previous_header_hash != *prev_hash
+        || !is_valid_proof_of_work(max_bits, header.bits, *hash)
+    {
+        warn!(
+            target: "runtime::bitcoin",
+            "[relay_headers] Invalid header from bitcoind, hash:{:?}, header:{:?}",
+            hash_rev(*hash),
+            header
+        );
+        return Err(RelayError::InvalidHeader);
+    }
+    Ok(())
+}
+
+/// Returns the main chain header at the height.
+fn main_chain_index<T: Config>(height: u32) -> Option<BtcHeaderIndex> {
+    Pallet::<T>::block_hash_for(height)
+        .into_iter()
+        .find(|hash| Pallet::<T>::main_chain(hash))
+        .map(|hash| BtcHeaderIndex { hash, height })
+}
+
+/// Returns the transaction spent by the first input, which tells the sender of `tx`.
+///
+/// It's optional for the deposits, so the failures only end up with a log.
+fn fetch_prev_tx(bitcoind: &Bitcoind, tx: &Transaction) -> Option<Transaction> {
+    let outpoint = &tx.inputs.first()?.previous_output;
+    match bitcoind.raw_transaction(&outpoint.hash) {
+        Ok(prev_tx) if prev_tx.hash() == outpoint.hash => Some(prev_tx),
+        result => {
+            warn!(
+                target: "runtime::bitcoin",
+                "[relay_transactions] Can't fetch the previous tx {:?} of {:?}: {:?}",
+                hash_rev(outpoint.hash),
+                hash_rev(tx.hash()),
+                result.map(|_| ())
+            );
+            None
+        }
+    }
+}
+
+fn bitcoind() -> Option<Bitcoind> {
+    let url = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, BITCOIND_URL_KEY)?;
+    let authorization =
+        sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, BITCOIND_AUTH_KEY);
+    Some(Bitcoind::new(url, authorization))
+}
+
+/// Runs the relay if a bitcoind endpoint is configured.
+pub fn offchain_worker<T: Config>(block_number: T::BlockNumber) {
+    let bitcoind = match bitcoind() {
+        Some(bitcoind) => bitcoind,
+        None => return,
+    };
+    let mut lock =
+        StorageLock::<Time>::with_deadline(LOCK_KEY, Duration::from_millis(LOCK_TIMEOUT));
+    let _guard = match lock.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    let mut relayer = match Relayer::<T>::new() {
+        Ok(relayer) => relayer,
+        Err(e) => {
+            warn!(target: "runtime::bitcoin", "[offchain_worker] Relay disabled: {:?}", e);
+            return;
+        }
+    };
+
+    // The extrinsics of the previous run would conflict with the new ones.
+    let pending = StorageValueRef::persistent(PENDING_NONCE_KEY);
+    if let Ok(Some(PendingNonce {
+        next_nonce,
+        submitted_at,
+    })) = pending.get::<PendingNonce<T::Index, T::BlockNumber>>()
+    {
+        if relayer.nonce < next_nonce
+            && block_number < submitted_at.saturating_add(PENDING_TIMEOUT.into())
+        {
+            debug!(
+                target: "runtime::bitcoin",
+                "[offchain_worker] Wait for the pending relay extrinsics"
+            );
+            return;
+        }
+    }
+
+    if let Err(e) = relayer.relay_headers(&bitcoind) {
+        warn!(target: "runtime::bitcoin", "[offchain_worker] Failed to relay headers: {:?}", e);
+    }
+    if let Err(e) = relayer.relay_transactions(&bitcoind) {
+        warn!(target: "runtime::bitcoin", "[offchain_worker] Failed to relay txs: {:?}", e);
+    }
+    if relayer.submitted() > 0 {
+        pending.set(&PendingNonce {
+            next_nonce: relayer.nonce,
+            submitted_at: block_number,
+        });
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! A minimal JSON-RPC client of bitcoind for the offchain worker.
+//!
+//! Only the few calls needed by the relay are supported, their results are either strings
+//! or numbers, so the responses are parsed by hand instead of pulling a JSON library into
+//! the runtime.
+
+use frame_support::log::warn;
+use sp_runtime::offchain::{http, Duration};
+use sp_std::prelude::*;
+
+use light_bitcoin::{
+    chain::{Block, BlockHeader as BtcHeader, Transaction},
+    merkle::PartialMerkleTree,
+    primitives::{hash_rev, H256},
+    serialization::{deserialize, Reader},
+};
+
+use super::RelayError;
+
+/// The timeout of a single request to bitcoind.
+const HTTP_TIMEOUT: u64 = 10_000;
+
+/// The size of a serialized block header, which prefixes the result of `gettxoutproof`.
+const HEADER_SIZE: usize = 80;
+
+/// Returns the body of the JSON-RPC request, `params` is the JSON array of the parameters.
+pub(crate) fn request_body(method: &str, params: &[u8]) -> Vec<u8> {
+    let mut body = b"{\"jsonrpc\":\"1.0\",\"id\":\"chainx\",\"method\":\"".to_vec();
+    body.extend_from_slice(method.as_bytes());
+    body.extend_from_slice(b"\",\"params\":");
+    body.extend_from_slice(params);
+    body.push(b'}');
+    body
+}
+
+/// Appends the decimal representation of `n` to `buf`.
+pub(crate) fn push_number(buf: &mut Vec<u8>, mut n: u32) {
+    let start = buf.len();
+    loop {
+        buf.push(b'0' + (n % 10) as u8);
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    buf[start..].reverse();
+}
+
+/// Appends the hash as a quoted JSON string in the byte order displayed by bitcoind.
+pub(crate) fn push_hash(buf: &mut Vec<u8>, hash: &H256) {
+    buf.push(b'"');
+    buf.extend_from_slice(hex::encode(hash_rev(*hash).as_bytes()).as_bytes());
+    buf.push(b'"');
+}
+
+/// Returns the raw JSON value of the `result` field, `null` is treated as an error since
+/// bitcoind only returns it along with an `error`.
+pub(crate) fn parse_result(body: &[u8]) -> Result<&[u8], RelayError> {
+    const FIELD: &[u8] = b"\"result\":";
+    let start = body
+        .windows(FIELD.len())
+        .position(|w| w == FIELD)
+        .ok_or(RelayError::InvalidResponse)?
+        + FIELD.len();
+    let value = &body[start..];
+    let value = &value[value
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .ok_or(RelayError::InvalidResponse)?..];
+    if value.first() == Some(&b'"') {
+        let end = value[1..]
+            .iter()
+            .position(|c| *c == b'"')
+            .ok_or(RelayError::InvalidResponse)?;
+        return Ok(&value[1..=end]);
+    }
+    let end = value
+        .iter()
+        .position(|c| *c == b',' || *c == b'}' || c.is_ascii_whitespace())
+        .unwrap_or(value.len());
+    match &value[..end] {
+        b"null" => Err(RelayError::Rpc),
+        v => Ok(v),
+    }
+}
+
+fn parse_number(value: &[u8]) -> Result<u32, RelayError> {
+    sp_std::str::from_utf8(value)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or(RelayError::InvalidResponse)
+}
+
+fn parse_hex(value: &[u8]) -> Result<Vec<u8>, RelayError> {
+    hex::decode(value).map_err(|_| RelayError::InvalidResponse)
+}
+
+fn parse_hash(value: &[u8]) -> Result<H256, RelayError> {
+    let bytes = parse_hex(value)?;
+    if bytes.len() != 32 {
+        return Err(RelayError::InvalidResponse);
+    }
+    Ok(hash_rev(H256::from_slice(&bytes)))
+}
+
+fn parse_raw<V: light_bitcoin::serialization::Deserializable>(
+    bytes: &[u8],
+) -> Result<V, RelayError> {
+    deserialize(Reader::new(bytes)).map_err(|_| RelayError::InvalidResponse)
+}
+
+/// The JSON-RPC endpoint of bitcoind.
+pub struct Bitcoind {
+    url: Vec<u8>,
+    authorization: Option<Vec<u8>>,
+}
+
+impl Bitcoind {
+    /// `authorization` is the value of the `Authorization` header, e.g. `Basic <base64>`.
+    pub fn new(url: Vec<u8>, authorization: Option<Vec<u8>>) -> Self {
+        Self { url, authorization }
+    }
+
+    fn call(&self, method: &str, params: &[u8]) -> Result<Vec<u8>, RelayError> {
+        let url = sp_std::str::from_utf8(&self.url).map_err(|_| RelayError::InvalidUrl)?;
+        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT));
+        let mut request = http::Request::post(url, vec![request_body(method, params)])
+            .add_header("Content-Type", "application/json");
+        if let Some(authorization) = &self.authorization {
+            let authorization =
+                sp_std::str::from_utf8(authorization).map_err(|_| RelayError::InvalidUrl)?;
+            request = request.add_header("Authorization", authorization);
+        }
+        let pending = request
+            .deadline(deadline)
+            .send()
+            .map_err(|_| RelayError::Http)?;
+        let response = pending
+            .try_wait(deadline)
+            .map_err(|_| RelayError::Http)?
+            .map_err(|_| RelayError::Http)?;
+        let body = response.body().collect::<Vec<u8>>();
+        // bitcoind answers the failed calls with 500 and the error in the body.
+        if response.code != 200 {
+            warn!(
+                target: "runtime::bitcoin",
+                "[bitcoind] {} failed with status {}: {}",
+                method,
+                response.code,
+                sp_std::str::from_utf8(&body).unwrap_or_default()
+            );
+            return Err(RelayError::Rpc);
+        }
+        parse_result(&body).map(|value| value.to_vec())
+    }
+
+    /// Returns the height of the best block.
+    pub fn block_count(&self) -> Result<u32, RelayError> {
+        parse_number(&self.call("getblockcount", b"[]")?)
+    }
+
+    /// Returns the hash of the best chain block at the height.
+    pub fn block_hash(&self, height: u32) -> Result<H256, RelayError> {
+        let mut params = vec![b'['];
+        push_number(&mut params, height);
+        params.push(b']');
+        parse_hash(&self.call("getblockhash", &params)?)
+    }
+
+    /// Returns the block header of the hash.
+    pub fn block_header(&self, hash: &H256) -> Result<BtcHeader, RelayError> {
+        let mut params = vec![b'['];
+        push_hash(&mut params, hash);
+        params.extend_from_slice(b",false]");
+        parse_raw(&parse_hex(&self.call("getblockheader", &params)?)?)
+    }
+
+    /// Returns the block of the hash.
+    pub fn block(&self, hash: &H256) -> Result<Block, RelayError> {
+        let mut params = vec![b'['];
+        push_hash(&mut params, hash);
+        params.extend_from_slice(b",0]");
+        parse_raw(&parse_hex(&self.call("getblock", &params)?)?)
+    }
+
+    /// Returns the transaction of the id, bitcoind needs `-txindex` to find the transactions
+    /// which are neither in the mempool nor in an unspent output.
+    pub fn raw_transaction(&self, txid: &H256) -> Result<Transaction, RelayError> {
+        let mut params = vec![b'['];
+        push_hash(&mut params, txid);
+        params.extend_from_slice(b",false]");
+        parse_raw(&parse_hex(&self.call("getrawtransaction", &params)?)?)
+    }
+
+    /// Returns the merkle proof of the transaction in the block.
+    pub fn tx_out_proof(
+        &self,
+        txid: &H256,
+        block_hash: &H256,
+    ) -> Result<PartialMerkleTree, RelayError> {
+        let mut params = b"[[".to_vec();
+        push_hash(&mut params, txid);
+        params.extend_from_slice(b"],");
+        push_hash(&mut params, block_hash);
+        params.push(b']');
+        // The result is a serialized merkle block, i.e. the block header and the proof.
+        let merkle_block = parse_hex(&self.call("gettxoutproof", &params)?)?;
+        if merkle_block.len() < HEADER_SIZE {
+            return Err(RelayError::InvalidResponse);
+        }
+        parse_raw(&merkle_block[HEADER_SIZE..])
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 
 mod header;
+mod relay;
 mod trustee;
 mod tx;
 
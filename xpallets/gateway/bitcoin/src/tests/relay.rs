@@ -0,0 +1,298 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use frame_support::assert_ok;
+use sp_core::{
+    crypto::{set_default_ss58_version, Ss58AddressFormatRegistry},
+    offchain::{
+        testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+        OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+    },
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::offchain::storage::StorageValueRef;
+
+use light_bitcoin::{
+    chain::{Block, BlockHeader as BtcHeader, Transaction},
+    merkle::PartialMerkleTree,
+    primitives::H256,
+    serialization::{self, Reader},
+};
+
+use crate::{
+    mock::{
+        generate_blocks_63290_63310, Call, ExtBuilder, Extrinsic, System, Test, XGatewayBitcoin,
+    },
+    relay::{
+        self,
+        rpc::{parse_result, push_hash, push_number, request_body},
+        Bitcoind, RelayError, Relayer, BITCOIND_URL_KEY, KEY_TYPE, RELAYED_HEIGHT_KEY,
+    },
+    types::{BtcHeaderIndex, BtcRelayedTxInfo},
+    ConfirmedIndex,
+};
+
+const BITCOIND_URL: &str = "http://127.0.0.1:8332";
+
+/// A mock bitcoind answering the expected JSON-RPC calls in order.
+#[derive(Default)]
+struct MockBitcoind {
+    requests: Vec<PendingRequest>,
+}
+
+impl MockBitcoind {
+    fn expect(mut self, method: &str, params: &[u8], result: &str) -> Self {
+        self.requests.push(PendingRequest {
+            method: "POST".into(),
+            uri: BITCOIND_URL.into(),
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: request_body(method, params),
+            response: Some(
+                format!("{{\"result\":{},\"error\":null,\"id\":\"chainx\"}}", result).into_bytes(),
+            ),
+            sent: true,
+            ..Default::default()
+        });
+        self
+    }
+
+    fn expect_error(mut self, method: &str, params: &[u8]) -> Self {
+        self = self.expect(method, params, "null");
+        let request = self.requests.last_mut().unwrap();
+        request.response = Some(
+            br#"{"result":null,"error":{"code":-5,"message":"No such transaction"},"id":"chainx"}"#
+                .to_vec(),
+        );
+        self
+    }
+
+    fn expect_block_hash(self, height: u32, hash: H256) -> Self {
+        let mut params = vec![b'['];
+        push_number(&mut params, height);
+        params.push(b']');
+        self.expect("getblockhash", &params, &quoted_hash(&hash))
+    }
+
+    fn expect_header(self, height: u32, header: &BtcHeader) -> Self {
+        let mut params = vec![b'['];
+        push_hash(&mut params, &header.hash());
+        params.extend_from_slice(b",false]");
+        self.expect_block_hash(height, header.hash()).expect(
+            "getblockheader",
+            &params,
+            &quoted_hex(&serialization::serialize(header)),
+        )
+    }
+}
+
+fn quoted_hash(hash: &H256) -> String {
+    let mut quoted = vec![];
+    push_hash(&mut quoted, hash);
+    String::from_utf8(quoted).unwrap()
+}
+
+fn quoted_hex(bytes: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(bytes))
+}
+
+fn bitcoind() -> Bitcoind {
+    Bitcoind::new(BITCOIND_URL.into(), None)
+}
+
+/// Runs the test with the offchain extensions and a relayer key, returns the submitted
+/// extrinsics.
+fn execute_with_relay(bitcoind: MockBitcoind, test: impl FnOnce()) -> Vec<Extrinsic> {
+    let (offchain, offchain_state) = TestOffchainExt::new();
+    for request in bitcoind.requests {
+        offchain_state.write().expect_request(request);
+    }
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, Some("//Relayer")).unwrap();
+
+    let mut ext = ExtBuilder::default().build();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.register_extension(KeystoreExt(Arc::new(keystore)));
+    ext.execute_with(|| System::set_block_number(1));
+    ext.execute_with(test);
+
+    let transactions = pool_state.read().transactions.clone();
+    transactions
+        .into_iter()
+        .map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap())
+        .collect()
+}
+
+fn push_header_call(header: &BtcHeader) -> Call {
+    Call::XGatewayBitcoin(crate::Call::push_header {
+        header: serialization::serialize(header).into(),
+    })
+}
+
+#[test]
+fn test_parse_result() {
+    assert_eq!(
+        parse_result(br#"{"result":"00ff","error":null,"id":"chainx"}"#),
+        Ok(&b"00ff"[..])
+    );
+    assert_eq!(
+        parse_result(br#"{"result": 63292, "error": null, "id": "chainx"}"#),
+        Ok(&b"63292"[..])
+    );
+    assert_eq!(
+        parse_result(br#"{"result":null,"error":{"code":-5},"id":"chainx"}"#),
+        Err(RelayError::Rpc)
+    );
+    assert_eq!(
+        parse_result(br#"{"error":null}"#),
+        Err(RelayError::InvalidResponse)
+    );
+    assert_eq!(
+        request_body("getblockhash", b"[1]"),
+        br#"{"jsonrpc":"1.0","id":"chainx","method":"getblockhash","params":[1]}"#.to_vec()
+    );
+}
+
+#[test]
+fn test_relay_headers() {
+    let headers = generate_blocks_63290_63310();
+    let bitcoind_mock = MockBitcoind::default()
+        .expect("getblockcount", b"[]", "63292")
+        .expect_block_hash(63290, headers[&63290].hash())
+        .expect_header(63291, &headers[&63291])
+        .expect_header(63292, &headers[&63292]);
+
+    let txs = execute_with_relay(bitcoind_mock, || {
+        let mut relayer = Relayer::<Test>::new().unwrap();
+        assert_ok!(relayer.relay_headers(&bitcoind()));
+        assert_eq!(relayer.submitted(), 2);
+    });
+
+    assert_eq!(txs.len(), 2);
+    for (nonce, (tx, height)) in txs.into_iter().zip([63291, 63292]).enumerate() {
+        assert_eq!(tx.signature, Some((nonce as u64, ())));
+        assert_eq!(tx.call, push_header_call(&headers[&height]));
+    }
+}
+
+#[test]
+fn test_relay_headers_rejects_invalid_header() {
+    let headers = generate_blocks_63290_63310();
+    let mut params = vec![b'['];
+    push_hash(&mut params, &headers[&63291].hash());
+    params.extend_from_slice(b",false]");
+    let bitcoind_mock = MockBitcoind::default()
+        .expect("getblockcount", b"[]", "63291")
+        .expect_block_hash(63290, headers[&63290].hash())
+        .expect_block_hash(63291, headers[&63291].hash())
+        // The header does not match the hash.
+        .expect(
+            "getblockheader",
+            &params,
+            &quoted_hex(&serialization::serialize(&headers[&63292])),
+        );
+
+    let txs = execute_with_relay(bitcoind_mock, || {
+        let mut relayer = Relayer::<Test>::new().unwrap();
+        assert_eq!(
+            relayer.relay_headers(&bitcoind()),
+            Err(RelayError::InvalidHeader)
+        );
+    });
+    assert!(txs.is_empty());
+}
+
+#[test]
+fn test_relay_transactions() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    // The deposit of `test_push_tx_call`, at height 63299.
+    let deposit: Transaction = "02000000000101aeee49e0bbf7a36f78ea4321b5c8bae0b8c72bdf2c024d2484b137fa7d0f8e1f01000000000000000003a0860100000000002251209a9ea267884f5549c206b2aec2bd56d98730f90532ea7f7154d4d4f923b7e3bb0000000000000000326a3035516a706f3772516e7751657479736167477a6334526a376f737758534c6d4d7141754332416255364c464646476a38801a060000000000225120c9929543dfa1e0bb84891acd47bfa6546b05e26b7a04af8eb6765fcc969d565f01409e325889515ed47099fdd7098e6fafdc880b21456d3f368457de923f4229286e34cef68816348a0581ae5885ede248a35ac4b09da61a7b9b90f34c200872d2e300000000".parse().unwrap();
+    let raw_proof = hex::decode("0a000000050a59b195a68a29037580798ca0414941eb46eaf7607db2d0da1ff89e9570ce455fea22ec1a3e3e7e1167fa220cc8376225f07bd20aa194e7f3c4ac68c7375d8e0a35e47541de7d0aa7312dabcf3bc9f06603e832427b8e4fe9a97a309f8cd7141687d11a3fd8f21e2105a52a3c36a17ea870e326ecddb23221d4cc0398b6c44bdcce3f191919a31f4cfaca5a786cc8315db76683ad6b8008f2ed9b348df76a0d022f00").unwrap();
+    let proof: PartialMerkleTree = serialization::deserialize(Reader::new(&raw_proof)).unwrap();
+    let headers = generate_blocks_63290_63310();
+    let header = headers[&63299];
+    let block = Block::new(header, vec![deposit.clone()]);
+
+    let mut block_params = vec![b'['];
+    push_hash(&mut block_params, &header.hash());
+    block_params.extend_from_slice(b",0]");
+    let mut prev_tx_params = vec![b'['];
+    push_hash(&mut prev_tx_params, &deposit.inputs[0].previous_output.hash);
+    prev_tx_params.extend_from_slice(b",false]");
+    let mut proof_params = b"[[".to_vec();
+    push_hash(&mut proof_params, &deposit.hash());
+    proof_params.extend_from_slice(b"],");
+    push_hash(&mut proof_params, &header.hash());
+    proof_params.push(b']');
+    let mut merkle_block: Vec<u8> = serialization::serialize(&header).into();
+    merkle_block.extend_from_slice(&raw_proof);
+
+    let bitcoind_mock = MockBitcoind::default()
+        .expect(
+            "getblock",
+            &block_params,
+            &quoted_hex(&serialization::serialize(&block)),
+        )
+        // bitcoind without `-txindex` can't find the previous tx.
+        .expect_error("getrawtransaction", &prev_tx_params)
+        .expect("gettxoutproof", &proof_params, &quoted_hex(&merkle_block));
+
+    let txs = execute_with_relay(bitcoind_mock, || {
+        let confirmed = XGatewayBitcoin::confirmation_number();
+        for i in 63291..=63299 + confirmed {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        ConfirmedIndex::<Test>::put(BtcHeaderIndex {
+            hash: header.hash(),
+            height: 63299,
+        });
+        let relayed_height = StorageValueRef::persistent(RELAYED_HEIGHT_KEY);
+        relayed_height.set(&63298u32);
+
+        let mut relayer = Relayer::<Test>::new().unwrap();
+        assert_ok!(relayer.relay_transactions(&bitcoind()));
+        assert_eq!(relayed_height.get::<u32>(), Ok(Some(63299)));
+    });
+
+    assert_eq!(txs.len(), 1);
+    assert_eq!(
+        txs[0].call,
+        Call::XGatewayBitcoin(crate::Call::push_transaction {
+            raw_tx: serialization::serialize(&deposit).into(),
+            relayed_info: BtcRelayedTxInfo {
+                block_hash: header.hash(),
+                merkle_proof: proof,
+            }
+            .encode(),
+            prev_tx: None,
+        })
+    );
+}
+
+#[test]
+fn test_offchain_worker_requires_bitcoind_url() {
+    let txs = execute_with_relay(MockBitcoind::default(), || {
+        relay::offchain_worker::<Test>(1);
+    });
+    assert!(txs.is_empty());
+
+    let headers = generate_blocks_63290_63310();
+    let bitcoind_mock = MockBitcoind::default()
+        .expect("getblockcount", b"[]", "63291")
+        .expect_block_hash(63290, headers[&63290].hash())
+        .expect_header(63291, &headers[&63291]);
+    let txs = execute_with_relay(bitcoind_mock, || {
+        sp_io::offchain::local_storage_set(
+            StorageKind::PERSISTENT,
+            BITCOIND_URL_KEY,
+            BITCOIND_URL.as_bytes(),
+        );
+        relay::offchain_worker::<Test>(1);
+    });
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].call, push_header_call(&headers[&63291]));
+}
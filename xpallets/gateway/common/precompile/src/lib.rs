@@ -0,0 +1,155 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Gateway withdrawal precompile, which allows the EVM holders of X-BTC to apply for
+//! withdrawals to the Bitcoin network directly.
+//!
+//! ```solidity
+//! interface IGatewayWithdrawal {
+//!     function withdraw(uint256 value, string calldata btcAddress) external returns (uint32);
+//!     function withdrawalLimit() external view returns (uint256 minimalWithdrawal, uint256 fee);
+//!     function pendingWithdrawalsOf(address applicant)
+//!         external view returns (uint32[] memory ids, uint256[] memory balances, uint8[] memory states);
+//! }
+//! ```
+//!
+//! The applicant of the withdrawal is the Substrate account mapped from the caller's address.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::{Context, ExitSucceed, PrecompileOutput, PrecompileResult};
+use frame_system::RawOrigin;
+use sp_core::{H160, U256};
+use sp_runtime::traits::{SaturatedConversion, UniqueSaturatedInto};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+use xp_protocol::X_BTC;
+use xp_runtime::Memo;
+use xpallet_assets::BalanceOf;
+use xpallet_assets_bridge::precompile::{
+    encode_u256, encode_uint_arrays, evm_to_sub_account, revert, revert_dispatch_error,
+    EvmDataReader, Gasometer,
+};
+use xpallet_gateway_common::{Pallet as XGatewayCommon, WeightInfo};
+use xpallet_gateway_records::Pallet as XGatewayRecords;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The function selectors, i.e. the first 4 bytes of keccak256(signature).
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum Action {
+    /// withdraw(uint256,string)
+    Withdraw = 0xdc048cf2,
+    /// withdrawalLimit()
+    WithdrawalLimit = 0x7ddfe78d,
+    /// pendingWithdrawalsOf(address)
+    PendingWithdrawalsOf = 0xa6500e11,
+}
+
+impl Action {
+    fn from_selector(selector: u32) -> Option<Self> {
+        [
+            Self::Withdraw,
+            Self::WithdrawalLimit,
+            Self::PendingWithdrawalsOf,
+        ]
+        .into_iter()
+        .find(|action| *action as u32 == selector)
+    }
+}
+
+/// Precompile of the X-BTC withdrawal in xpallet-gateway-common.
+pub struct WithdrawalPrecompile<T>(PhantomData<T>);
+
+impl<T> WithdrawalPrecompile<T>
+where
+    T: xpallet_gateway_common::Config + xpallet_assets_bridge::Config,
+{
+    pub fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        let (mut reader, selector) = EvmDataReader::new_with_selector(input)?;
+        let action = Action::from_selector(selector).ok_or_else(|| revert("unknown selector"))?;
+
+        let mut gasometer = Gasometer::<T>::new(target_gas);
+        let output = match action {
+            Action::Withdraw => {
+                if is_static {
+                    return Err(revert("cannot modify state in static context"));
+                }
+                if !context.apparent_value.is_zero() {
+                    return Err(revert("function is not payable"));
+                }
+                // Forbid the delegatecall and callcode, the calling contract could
+                // withdraw on behalf of its caller otherwise.
+                if context.address != address {
+                    return Err(revert("cannot be called with delegatecall or callcode"));
+                }
+                let value: BalanceOf<T> = reader.read_u128()?.saturated_into();
+                let addr = reader.read_bytes()?;
+                gasometer
+                    .record_weight(<T as xpallet_gateway_common::Config>::WeightInfo::withdraw())?;
+
+                let id = XGatewayRecords::<T>::id();
+                XGatewayCommon::<T>::withdraw(
+                    RawOrigin::Signed(evm_to_sub_account::<T>(context.caller)).into(),
+                    X_BTC,
+                    value,
+                    addr,
+                    Memo::default(),
+                )
+                .map_err(revert_dispatch_error)?;
+                encode_u256(U256::from(id))
+            }
+            Action::WithdrawalLimit => {
+                gasometer.record_db(2, 0)?;
+                let limit =
+                    XGatewayCommon::<T>::withdrawal_limit(&X_BTC).map_err(revert_dispatch_error)?;
+                let mut output = encode_u256(balance_to_u256::<T>(limit.minimal_withdrawal));
+                output.extend(encode_u256(balance_to_u256::<T>(limit.fee)));
+                output
+            }
+            Action::PendingWithdrawalsOf => {
+                let applicant = evm_to_sub_account::<T>(reader.read_address()?);
+                let (mut ids, mut balances, mut states) = (Vec::new(), Vec::new(), Vec::new());
+                let mut records = XGatewayRecords::<T>::pending_withdrawal_set();
+                loop {
+                    // Every pending withdrawal record is charged before it is read, so that
+                    // the scan stops as soon as the gas runs out.
+                    gasometer.record_db(1, 0)?;
+                    let (id, record) = match records.next() {
+                        Some(item) => item,
+                        None => break,
+                    };
+                    if record.applicant() != &applicant || record.asset_id() != X_BTC {
+                        continue;
+                    }
+                    gasometer.record_db(1, 0)?;
+                    let state = XGatewayRecords::<T>::state_of(id).unwrap_or_default();
+                    ids.push(U256::from(id));
+                    balances.push(balance_to_u256::<T>(record.balance()));
+                    states.push(U256::from(state as u8));
+                }
+                encode_uint_arrays(&[ids, balances, states])
+            }
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used(),
+            output,
+            logs: Vec::new(),
+        })
+    }
+}
+
+fn balance_to_u256<T: xpallet_assets::Config>(balance: BalanceOf<T>) -> U256 {
+    U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
@@ -0,0 +1,368 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use std::cell::RefCell;
+
+use frame_support::{
+    parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, GenesisBuild, LockIdentifier},
+    weights::RuntimeDbWeight,
+};
+use pallet_evm::AddressMapping;
+use sp_core::{H160, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    AccountId32, DispatchError, DispatchResult,
+};
+
+use xp_protocol::X_BTC;
+use xpallet_assets::{AssetInfo, AssetRestrictions, Chain, ChainT, WithdrawalLimit};
+use xpallet_assets_bridge::AssetId;
+use xpallet_gateway_common::{
+    traits::{TotalSupply, TrusteeForChain},
+    trustees::bitcoin::{BtcTrusteeAddrInfo, BtcTrusteeType},
+    types::{ScriptInfo, TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo},
+};
+use xpallet_support::traits::{
+    AccountRestriction, AssetPause, MultisigAddressFor, RestrictedAction, Validator,
+};
+
+pub(crate) type AccountId = AccountId32;
+pub(crate) type BlockNumber = u64;
+pub(crate) type Balance = u128;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Elections: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>},
+        Evm: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
+        XAssetsRegistrar: xpallet_assets_registrar::{Pallet, Call, Storage, Event<T>, Config},
+        XAssets: xpallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>},
+        XAssetsBridge: xpallet_assets_bridge::{Pallet, Call, Storage, Config<T>, Event<T>},
+        XGatewayRecords: xpallet_gateway_records::{Pallet, Call, Storage, Event<T>},
+        XGatewayCommon: xpallet_gateway_common::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 44;
+    pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight {
+        read: 1_000,
+        write: 10_000,
+    };
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = TestDbWeight;
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = ();
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type Event = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+}
+
+parameter_types! {
+    pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
+}
+
+impl pallet_elections_phragmen::Config for Test {
+    type PalletId = ElectionsPhragmenPalletId;
+    type Event = ();
+    type Currency = Balances;
+    type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
+    type ChangeMembers = ();
+    type InitializeMembers = ();
+    type CandidacyBond = ConstU128<3>;
+    type VotingBondBase = ConstU128<2>;
+    type VotingBondFactor = ConstU128<0>;
+    type TermDuration = ConstU64<5>;
+    type DesiredMembers = ConstU32<4>;
+    type DesiredRunnersUp = ConstU32<2>;
+    type LoserCandidate = ();
+    type KickedMember = ();
+    type WeightInfo = ();
+}
+
+impl pallet_evm::Config for Test {
+    type FeeCalculator = ();
+    type GasWeightMapping = ();
+    type CallOrigin = pallet_evm::EnsureAddressRoot<Self::AccountId>;
+    type WithdrawOrigin = pallet_evm::EnsureAddressNever<Self::AccountId>;
+    type AddressMapping = pallet_evm::HashedAddressMapping<BlakeTwo256>;
+    type Currency = Balances;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type Event = ();
+    type PrecompilesType = ();
+    type PrecompilesValue = ();
+    type ChainId = ();
+    type BlockGasLimit = ();
+    type OnChargeTransaction = ();
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type FindAuthor = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const ChainXAssetId: u32 = 0;
+}
+
+impl xpallet_assets_registrar::Config for Test {
+    type Event = ();
+    type NativeAssetId = ChainXAssetId;
+    type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
+    type WeightInfo = ();
+}
+
+thread_local! {
+    static PAUSED_ASSETS: RefCell<Vec<AssetId>> = RefCell::new(Vec::new());
+    static RESTRICTIONS: RefCell<Vec<(AccountId, RestrictedAction)>> = RefCell::new(Vec::new());
+}
+
+/// Restricts `who` from `action` in the tests.
+pub fn restrict(who: AccountId, action: RestrictedAction) {
+    RESTRICTIONS.with(|restrictions| restrictions.borrow_mut().push((who, action)));
+}
+
+pub struct MockRestriction;
+impl AccountRestriction<AccountId> for MockRestriction {
+    fn is_restricted(who: &AccountId, action: RestrictedAction) -> bool {
+        RESTRICTIONS.with(|restrictions| restrictions.borrow().contains(&(who.clone(), action)))
+    }
+}
+
+/// Pauses or unpauses the user operations moving the asset in the tests.
+pub fn set_asset_paused(id: AssetId, paused: bool) {
+    PAUSED_ASSETS.with(|assets| {
+        let mut assets = assets.borrow_mut();
+        assets.retain(|asset| *asset != id);
+        if paused {
+            assets.push(id);
+        }
+    });
+}
+
+pub struct MockAssetPause;
+impl AssetPause<AssetId> for MockAssetPause {
+    fn is_paused(asset_id: &AssetId) -> bool {
+        PAUSED_ASSETS.with(|assets| assets.borrow().contains(asset_id))
+    }
+}
+
+impl xpallet_assets::Config for Test {
+    type Event = ();
+    type Currency = Balances;
+    type TreasuryAccount = ();
+    type OnCreatedAccount = frame_system::Provider<Test>;
+    type OnAssetChanged = ();
+    type AccountRestriction = MockRestriction;
+    type AssetPause = MockAssetPause;
+    type MinVestedTransfer = ConstU128<1>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub EvmCaller: H160 = H160::from_slice(&[17u8; 20][..]);
+    pub ClaimBond: Balance = 2;
+}
+
+impl xpallet_assets_bridge::Config for Test {
+    type Event = ();
+    type EvmCaller = EvmCaller;
+    type ClaimBond = ClaimBond;
+    type PauserOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type BackForeignOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrationDelay = ();
+}
+
+impl xpallet_gateway_records::Config for Test {
+    type Event = ();
+    type WeightInfo = ();
+}
+
+/// The minimal withdrawal of X-BTC in the mock.
+pub const MINIMAL_WITHDRAWAL: Balance = 10;
+/// The withdrawal fee of X-BTC in the mock.
+pub const WITHDRAWAL_FEE: Balance = 2;
+
+/// Bitcoin without the relay, only the withdrawal related parts are mocked.
+pub struct MockBitcoin;
+impl ChainT<Balance> for MockBitcoin {
+    const ASSET_ID: u32 = X_BTC;
+
+    fn chain() -> Chain {
+        Chain::Bitcoin
+    }
+
+    fn check_addr(addr: &[u8], _: &[u8]) -> DispatchResult {
+        if addr.is_empty() {
+            return Err("InvalidAddress".into());
+        }
+        Ok(())
+    }
+
+    fn withdrawal_limit(_: &u32) -> Result<WithdrawalLimit<Balance>, DispatchError> {
+        Ok(WithdrawalLimit {
+            minimal_withdrawal: MINIMAL_WITHDRAWAL,
+            fee: WITHDRAWAL_FEE,
+        })
+    }
+}
+
+impl TotalSupply<Balance> for MockBitcoin {
+    fn total_supply() -> Balance {
+        Default::default()
+    }
+}
+
+impl TrusteeForChain<AccountId, BlockNumber, BtcTrusteeType, BtcTrusteeAddrInfo> for MockBitcoin {
+    fn check_trustee_entity(_: &[u8]) -> Result<BtcTrusteeType, DispatchError> {
+        Err("NoTrustee".into())
+    }
+
+    fn generate_trustee_session_info(
+        _: Vec<(AccountId, TrusteeIntentionProps<AccountId, BtcTrusteeType>)>,
+        _: TrusteeInfoConfig,
+    ) -> Result<
+        (
+            TrusteeSessionInfo<AccountId, BlockNumber, BtcTrusteeAddrInfo>,
+            ScriptInfo<AccountId>,
+        ),
+        DispatchError,
+    > {
+        Err("NoTrustee".into())
+    }
+}
+
+pub struct MultisigAddr;
+impl MultisigAddressFor<AccountId> for MultisigAddr {
+    fn calc_multisig(_: &[AccountId], _: u16) -> AccountId {
+        [0u8; 32].into()
+    }
+}
+
+pub struct AlwaysValidator;
+impl Validator<AccountId> for AlwaysValidator {
+    fn is_validator(_: &AccountId) -> bool {
+        true
+    }
+
+    fn validator_for(_: &[u8]) -> Option<AccountId> {
+        None
+    }
+}
+
+impl xpallet_gateway_common::Config for Test {
+    type Event = ();
+    type Validator = AlwaysValidator;
+    type DetermineMultisigAddress = MultisigAddr;
+    type CouncilOrigin = frame_system::EnsureRoot<AccountId>;
+    type Bitcoin = MockBitcoin;
+    type BitcoinTrustee = MockBitcoin;
+    type BitcoinTrusteeSessionProvider = ();
+    type BitcoinTotalSupply = MockBitcoin;
+    type BitcoinWithdrawalProposal = ();
+    type WeightInfo = ();
+}
+
+/// The EVM address of the X-BTC holder.
+pub const HOLDER: [u8; 20] = [2u8; 20];
+/// The X-BTC balance of the holder.
+pub const HOLDER_BALANCE: Balance = 1_000;
+
+/// Returns the Substrate account mapped from the EVM `address`.
+pub fn mapped_account(address: H160) -> AccountId {
+    <Test as pallet_evm::Config>::AddressMapping::into_account_id(address)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    let btc = AssetInfo::new::<Test>(
+        b"X-BTC".to_vec(),
+        b"X-BTC".to_vec(),
+        Chain::Bitcoin,
+        8,
+        b"ChainX's cross-chain Bitcoin".to_vec(),
+    )
+    .unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &xpallet_assets_registrar::GenesisConfig {
+            assets: vec![(X_BTC, btc, true, true)],
+        },
+        &mut t,
+    )
+    .unwrap();
+
+    xpallet_assets::GenesisConfig::<Test> {
+        assets_restrictions: vec![(X_BTC, AssetRestrictions::DESTROY_USABLE)],
+        endowed: vec![(X_BTC, vec![(mapped_account(H160(HOLDER)), HOLDER_BALANCE)])]
+            .into_iter()
+            .collect(),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,332 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use ethabi::Token;
+use fp_evm::{Context, PrecompileFailure, PrecompileOutput};
+use frame_support::{assert_noop, traits::Get};
+use sp_core::{H160, U256};
+use sp_io::hashing::keccak_256;
+
+use xp_protocol::X_BTC;
+use xpallet_assets::{AssetType, Pallet as XAssets};
+use xpallet_assets_bridge::precompile::encode_u256;
+use xpallet_gateway_common::Pallet as XGatewayCommon;
+use xpallet_gateway_common::WeightInfo;
+use xpallet_gateway_records::{Pallet as XGatewayRecords, WithdrawalState};
+use xpallet_support::traits::RestrictedAction;
+
+use crate::{mock::*, Action, WithdrawalPrecompile};
+
+fn precompile_address() -> H160 {
+    H160::from_low_u64_be(2050)
+}
+
+fn call(
+    caller: H160,
+    input: Vec<u8>,
+    target_gas: Option<u64>,
+    is_static: bool,
+) -> Result<PrecompileOutput, PrecompileFailure> {
+    let address = precompile_address();
+    let context = Context {
+        address,
+        caller,
+        apparent_value: U256::zero(),
+    };
+    WithdrawalPrecompile::<Test>::execute(address, &input, target_gas, &context, is_static)
+}
+
+fn input(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut input = keccak_256(signature.as_bytes())[..4].to_vec();
+    input.extend(ethabi::encode(tokens));
+    input
+}
+
+fn withdraw_input(value: u128, addr: &str) -> Vec<u8> {
+    input(
+        "withdraw(uint256,string)",
+        &[Token::Uint(U256::from(value)), Token::String(addr.into())],
+    )
+}
+
+fn pending_withdrawals_of(applicant: H160) -> Vec<u8> {
+    input(
+        "pendingWithdrawalsOf(address)",
+        &[Token::Address(applicant)],
+    )
+}
+
+fn uint_array(values: &[u128]) -> Token {
+    Token::Array(
+        values
+            .iter()
+            .map(|value| Token::Uint(U256::from(*value)))
+            .collect(),
+    )
+}
+
+/// Reads and writes are the only gas the views cost in the mock.
+fn db_gas(reads: u64) -> u64 {
+    TestDbWeight::get().reads(reads)
+}
+
+#[test]
+fn selectors_should_match_signatures() {
+    for (action, signature) in [
+        (Action::Withdraw, "withdraw(uint256,string)"),
+        (Action::WithdrawalLimit, "withdrawalLimit()"),
+        (
+            Action::PendingWithdrawalsOf,
+            "pendingWithdrawalsOf(address)",
+        ),
+    ] {
+        let selector = keccak_256(signature.as_bytes());
+        assert_eq!(
+            action as u32,
+            u32::from_be_bytes([selector[0], selector[1], selector[2], selector[3]]),
+            "{}",
+            signature
+        );
+        assert!(Action::from_selector(action as u32) == Some(action));
+    }
+    assert!(Action::from_selector(0xdeadbeef).is_none());
+}
+
+#[test]
+fn withdraw_should_work() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        let applicant = mapped_account(caller);
+
+        let output = call(caller, withdraw_input(100, "btc address"), None, false).unwrap();
+        assert_eq!(output.output, encode_u256(U256::zero()));
+        assert_eq!(output.cost, <() as WeightInfo>::withdraw());
+        assert_eq!(
+            XAssets::<Test>::asset_balance_of(&applicant, &X_BTC, AssetType::Usable),
+            HOLDER_BALANCE - 100
+        );
+        assert_eq!(
+            XAssets::<Test>::asset_balance_of(&applicant, &X_BTC, AssetType::ReservedWithdrawal),
+            100
+        );
+
+        let output = call(caller, pending_withdrawals_of(caller), None, true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[uint_array(&[0]), uint_array(&[100]), uint_array(&[0])])
+        );
+
+        let output = call(caller, withdraw_input(50, "btc address"), None, false).unwrap();
+        assert_eq!(output.output, encode_u256(U256::one()));
+    })
+}
+
+#[test]
+fn withdrawal_limit_should_work() {
+    new_test_ext().execute_with(|| {
+        let limit = input("withdrawalLimit()", &[]);
+        let output = call(H160(HOLDER), limit, None, true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[
+                Token::Uint(U256::from(MINIMAL_WITHDRAWAL)),
+                Token::Uint(U256::from(WITHDRAWAL_FEE)),
+            ])
+        );
+        assert_eq!(output.cost, db_gas(2));
+    })
+}
+
+#[test]
+fn invalid_withdrawals_should_revert() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        // less than the minimal withdrawal
+        let below_minimal = withdraw_input(MINIMAL_WITHDRAWAL - 1, "btc address");
+        assert!(call(caller, below_minimal, None, false).is_err());
+        // invalid address
+        assert!(call(caller, withdraw_input(100, ""), None, false).is_err());
+        // more than the usable balance
+        let too_much = withdraw_input(HOLDER_BALANCE + 1, "btc address");
+        assert!(call(caller, too_much, None, false).is_err());
+        // no X-BTC at all
+        let stranger = H160([3u8; 20]);
+        assert!(call(stranger, withdraw_input(100, "btc address"), None, false).is_err());
+
+        assert_eq!(XGatewayRecords::<Test>::id(), 0);
+        assert!(call(caller, vec![0xde, 0xad, 0xbe, 0xef], None, false).is_err());
+    })
+}
+
+#[test]
+fn pending_withdrawals_should_charge_per_record() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        let output = call(caller, pending_withdrawals_of(caller), None, true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[uint_array(&[]), uint_array(&[]), uint_array(&[])])
+        );
+        // only the end of the empty set is read
+        assert_eq!(output.cost, db_gas(1));
+
+        for value in [10, 20, 30] {
+            call(caller, withdraw_input(value, "btc address"), None, false).unwrap();
+        }
+        // the withdrawal of another applicant is scanned, but not returned
+        let other = mapped_account(H160([3u8; 20]));
+        XAssets::<Test>::issue(&X_BTC, &other, 100).unwrap();
+        XGatewayRecords::<Test>::withdraw(
+            &other,
+            X_BTC,
+            40,
+            b"btc address".to_vec(),
+            vec![].into(),
+        )
+        .unwrap();
+        XGatewayRecords::<Test>::withdrawal_state_insert(1, WithdrawalState::Processing);
+
+        let output = call(caller, pending_withdrawals_of(caller), None, true).unwrap();
+        let uint_array_type = || ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(256)));
+        let arrays = ethabi::decode(
+            &[uint_array_type(), uint_array_type(), uint_array_type()],
+            &output.output,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|array| {
+            array
+                .into_array()
+                .unwrap()
+                .into_iter()
+                .map(|value| value.into_uint().unwrap().as_u128())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+        // the order of the storage iteration is not the order of the ids
+        let mut records = (0..arrays[0].len())
+            .map(|i| (arrays[0][i], arrays[1][i], arrays[2][i]))
+            .collect::<Vec<_>>();
+        records.sort_unstable();
+        assert_eq!(records, vec![(0, 10, 0), (1, 20, 1), (2, 30, 0)]);
+        // 4 records, 3 states of the caller's records and the end of the set
+        let cost = db_gas(4 + 3 + 1);
+        assert_eq!(output.cost, cost);
+
+        // the scan stops as soon as the gas runs out
+        assert!(matches!(
+            call(caller, pending_withdrawals_of(caller), Some(cost - 1), true),
+            Err(PrecompileFailure::Error { .. })
+        ));
+        assert!(matches!(
+            call(
+                caller,
+                pending_withdrawals_of(caller),
+                Some(db_gas(1)),
+                true
+            ),
+            Err(PrecompileFailure::Error { .. })
+        ));
+    })
+}
+
+#[test]
+fn withdraw_should_be_guarded() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        let withdraw = withdraw_input(100, "btc address");
+        // static call
+        assert!(call(caller, withdraw.clone(), None, true).is_err());
+
+        // delegatecall
+        let context = Context {
+            address: H160::from_low_u64_be(42),
+            caller,
+            apparent_value: U256::zero(),
+        };
+        assert!(WithdrawalPrecompile::<Test>::execute(
+            precompile_address(),
+            &withdraw,
+            None,
+            &context,
+            false
+        )
+        .is_err());
+
+        // payable
+        let context = Context {
+            address: precompile_address(),
+            caller,
+            apparent_value: U256::one(),
+        };
+        assert!(WithdrawalPrecompile::<Test>::execute(
+            precompile_address(),
+            &withdraw,
+            None,
+            &context,
+            false
+        )
+        .is_err());
+
+        // out of gas
+        assert!(matches!(
+            call(
+                caller,
+                withdraw,
+                Some(<() as WeightInfo>::withdraw() - 1),
+                false
+            ),
+            Err(PrecompileFailure::Error { .. })
+        ));
+
+        assert_eq!(XGatewayRecords::<Test>::id(), 0);
+        assert_eq!(
+            XAssets::<Test>::asset_balance_of(&mapped_account(caller), &X_BTC, AssetType::Usable),
+            HOLDER_BALANCE
+        );
+    })
+}
+
+#[test]
+fn withdraw_should_be_paused_with_the_asset() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        set_asset_paused(X_BTC, true);
+        assert!(call(caller, withdraw_input(100, "btc address"), None, false).is_err());
+        assert_eq!(XGatewayRecords::<Test>::id(), 0);
+
+        set_asset_paused(X_BTC, false);
+        call(caller, withdraw_input(100, "btc address"), None, false).unwrap();
+        assert_eq!(XGatewayRecords::<Test>::id(), 1);
+    })
+}
+
+#[test]
+fn withdraw_should_be_restricted() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(HOLDER);
+        let applicant = mapped_account(caller);
+
+        // moving the assets is not withdrawing them
+        restrict(applicant.clone(), RestrictedAction::MoveAsset);
+        call(caller, withdraw_input(100, "btc address"), None, false).unwrap();
+        assert_eq!(XGatewayRecords::<Test>::id(), 1);
+
+        restrict(applicant.clone(), RestrictedAction::Withdraw);
+        assert!(call(caller, withdraw_input(100, "btc address"), None, false).is_err());
+        assert_noop!(
+            XGatewayCommon::<Test>::withdraw(
+                Origin::signed(applicant.clone()),
+                X_BTC,
+                100,
+                b"btc address".to_vec(),
+                Default::default()
+            ),
+            xpallet_assets::Error::<Test>::AccountRestricted
+        );
+        assert_eq!(XGatewayRecords::<Test>::id(), 1);
+        assert_eq!(
+            XAssets::<Test>::asset_balance_of(&applicant, &X_BTC, AssetType::Usable),
+            HOLDER_BALANCE - 100
+        );
+    })
+}
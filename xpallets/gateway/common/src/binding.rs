@@ -157,6 +157,12 @@ impl<T: Config> Pallet<T> {
         BoundAddressOf::<T>::iter_prefix(&who).collect()
     }
 
+    /// Restore the binding of the address, used by the regenesis.
+    #[cfg(feature = "std")]
+    pub fn force_bind_address(chain: Chain, address: ChainAddress, who: T::AccountId) {
+        Self::update_wasm_binding(chain, address, who)
+    }
+
     fn update_wasm_binding<Address>(chain: Chain, address: Address, who: T::AccountId)
     where
         Address: Into<Vec<u8>>,
@@ -48,7 +48,9 @@ use xp_runtime::Memo;
 /// ChainX pallets
 use xpallet_assets::{AssetRestrictions, BalanceOf, Chain, ChainT, WithdrawalLimit};
 use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
-use xpallet_support::traits::{MultisigAddressFor, Validator};
+use xpallet_support::traits::{
+    AccountRestriction, MultisigAddressFor, RestrictedAction, Validator,
+};
 
 use self::{
     traits::{ProposalProvider, TotalSupply, TrusteeForChain, TrusteeInfoUpdate, TrusteeSession},
@@ -141,6 +143,14 @@ pub mod pallet {
                 xpallet_assets::Pallet::<T>::can_do(&asset_id, AssetRestrictions::WITHDRAW),
                 xpallet_assets::Error::<T>::ActionNotAllowed,
             );
+            xpallet_assets::Pallet::<T>::ensure_not_paused(&asset_id)?;
+            ensure!(
+                !<T as xpallet_assets::Config>::AccountRestriction::is_restricted(
+                    &who,
+                    RestrictedAction::Withdraw
+                ),
+                xpallet_assets::Error::<T>::AccountRestricted,
+            );
             Self::verify_withdrawal(asset_id, value, &addr, &ext)?;
 
             xpallet_gateway_records::Pallet::<T>::withdraw(&who, asset_id, value, addr, ext)?;
@@ -1206,6 +1216,24 @@ impl<T: Config> Pallet<T> {
     pub fn trustee_multisigs() -> BTreeMap<Chain, T::AccountId> {
         TrusteeMultiSigAddr::<T>::iter().collect()
     }
+
+    /// Restore the trustee session, used by the regenesis.
+    ///
+    /// The latest session restored becomes the current one of the chain.
+    #[cfg(feature = "std")]
+    pub fn force_set_trustee_session(
+        chain: Chain,
+        session_number: u32,
+        session_info: GenericTrusteeSessionInfo<T::AccountId, T::BlockNumber>,
+    ) {
+        if session_number >= Self::trustee_session_info_len(chain) {
+            TrusteeSessionInfoLen::<T>::insert(chain, session_number);
+            if let Some(multi_account) = &session_info.0.multi_account {
+                TrusteeMultiSigAddr::<T>::insert(chain, multi_account);
+            }
+        }
+        TrusteeSessionInfoOf::<T>::insert(chain, session_number, session_info);
+    }
 }
 
 /// Trustee rewards
@@ -18,9 +18,11 @@ use sp_core::{H160, H256};
 use sp_io::hashing::blake2_256;
 use sp_keyring::sr25519;
 use sp_runtime::{
-    testing::Header,
-    traits::{AccountIdConversion, BlakeTwo256, IdentityLookup, Saturating},
-    AccountId32, DispatchError, DispatchResult,
+    testing::{Header, TestXt},
+    traits::{
+        AccountIdConversion, BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup, Saturating,
+    },
+    AccountId32, DispatchError, DispatchResult, MultiSignature, MultiSigner,
 };
 
 use crate::traits::TotalSupply;
@@ -211,6 +213,11 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = ();
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -267,6 +274,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = ();
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -274,6 +284,10 @@ impl xpallet_assets_bridge::Config for Test {
     type Event = ();
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
+    type PauserOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type BackForeignOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrationDelay = ();
 }
 
 // assets
@@ -313,9 +327,39 @@ impl xpallet_gateway_bitcoin::Config for Test {
     type TrusteeInfoUpdate = ();
     type ReferralBinding = ();
     type AddressBinding = ();
+    type AuthorityId = xpallet_gateway_bitcoin::relay::crypto::RelayerAuthId;
     type WeightInfo = ();
 }
 
+type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = MultiSigner;
+    type Signature = MultiSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: MultiSigner,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (nonce, ())))
+    }
+}
+
 pub struct MultisigAddr;
 impl MultisigAddressFor<AccountId> for MultisigAddr {
     fn calc_multisig(who: &[AccountId], threshold: u16) -> AccountId {
@@ -2,7 +2,7 @@ use frame_system::RawOrigin;
 
 use crate::{
     mock::{bob, charlie, dave, ExtBuilder, Test, XAssets, XGatewayCommon, XGatewayRecords},
-    Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
+    AddressBindingOf, Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
 };
 use frame_support::assert_ok;
 use xp_assets_registrar::Chain;
@@ -83,3 +83,37 @@ fn test_claim_not_native_asset_reward() {
         assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 1);
     });
 }
+
+#[test]
+fn test_force_restore_trustee_session_and_binding() {
+    let session_info = ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        TrusteeSessionInfoOf::<Test>::get(Chain::Bitcoin, 1).unwrap()
+    });
+    let multi_account = session_info.0.multi_account.clone().unwrap();
+
+    ExtBuilder::default().build().execute_with(|| {
+        XGatewayCommon::force_set_trustee_session(Chain::Bitcoin, 0, session_info.clone());
+        XGatewayCommon::force_set_trustee_session(Chain::Bitcoin, 1, session_info.clone());
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+        assert_eq!(
+            TrusteeSessionInfoOf::<Test>::get(Chain::Bitcoin, 1),
+            Some(session_info)
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_multisigs().get(&Chain::Bitcoin),
+            Some(&multi_account)
+        );
+
+        let address = b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec();
+        XGatewayCommon::force_bind_address(Chain::Bitcoin, address.clone(), bob());
+        assert_eq!(
+            AddressBindingOf::<Test>::get(Chain::Bitcoin, &address),
+            Some(bob())
+        );
+        assert_eq!(
+            XGatewayCommon::bound_addrs(&bob()).get(&Chain::Bitcoin),
+            Some(&vec![address])
+        );
+    })
+}
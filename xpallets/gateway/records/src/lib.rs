@@ -193,12 +193,7 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetId,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        let available = xpallet_assets::Pallet::<T>::usable_balance(who, &asset_id);
-        ensure!(
-            available >= value,
-            xpallet_assets::Error::<T>::InsufficientBalance
-        );
-        Ok(())
+        xpallet_assets::Pallet::<T>::ensure_can_withdraw(who, &asset_id, value)
     }
 
     fn ensure_withdrawal_records_exists(
@@ -578,4 +573,20 @@ impl<T: Config> Pallet<T> {
     pub fn withdrawal_state_insert(id: WithdrawalRecordId, state: WithdrawalState) {
         WithdrawalStateOf::<T>::insert(id, state)
     }
+
+    /// Restore a pending withdrawal and lock its balance again, used by the regenesis.
+    #[cfg(feature = "std")]
+    pub fn force_restore_withdrawal(
+        id: WithdrawalRecordId,
+        record: WithdrawalRecordOf<T>,
+        state: WithdrawalState,
+    ) -> DispatchResult {
+        Self::lock(record.applicant(), record.asset_id(), record.balance())?;
+        PendingWithdrawals::<T>::insert(id, record);
+        WithdrawalStateOf::<T>::insert(id, state);
+        if id >= Self::id() {
+            NextWithdrawalRecordId::<T>::put(id.saturating_add(1));
+        }
+        Ok(())
+    }
 }
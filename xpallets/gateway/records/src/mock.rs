@@ -94,6 +94,11 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = ();
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -103,6 +108,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AccountRestriction = ();
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -206,3 +206,46 @@ fn test_withdrawal_chainx() {
         );
     })
 }
+
+#[test]
+fn test_force_restore_withdrawal() {
+    let (record, state) = ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_BTC,
+            50,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::process_withdrawal(0, Chain::Bitcoin));
+        (
+            XGatewayRecords::pending_withdrawals(0).unwrap(),
+            XGatewayRecords::state_of(0).unwrap(),
+        )
+    });
+
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::force_restore_withdrawal(
+            3,
+            record.clone(),
+            state
+        ));
+        assert_eq!(XGatewayRecords::pending_withdrawals(3), Some(record));
+        assert_eq!(
+            XGatewayRecords::state_of(3),
+            Some(WithdrawalState::Processing)
+        );
+        assert_eq!(XGatewayRecords::id(), 4);
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 50);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::ReservedWithdrawal),
+            50
+        );
+
+        assert_ok!(XGatewayRecords::finish_withdrawal(3, None));
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+    })
+}
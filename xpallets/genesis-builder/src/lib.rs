@@ -33,6 +33,8 @@ pub mod pallet {
     pub trait Config:
         frame_system::Config
         + pallet_balances::Config
+        + xpallet_gateway_bitcoin::Config
+        + xpallet_gateway_common::Config
         + xpallet_mining_asset::Config
         + xpallet_mining_staking::Config
     {
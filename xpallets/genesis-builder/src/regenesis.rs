@@ -88,15 +88,118 @@ pub(crate) mod xstaking {
     }
 }
 
+pub(crate) mod xgateway {
+    use xp_genesis_builder::{
+        AddressBindingInfo, BtcDepositInfo, BtcPendingDepositInfo, TrusteeSessionParams,
+        WithdrawalInfo, XGatewayParams,
+    };
+    use xpallet_gateway_bitcoin::BtcDepositCache;
+    use xpallet_gateway_common::types::{GenericTrusteeSessionInfo, TrusteeSessionInfo};
+    use xpallet_gateway_records::{WithdrawalRecord, WithdrawalState};
+
+    use super::*;
+    use crate::AssetBalanceOf;
+
+    // Restore the pending withdrawals, trustee sessions, address bindings and unclaimed deposits.
+    pub fn initialize<T: Config>(params: &XGatewayParams<T::AccountId, AssetBalanceOf<T>>) {
+        let current_block = frame_system::Pallet::<T>::block_number();
+
+        for WithdrawalInfo {
+            id,
+            asset_id,
+            applicant,
+            balance,
+            addr,
+            ext,
+            processing,
+        } in &params.pending_withdrawals
+        {
+            let record = WithdrawalRecord::new(
+                applicant.clone(),
+                *asset_id,
+                *balance,
+                addr.clone(),
+                ext.clone().into(),
+                current_block,
+            );
+            let state = if *processing {
+                WithdrawalState::Processing
+            } else {
+                WithdrawalState::Applying
+            };
+            xpallet_gateway_records::Pallet::<T>::force_restore_withdrawal(*id, record, state)
+                .expect("the withdrawal balance is included in the free balance; qed");
+        }
+
+        for TrusteeSessionParams {
+            chain,
+            session_number,
+            trustee_list,
+            threshold,
+            hot_address,
+            cold_address,
+            multi_account,
+        } in &params.trustee_sessions
+        {
+            let session_info = GenericTrusteeSessionInfo(TrusteeSessionInfo {
+                trustee_list: trustee_list.clone(),
+                threshold: *threshold,
+                hot_address: hot_address.clone(),
+                cold_address: cold_address.clone(),
+                multi_account: multi_account.clone(),
+                start_height: None,
+                end_height: None,
+            });
+            xpallet_gateway_common::Pallet::<T>::force_set_trustee_session(
+                *chain,
+                *session_number,
+                session_info,
+            );
+        }
+
+        for AddressBindingInfo {
+            chain,
+            address,
+            who,
+        } in &params.address_bindings
+        {
+            xpallet_gateway_common::Pallet::<T>::force_bind_address(
+                *chain,
+                address.clone(),
+                who.clone(),
+            );
+        }
+
+        for BtcPendingDepositInfo { address, deposits } in &params.btc_pending_deposits {
+            let deposits = deposits
+                .iter()
+                .map(|BtcDepositInfo { txid, balance }| BtcDepositCache {
+                    txid: *txid,
+                    balance: *balance,
+                })
+                .collect();
+            xpallet_gateway_bitcoin::Pallet::<T>::force_set_pending_deposits(
+                address.clone(),
+                deposits,
+            );
+        }
+    }
+}
+
 pub(crate) mod xmining_asset {
-    use xp_genesis_builder::FreeBalanceInfo;
+    use xp_genesis_builder::{
+        AssetLedgerInfo, FreeBalanceInfo, MinerLedgerInfo, XMiningAssetParams,
+    };
     use xp_protocol::X_BTC;
 
     use super::*;
     use crate::AssetBalanceOf;
 
-    // Set the weight related to zero.
-    pub fn initialize<T: Config>(xbtc_assets: &[FreeBalanceInfo<T::AccountId, AssetBalanceOf<T>>]) {
+    // Set the weight related to zero, then carry the settled weights over.
+    pub fn initialize<T: Config>(
+        xbtc_assets: &[FreeBalanceInfo<T::AccountId, AssetBalanceOf<T>>],
+        params: &XMiningAssetParams<T::AccountId>,
+    ) {
         let current_block = frame_system::Pallet::<T>::block_number();
 
         for FreeBalanceInfo { who, .. } in xbtc_assets {
@@ -113,6 +216,32 @@ pub(crate) mod xmining_asset {
             Default::default(),
             current_block,
         );
+
+        for MinerLedgerInfo {
+            miner,
+            asset_id,
+            mining_weight,
+        } in &params.miner_ledgers
+        {
+            xpallet_mining_asset::Pallet::<T>::force_set_miner_mining_weight(
+                miner,
+                asset_id,
+                *mining_weight,
+                current_block,
+            );
+        }
+
+        for AssetLedgerInfo {
+            asset_id,
+            total_mining_weight,
+        } in &params.asset_ledgers
+        {
+            xpallet_mining_asset::Pallet::<T>::force_set_asset_mining_weight(
+                asset_id,
+                *total_mining_weight,
+                current_block,
+            );
+        }
     }
 }
 
@@ -121,8 +250,9 @@ pub(crate) fn initialize<T: Config>(config: &GenesisConfig<T>) {
 
     balances::initialize::<T>(&config.params.balances);
     xassets::initialize::<T>(&config.params.xassets);
+    xgateway::initialize::<T>(&config.params.xgateway);
     xstaking::initialize::<T>(&config.params.xstaking, &config.initial_authorities);
-    xmining_asset::initialize::<T>(&config.params.xassets);
+    xmining_asset::initialize::<T>(&config.params.xassets, &config.params.xmining_asset);
 
     frame_support::log::info!(
         "Took {:?}ms to orchestrate the regenesis state",
@@ -11,11 +11,13 @@ use codec::Codec;
 
 pub use chainx_primitives::AssetId;
 pub use xpallet_mining_asset::{
-    AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendInfo, MiningWeight,
+    AssetBalanceChange, AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendEstimate,
+    MiningDividendInfo, MiningWeight,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query mining asset info.
+    #[api_version(2)]
     pub trait XMiningAssetApi<AccountId, Balance, MiningWeight, BlockNumber>
     where
         AccountId: Codec,
@@ -31,5 +33,13 @@ sp_api::decl_runtime_apis! {
 
         /// Get the mining ledger details given the asset miner AccountId.
         fn miner_ledger(who: AccountId) -> BTreeMap<AssetId, MinerLedger<MiningWeight, BlockNumber>>;
+
+        /// Get the projected asset mining dividend of `who` at `horizon` blocks later, with and without `change`.
+        fn simulate_mining_dividend(
+            who: AccountId,
+            asset_id: AssetId,
+            change: AssetBalanceChange<Balance>,
+            horizon: BlockNumber,
+        ) -> MiningDividendEstimate<Balance, BlockNumber>;
     }
 }
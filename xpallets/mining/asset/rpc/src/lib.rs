@@ -17,8 +17,8 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcMiningWeight};
 
 use xpallet_mining_asset_rpc_runtime_api::{
-    AssetId, AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendInfo,
-    XMiningAssetApi as XMiningAssetRuntimeApi,
+    AssetBalanceChange, AssetId, AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendEstimate,
+    MiningDividendInfo, XMiningAssetApi as XMiningAssetRuntimeApi,
 };
 
 /// XMiningAsset RPC methods.
@@ -59,6 +59,17 @@ where
         who: AccountId,
         at: Option<BlockHash>,
     ) -> Result<BTreeMap<AssetId, MinerLedger<RpcMiningWeight<MiningWeight>, BlockNumber>>>;
+
+    /// Get the projected asset mining dividend at `horizon` blocks later, with and without `change`.
+    #[rpc(name = "xminingasset_simulateDividend")]
+    fn simulate_mining_dividend(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        change: AssetBalanceChange<RpcBalance<Balance>>,
+        horizon: BlockNumber,
+        at: Option<BlockHash>,
+    ) -> Result<MiningDividendEstimate<RpcBalance<Balance>, BlockNumber>>;
 }
 
 /// A struct that implements the [`XMiningAssetApi`].
@@ -179,4 +190,26 @@ where
             })
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn simulate_mining_dividend(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        change: AssetBalanceChange<RpcBalance<Balance>>,
+        horizon: BlockNumber,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<MiningDividendEstimate<RpcBalance<Balance>, BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let change = change.map_balance(|value| value.into_inner());
+        api.simulate_mining_dividend(&at, who, asset_id, change, horizon)
+            .map(|estimate| MiningDividendEstimate {
+                current: estimate.current.into(),
+                projected: estimate.projected.into(),
+                simulated: estimate.simulated.into(),
+                claimable_from: estimate.claimable_from,
+                insufficient_stake: estimate.insufficient_stake.into(),
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
@@ -245,12 +245,19 @@ pub mod pallet {
     pub trait StakingInterface<AccountId, Balance> {
         /// Returns the amount of `who`s locked balances in Staking.
         fn staked_of(who: &AccountId) -> Balance;
+
+        /// Returns the estimated reward issued to the reward pot of `asset_id` in the next `blocks`.
+        fn estimated_mining_reward(asset_id: AssetId, blocks: u32) -> Balance;
     }
 
     impl<AccountId, Balance: Default> StakingInterface<AccountId, Balance> for () {
         fn staked_of(_: &AccountId) -> Balance {
             Default::default()
         }
+
+        fn estimated_mining_reward(_: AssetId, _: u32) -> Balance {
+            Default::default()
+        }
     }
 
     impl<T: Config> StakingInterface<<T as frame_system::Config>::AccountId, u128> for T
@@ -260,6 +267,14 @@ pub mod pallet {
         fn staked_of(who: &<T as frame_system::Config>::AccountId) -> u128 {
             xpallet_mining_staking::Pallet::<T>::staked_of(who).saturated_into()
         }
+
+        fn estimated_mining_reward(asset_id: AssetId, blocks: u32) -> u128 {
+            xpallet_mining_staking::Pallet::<T>::estimated_asset_mining_reward(
+                asset_id,
+                blocks.saturated_into(),
+            )
+            .saturated_into()
+        }
     }
 
     pub trait GatewayInterface<AccountId> {
@@ -123,6 +123,11 @@ impl xpallet_assets_registrar::Config for Test {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = XMiningAsset;
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
     type WeightInfo = ();
 }
 
@@ -132,6 +137,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = XMiningAsset;
+    type AccountRestriction = ();
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
     type WeightInfo = ();
 }
 
@@ -258,6 +266,7 @@ impl xpallet_mining_staking::Config for Test {
     type TreasuryAccount = DummyTreasuryAccount;
     type DetermineRewardPotAccount = DummyStakingRewardPotAccountDeterminer;
     type ValidatorRegistration = Registration;
+    type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
@@ -6,14 +6,17 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::{RuntimeDebug, SaturatedConversion};
+use sp_runtime::{
+    traits::{One, Saturating, Zero},
+    RuntimeDebug, SaturatedConversion,
+};
 
 use chainx_primitives::AssetId;
-use xp_mining_common::RewardPotAccountFor;
+use xp_mining_common::{Delta, RewardPotAccountFor, SimulatedMiningWeight};
 
 use crate::{
     types::*, AssetLedgers, BalanceOf, ClaimRestrictionOf, Config, FixedAssetPowerOf, MinerLedgers,
-    MiningPrevilegedAssets, Pallet,
+    MiningPrevilegedAssets, Pallet, StakingInterface,
 };
 
 /// Mining asset info.
@@ -42,6 +45,45 @@ pub struct MiningDividendInfo<Balance> {
     pub insufficient_stake: Balance,
 }
 
+/// A hypothetical change of the asset balance of a miner, used for the dividend simulation.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum AssetBalanceChange<Balance> {
+    /// Deposit some balance, the total issuance of the asset increases as well.
+    Deposit(Balance),
+    /// Withdraw some balance, the total issuance of the asset decreases as well.
+    Withdraw(Balance),
+}
+
+impl<Balance> AssetBalanceChange<Balance> {
+    /// Converts the balance type of this change, e.g., from the RPC balance.
+    pub fn map_balance<B, F: Fn(Balance) -> B>(self, f: F) -> AssetBalanceChange<B> {
+        match self {
+            Self::Deposit(value) => AssetBalanceChange::Deposit(f(value)),
+            Self::Withdraw(value) => AssetBalanceChange::Withdraw(f(value)),
+        }
+    }
+}
+
+/// Estimated asset mining dividend of a miner, including the 10% cut for the referral.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MiningDividendEstimate<Balance, BlockNumber> {
+    /// Dividend that can be claimed right now.
+    pub current: Balance,
+    /// Projected dividend at the end of the horizon if nothing changes.
+    pub projected: Balance,
+    /// Projected dividend at the end of the horizon if the change is applied right now.
+    pub simulated: Balance,
+    /// The first block from which the claim frequency limit of the asset allows to claim.
+    pub claimable_from: BlockNumber,
+    /// Extra Staking locked balance required by the claim staking requirement of the asset
+    /// for claiming the simulated dividend.
+    pub insufficient_stake: Balance,
+}
+
 impl<T: Config> Pallet<T> {
     /// Get overall information about all mining assets.
     pub fn mining_assets(
@@ -104,4 +146,72 @@ impl<T: Config> Pallet<T> {
     ) -> BTreeMap<AssetId, MinerLedger<MiningWeight, T::BlockNumber>> {
         MinerLedgers::<T>::iter_prefix(&who).collect()
     }
+
+    /// Projects the asset mining dividend of `who` at `horizon` blocks later, with and without `change`.
+    ///
+    /// The reward pot of the asset is assumed to receive its share of the session reward
+    /// given the current issuance, and nobody else changes the balance meanwhile. The
+    /// `ClaimRestriction` of the asset is reported along with the dividends as the claim
+    /// is rejected until both the frequency limit and the staking requirement are met.
+    pub fn simulate_mining_dividend(
+        who: T::AccountId,
+        asset_id: AssetId,
+        change: AssetBalanceChange<BalanceOf<T>>,
+        horizon: T::BlockNumber,
+    ) -> MiningDividendEstimate<BalanceOf<T>, T::BlockNumber> {
+        let current_block = <frame_system::Pallet<T>>::block_number();
+        let future_block = current_block.saturating_add(horizon);
+
+        let mut miner_ledger = MinerLedgers::<T>::get(&who, &asset_id);
+        let mut miner_weight = SimulatedMiningWeight::from_weight(&MinerLedgerWrapper::<T>::new(
+            &who,
+            &asset_id,
+            &mut miner_ledger,
+        ));
+        let mut asset_ledger = AssetLedgers::<T>::get(&asset_id);
+        let mut asset_weight = SimulatedMiningWeight::from_weight(&AssetLedgerWrapper::<T>::new(
+            &asset_id,
+            &mut asset_ledger,
+        ));
+
+        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(&asset_id);
+        let income: BalanceOf<T> =
+            T::StakingInterface::estimated_mining_reward(asset_id, horizon.saturated_into())
+                .saturated_into();
+        let reward_pot_balance = Self::free_balance(&reward_pot).saturating_add(income);
+
+        let current = Self::compute_dividend_at(&who, &asset_id, current_block).unwrap_or_default();
+        let projected = miner_weight.dividend_at(&asset_weight, future_block, reward_pot_balance);
+
+        let delta = match change {
+            AssetBalanceChange::Deposit(value) => Delta::Add(value),
+            AssetBalanceChange::Withdraw(value) => Delta::Sub(value.min(miner_weight.amount)),
+        };
+        miner_weight.apply(current_block, &delta);
+        asset_weight.apply(current_block, &delta);
+        let simulated = miner_weight.dividend_at(&asset_weight, future_block, reward_pot_balance);
+
+        let ClaimRestriction {
+            staking_requirement,
+            frequency_limit,
+        } = ClaimRestrictionOf::<T>::get(&asset_id);
+        let claimable_from = match Self::last_claim(&who, &asset_id) {
+            Some(last_claim) if !frequency_limit.is_zero() => current_block.max(
+                last_claim
+                    .saturating_add(frequency_limit)
+                    .saturating_add(One::one()),
+            ),
+            _ => current_block,
+        };
+        let insufficient_stake =
+            Self::need_more_stake(&who, simulated, staking_requirement).unwrap_or_default();
+
+        MiningDividendEstimate {
+            current,
+            projected,
+            simulated,
+            claimable_from,
+            insufficient_stake,
+        }
+    }
 }
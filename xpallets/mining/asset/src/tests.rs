@@ -345,6 +345,66 @@ fn claim_restriction_should_work() {
     });
 }
 
+#[test]
+fn simulate_mining_dividend_should_report_claim_restriction() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        let t_1 = 777;
+        assert_ok!(t_issue_xbtc(t_1, 100));
+
+        // Block 1
+        t_start_session(1);
+        t_xbtc_set_claim_frequency_limit(2);
+        t_xbtc_set_claim_staking_requirement(0);
+
+        // Block 2
+        t_start_session(2);
+        assert_ok!(XMiningAsset::claim(Origin::signed(t_1), X_BTC));
+
+        // The claim is rejected until block 5 by the frequency limit.
+        let estimate = XMiningAsset::simulate_mining_dividend(
+            t_1,
+            X_BTC,
+            AssetBalanceChange::Deposit(100),
+            10,
+        );
+        assert_eq!(estimate.claimable_from, 5);
+        assert_eq!(estimate.insufficient_stake, 0);
+        assert!(estimate.simulated > 0);
+
+        // Block 6
+        t_start_session(6);
+        let estimate = XMiningAsset::simulate_mining_dividend(
+            t_1,
+            X_BTC,
+            AssetBalanceChange::Deposit(100),
+            10,
+        );
+        assert_eq!(estimate.claimable_from, 6);
+
+        // The claimer needs 10x dividend of Staking locked.
+        t_xbtc_set_claim_staking_requirement(10);
+        let estimate = XMiningAsset::simulate_mining_dividend(
+            t_1,
+            X_BTC,
+            AssetBalanceChange::Deposit(100),
+            10,
+        );
+        assert_eq!(estimate.insufficient_stake, estimate.simulated * 10);
+        t_issue_pcx(1, 1_000_000_000_000u128);
+        assert_ok!(t_bond(1, 1, 100_000_000_000));
+        t_issue_pcx(t_1, estimate.simulated * 10);
+        assert_ok!(t_bond(t_1, 1, estimate.simulated * 10));
+        let estimate = XMiningAsset::simulate_mining_dividend(
+            t_1,
+            X_BTC,
+            AssetBalanceChange::Deposit(100),
+            10,
+        );
+        assert_eq!(estimate.insufficient_stake, 0);
+    });
+}
+
 #[test]
 fn total_issuance_should_work() {
     ExtBuilder::default().build_and_execute(|| {
@@ -0,0 +1,251 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Staking precompile, which allows the EVM contracts and wallets to interact with
+//! xpallet-mining-staking via the Solidity ABI.
+//!
+//! ```solidity
+//! interface IStaking {
+//!     function bond(bytes32 validator, uint256 value) external;
+//!     function unbond(bytes32 validator, uint256 value) external;
+//!     function rebond(bytes32 from, bytes32 to, uint256 value) external;
+//!     function unlockUnbondedWithdrawal(bytes32 validator, uint32 unbondedIndex) external;
+//!     function claim(bytes32 validator) external;
+//!     function stakedOf(address nominator) external view returns (uint256);
+//!     function nominationOf(address nominator, bytes32 validator)
+//!         external view returns (uint256 nomination, uint256 unbondedChunks);
+//!     function unbondedChunkOf(address nominator, bytes32 validator, uint32 index)
+//!         external view returns (uint256 value, uint256 lockedUntil);
+//!     function pendingDividend(address nominator, bytes32 validator)
+//!         external view returns (uint256);
+//! }
+//! ```
+//!
+//! The validators are identified by their Substrate public key, and the nominator is
+//! the Substrate account mapped from the caller's address.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::Decode;
+use fp_evm::{Context, ExitSucceed, PrecompileFailure, PrecompileOutput, PrecompileResult};
+use frame_support::dispatch::DispatchResult;
+use frame_system::RawOrigin;
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::{SaturatedConversion, StaticLookup, UniqueSaturatedInto};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+use xpallet_assets_bridge::precompile::{
+    encode_u256, evm_to_sub_account, revert, revert_dispatch_error, EvmDataReader, Gasometer,
+};
+use xpallet_mining_staking::{BalanceOf, Pallet as XStaking, WeightInfo};
+
+/// The function selectors, i.e. the first 4 bytes of keccak256(signature).
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum Action {
+    /// bond(bytes32,uint256)
+    Bond = 0x51f93215,
+    /// unbond(bytes32,uint256)
+    Unbond = 0x4d8de4fc,
+    /// rebond(bytes32,bytes32,uint256)
+    Rebond = 0xc9a4af37,
+    /// unlockUnbondedWithdrawal(bytes32,uint32)
+    UnlockUnbondedWithdrawal = 0x0fc5ab4c,
+    /// claim(bytes32)
+    Claim = 0xbd66528a,
+    /// stakedOf(address)
+    StakedOf = 0xaf500ba3,
+    /// nominationOf(address,bytes32)
+    NominationOf = 0x04b6e0c3,
+    /// unbondedChunkOf(address,bytes32,uint32)
+    UnbondedChunkOf = 0xabc70b58,
+    /// pendingDividend(address,bytes32)
+    PendingDividend = 0xc6c2aaae,
+}
+
+impl Action {
+    fn from_selector(selector: u32) -> Option<Self> {
+        [
+            Self::Bond,
+            Self::Unbond,
+            Self::Rebond,
+            Self::UnlockUnbondedWithdrawal,
+            Self::Claim,
+            Self::StakedOf,
+            Self::NominationOf,
+            Self::UnbondedChunkOf,
+            Self::PendingDividend,
+        ]
+        .into_iter()
+        .find(|action| *action as u32 == selector)
+    }
+
+    fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Self::Bond | Self::Unbond | Self::Rebond | Self::UnlockUnbondedWithdrawal | Self::Claim
+        )
+    }
+}
+
+/// Precompile of xpallet-mining-staking.
+pub struct StakingPrecompile<T>(PhantomData<T>);
+
+impl<T> StakingPrecompile<T>
+where
+    T: xpallet_mining_staking::Config + xpallet_assets_bridge::Config,
+{
+    pub fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        let (mut reader, selector) = EvmDataReader::new_with_selector(input)?;
+        let action = Action::from_selector(selector).ok_or_else(|| revert("unknown selector"))?;
+        if action.is_mutating() {
+            if is_static {
+                return Err(revert("cannot modify state in static context"));
+            }
+            if !context.apparent_value.is_zero() {
+                return Err(revert("function is not payable"));
+            }
+            // Forbid the delegatecall and callcode, the calling contract could act on
+            // behalf of its caller otherwise.
+            if context.address != address {
+                return Err(revert("cannot be called with delegatecall or callcode"));
+            }
+        }
+
+        let mut gasometer = Gasometer::<T>::new(target_gas);
+        let caller = || evm_to_sub_account::<T>(context.caller);
+        let output = match action {
+            Action::Bond => {
+                let target = Self::read_validator(&mut reader)?;
+                let value = Self::read_balance(&mut reader)?;
+                gasometer
+                    .record_weight(<T as xpallet_mining_staking::Config>::WeightInfo::bond())?;
+                Self::dispatch(XStaking::<T>::bond(
+                    RawOrigin::Signed(caller()).into(),
+                    T::Lookup::unlookup(target),
+                    value,
+                ))?
+            }
+            Action::Unbond => {
+                let target = Self::read_validator(&mut reader)?;
+                let value = Self::read_balance(&mut reader)?;
+                gasometer
+                    .record_weight(<T as xpallet_mining_staking::Config>::WeightInfo::unbond())?;
+                Self::dispatch(XStaking::<T>::unbond(
+                    RawOrigin::Signed(caller()).into(),
+                    T::Lookup::unlookup(target),
+                    value,
+                ))?
+            }
+            Action::Rebond => {
+                let from = Self::read_validator(&mut reader)?;
+                let to = Self::read_validator(&mut reader)?;
+                let value = Self::read_balance(&mut reader)?;
+                gasometer
+                    .record_weight(<T as xpallet_mining_staking::Config>::WeightInfo::rebond())?;
+                Self::dispatch(XStaking::<T>::rebond(
+                    RawOrigin::Signed(caller()).into(),
+                    T::Lookup::unlookup(from),
+                    T::Lookup::unlookup(to),
+                    value,
+                ))?
+            }
+            Action::UnlockUnbondedWithdrawal => {
+                let target = Self::read_validator(&mut reader)?;
+                let unbonded_index = reader.read_u32()?;
+                gasometer.record_weight(
+                    <T as xpallet_mining_staking::Config>::WeightInfo::unlock_unbonded_withdrawal(),
+                )?;
+                Self::dispatch(XStaking::<T>::unlock_unbonded_withdrawal(
+                    RawOrigin::Signed(caller()).into(),
+                    T::Lookup::unlookup(target),
+                    unbonded_index,
+                ))?
+            }
+            Action::Claim => {
+                let target = Self::read_validator(&mut reader)?;
+                gasometer
+                    .record_weight(<T as xpallet_mining_staking::Config>::WeightInfo::claim())?;
+                Self::dispatch(XStaking::<T>::claim(
+                    RawOrigin::Signed(caller()).into(),
+                    T::Lookup::unlookup(target),
+                ))?
+            }
+            Action::StakedOf => {
+                let nominator = evm_to_sub_account::<T>(reader.read_address()?);
+                gasometer.record_db(2, 0)?;
+                encode_u256(balance_to_u256::<T>(XStaking::<T>::staked_of(&nominator)))
+            }
+            Action::NominationOf => {
+                let nominator = evm_to_sub_account::<T>(reader.read_address()?);
+                let validator = Self::read_validator(&mut reader)?;
+                gasometer.record_db(2, 0)?;
+                let ledger = XStaking::<T>::nominations(&nominator, &validator);
+                let mut output = encode_u256(balance_to_u256::<T>(ledger.nomination));
+                output.extend(encode_u256(U256::from(ledger.unbonded_chunks.len())));
+                output
+            }
+            Action::UnbondedChunkOf => {
+                let nominator = evm_to_sub_account::<T>(reader.read_address()?);
+                let validator = Self::read_validator(&mut reader)?;
+                let index = reader.read_u32()?;
+                gasometer.record_db(2, 0)?;
+                let ledger = XStaking::<T>::nominations(&nominator, &validator);
+                let chunk = ledger
+                    .unbonded_chunks
+                    .get(index as usize)
+                    .ok_or_else(|| revert("unbonded chunk does not exist"))?;
+                let locked_until: u128 = chunk.locked_until.unique_saturated_into();
+                let mut output = encode_u256(balance_to_u256::<T>(chunk.value));
+                output.extend(encode_u256(U256::from(locked_until)));
+                output
+            }
+            Action::PendingDividend => {
+                let nominator = evm_to_sub_account::<T>(reader.read_address()?);
+                let validator = Self::read_validator(&mut reader)?;
+                gasometer.record_db(6, 0)?;
+                let current_block = frame_system::Pallet::<T>::block_number();
+                let dividend =
+                    XStaking::<T>::compute_dividend_at(&nominator, &validator, current_block)
+                        .map_err(|e| revert_dispatch_error(e.into()))?;
+                encode_u256(balance_to_u256::<T>(dividend))
+            }
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used(),
+            output,
+            logs: Vec::new(),
+        })
+    }
+
+    /// Reads the Substrate account of a validator from its 32 bytes public key.
+    fn read_validator(reader: &mut EvmDataReader) -> Result<T::AccountId, PrecompileFailure> {
+        let key: H256 = reader.read_bytes32()?;
+        T::AccountId::decode(&mut key.as_bytes()).map_err(|_| revert("invalid validator"))
+    }
+
+    fn read_balance(reader: &mut EvmDataReader) -> Result<BalanceOf<T>, PrecompileFailure> {
+        Ok(reader.read_u128()?.saturated_into())
+    }
+
+    fn dispatch(result: DispatchResult) -> Result<Vec<u8>, PrecompileFailure> {
+        result.map_err(revert_dispatch_error)?;
+        Ok(Vec::new())
+    }
+}
+
+fn balance_to_u256<T: xpallet_mining_staking::Config>(balance: BalanceOf<T>) -> U256 {
+    U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
@@ -0,0 +1,247 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, GenesisBuild, ValidatorRegistration},
+};
+use pallet_evm::AddressMapping;
+use sp_core::{H160, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    AccountId32,
+};
+
+use xpallet_mining_staking::SessionInterface;
+
+pub(crate) type AccountId = AccountId32;
+pub(crate) type Balance = u128;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Evm: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
+        XAssetsRegistrar: xpallet_assets_registrar::{Pallet, Call, Config, Storage, Event<T>},
+        XAssets: xpallet_assets::{Pallet, Call, Config<T>, Storage, Event<T>},
+        XAssetsBridge: xpallet_assets_bridge::{Pallet, Call, Storage, Config<T>, Event<T>},
+        XStaking: xpallet_mining_staking::{Pallet, Call, Storage, Event<T>, Config<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 44;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+    pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+}
+
+parameter_types! {
+    pub const ChainXAssetId: u32 = 0;
+}
+
+impl xpallet_assets_registrar::Config for Test {
+    type Event = Event;
+    type NativeAssetId = ChainXAssetId;
+    type RegistrarHandler = ();
+    type Currency = Balances;
+    type ListingOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type ListingDeposit = ();
+    type RejectionSlash = ();
+    type OnSlash = ();
+    type WeightInfo = ();
+}
+
+impl xpallet_assets::Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type TreasuryAccount = ();
+    type OnCreatedAccount = frame_system::Provider<Test>;
+    type OnAssetChanged = ();
+    type AccountRestriction = ();
+    type AssetPause = ();
+    type MinVestedTransfer = frame_support::traits::ConstU128<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_evm::Config for Test {
+    type FeeCalculator = ();
+    type GasWeightMapping = ();
+    type CallOrigin = pallet_evm::EnsureAddressRoot<Self::AccountId>;
+    type WithdrawOrigin = pallet_evm::EnsureAddressNever<Self::AccountId>;
+    type AddressMapping = pallet_evm::HashedAddressMapping<BlakeTwo256>;
+    type Currency = Balances;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type Event = Event;
+    type PrecompilesType = ();
+    type PrecompilesValue = ();
+    type ChainId = ();
+    type BlockGasLimit = ();
+    type OnChargeTransaction = ();
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type FindAuthor = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub EvmCaller: H160 = H160::from_slice(&[17u8; 20][..]);
+    pub ClaimBond: Balance = 2;
+    pub const RegistrationDelay: u64 = 0;
+}
+
+impl xpallet_assets_bridge::Config for Test {
+    type Event = Event;
+    type EvmCaller = EvmCaller;
+    type ClaimBond = ClaimBond;
+    type PauserOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type BackForeignOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrationDelay = RegistrationDelay;
+}
+
+/// There is no session in the mock, the validators are never disabled.
+impl SessionInterface<AccountId> for Test {
+    fn disable_validator(_validator: &AccountId) -> bool {
+        false
+    }
+
+    fn validators() -> Vec<AccountId> {
+        vec![VALIDATOR.into()]
+    }
+}
+
+pub struct DummyStakingRewardPotAccountDeterminer;
+impl xp_mining_common::RewardPotAccountFor<AccountId, AccountId>
+    for DummyStakingRewardPotAccountDeterminer
+{
+    fn reward_pot_account_for(validator: &AccountId) -> AccountId {
+        let mut pot: [u8; 32] = validator.clone().into();
+        pot[0] = 0xff;
+        pot.into()
+    }
+}
+
+pub struct Registration;
+impl ValidatorRegistration<AccountId> for Registration {
+    fn is_registered(_id: &AccountId) -> bool {
+        true
+    }
+}
+
+parameter_types! {
+    pub const SessionDuration: u64 = 50;
+    pub const MinimumReferralId: u32 = 2;
+    pub const MaximumReferralId: u32 = 12;
+}
+
+impl xpallet_mining_staking::Config for Test {
+    type Currency = Balances;
+    type Event = Event;
+    type AssetMining = ();
+    type SessionDuration = SessionDuration;
+    type MinimumReferralId = MinimumReferralId;
+    type MaximumReferralId = MaximumReferralId;
+    type SessionInterface = Self;
+    type TreasuryAccount = ();
+    type DetermineRewardPotAccount = DummyStakingRewardPotAccountDeterminer;
+    type ValidatorRegistration = Registration;
+    type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = ();
+}
+
+/// The public key of the validator.
+pub const VALIDATOR: [u8; 32] = [1u8; 32];
+/// The EVM address of the nominator.
+pub const NOMINATOR: [u8; 20] = [2u8; 20];
+
+/// Returns the Substrate account mapped from the EVM `address`.
+pub fn mapped_account(address: H160) -> AccountId {
+    <Test as pallet_evm::Config>::AddressMapping::into_account_id(address)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (VALIDATOR.into(), 10_000),
+            (mapped_account(H160(NOMINATOR)), 1_000),
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    xpallet_mining_staking::GenesisConfig::<Test> {
+        validators: vec![(VALIDATOR.into(), b"validator".to_vec(), 1_000)],
+        validator_count: 1,
+        bonding_duration: 10,
+        glob_dist_ratio: (12, 88),
+        mining_ratio: (10, 90),
+        ..Default::default()
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,216 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use ethabi::Token;
+use fp_evm::{Context, PrecompileFailure, PrecompileOutput};
+use sp_core::{H160, U256};
+use sp_io::hashing::keccak_256;
+
+use xpallet_assets_bridge::precompile::encode_u256;
+use xpallet_mining_staking::{Pallet as XStaking, WeightInfo};
+
+use crate::{mock::*, Action, StakingPrecompile};
+
+fn precompile_address() -> H160 {
+    H160::from_low_u64_be(2049)
+}
+
+fn call(
+    caller: H160,
+    input: Vec<u8>,
+    is_static: bool,
+) -> Result<PrecompileOutput, PrecompileFailure> {
+    let address = precompile_address();
+    let context = Context {
+        address,
+        caller,
+        apparent_value: U256::zero(),
+    };
+    StakingPrecompile::<Test>::execute(address, &input, None, &context, is_static)
+}
+
+fn input(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut input = keccak_256(signature.as_bytes())[..4].to_vec();
+    input.extend(ethabi::encode(tokens));
+    input
+}
+
+fn validator() -> Token {
+    Token::FixedBytes(VALIDATOR.to_vec())
+}
+
+#[test]
+fn selectors_should_match_signatures() {
+    for (action, signature) in [
+        (Action::Bond, "bond(bytes32,uint256)"),
+        (Action::Unbond, "unbond(bytes32,uint256)"),
+        (Action::Rebond, "rebond(bytes32,bytes32,uint256)"),
+        (
+            Action::UnlockUnbondedWithdrawal,
+            "unlockUnbondedWithdrawal(bytes32,uint32)",
+        ),
+        (Action::Claim, "claim(bytes32)"),
+        (Action::StakedOf, "stakedOf(address)"),
+        (Action::NominationOf, "nominationOf(address,bytes32)"),
+        (
+            Action::UnbondedChunkOf,
+            "unbondedChunkOf(address,bytes32,uint32)",
+        ),
+        (Action::PendingDividend, "pendingDividend(address,bytes32)"),
+    ] {
+        let selector = keccak_256(signature.as_bytes());
+        assert_eq!(
+            action as u32,
+            u32::from_be_bytes([selector[0], selector[1], selector[2], selector[3]]),
+            "{}",
+            signature
+        );
+        assert!(Action::from_selector(action as u32) == Some(action));
+    }
+    assert!(Action::from_selector(0xdeadbeef).is_none());
+}
+
+#[test]
+fn bond_and_unbond_should_work() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(NOMINATOR);
+        let nominator = mapped_account(caller);
+
+        let bond = input(
+            "bond(bytes32,uint256)",
+            &[validator(), Token::Uint(U256::from(100))],
+        );
+        let output = call(caller, bond, false).unwrap();
+        assert!(output.output.is_empty());
+        assert_eq!(output.cost, <() as WeightInfo>::bond());
+        assert_eq!(XStaking::<Test>::staked_of(&nominator), 100);
+
+        let staked_of = input("stakedOf(address)", &[Token::Address(caller)]);
+        let output = call(caller, staked_of, true).unwrap();
+        assert_eq!(output.output, encode_u256(U256::from(100)));
+
+        let unbond = input(
+            "unbond(bytes32,uint256)",
+            &[validator(), Token::Uint(U256::from(40))],
+        );
+        let output = call(caller, unbond, false).unwrap();
+        assert_eq!(output.cost, <() as WeightInfo>::unbond());
+
+        let nomination_of = input(
+            "nominationOf(address,bytes32)",
+            &[Token::Address(caller), validator()],
+        );
+        let output = call(caller, nomination_of, true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[Token::Uint(U256::from(60)), Token::Uint(U256::one())])
+        );
+
+        let chunk_of = |index: u32| {
+            input(
+                "unbondedChunkOf(address,bytes32,uint32)",
+                &[
+                    Token::Address(caller),
+                    validator(),
+                    Token::Uint(U256::from(index)),
+                ],
+            )
+        };
+        let output = call(caller, chunk_of(0), true).unwrap();
+        assert_eq!(
+            output.output,
+            ethabi::encode(&[Token::Uint(U256::from(40)), Token::Uint(U256::from(11))])
+        );
+        assert!(call(caller, chunk_of(1), true).is_err());
+    })
+}
+
+#[test]
+fn dispatch_errors_should_revert() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(NOMINATOR);
+        // more than the free balance
+        let bond = input(
+            "bond(bytes32,uint256)",
+            &[validator(), Token::Uint(U256::from(1_001))],
+        );
+        assert!(call(caller, bond, false).is_err());
+        // not a validator
+        let bond = input(
+            "bond(bytes32,uint256)",
+            &[
+                Token::FixedBytes([9u8; 32].to_vec()),
+                Token::Uint(U256::from(100)),
+            ],
+        );
+        assert!(call(caller, bond, false).is_err());
+        // nothing to unbond
+        let unbond = input(
+            "unbond(bytes32,uint256)",
+            &[validator(), Token::Uint(U256::from(100))],
+        );
+        assert!(call(caller, unbond, false).is_err());
+        assert!(call(caller, vec![0xde, 0xad, 0xbe, 0xef], false).is_err());
+    })
+}
+
+#[test]
+fn mutating_calls_should_be_guarded() {
+    new_test_ext().execute_with(|| {
+        let caller = H160(NOMINATOR);
+        let bond = input(
+            "bond(bytes32,uint256)",
+            &[validator(), Token::Uint(U256::from(100))],
+        );
+        // static call
+        assert!(call(caller, bond.clone(), true).is_err());
+
+        // delegatecall
+        let context = Context {
+            address: H160::from_low_u64_be(42),
+            caller,
+            apparent_value: U256::zero(),
+        };
+        assert!(StakingPrecompile::<Test>::execute(
+            precompile_address(),
+            &bond,
+            None,
+            &context,
+            false
+        )
+        .is_err());
+
+        // payable
+        let context = Context {
+            address: precompile_address(),
+            caller,
+            apparent_value: U256::one(),
+        };
+        assert!(StakingPrecompile::<Test>::execute(
+            precompile_address(),
+            &bond,
+            None,
+            &context,
+            false
+        )
+        .is_err());
+
+        // out of gas
+        let context = Context {
+            address: precompile_address(),
+            caller,
+            apparent_value: U256::zero(),
+        };
+        assert!(matches!(
+            StakingPrecompile::<Test>::execute(
+                precompile_address(),
+                &bond,
+                Some(<() as WeightInfo>::bond() - 1),
+                &context,
+                false
+            ),
+            Err(PrecompileFailure::Error { .. })
+        ));
+
+        assert_eq!(XStaking::<Test>::staked_of(&mapped_account(caller)), 0);
+    })
+}
@@ -10,11 +10,14 @@ use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 use codec::Codec;
 
 pub use xpallet_mining_staking::{
-    NominatorInfo, NominatorLedger, Unbonded, ValidatorInfo, ValidatorLedger, VoteWeight,
+    EraHistory, EraIndex, EraRewardSplit, EraValidatorInfo, EraValidatorStats, NominationChange,
+    NominatorInfo, NominatorLedger, StakingDividendEstimate, UnappliedSlash, Unbonded,
+    ValidatorInfo, ValidatorLedger, VoteWeight,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query Staking info.
+    #[api_version(2)]
     pub trait XStakingApi<AccountId, Balance, VoteWeight, BlockNumber>
     where
         AccountId: Codec + Ord,
@@ -36,5 +39,18 @@ sp_api::decl_runtime_apis! {
 
         /// Get individual nominator information given the nominator AccountId.
         fn nominator_info_of(who: AccountId) -> NominatorInfo<BlockNumber>;
+
+        /// Get all the deferred nominator slashes, keyed by the era to apply.
+        fn unapplied_slashes() -> BTreeMap<EraIndex, Vec<UnappliedSlash<AccountId>>>;
+
+        /// Get the validators and the reward distribution of the given era.
+        fn era_history(era: EraIndex) -> Option<EraHistory<AccountId, Balance>>;
+
+        /// Get the projected staking dividends of `who` at `horizon` blocks later, with and without `change`.
+        fn simulate_staking_dividend(
+            who: AccountId,
+            change: NominationChange<AccountId, Balance>,
+            horizon: BlockNumber,
+        ) -> BTreeMap<AccountId, StakingDividendEstimate<Balance>>;
     }
 }
@@ -17,8 +17,9 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcVoteWeight};
 
 use xpallet_mining_staking_rpc_runtime_api::{
-    NominatorInfo, NominatorLedger, Unbonded, ValidatorInfo, ValidatorLedger,
-    XStakingApi as XStakingRuntimeApi,
+    EraHistory, EraIndex, EraRewardSplit, EraValidatorInfo, EraValidatorStats, NominationChange,
+    NominatorInfo, NominatorLedger, StakingDividendEstimate, UnappliedSlash, Unbonded,
+    ValidatorInfo, ValidatorLedger, XStakingApi as XStakingRuntimeApi,
 };
 
 /// XStaking RPC methods.
@@ -74,6 +75,31 @@ where
         who: AccountId,
         at: Option<BlockHash>,
     ) -> Result<NominatorInfo<BlockNumber>>;
+
+    /// Get all the deferred nominator slashes, keyed by the era to apply.
+    #[rpc(name = "xstaking_getUnappliedSlashes")]
+    fn unapplied_slashes(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<BTreeMap<EraIndex, Vec<UnappliedSlash<AccountId>>>>;
+
+    /// Get the validators and the reward distribution of the given era.
+    #[rpc(name = "xstaking_getEraHistory")]
+    fn era_history(
+        &self,
+        era: EraIndex,
+        at: Option<BlockHash>,
+    ) -> Result<Option<EraHistory<AccountId, RpcBalance<Balance>>>>;
+
+    /// Get the projected staking dividends at `horizon` blocks later, with and without `change`.
+    #[rpc(name = "xstaking_simulateDividend")]
+    fn simulate_staking_dividend(
+        &self,
+        who: AccountId,
+        change: NominationChange<AccountId, RpcBalance<Balance>>,
+        horizon: BlockNumber,
+        at: Option<BlockHash>,
+    ) -> Result<BTreeMap<AccountId, StakingDividendEstimate<RpcBalance<Balance>>>>;
 }
 
 /// A struct that implements the [`XStakingApi`].
@@ -227,4 +253,78 @@ where
         api.nominator_info_of(&at, who)
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn unapplied_slashes(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<BTreeMap<EraIndex, Vec<UnappliedSlash<AccountId>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.unapplied_slashes(&at)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn era_history(
+        &self,
+        era: EraIndex,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<EraHistory<AccountId, RpcBalance<Balance>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.era_history(&at, era)
+            .map(|history| {
+                history.map(|history| EraHistory {
+                    era: history.era,
+                    start_session_index: history.start_session_index,
+                    reward_split: EraRewardSplit {
+                        treasury: history.reward_split.treasury.into(),
+                        staking: history.reward_split.staking.into(),
+                        asset_mining: history.reward_split.asset_mining.into(),
+                    },
+                    validators: history
+                        .validators
+                        .into_iter()
+                        .map(|validator| EraValidatorInfo {
+                            account: validator.account,
+                            stats: EraValidatorStats {
+                                total_votes: validator.stats.total_votes.into(),
+                                reward: validator.stats.reward.into(),
+                                penalty: validator.stats.penalty.into(),
+                                nominator_slashed: validator.stats.nominator_slashed.into(),
+                            },
+                        })
+                        .collect(),
+                })
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn simulate_staking_dividend(
+        &self,
+        who: AccountId,
+        change: NominationChange<AccountId, RpcBalance<Balance>>,
+        horizon: BlockNumber,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<BTreeMap<AccountId, StakingDividendEstimate<RpcBalance<Balance>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let change = change.map_balance(|value| value.into_inner());
+        api.simulate_staking_dividend(&at, who, change, horizon)
+            .map(|estimates| {
+                estimates
+                    .into_iter()
+                    .map(|(validator, estimate)| {
+                        (
+                            validator,
+                            StakingDividendEstimate {
+                                current: estimate.current.into(),
+                                projected: estimate.projected.into(),
+                                simulated: estimate.simulated.into(),
+                            },
+                        )
+                    })
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
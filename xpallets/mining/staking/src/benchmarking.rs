@@ -181,6 +181,26 @@ benchmarks! {
     verify {
         assert_eq!(SessionsPerEra::<T>::get(), c);
     }
+
+    set_history_depth {
+        let e in 1 .. 100;
+        // The history of eras 0..e is removed by the new depth of 1 in era e.
+        let validators = (0..40)
+            .map(|n| account::<T::AccountId>("validator", n, SEED))
+            .collect::<Vec<_>>();
+        for era in 0..e {
+            for validator in validators.iter() {
+                ErasValidatorStats::<T>::insert(era, validator, EraValidatorStats::default());
+            }
+            ErasRewardSplit::<T>::insert(era, EraRewardSplit::default());
+        }
+        HistoryDepth::<T>::put(e);
+        ActiveEra::<T>::put(ActiveEraInfo { index: e, start: None });
+    }: _(RawOrigin::Root, 1, e)
+    verify {
+        assert_eq!(HistoryDepth::<T>::get(), 1);
+        assert!(!ErasRewardSplit::<T>::contains_key(e - 1));
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +224,7 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_minimum_validator_count());
             assert_ok!(Pallet::<Test>::test_benchmark_set_bonding_duration());
             assert_ok!(Pallet::<Test>::test_benchmark_set_validator_bonding_duration());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_history_depth());
         });
     }
 }
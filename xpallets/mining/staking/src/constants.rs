@@ -36,3 +36,20 @@ pub const DEFAULT_BONDING_DURATION: u64 = DEFAULT_BLOCKS_PER_SESSION * 12 * 24 *
 
 /// The default bonding duration for validator is 3 * 10 days.
 pub const DEFAULT_VALIDATOR_BONDING_DURATION: u64 = DEFAULT_BONDING_DURATION * 10;
+
+/// The default number of eras that a reported nominator slash is deferred by.
+///
+/// An era lasts 12 sessions, i.e., 1 hour, 7 days in total leaves enough time
+/// for the council to review and cancel the slash if necessary.
+pub const DEFAULT_SLASH_DEFER_DURATION: u32 = 24 * 7;
+
+/// The maximum number of nominations slashed per block.
+///
+/// The nominator slash of a validator having more nominators is applied across blocks.
+pub const MAX_SLASHED_NOMINATIONS_PER_BLOCK: u32 = 256;
+
+/// The maximum number of deferred slashes cancelled by a single `cancel_deferred_slash`.
+pub const MAX_CANCELLED_SLASHES: u32 = 256;
+
+/// The default number of eras that the era history is kept for, i.e., 30 days.
+pub const DEFAULT_HISTORY_DEPTH: u32 = 24 * 30;
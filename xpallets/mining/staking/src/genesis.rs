@@ -39,6 +39,7 @@ impl<T: Config> Pallet<T> {
             Nominations::<T>::mutate(sender, target, |nominator| {
                 nominator.nomination = value;
             });
+            ValidatorNominators::<T>::insert(target, sender, ());
         }
         Ok(())
     }
@@ -60,6 +61,7 @@ impl<T: Config> Pallet<T> {
         );
         Self::unbond_reserve(sender, value)?;
         Self::mutate_unbonded_chunks(sender, target, value, locked_until);
+        ValidatorNominators::<T>::insert(target, sender, ());
         Ok(())
     }
 
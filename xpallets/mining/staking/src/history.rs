@@ -0,0 +1,66 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Bounded per-era history of the validators and the session reward distribution.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Snapshots the total votes of the active validators at the beginning of `era`,
+    /// and prunes the history that is older than `HistoryDepth` eras.
+    pub(crate) fn start_era_history(era: EraIndex) {
+        T::SessionInterface::validators()
+            .into_iter()
+            .filter(|v| Self::is_active(v))
+            .for_each(|validator| {
+                let total_votes = Self::total_votes_of(&validator);
+                ErasValidatorStats::<T>::mutate(era, &validator, |stats| {
+                    stats.total_votes = total_votes
+                });
+            });
+
+        if let Some(stale_era) = era.checked_sub(Self::history_depth()) {
+            Self::clear_era_history(stale_era);
+        }
+    }
+
+    /// Removes all the history of `era`.
+    pub(crate) fn clear_era_history(era: EraIndex) {
+        ErasValidatorStats::<T>::remove_prefix(era, None);
+        ErasRewardSplit::<T>::remove(era);
+        NominatorSlashInEra::<T>::remove_prefix(era, None);
+    }
+
+    /// Records the reward minted for `validator` in the active era.
+    pub(crate) fn note_validator_reward(validator: &T::AccountId, reward: BalanceOf<T>) {
+        if let Some(era) = Self::active_era().map(|e| e.index) {
+            ErasValidatorStats::<T>::mutate(era, validator, |stats| {
+                stats.reward = stats.reward.saturating_add(reward)
+            });
+        }
+    }
+
+    /// Records the penalty slashed from the reward pot of `validator` in the active era.
+    pub(crate) fn note_validator_penalty(validator: &T::AccountId, penalty: BalanceOf<T>) {
+        if let Some(era) = Self::active_era().map(|e| e.index) {
+            ErasValidatorStats::<T>::mutate(era, validator, |stats| {
+                stats.penalty = stats.penalty.saturating_add(penalty)
+            });
+        }
+    }
+
+    /// Records the balance slashed from a nomination of `validator` in the active era.
+    pub(crate) fn note_nominator_slash(validator: &T::AccountId, slashed: BalanceOf<T>) {
+        if let Some(era) = Self::active_era().map(|e| e.index) {
+            ErasValidatorStats::<T>::mutate(era, validator, |stats| {
+                stats.nominator_slashed = stats.nominator_slashed.saturating_add(slashed)
+            });
+        }
+    }
+
+    /// Records the session reward split of the active era.
+    pub(crate) fn note_reward_split<F: FnOnce(&mut EraRewardSplit<BalanceOf<T>>)>(f: F) {
+        if let Some(era) = Self::active_era().map(|e| e.index) {
+            ErasRewardSplit::<T>::mutate(era, f);
+        }
+    }
+}
@@ -156,11 +156,17 @@ impl<T: Config> Pallet<T> {
         current_block: T::BlockNumber,
         delta: Delta<BalanceOf<T>>,
     ) {
-        Nominations::<T>::mutate(nominator, validator, |claimer| {
+        let is_empty = Nominations::<T>::mutate(nominator, validator, |claimer| {
             claimer.nomination = delta.calculate(claimer.nomination);
             claimer.last_vote_weight = new_weight;
             claimer.last_vote_weight_update = current_block;
+            claimer.nomination.is_zero() && claimer.unbonded_chunks.is_empty()
         });
+        if is_empty {
+            ValidatorNominators::<T>::remove(validator, nominator);
+        } else {
+            ValidatorNominators::<T>::insert(validator, nominator, ());
+        }
     }
 
     ///
@@ -333,9 +339,10 @@ impl<T: Config> Pallet<T> {
 
     /// * Increment `active_era.index`,
     /// * reset `active_era.start`,
-    /// * update `BondedEras` and apply slashes.
+    /// * apply the deferred nominator slashes,
+    /// * snapshot the validators into the era history.
     fn start_era(_start_session: SessionIndex) {
-        let _active_era = ActiveEra::<T>::mutate(|active_era| {
+        let active_era = ActiveEra::<T>::mutate(|active_era| {
             let new_index = active_era.as_ref().map(|info| info.index + 1).unwrap_or(0);
             *active_era = Some(ActiveEraInfo {
                 index: new_index,
@@ -344,6 +351,9 @@ impl<T: Config> Pallet<T> {
             });
             new_index
         });
+
+        Self::apply_unapplied_slashes(active_era);
+        Self::start_era_history(active_era);
     }
 
     /// Compute payout for era.
@@ -386,7 +396,8 @@ pub type IdentificationTuple<T> = (
 type Offender<T> = IdentificationTuple<T>;
 
 /// This is intended to be used with `FilterHistoricalOffences` in Substrate/Staking.
-/// In ChainX, we always apply the slash immediately, no deferred slash.
+/// In ChainX, the slash on the reward pot is always applied immediately, only the optional
+/// nominator slash of the severe offences(e.g., equivocation) is deferred.
 impl<T: Config> OnOffenceHandler<Reporter<T>, IdentificationTuple<T>, Weight> for Pallet<T>
 where
     T: pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
@@ -401,7 +412,7 @@ where
         offenders: &[OffenceDetails<Reporter<T>, Offender<T>>],
         slash_fraction: &[Perbill],
         slash_session: SessionIndex,
-        _disable_strategy: DisableStrategy,
+        disable_strategy: DisableStrategy,
     ) -> Weight {
         let offenders_tuple = offenders
             .iter()
@@ -419,6 +430,11 @@ where
             offenders_tuple, slash_session
         );
 
+        // The unresponsiveness offences never disable the offender, the rest are equivocations.
+        if !matches!(disable_strategy, DisableStrategy::Never) {
+            Self::defer_nominator_slashes(&offenders_tuple);
+        }
+
         // Write a temp environment storage so that we can sum the session reward
         // together later and then perform the slashing operation only once.
         <SessionOffenders<T>>::put(offenders_tuple);
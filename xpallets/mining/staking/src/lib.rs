@@ -15,6 +15,7 @@
 
 mod constants;
 mod election;
+mod history;
 mod impls;
 mod reward;
 mod rpc;
@@ -35,9 +36,10 @@ use frame_support::{
     ensure,
     log::debug,
     traits::{
-        Currency, ExistenceRequirement, Get, LockableCurrency, ValidatorRegistration,
-        WithdrawReasons,
+        Currency, EnsureOrigin, ExistenceRequirement, Get, GetStorageVersion, LockableCurrency,
+        StorageVersion, ValidatorRegistration, WithdrawReasons,
     },
+    weights::Weight,
 };
 use frame_system::{ensure_root, ensure_signed};
 use sp_runtime::{
@@ -67,6 +69,9 @@ pub type BalanceOf<T> =
 /// Counter for the number of eras that have passed.
 pub type EraIndex = u32;
 
+/// The current storage version.
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -111,15 +116,33 @@ pub mod pallet {
         /// Provide information about whether or not some
         /// validator has been registered with them
         type ValidatorRegistration: ValidatorRegistration<Self::AccountId>;
+
+        /// The origin which can cancel a deferred nominator slash.
+        type SlashCancelOrigin: EnsureOrigin<Self::Origin>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_now: T::BlockNumber) -> Weight {
+            if ApplyingSlashes::<T>::decode_len().unwrap_or(0) == 0 {
+                return T::DbWeight::get().reads(1);
+            }
+            let slashed = Self::apply_nominator_slashes(MAX_SLASHED_NOMINATIONS_PER_BLOCK);
+            T::DbWeight::get()
+                .reads_writes(3, 2)
+                .saturating_add(Self::slash_nomination_weight().saturating_mul(slashed.into()))
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Nominate the `target` with `value` of the origin account's balance locked.
@@ -234,6 +257,7 @@ pub mod pallet {
             Nominations::<T>::mutate(&sender, &target, |nominator| {
                 nominator.unbonded_chunks = unbonded_chunks;
             });
+            Self::prune_validator_nominator(&sender, &target);
 
             Self::deposit_event(Event::<T>::Withdrawn(sender, value));
             Ok(())
@@ -421,6 +445,106 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Enable or disable slashing the nominations of the severe offenders.
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn set_nominator_slash_enabled(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            ensure_root(origin)?;
+            NominatorSlashEnabled::<T>::put(enabled);
+            Ok(())
+        }
+
+        /// Set the number of eras that the nominator slashes are deferred by.
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn set_slash_defer_duration(
+            origin: OriginFor<T>,
+            #[pallet::compact] new: EraIndex,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            SlashDeferDuration::<T>::put(new);
+            Ok(())
+        }
+
+        /// Cancel the deferred nominator slashes of `era` given the indices in `UnappliedSlashes`.
+        ///
+        /// At most `MAX_CANCELLED_SLASHES` slashes can be cancelled at once.
+        #[pallet::weight(Pallet::<T>::cancel_deferred_slash_weight(slash_indices.len() as u32))]
+        pub fn cancel_deferred_slash(
+            origin: OriginFor<T>,
+            #[pallet::compact] era: EraIndex,
+            slash_indices: Vec<u32>,
+        ) -> DispatchResult {
+            T::SlashCancelOrigin::ensure_origin(origin)?;
+
+            ensure!(!slash_indices.is_empty(), Error::<T>::EmptyTargets);
+            ensure!(
+                slash_indices.len() as u32 <= MAX_CANCELLED_SLASHES,
+                Error::<T>::TooManySlashIndices
+            );
+            ensure!(
+                slash_indices.windows(2).all(|pair| pair[0] < pair[1]),
+                Error::<T>::NotSortedAndUnique
+            );
+
+            let mut unapplied = UnappliedSlashes::<T>::get(&era);
+            let last_item = slash_indices[slash_indices.len() - 1];
+            ensure!(
+                (last_item as usize) < unapplied.len(),
+                Error::<T>::InvalidSlashIndex
+            );
+
+            // Remove from the back so that the indices are still valid.
+            for index in slash_indices.into_iter().rev() {
+                let cancelled = unapplied.remove(index as usize);
+                // Let a later report of the same era slash the cancelled fraction again.
+                NominatorSlashInEra::<T>::mutate(
+                    cancelled.reported_era,
+                    &cancelled.validator,
+                    |fraction| *fraction = fraction.saturating_sub(cancelled.fraction),
+                );
+                Self::deposit_event(Event::<T>::SlashCancelled(era, cancelled.validator));
+            }
+
+            if unapplied.is_empty() {
+                UnappliedSlashes::<T>::remove(&era);
+            } else {
+                UnappliedSlashes::<T>::insert(&era, &unapplied);
+            }
+            Ok(())
+        }
+
+        /// Set the number of eras that the era history is kept for.
+        ///
+        /// The history older than the new depth will be removed immediately, `pruned_eras` is
+        /// the upper bound of the number of the removed eras for weighing this call.
+        #[pallet::weight(T::WeightInfo::set_history_depth(*pruned_eras))]
+        pub fn set_history_depth(
+            origin: OriginFor<T>,
+            #[pallet::compact] new: EraIndex,
+            #[pallet::compact] pruned_eras: EraIndex,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(!new.is_zero(), Error::<T>::ZeroHistoryDepth);
+
+            let old = Self::history_depth();
+            if new < old {
+                if let Some(active_era) = Self::active_era().map(|e| e.index) {
+                    if let Some(last_stale_era) = active_era.checked_sub(new) {
+                        let first_stale_era = active_era.saturating_sub(old);
+                        ensure!(
+                            last_stale_era - first_stale_era < pruned_eras,
+                            Error::<T>::TooManyPrunedEras
+                        );
+                        for era in first_stale_era..=last_stale_era {
+                            Self::clear_era_history(era);
+                        }
+                    }
+                }
+            }
+
+            HistoryDepth::<T>::put(new);
+            Ok(())
+        }
+
         #[pallet::weight(10_000_000)]
         pub fn force_set_lock(
             origin: OriginFor<T>,
@@ -457,6 +581,12 @@ pub mod pallet {
         ForceChilled(SessionIndex, Vec<T::AccountId>),
         /// Unlock the unbonded withdrawal by force. [account]
         ForceAllWithdrawn(T::AccountId),
+        /// A nominator slash was reported and deferred to the era. [validator, fraction, apply_era]
+        SlashDeferred(T::AccountId, Perbill, EraIndex),
+        /// A deferred nominator slash was cancelled. [era, validator]
+        SlashCancelled(EraIndex, T::AccountId),
+        /// The nomination of a nominator was slashed due to the offence of validator. [nominator, validator, amount]
+        NominatorSlashed(T::AccountId, T::AccountId, BalanceOf<T>),
     }
 
     /// Old name generated by `decl_event`.
@@ -506,6 +636,18 @@ pub mod pallet {
         XssCheckFailed,
         /// Failed to allocate the dividend.
         AllocateDividendFailed,
+        /// No slash indices were given.
+        EmptyTargets,
+        /// The slash indices must be sorted and unique.
+        NotSortedAndUnique,
+        /// Can not find the unapplied slash given the index.
+        InvalidSlashIndex,
+        /// Too many slash indices were given.
+        TooManySlashIndices,
+        /// The history depth can not be zero.
+        ZeroHistoryDepth,
+        /// The new history depth removes more eras than the given bound.
+        TooManyPrunedEras,
     }
 
     /// The ideal number of staking participants.
@@ -634,6 +776,11 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The index of `Nominations` by the nominee, i.e., all the nominators of a validator.
+    #[pallet::storage]
+    pub type ValidatorNominators<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, ()>;
+
     /// The map from nominator to the block number of last `rebond` operation.
     #[pallet::storage]
     #[pallet::getter(fn last_rebond_of)]
@@ -676,6 +823,37 @@ pub mod pallet {
     #[pallet::getter(fn eras_start_session_index)]
     pub type ErasStartSessionIndex<T: Config> = StorageMap<_, Twox64Concat, EraIndex, SessionIndex>;
 
+    #[pallet::type_value]
+    pub fn DefaultForHistoryDepth() -> EraIndex {
+        DEFAULT_HISTORY_DEPTH
+    }
+
+    /// Number of eras to keep in the era history.
+    #[pallet::storage]
+    #[pallet::getter(fn history_depth)]
+    pub type HistoryDepth<T: Config> =
+        StorageValue<_, EraIndex, ValueQuery, DefaultForHistoryDepth>;
+
+    /// Stake snapshot and the reward/penalty summary of each validator for the last
+    /// `HistoryDepth` eras.
+    #[pallet::storage]
+    #[pallet::getter(fn eras_validator_stats)]
+    pub type ErasValidatorStats<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        EraIndex,
+        Twox64Concat,
+        T::AccountId,
+        EraValidatorStats<BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// How the session rewards were split for the last `HistoryDepth` eras.
+    #[pallet::storage]
+    #[pallet::getter(fn eras_reward_split)]
+    pub type ErasRewardSplit<T: Config> =
+        StorageMap<_, Twox64Concat, EraIndex, EraRewardSplit<BalanceOf<T>>, ValueQuery>;
+
     #[pallet::type_value]
     pub fn DefaultForIsCurrentSessionFinal() -> bool {
         false
@@ -705,6 +883,56 @@ pub mod pallet {
     #[pallet::getter(fn immortals)]
     pub(super) type Immortals<T: Config> = StorageValue<_, Vec<T::AccountId>>;
 
+    /// Whether the nominations of the severe offenders will be slashed too.
+    #[pallet::storage]
+    #[pallet::getter(fn nominator_slash_enabled)]
+    pub type NominatorSlashEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultForSlashDeferDuration() -> EraIndex {
+        DEFAULT_SLASH_DEFER_DURATION
+    }
+
+    /// Number of eras that the nominator slashes are deferred by, after computation.
+    ///
+    /// This should be long enough for `SlashCancelOrigin` to cancel the slash if necessary.
+    #[pallet::storage]
+    #[pallet::getter(fn slash_defer_duration)]
+    pub type SlashDeferDuration<T: Config> =
+        StorageValue<_, EraIndex, ValueQuery, DefaultForSlashDeferDuration>;
+
+    /// All the unapplied nominator slashes that are queued for later, keyed by the era to apply.
+    #[pallet::storage]
+    #[pallet::getter(fn unapplied_slashes)]
+    pub type UnappliedSlashes<T: Config> =
+        StorageMap<_, Twox64Concat, EraIndex, Vec<UnappliedSlash<T::AccountId>>, ValueQuery>;
+
+    /// The highest nominator slash fraction reported for each validator in an era.
+    ///
+    /// A repeated report of the same validator in the same era only slashes the excess.
+    #[pallet::storage]
+    #[pallet::getter(fn nominator_slash_in_era)]
+    pub type NominatorSlashInEra<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        EraIndex,
+        Twox64Concat,
+        T::AccountId,
+        Perbill,
+        ValueQuery,
+    >;
+
+    /// The due nominator slashes being applied, at most `MAX_SLASHED_NOMINATIONS_PER_BLOCK`
+    /// nominations are slashed per block.
+    #[pallet::storage]
+    #[pallet::getter(fn applying_slashes)]
+    pub type ApplyingSlashes<T: Config> =
+        StorageValue<_, Vec<UnappliedSlash<T::AccountId>>, ValueQuery>;
+
+    /// The last slashed nominator of the first slash in `ApplyingSlashes`.
+    #[pallet::storage]
+    pub(super) type SlashCursor<T: Config> = StorageValue<_, T::AccountId>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub validator_count: u32,
@@ -964,6 +1192,16 @@ impl<T: Config> Pallet<T> {
             Nominations::<T>::mutate(&who, &target, |nominator| {
                 nominator.unbonded_chunks.clear();
             });
+            Self::prune_validator_nominator(who, &target);
+        }
+    }
+
+    /// Removes `nominator` from the `ValidatorNominators` of `validator` once nothing is left
+    /// to slash, i.e., neither nomination nor unbonded chunk.
+    pub(crate) fn prune_validator_nominator(nominator: &T::AccountId, validator: &T::AccountId) {
+        let ledger = Nominations::<T>::get(nominator, validator);
+        if ledger.nomination.is_zero() && ledger.unbonded_chunks.is_empty() {
+            ValidatorNominators::<T>::remove(validator, nominator);
         }
     }
 
@@ -213,6 +213,7 @@ impl Config for Test {
     type TreasuryAccount = DummyTreasuryAccount;
     type DetermineRewardPotAccount = DummyStakingRewardPotAccountDeterminer;
     type ValidatorRegistration = Registration;
+    type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
@@ -5,6 +5,8 @@ use super::*;
 use micromath::F32Ext;
 use sp_std::vec::Vec;
 
+use chainx_primitives::AssetId;
+
 mod proposal09;
 
 impl<T: Config> Pallet<T> {
@@ -82,6 +84,66 @@ impl<T: Config> Pallet<T> {
         Self::apply_reward_validator(validator, reward);
     }
 
+    /// Returns the number of whole sessions in the next `blocks`.
+    fn sessions_in(blocks: T::BlockNumber) -> u128 {
+        let session_duration = T::SessionDuration::get().saturated_into::<u128>().max(1);
+        blocks.saturated_into::<u128>() / session_duration
+    }
+
+    /// Returns the estimated (staking_reward, asset_mining_reward) per session given
+    /// the current issuance and distribution ratios.
+    fn estimated_session_rewards() -> (BalanceOf<T>, BalanceOf<T>) {
+        let (_, mining_reward) =
+            Self::global_distribution_ratio().calc_rewards::<T>(Self::this_session_reward());
+
+        let mining_distribution = Self::mining_distribution_ratio();
+        let staking_reward = mining_distribution.calc_staking_reward::<T>(mining_reward);
+        let max_asset_mining_reward = mining_reward - staking_reward;
+        let asset_mining_reward = mining_distribution
+            .has_treasury_extra::<T>(max_asset_mining_reward)
+            .map(|treasury_extra| max_asset_mining_reward - treasury_extra)
+            .unwrap_or(max_asset_mining_reward);
+
+        (staking_reward, asset_mining_reward)
+    }
+
+    /// Returns the estimated reward issued to the reward pot of an active validator
+    /// in the next `blocks`, given its total votes and the total votes of all active validators.
+    pub(crate) fn estimated_reward_pot_income(
+        validator_votes: BalanceOf<T>,
+        total_staked: BalanceOf<T>,
+        blocks: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        if total_staked.is_zero() {
+            return Zero::zero();
+        }
+        let (staking_reward, _) = Self::estimated_session_rewards();
+        let reward =
+            Self::calc_individual_staking_reward(staking_reward, validator_votes, total_staked);
+        // Only 80% of the validator reward goes to the reward pot.
+        let to_reward_pot = reward - reward / 5u32.saturated_into();
+        to_reward_pot.saturating_mul(Self::sessions_in(blocks).saturated_into())
+    }
+
+    /// Returns the estimated reward issued to the reward pot of `asset_id` in the next `blocks`.
+    pub fn estimated_asset_mining_reward(
+        asset_id: AssetId,
+        blocks: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        let asset_mining_info = T::AssetMining::asset_mining_power();
+        let total_power: u128 = asset_mining_info.iter().map(|(_, power)| power).sum();
+        let power = asset_mining_info
+            .into_iter()
+            .find_map(|(id, power)| if id == asset_id { Some(power) } else { None })
+            .unwrap_or_default();
+        if total_power.is_zero() || power.is_zero() {
+            return Zero::zero();
+        }
+        let (_, asset_mining_reward) = Self::estimated_session_rewards();
+        Self::generic_calculate_by_proportion(asset_mining_reward, power, total_power)
+            .saturating_mul(Self::sessions_in(blocks).saturated_into())
+    }
+
     /// Distribute the session reward to all the receivers, returns the total reward for validators.
     pub(crate) fn distribute_session_reward() -> Vec<(T::AccountId, BalanceOf<T>)> {
         let session_reward = Self::this_session_reward();
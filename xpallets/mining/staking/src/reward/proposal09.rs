@@ -6,7 +6,7 @@ use super::*;
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
-    fn generic_calculate_by_proportion<S: Into<u128>>(
+    pub(super) fn generic_calculate_by_proportion<S: Into<u128>>(
         total_reward: BalanceOf<T>,
         mine: S,
         total: S,
@@ -28,7 +28,7 @@ impl<T: Config> Pallet<T> {
     }
 
     /// Calculates the individual reward according to the proportion and total reward.
-    fn calc_individual_staking_reward(
+    pub(super) fn calc_individual_staking_reward(
         total_reward: BalanceOf<T>,
         my_stake: BalanceOf<T>,
         total_stake: BalanceOf<T>,
@@ -98,6 +98,7 @@ impl<T: Config> Pallet<T> {
                     let reward =
                         Self::calc_individual_staking_reward(total_reward, stake, total_stake);
                     Self::reward_active_validator(&validator, reward);
+                    Self::note_validator_reward(&validator, reward);
                     total_stake -= stake;
                     total_reward -= reward;
                     Some((validator, reward))
@@ -119,11 +120,15 @@ impl<T: Config> Pallet<T> {
         let max_asset_mining_reward = total - staking_reward;
 
         let validator_rewards = Self::distribute_to_active_validators(staking_reward);
+        let staking_paid = validator_rewards
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, (_, x)| acc + *x);
 
         let real_asset_mining_reward = if let Some(treasury_extra) =
             mining_distribution.has_treasury_extra::<T>(max_asset_mining_reward)
         {
             Self::mint(treasury_account, treasury_extra);
+            Self::note_reward_split(|split| split.treasury += treasury_extra);
             max_asset_mining_reward - treasury_extra
         } else {
             max_asset_mining_reward
@@ -140,6 +145,12 @@ impl<T: Config> Pallet<T> {
             Self::mint(treasury_account, unpaid_asset_mining_reward);
         }
 
+        Self::note_reward_split(|split| {
+            split.staking += staking_paid;
+            split.asset_mining += real_asset_mining_reward - unpaid_asset_mining_reward;
+            split.treasury += unpaid_asset_mining_reward;
+        });
+
         validator_rewards
     }
 
@@ -158,6 +169,7 @@ impl<T: Config> Pallet<T> {
             T::TreasuryAccount::treasury_account().expect("TreasuryAccount is some; qed");
         if !treasury_reward.is_zero() {
             Self::mint(&treasury_account, treasury_reward);
+            Self::note_reward_split(|split| split.treasury += treasury_reward);
         }
 
         // -> Mining
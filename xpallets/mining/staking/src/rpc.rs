@@ -1,17 +1,26 @@
 // Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
 #![allow(clippy::type_complexity)]
-use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec,
+    vec::Vec,
+};
 
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    RuntimeDebug,
+};
 
-use xp_mining_common::RewardPotAccountFor;
+use xp_mining_common::{Delta, RewardPotAccountFor, SimulatedMiningWeight};
+use xp_mining_staking::SessionIndex;
 
 use crate::{
-    types::*, BalanceOf, Config, LastRebondOf, Nominations, Pallet, SessionInterface,
+    types::*, BalanceOf, Config, EraIndex, ErasRewardSplit, ErasStartSessionIndex,
+    ErasValidatorStats, LastRebondOf, Nominations, Pallet, SessionInterface, UnappliedSlashes,
     ValidatorLedgers, Validators,
 };
 
@@ -45,6 +54,83 @@ pub struct NominatorInfo<BlockNumber> {
     pub last_rebond: Option<BlockNumber>,
 }
 
+/// Stake snapshot and the reward/penalty summary of a validator in an era.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EraValidatorInfo<AccountId, Balance> {
+    /// AccountId of the validator.
+    pub account: AccountId,
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub stats: EraValidatorStats<Balance>,
+}
+
+/// Total history of an era.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EraHistory<AccountId, Balance> {
+    /// Index of the era.
+    pub era: EraIndex,
+    /// Session index at which the era started.
+    pub start_session_index: Option<SessionIndex>,
+    /// How the session rewards issued in this era were split.
+    pub reward_split: EraRewardSplit<Balance>,
+    /// All the validators that were active or rewarded in this era.
+    pub validators: Vec<EraValidatorInfo<AccountId, Balance>>,
+}
+
+/// A hypothetical change of the nominations, used for the dividend simulation.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum NominationChange<AccountId, Balance> {
+    /// Bond `value` to `target`.
+    Bond { target: AccountId, value: Balance },
+    /// Unbond `value` from `target`.
+    Unbond { target: AccountId, value: Balance },
+    /// Move `value` of the nomination from `from` to `to`.
+    Rebond {
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+    },
+}
+
+impl<AccountId, Balance> NominationChange<AccountId, Balance> {
+    /// Converts the balance type of this change, e.g., from the RPC balance.
+    pub fn map_balance<B, F: Fn(Balance) -> B>(self, f: F) -> NominationChange<AccountId, B> {
+        match self {
+            Self::Bond { target, value } => NominationChange::Bond {
+                target,
+                value: f(value),
+            },
+            Self::Unbond { target, value } => NominationChange::Unbond {
+                target,
+                value: f(value),
+            },
+            Self::Rebond { from, to, value } => NominationChange::Rebond {
+                from,
+                to,
+                value: f(value),
+            },
+        }
+    }
+}
+
+/// Estimated staking dividend of a nominator to a validator.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct StakingDividendEstimate<Balance> {
+    /// Dividend that can be claimed right now.
+    pub current: Balance,
+    /// Projected dividend at the end of the horizon if nothing changes.
+    pub projected: Balance,
+    /// Projected dividend at the end of the horizon if the change is applied right now.
+    pub simulated: Balance,
+}
+
 impl<T: Config> Pallet<T> {
     pub fn validators_info(
     ) -> Vec<ValidatorInfo<T::AccountId, BalanceOf<T>, VoteWeight, T::BlockNumber>> {
@@ -96,4 +182,129 @@ impl<T: Config> Pallet<T> {
         let last_rebond = LastRebondOf::<T>::get(&who);
         NominatorInfo { last_rebond }
     }
+
+    pub fn pending_slashes() -> BTreeMap<EraIndex, Vec<UnappliedSlash<T::AccountId>>> {
+        UnappliedSlashes::<T>::iter().collect()
+    }
+
+    /// Returns the history of `era`, or None if it has been pruned or never recorded.
+    pub fn era_history(era: EraIndex) -> Option<EraHistory<T::AccountId, BalanceOf<T>>> {
+        let validators = ErasValidatorStats::<T>::iter_prefix(era)
+            .map(|(account, stats)| EraValidatorInfo { account, stats })
+            .collect::<Vec<_>>();
+        if validators.is_empty() && !ErasRewardSplit::<T>::contains_key(era) {
+            return None;
+        }
+        Some(EraHistory {
+            era,
+            start_session_index: ErasStartSessionIndex::<T>::get(era),
+            reward_split: ErasRewardSplit::<T>::get(era),
+            validators,
+        })
+    }
+
+    /// Projects the staking dividends of `who` at `horizon` blocks later, with and without `change`.
+    ///
+    /// The reward pot of each active validator is assumed to receive its share of the session
+    /// reward given the current issuance, and nobody else changes the nominations meanwhile.
+    pub fn simulate_staking_dividend(
+        who: T::AccountId,
+        change: NominationChange<T::AccountId, BalanceOf<T>>,
+        horizon: T::BlockNumber,
+    ) -> BTreeMap<T::AccountId, StakingDividendEstimate<BalanceOf<T>>> {
+        let current_block = <frame_system::Pallet<T>>::block_number();
+        let future_block = current_block.saturating_add(horizon);
+
+        let deltas = Self::nomination_deltas(&who, change);
+
+        let active_votes = Self::active_validator_votes().collect::<BTreeMap<_, _>>();
+        let total_staked = active_votes
+            .values()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc + *x);
+        let simulated_total_staked = deltas
+            .iter()
+            .filter(|(validator, _)| active_votes.contains_key(validator))
+            .fold(total_staked, |acc, (_, delta)| delta.calculate(acc));
+
+        let project = |validator: &T::AccountId,
+                       nominator_weight: &SimulatedMiningWeight<BalanceOf<T>, T::BlockNumber>,
+                       validator_weight: &SimulatedMiningWeight<BalanceOf<T>, T::BlockNumber>,
+                       total_staked: BalanceOf<T>| {
+            let reward_pot_balance = Self::free_balance(&Self::reward_pot_for(validator));
+            let income = if active_votes.contains_key(validator) {
+                Self::estimated_reward_pot_income(validator_weight.amount, total_staked, horizon)
+            } else {
+                Zero::zero()
+            };
+            nominator_weight.dividend_at(
+                validator_weight,
+                future_block,
+                reward_pot_balance.saturating_add(income),
+            )
+        };
+
+        Nominations::<T>::iter_prefix(&who)
+            .map(|(validator, _)| validator)
+            .chain(deltas.keys().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|validator| {
+                let mut nominator_weight =
+                    SimulatedMiningWeight::from_weight(&Nominations::<T>::get(&who, &validator));
+                let mut validator_weight =
+                    SimulatedMiningWeight::from_weight(&ValidatorLedgers::<T>::get(&validator));
+
+                let current =
+                    Self::compute_dividend_at(&who, &validator, current_block).unwrap_or_default();
+                let projected = project(
+                    &validator,
+                    &nominator_weight,
+                    &validator_weight,
+                    total_staked,
+                );
+
+                if let Some(delta) = deltas.get(&validator) {
+                    nominator_weight.apply(current_block, delta);
+                    validator_weight.apply(current_block, delta);
+                }
+                let simulated = project(
+                    &validator,
+                    &nominator_weight,
+                    &validator_weight,
+                    simulated_total_staked,
+                );
+
+                (
+                    validator,
+                    StakingDividendEstimate {
+                        current,
+                        projected,
+                        simulated,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the nomination changes of each validator given the hypothetical `change`,
+    /// the unbonded value is capped by the current nomination.
+    fn nomination_deltas(
+        who: &T::AccountId,
+        change: NominationChange<T::AccountId, BalanceOf<T>>,
+    ) -> BTreeMap<T::AccountId, Delta<BalanceOf<T>>> {
+        match change {
+            NominationChange::Bond { target, value } => vec![(target, Delta::Add(value))],
+            NominationChange::Unbond { target, value } => {
+                let value = value.min(Self::bonded_to(who, &target));
+                vec![(target, Delta::Sub(value))]
+            }
+            NominationChange::Rebond { from, to, value } if from != to => {
+                let value = value.min(Self::bonded_to(who, &from));
+                vec![(from, Delta::Sub(value)), (to, Delta::Add(value))]
+            }
+            NominationChange::Rebond { .. } => Vec::new(),
+        }
+        .into_iter()
+        .collect()
+    }
 }
@@ -3,6 +3,8 @@
 use sp_std::ops::Mul;
 use sp_std::vec::Vec;
 
+use frame_support::traits::Imbalance;
+
 use super::*;
 
 impl<T: Config> Pallet<T> {
@@ -56,7 +58,8 @@ impl<T: Config> Pallet<T> {
                     .unwrap_or(base_slash)
                     .max(minimum_penalty);
                 match slasher.try_slash(&offender, penalty) {
-                    SlashOutcome::Slashed(_) => {
+                    SlashOutcome::Slashed(actual_slashed) => {
+                        Self::note_validator_penalty(&offender, actual_slashed);
                         debug!(
                             target: "runtime::mining::staking",
                             "Slash the offender:{:?} for penalty {:?} by the given slash_fraction:{:?} successfully",
@@ -65,6 +68,7 @@ impl<T: Config> Pallet<T> {
                         None
                     }
                     SlashOutcome::InsufficientSlash(actual_slashed) => {
+                        Self::note_validator_penalty(&offender, actual_slashed);
                         debug!(
                             target: "runtime::mining::staking",
                             "Insufficient reward pot balance of {:?}, actual slashed:{:?}",
@@ -88,4 +92,220 @@ impl<T: Config> Pallet<T> {
             })
             .collect()
     }
+
+    /// Queues the nominator slashes of the severe offenders if the nominator slash is enabled.
+    ///
+    /// The slashes are applied at the beginning of era `active_era + SlashDeferDuration`,
+    /// or from the next block if there is no deferring. Only the highest fraction reported
+    /// for a validator in an era is slashed, the repeated reports only slash the excess.
+    pub(crate) fn defer_nominator_slashes(offenders: &BTreeMap<&T::AccountId, &Perbill>) {
+        if !Self::nominator_slash_enabled() {
+            return;
+        }
+
+        let active_era = Self::active_era().map(|e| e.index).unwrap_or(0);
+        let defer_duration = Self::slash_defer_duration();
+        let apply_era = active_era.saturating_add(defer_duration);
+
+        for (&offender, &&fraction) in offenders.iter() {
+            let reported = Self::nominator_slash_in_era(active_era, offender);
+            if fraction <= reported {
+                continue;
+            }
+            NominatorSlashInEra::<T>::insert(active_era, offender, fraction);
+
+            let unapplied = UnappliedSlash {
+                validator: offender.clone(),
+                fraction: fraction.saturating_sub(reported),
+                reported_era: active_era,
+            };
+            if defer_duration.is_zero() {
+                ApplyingSlashes::<T>::append(unapplied);
+            } else {
+                Self::deposit_event(Event::<T>::SlashDeferred(
+                    offender.clone(),
+                    unapplied.fraction,
+                    apply_era,
+                ));
+                UnappliedSlashes::<T>::append(apply_era, unapplied);
+            }
+        }
+    }
+
+    /// Queues all the nominator slashes that are due in era `era` for applying.
+    pub(crate) fn apply_unapplied_slashes(era: EraIndex) {
+        let due = UnappliedSlashes::<T>::take(era);
+        if !due.is_empty() {
+            ApplyingSlashes::<T>::mutate(|applying| applying.extend(due));
+        }
+    }
+
+    /// Returns the weight of slashing a single nomination.
+    ///
+    /// Nominations, ValidatorLedgers, Locks, the balance lock, the nominator account,
+    /// the treasury account and the era history are read and written.
+    pub(crate) fn slash_nomination_weight() -> Weight {
+        T::DbWeight::get().reads_writes(9, 8)
+    }
+
+    /// Returns the weight of cancelling `count` deferred slashes.
+    ///
+    /// UnappliedSlashes is read and written once, NominatorSlashInEra once per slash.
+    pub(crate) fn cancel_deferred_slash_weight(count: u32) -> Weight {
+        T::DbWeight::get().reads_writes(1, 1).saturating_add(
+            T::DbWeight::get()
+                .reads_writes(1, 1)
+                .saturating_mul(count as Weight),
+        )
+    }
+
+    /// Applies the slashes in `ApplyingSlashes` in order, slashing at most `limit` nominations.
+    ///
+    /// Returns the number of the slashed nominations, the remaining ones are slashed in the
+    /// following blocks starting from the nominator after `SlashCursor`.
+    pub(crate) fn apply_nominator_slashes(limit: u32) -> u32 {
+        let treasury_account =
+            T::TreasuryAccount::treasury_account().expect("TreasuryAccount is some; qed");
+
+        let mut applying = Self::applying_slashes();
+        let mut slashed = 0u32;
+        while slashed < limit && !applying.is_empty() {
+            let UnappliedSlash {
+                validator,
+                fraction,
+                ..
+            } = applying[0].clone();
+
+            let remaining = (limit - slashed) as usize;
+            let mut nominators = match SlashCursor::<T>::take() {
+                Some(last) => ValidatorNominators::<T>::iter_key_prefix_from(
+                    &validator,
+                    ValidatorNominators::<T>::hashed_key_for(&validator, &last),
+                )
+                .take(remaining + 1)
+                .collect::<Vec<_>>(),
+                None => ValidatorNominators::<T>::iter_key_prefix(&validator)
+                    .take(remaining + 1)
+                    .collect::<Vec<_>>(),
+            };
+            let finished = nominators.len() <= remaining;
+            nominators.truncate(remaining);
+
+            for nominator in nominators.iter() {
+                Self::slash_nomination(nominator, &validator, fraction, &treasury_account);
+            }
+            slashed += nominators.len() as u32;
+
+            if finished {
+                applying.remove(0);
+            } else if let Some(last) = nominators.pop() {
+                SlashCursor::<T>::put(last);
+            }
+        }
+
+        if applying.is_empty() {
+            ApplyingSlashes::<T>::kill();
+        } else {
+            ApplyingSlashes::<T>::put(applying);
+        }
+        slashed
+    }
+
+    /// Slashes `fraction` of the nomination of `nominator` to the offender `validator`.
+    ///
+    /// The bonded nomination is slashed first, then the still locked unbonded chunks,
+    /// so that unbonding right after the offence can not escape the slashing.
+    fn slash_nomination(
+        nominator: &T::AccountId,
+        validator: &T::AccountId,
+        fraction: Perbill,
+        treasury_account: &T::AccountId,
+    ) {
+        let ledger = Nominations::<T>::get(nominator, validator);
+        let total_unbonding = ledger
+            .unbonded_chunks
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc + x.value);
+        let expected_slash = fraction.mul(ledger.nomination + total_unbonding);
+        if expected_slash.is_zero() {
+            return;
+        }
+
+        let from_bonded = expected_slash.min(ledger.nomination);
+        let mut from_unbonding = expected_slash - from_bonded;
+
+        if !from_bonded.is_zero() {
+            Self::update_vote_weight(nominator, validator, Delta::Sub(from_bonded));
+        }
+
+        if !from_unbonding.is_zero() {
+            Nominations::<T>::mutate(nominator, validator, |nominator_ledger| {
+                for chunk in nominator_ledger.unbonded_chunks.iter_mut() {
+                    let slashed = from_unbonding.min(chunk.value);
+                    chunk.value -= slashed;
+                    from_unbonding -= slashed;
+                    if from_unbonding.is_zero() {
+                        break;
+                    }
+                }
+                nominator_ledger
+                    .unbonded_chunks
+                    .retain(|chunk| !chunk.value.is_zero());
+            });
+            Self::prune_validator_nominator(nominator, validator);
+        }
+
+        let slashed_unbonding = expected_slash - from_bonded;
+        Locks::<T>::mutate(nominator, |locks| {
+            if let Some(bonded) = locks.get_mut(&LockedType::Bonded) {
+                *bonded = bonded.saturating_sub(from_bonded);
+            }
+            if let Some(unbonding) = locks.get_mut(&LockedType::BondedWithdrawal) {
+                *unbonding = unbonding.saturating_sub(slashed_unbonding);
+            }
+            locks.retain(|_, locked| !locked.is_zero());
+
+            let staking_locked = locks
+                .values()
+                .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc + *x);
+            Self::set_lock(nominator, staking_locked);
+        });
+
+        // The staking lock has been reduced above, the slashed balance is moved to the treasury.
+        let (imbalance, _) = T::Currency::slash(nominator, expected_slash);
+        let actual_slashed = imbalance.peek();
+        T::Currency::resolve_creating(treasury_account, imbalance);
+        Self::note_nominator_slash(validator, actual_slashed);
+
+        debug!(
+            target: "runtime::mining::staking",
+            "Slash the nominator:{:?} of offender:{:?} for {:?}, actual slashed:{:?}",
+            nominator, validator, expected_slash, actual_slashed
+        );
+
+        Self::deposit_event(Event::<T>::NominatorSlashed(
+            nominator.clone(),
+            validator.clone(),
+            actual_slashed,
+        ));
+    }
+
+    /// Builds the `ValidatorNominators` index of the existing nominations, skipping the
+    /// emptied ones.
+    pub fn migrate_to_v1() -> Weight {
+        if Self::on_chain_storage_version() >= STORAGE_VERSION {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut count: Weight = 0;
+        for (nominator, validator, ledger) in Nominations::<T>::iter() {
+            if !ledger.nomination.is_zero() || !ledger.unbonded_chunks.is_empty() {
+                ValidatorNominators::<T>::insert(&validator, &nominator, ());
+            }
+            count += 1;
+        }
+        STORAGE_VERSION.put::<Self>();
+
+        T::DbWeight::get().reads_writes(1 + count, 1 + count)
+    }
 }
@@ -2,7 +2,8 @@
 
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_err, assert_ok, traits::OnInitialize};
+use frame_support::{assert_err, assert_ok, traits::OnInitialize, weights::Weight};
+use sp_staking::offence::{DisableStrategy, OffenceDetails, OnOffenceHandler};
 
 fn t_issue_pcx(to: AccountId, value: Balance) {
     XStaking::mint(&to, value);
@@ -58,7 +59,7 @@ fn t_start_session(session_index: SessionIndex) {
         System::set_block_number((i + 1).into());
         Timestamp::set_timestamp(System::block_number() * 1000 + INIT_TIMESTAMP);
         Session::on_initialize(System::block_number());
-        // XStaking::on_initialize(System::block_number());
+        XStaking::on_initialize(System::block_number());
     }
 
     assert_eq!(Session::current_index(), session_index);
@@ -718,6 +719,319 @@ fn slash_should_work() {
     });
 }
 
+fn t_report_equivocation(offender: AccountId, fraction: Perbill) {
+    let _ = <XStaking as OnOffenceHandler<AccountId, (AccountId, AccountId), Weight>>::on_offence(
+        &[OffenceDetails {
+            offender: (offender, offender),
+            reporters: vec![],
+        }],
+        &[fraction],
+        Session::current_index(),
+        DisableStrategy::WhenSlashed,
+    );
+}
+
+#[test]
+fn deferred_nominator_slash_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let nominator = 1111;
+        t_issue_pcx(nominator, 100);
+        assert_ok!(t_bond(nominator, 1, 50));
+        assert_ok!(t_unbond(nominator, 1, 10));
+
+        // The nominations are not at risk by default.
+        t_report_equivocation(1, Perbill::from_percent(10));
+        assert_eq!(UnappliedSlashes::<Test>::iter().count(), 0);
+
+        assert_ok!(XStaking::set_nominator_slash_enabled(Origin::root(), true));
+        assert_ok!(XStaking::set_slash_defer_duration(Origin::root(), 1));
+        let apply_era: EraIndex = 1;
+        t_report_equivocation(1, Perbill::from_percent(10));
+        assert_eq!(
+            XStaking::unapplied_slashes(apply_era),
+            vec![UnappliedSlash {
+                validator: 1,
+                fraction: Perbill::from_percent(10),
+                reported_era: 0,
+            }]
+        );
+        assert_eq!(<Nominations<Test>>::get(nominator, 1).nomination, 40);
+
+        t_start_session(3);
+        assert_eq!(UnappliedSlashes::<Test>::iter().count(), 0);
+
+        // (40 + 10) * 10% = 5, slashed from the bonded nomination first.
+        assert_eq!(<Nominations<Test>>::get(nominator, 1).nomination, 35);
+        assert_bonded_locks(nominator, 35);
+        assert_bonded_withdrawal_locks(nominator, 10);
+        assert_eq!(Balances::free_balance(nominator), 95);
+
+        // The self-bonded balance of validator is slashed as well.
+        assert_eq!(<Nominations<Test>>::get(1, 1).nomination, 9);
+        assert_eq!(<ValidatorLedgers<Test>>::get(1).total_nomination, 35 + 9);
+
+        // The nominator slashes are recorded in the era history.
+        assert_eq!(
+            XStaking::eras_validator_stats(1, 1).nominator_slashed,
+            5 + 1
+        );
+    });
+}
+
+#[test]
+fn cancel_deferred_slash_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XStaking::set_nominator_slash_enabled(Origin::root(), true));
+        assert_ok!(XStaking::set_slash_defer_duration(Origin::root(), 1));
+        let apply_era: EraIndex = 1;
+        t_report_equivocation(1, Perbill::from_percent(10));
+        t_report_equivocation(2, Perbill::from_percent(10));
+        assert_eq!(XStaking::unapplied_slashes(apply_era).len(), 2);
+
+        assert_err!(
+            XStaking::cancel_deferred_slash(Origin::signed(1), apply_era, vec![0]),
+            sp_runtime::traits::BadOrigin
+        );
+        assert_err!(
+            XStaking::cancel_deferred_slash(Origin::root(), apply_era, vec![1, 0]),
+            Error::<Test>::NotSortedAndUnique
+        );
+        assert_err!(
+            XStaking::cancel_deferred_slash(Origin::root(), apply_era, vec![2]),
+            Error::<Test>::InvalidSlashIndex
+        );
+        assert_err!(
+            XStaking::cancel_deferred_slash(
+                Origin::root(),
+                apply_era,
+                (0..=MAX_CANCELLED_SLASHES).collect()
+            ),
+            Error::<Test>::TooManySlashIndices
+        );
+        assert_ok!(XStaking::cancel_deferred_slash(
+            Origin::root(),
+            apply_era,
+            vec![0]
+        ));
+        assert_eq!(
+            XStaking::unapplied_slashes(apply_era)
+                .into_iter()
+                .map(|slash| slash.validator)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        t_start_session(3);
+        assert_eq!(<Nominations<Test>>::get(1, 1).nomination, 10);
+        assert_eq!(<Nominations<Test>>::get(2, 2).nomination, 18);
+    });
+}
+
+#[test]
+fn repeated_nominator_slash_should_be_deduplicated() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XStaking::set_nominator_slash_enabled(Origin::root(), true));
+        assert_ok!(XStaking::set_slash_defer_duration(Origin::root(), 1));
+        let apply_era: EraIndex = 1;
+        t_report_equivocation(1, Perbill::from_percent(10));
+        t_report_equivocation(1, Perbill::from_percent(10));
+        t_report_equivocation(1, Perbill::from_percent(5));
+        assert_eq!(XStaking::unapplied_slashes(apply_era).len(), 1);
+
+        // Only the excess of a higher fraction is queued.
+        t_report_equivocation(1, Perbill::from_percent(30));
+        assert_eq!(
+            XStaking::unapplied_slashes(apply_era)
+                .into_iter()
+                .map(|slash| slash.fraction)
+                .collect::<Vec<_>>(),
+            vec![Perbill::from_percent(10), Perbill::from_percent(20)]
+        );
+        assert_eq!(
+            XStaking::nominator_slash_in_era(0, 1),
+            Perbill::from_percent(30)
+        );
+
+        // The cancelled fraction can be reported again.
+        assert_ok!(XStaking::cancel_deferred_slash(
+            Origin::root(),
+            apply_era,
+            vec![1]
+        ));
+        assert_eq!(
+            XStaking::nominator_slash_in_era(0, 1),
+            Perbill::from_percent(10)
+        );
+        t_report_equivocation(1, Perbill::from_percent(20));
+        assert_eq!(XStaking::unapplied_slashes(apply_era).len(), 2);
+    });
+}
+
+#[test]
+fn nominator_slash_should_be_applied_across_blocks() {
+    ExtBuilder::default().build_and_execute(|| {
+        for nominator in [1111, 2222] {
+            t_issue_pcx(nominator, 100);
+            assert_ok!(t_bond(nominator, 1, 40));
+        }
+        assert_ok!(XStaking::set_nominator_slash_enabled(Origin::root(), true));
+        assert_ok!(XStaking::set_slash_defer_duration(Origin::root(), 0));
+        t_report_equivocation(1, Perbill::from_percent(10));
+        assert_eq!(XStaking::applying_slashes().len(), 1);
+
+        // The validator itself, 1111 and 2222 are the nominators of validator 1.
+        assert_eq!(XStaking::apply_nominator_slashes(2), 2);
+        assert_eq!(XStaking::applying_slashes().len(), 1);
+        assert_eq!(XStaking::apply_nominator_slashes(2), 1);
+        assert!(XStaking::applying_slashes().is_empty());
+        assert_eq!(XStaking::apply_nominator_slashes(2), 0);
+
+        assert_eq!(<Nominations<Test>>::get(1111, 1).nomination, 36);
+        assert_eq!(<Nominations<Test>>::get(2222, 1).nomination, 36);
+        assert_eq!(<Nominations<Test>>::get(1, 1).nomination, 9);
+        assert_eq!(
+            <ValidatorLedgers<Test>>::get(1).total_nomination,
+            36 + 36 + 9
+        );
+    });
+}
+
+#[test]
+fn migrate_to_v1_should_index_nominations() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_issue_pcx(1111, 100);
+        assert_ok!(t_bond(1111, 1, 40));
+        assert_ok!(t_bond(1111, 2, 40));
+        let _ = ValidatorNominators::<Test>::remove_all(None);
+        StorageVersion::new(0).put::<XStaking>();
+
+        XStaking::migrate_to_v1();
+        assert_eq!(XStaking::on_chain_storage_version(), STORAGE_VERSION);
+        let mut nominators = ValidatorNominators::<Test>::iter_key_prefix(1).collect::<Vec<_>>();
+        nominators.sort_unstable();
+        assert_eq!(nominators, vec![1, 1111]);
+        assert!(ValidatorNominators::<Test>::contains_key(2, 1111));
+    });
+}
+
+#[test]
+fn validator_nominators_should_be_pruned() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_issue_pcx(1111, 100);
+        assert_ok!(t_bond(1111, 1, 40));
+        assert!(ValidatorNominators::<Test>::contains_key(1, 1111));
+
+        // The unbonded chunk can still be slashed.
+        assert_ok!(t_unbond(1111, 1, 40));
+        assert!(ValidatorNominators::<Test>::contains_key(1, 1111));
+
+        t_system_block_number_inc(DEFAULT_BONDING_DURATION + 1);
+        assert_ok!(t_withdraw_unbonded(1111, 1, 0));
+        assert!(!ValidatorNominators::<Test>::contains_key(1, 1111));
+
+        // Bonding again indexes the nominator again.
+        assert_ok!(t_bond(1111, 1, 10));
+        assert!(ValidatorNominators::<Test>::contains_key(1, 1111));
+    });
+}
+
+#[test]
+fn era_history_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        t_start_session(1);
+
+        // 2_500_000_000 per session, no mining assets in the mock so that the
+        // asset mining reward all goes to the treasury.
+        let treasury_reward = 300_000_000 + 220_000_000;
+        let staking_reward = 1_980_000_000;
+        let history = XStaking::era_history(0).unwrap();
+        assert_eq!(history.start_session_index, Some(0));
+        assert_eq!(
+            history.reward_split,
+            EraRewardSplit {
+                treasury: treasury_reward,
+                staking: staking_reward,
+                asset_mining: 0,
+            }
+        );
+        assert_eq!(
+            XStaking::eras_validator_stats(0, 1).reward,
+            staking_reward / 10
+        );
+
+        // The reward of the last session of era 0 is minted before era 1 starts.
+        t_start_session(3);
+        assert_eq!(XStaking::eras_reward_split(0).staking, staking_reward * 3);
+        assert_eq!(
+            XStaking::eras_validator_stats(1, 4),
+            EraValidatorStats {
+                total_votes: 40,
+                reward: 0,
+                penalty: 0,
+                nominator_slashed: 0,
+            }
+        );
+        assert_eq!(XStaking::era_history(1).unwrap().validators.len(), 4);
+        assert!(XStaking::era_history(2).is_none());
+
+        assert_err!(
+            XStaking::set_history_depth(Origin::root(), 0, 0),
+            Error::<Test>::ZeroHistoryDepth
+        );
+        // Era 0 is pruned by the new depth.
+        assert_err!(
+            XStaking::set_history_depth(Origin::root(), 1, 0),
+            Error::<Test>::TooManyPrunedEras
+        );
+        assert_ok!(XStaking::set_history_depth(Origin::root(), 1, 1));
+        assert!(XStaking::era_history(0).is_none());
+        assert!(XStaking::era_history(1).is_some());
+    });
+}
+
+#[test]
+fn simulate_staking_dividend_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_start_session(1);
+        let nominator = 1111;
+        t_issue_pcx(nominator, 100);
+        assert_ok!(t_bond(nominator, 1, 10));
+        t_system_block_number_inc(5);
+
+        // Shorter than a session, no more reward will be issued to the reward pot.
+        let horizon = 10;
+        let future_block = System::block_number() + horizon;
+        let estimates = XStaking::simulate_staking_dividend(
+            nominator,
+            NominationChange::Bond {
+                target: 1,
+                value: 20,
+            },
+            horizon,
+        );
+        assert_eq!(estimates.len(), 1);
+        let estimate = estimates[&1].clone();
+        assert_eq!(
+            estimate.current,
+            XStaking::compute_dividend_at(&nominator, &1, System::block_number()).unwrap()
+        );
+        assert_eq!(
+            estimate.projected,
+            XStaking::compute_dividend_at(&nominator, &1, future_block).unwrap()
+        );
+        assert!(estimate.simulated > estimate.projected);
+
+        // The estimate matches what the chain actually pays.
+        assert_ok!(t_bond(nominator, 1, 20));
+        t_system_block_number_inc(horizon);
+        assert_eq!(
+            XStaking::compute_dividend_at(&nominator, &1, System::block_number()).unwrap(),
+            estimate.simulated
+        );
+    });
+}
+
 #[test]
 fn mint_should_work() {
     ExtBuilder::default().build_and_execute(|| {
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 
 use sp_runtime::{
     traits::{SaturatedConversion, Saturating},
-    DispatchError, DispatchResult, RuntimeDebug,
+    DispatchError, DispatchResult, Perbill, RuntimeDebug,
 };
 
 use chainx_primitives::{AssetId, ReferralId};
@@ -224,6 +224,48 @@ impl MiningDistribution {
     }
 }
 
+/// A pending slash on the nominations of an offender, applied after `SlashDeferDuration` eras.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UnappliedSlash<AccountId> {
+    /// The offending validator.
+    pub validator: AccountId,
+    /// Proportion of each nomination to the validator that will be slashed.
+    pub fraction: Perbill,
+    /// Era in which the offence was reported.
+    pub reported_era: EraIndex,
+}
+
+/// Stake snapshot and the reward/penalty summary of a validator in an era.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EraValidatorStats<Balance> {
+    /// Total votes of the validator when the era started.
+    pub total_votes: Balance,
+    /// Total reward minted for the validator and its reward pot in this era.
+    pub reward: Balance,
+    /// Total penalty slashed from the reward pot of the validator in this era.
+    pub penalty: Balance,
+    /// Total slashed from the nominations of the validator in this era, including the
+    /// self-bonded one.
+    pub nominator_slashed: Balance,
+}
+
+/// How the session rewards issued in an era were split.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EraRewardSplit<Balance> {
+    /// Minted to the treasury, including the unpaid asset mining reward.
+    pub treasury: Balance,
+    /// Minted to the active validators and their reward pots.
+    pub staking: Balance,
+    /// Minted to the reward pots of the mining assets.
+    pub asset_mining: Balance,
+}
+
 /// Result of performing a slash operation.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum SlashOutcome<Balance> {
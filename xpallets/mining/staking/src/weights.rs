@@ -45,6 +45,7 @@ pub trait WeightInfo {
     fn set_validator_bonding_duration() -> Weight;
     fn set_minimum_penalty() -> Weight;
     fn set_sessions_per_era() -> Weight;
+    fn set_history_depth(e: u32) -> Weight;
 }
 
 /// Weights for xpallet_mining_staking using the Substrate node and recommended hardware.
@@ -108,6 +109,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_sessions_per_era() -> Weight {
         (2_275_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn set_history_depth(e: u32) -> Weight {
+        (4_312_000 as Weight)
+            .saturating_add((27_560_000 as Weight).saturating_mul(e as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((43 as Weight).saturating_mul(e as Weight)))
+    }
 }
 
 // For backwards compatibility and tests
@@ -170,4 +178,11 @@ impl WeightInfo for () {
     fn set_sessions_per_era() -> Weight {
         (2_275_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn set_history_depth(e: u32) -> Weight {
+        (4_312_000 as Weight)
+            .saturating_add((27_560_000 as Weight).saturating_mul(e as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((43 as Weight).saturating_mul(e as Weight)))
+    }
 }
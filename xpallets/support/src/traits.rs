@@ -40,3 +40,37 @@ impl<AccountId> TreasuryAccount<AccountId> for () {
         None
     }
 }
+
+/// The actions of an account which can be restricted, e.g. by the sanctions in XSystem.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RestrictedAction {
+    /// Move the assets out of the account.
+    MoveAsset,
+    /// Withdraw the assets via the gateway.
+    Withdraw,
+    /// Put orders on the DEX.
+    Trade,
+}
+
+/// This trait checks whether an account is restricted from the given action.
+pub trait AccountRestriction<AccountId> {
+    fn is_restricted(who: &AccountId, action: RestrictedAction) -> bool;
+}
+
+impl<AccountId> AccountRestriction<AccountId> for () {
+    fn is_restricted(_: &AccountId, _: RestrictedAction) -> bool {
+        false
+    }
+}
+
+/// This trait checks whether the user operations moving an asset are paused, e.g. by
+/// the emergency pauses in XSystem.
+pub trait AssetPause<AssetId> {
+    fn is_paused(asset_id: &AssetId) -> bool;
+}
+
+impl<AssetId> AssetPause<AssetId> for () {
+    fn is_paused(_: &AssetId) -> bool {
+        false
+    }
+}
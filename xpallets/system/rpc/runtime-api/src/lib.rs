@@ -0,0 +1,23 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Runtime API definition for the system module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use sp_std::prelude::*;
+
+use codec::Codec;
+
+pub use xpallet_system::{AccountRestrictions, Restriction};
+
+sp_api::decl_runtime_apis! {
+    pub trait XSystemApi<AccountId, BlockNumber>
+    where
+        AccountId: Codec,
+        BlockNumber: Codec,
+    {
+        /// Returns the restricted accounts whose restrictions are still in force.
+        fn restricted_accounts() -> Vec<(AccountId, Restriction<BlockNumber>)>;
+    }
+}
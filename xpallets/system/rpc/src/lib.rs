@@ -0,0 +1,92 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! RPC interface for the system module.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use xp_rpc::{runtime_error_into_rpc_err, Result};
+
+use xpallet_system_rpc_runtime_api::{
+    AccountRestrictions, Restriction, XSystemApi as XSystemRuntimeApi,
+};
+
+pub struct XSystem<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> XSystem<C, B> {
+    /// Create new `XSystem` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[rpc]
+pub trait XSystemApi<BlockHash, AccountId, BlockNumber> {
+    /// Return the restricted accounts with their restriction flags, reason and expiry.
+    #[rpc(name = "xsystem_restrictedAccounts")]
+    fn restricted_accounts(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<RpcRestrictedAccount<AccountId, BlockNumber>>>;
+}
+
+impl<C, Block, AccountId, BlockNumber> XSystemApi<<Block as BlockT>::Hash, AccountId, BlockNumber>
+    for XSystem<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: XSystemRuntimeApi<Block, AccountId, BlockNumber>,
+    AccountId: Codec,
+    BlockNumber: Codec,
+{
+    fn restricted_accounts(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<RpcRestrictedAccount<AccountId, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.restricted_accounts(&at)
+            .map(|accounts| {
+                accounts
+                    .into_iter()
+                    .map(|(account, restriction)| (account, restriction).into())
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRestrictedAccount<AccountId, BlockNumber> {
+    pub account: AccountId,
+    pub flags: AccountRestrictions,
+    pub reason: String,
+    pub expiry: Option<BlockNumber>,
+}
+
+impl<AccountId, BlockNumber> From<(AccountId, Restriction<BlockNumber>)>
+    for RpcRestrictedAccount<AccountId, BlockNumber>
+{
+    fn from((account, restriction): (AccountId, Restriction<BlockNumber>)) -> Self {
+        Self {
+            account,
+            flags: restriction.flags,
+            reason: String::from_utf8_lossy(&restriction.reason).into_owned(),
+            expiry: restriction.expiry,
+        }
+    }
+}
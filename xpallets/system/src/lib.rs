@@ -2,23 +2,40 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
 
 use frame_support::{
-    dispatch::{CallMetadata, DispatchResult},
-    traits::Currency,
+    dispatch::{CallMetadata, DispatchResult, Weight},
+    storage::migration::storage_key_iter,
+    traits::{Currency, Get, GetStorageVersion, PalletInfoAccess, StorageVersion},
+    Blake2_128Concat, BoundedVec,
 };
 
 use frame_system::ensure_root;
 use xp_protocol::NetworkType;
 
+use chainx_primitives::AssetId;
+use xpallet_support::traits::{AccountRestriction, AssetPause, RestrictedAction};
+
+pub use self::types::{
+    AccountRestrictions, MaxReasonLength, PauseScope, Restriction, MAX_REASON_LENGTH,
+};
 pub use pallet::*;
 
 const PALLET_MARK: &[u8; 1] = b"#";
 const ALWAYS_ALLOW: [&str; 1] = ["Sudo"];
 
+/// The current storage version.
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 /// The pallet's config trait.
 ///
 /// `frame_system::Config` should always be included in our implied traits.
@@ -38,10 +55,36 @@ pub mod pallet {
 
         /// The currency mechanism.
         type Currency: Currency<Self::AccountId>;
+
+        /// The origin which can pause and unpause for a bounded duration in emergency.
+        type EmergencyOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The maximum duration of an emergency pause.
+        #[pallet::constant]
+        type MaxPauseDuration: Get<Self::BlockNumber>;
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let due = PausesExpiringAt::<T>::take(now);
+            let reads = 1 + due.len() as Weight;
+            let mut writes = 1;
+            for scope in due {
+                // The pause could have been revoked or extended in the meantime.
+                if Self::emergency_pauses(&scope) == Some(now) {
+                    EmergencyPauses::<T>::remove(&scope);
+                    writes += 1;
+                    Self::deposit_event(Event::<T>::PauseExpired(scope));
+                }
+            }
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
@@ -80,30 +123,103 @@ pub mod pallet {
             if paused.is_empty() {
                 Paused::<T>::remove(&pallet);
             } else {
-                Paused::<T>::insert(pallet, paused);
+                Paused::<T>::insert(pallet.clone(), paused);
+            }
+
+            let scope = PauseScope::Call(pallet, call);
+            if should_paused {
+                Self::deposit_event(Event::<T>::Paused(scope, None));
+            } else {
+                Self::deposit_event(Event::<T>::Unpaused(scope));
+            }
+            Ok(())
+        }
+
+        /// Pause the given scope for `duration` blocks, the pause expires on its own.
+        ///
+        /// Pausing a scope that is already paused in emergency resets its expiry.
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub fn emergency_pause(
+            origin: OriginFor<T>,
+            scope: PauseScope,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+            ensure!(
+                !duration.is_zero() && duration <= T::MaxPauseDuration::get(),
+                Error::<T>::InvalidPauseDuration
+            );
+
+            let until = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+            if let Some(previous) = EmergencyPauses::<T>::get(&scope) {
+                Self::unschedule_expiry(previous, &scope);
             }
+            EmergencyPauses::<T>::insert(&scope, until);
+            PausesExpiringAt::<T>::append(until, &scope);
+            Self::deposit_event(Event::<T>::Paused(scope, Some(until)));
+            Ok(())
+        }
+
+        /// Revoke the emergency pause of the given scope before it expires.
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn emergency_unpause(origin: OriginFor<T>, scope: PauseScope) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+            let until = Self::emergency_pauses(&scope).ok_or(Error::<T>::NotPaused)?;
+
+            EmergencyPauses::<T>::remove(&scope);
+            Self::unschedule_expiry(until, &scope);
+            Self::deposit_event(Event::<T>::Unpaused(scope));
             Ok(())
         }
 
-        /// Toggle the blacklist status of the given account id.
+        /// Remove all the restrictions of the given account id.
         ///
         /// This is a root-only operation.
-        #[pallet::weight(0)]
-        pub fn toggle_blacklist(
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_restriction(
             origin: OriginFor<T>,
             who: <T::Lookup as StaticLookup>::Source,
-            should_blacklist: bool,
         ) -> DispatchResult {
             ensure_root(origin)?;
 
             let who = T::Lookup::lookup(who)?;
-            if should_blacklist {
-                Blacklist::<T>::insert(who.clone(), true);
-                Self::deposit_event(Event::<T>::Blacklisted(who))
-            } else {
-                Blacklist::<T>::remove(&who);
-                Self::deposit_event(Event::<T>::Unblacklisted(who));
-            }
+            ensure!(
+                Restrictions::<T>::contains_key(&who),
+                Error::<T>::NotRestricted
+            );
+            Restrictions::<T>::remove(&who);
+            Self::deposit_event(Event::<T>::RestrictionRemoved(who));
+            Ok(())
+        }
+
+        /// Set the restrictions of the given account id, replacing the existing ones.
+        ///
+        /// The restrictions are lifted at the `expiry` block if any, the `reason` is
+        /// at most `MAX_REASON_LENGTH` bytes.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn set_restriction(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            flags: AccountRestrictions,
+            reason: Vec<u8>,
+            expiry: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(!flags.is_empty(), Error::<T>::EmptyRestriction);
+            let reason = BoundedVec::try_from(reason).map_err(|_| Error::<T>::ReasonTooLong)?;
+
+            let who = T::Lookup::lookup(who)?;
+            Restrictions::<T>::insert(
+                &who,
+                Restriction {
+                    flags,
+                    reason,
+                    expiry,
+                },
+            );
+            Self::deposit_event(Event::<T>::RestrictionSet(who, flags, expiry));
             Ok(())
         }
     }
@@ -112,10 +228,31 @@ pub mod pallet {
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// An account was added to the blacklist. [who]
-        Blacklisted(T::AccountId),
-        /// An account was removed from the blacklist. [who]
-        Unblacklisted(T::AccountId),
+        /// The restrictions of an account were set. [who, flags, expiry]
+        RestrictionSet(T::AccountId, AccountRestrictions, Option<T::BlockNumber>),
+        /// The restrictions of an account were removed. [who]
+        RestrictionRemoved(T::AccountId),
+        /// The scope was paused, until the block if it is an emergency pause. [scope, until]
+        Paused(PauseScope, Option<T::BlockNumber>),
+        /// The scope was unpaused. [scope]
+        Unpaused(PauseScope),
+        /// The emergency pause of the scope expired. [scope]
+        PauseExpired(PauseScope),
+    }
+
+    /// Error for the XSystem Pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The pause duration is zero or exceeds `MaxPauseDuration`.
+        InvalidPauseDuration,
+        /// The scope is not paused in emergency.
+        NotPaused,
+        /// The restriction flags are empty.
+        EmptyRestriction,
+        /// The account has no restriction.
+        NotRestricted,
+        /// The reason of the restriction exceeds `MAX_REASON_LENGTH`.
+        ReasonTooLong,
     }
 
     /// Network property (Mainnet / Testnet).
@@ -128,10 +265,22 @@ pub mod pallet {
     #[pallet::getter(fn paused)]
     pub type Paused<T> = StorageMap<_, Twox64Concat, Vec<u8>, BTreeMap<Vec<u8>, ()>, ValueQuery>;
 
-    /// The accounts that are blocked
+    /// The emergency pauses and the block at which they expire.
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_pauses)]
+    pub type EmergencyPauses<T: Config> =
+        StorageMap<_, Blake2_128Concat, PauseScope, T::BlockNumber, OptionQuery>;
+
+    /// The scopes whose emergency pauses expire at the block.
+    #[pallet::storage]
+    pub(crate) type PausesExpiringAt<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<PauseScope>, ValueQuery>;
+
+    /// The restricted accounts and their restrictions, including the expired ones.
     #[pallet::storage]
-    #[pallet::getter(fn blacklist)]
-    pub type Blacklist<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+    #[pallet::getter(fn restrictions)]
+    pub type Restrictions<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Restriction<T::BlockNumber>, OptionQuery>;
 
     #[pallet::genesis_config]
     #[cfg_attr(feature = "std", derive(Default))]
@@ -163,14 +312,98 @@ impl<T: Config> Pallet<T> {
         if p.get(metadata.function_name.as_bytes()).is_some() {
             return true;
         }
+        // check whether this pallet or call has been paused in emergency
+        let pallet = metadata.pallet_name.as_bytes().to_vec();
+        let call = metadata.function_name.as_bytes().to_vec();
+        if EmergencyPauses::<T>::contains_key(PauseScope::Call(pallet.clone(), None))
+            || EmergencyPauses::<T>::contains_key(PauseScope::Call(pallet, Some(call)))
+        {
+            return true;
+        }
         // no pause
         false
     }
 
-    /// Returns the blocked account id list.
-    pub fn get_blacklist() -> Vec<T::AccountId> {
-        Blacklist::<T>::iter()
-            .filter_map(|(account, blocked)| if blocked { Some(account) } else { None })
+    /// Returns true if the calls moving the given asset have been paused.
+    pub fn is_asset_paused(asset_id: AssetId) -> bool {
+        EmergencyPauses::<T>::contains_key(PauseScope::Asset(asset_id))
+    }
+
+    /// Removes `scope` from the emergency pauses expiring at block `until`.
+    fn unschedule_expiry(until: T::BlockNumber, scope: &PauseScope) {
+        PausesExpiringAt::<T>::mutate_exists(until, |maybe_scopes| {
+            if let Some(scopes) = maybe_scopes {
+                scopes.retain(|s| s != scope);
+                if scopes.is_empty() {
+                    *maybe_scopes = None;
+                }
+            }
+        });
+    }
+
+    /// Returns the restrictions of the account in force.
+    pub fn restrictions_of(who: &T::AccountId) -> AccountRestrictions {
+        let now = frame_system::Pallet::<T>::block_number();
+        Self::restrictions(who)
+            .filter(|restriction| restriction.is_active(&now))
+            .map(|restriction| restriction.flags)
+            .unwrap_or_default()
+    }
+
+    /// Returns the restricted accounts whose restrictions are still in force.
+    pub fn restricted_accounts() -> Vec<(T::AccountId, Restriction<T::BlockNumber>)> {
+        let now = frame_system::Pallet::<T>::block_number();
+        Restrictions::<T>::iter()
+            .filter(|(_, restriction)| restriction.is_active(&now))
             .collect()
     }
+
+    /// Converts the accounts in the legacy `Blacklist` into the `TRANSACT` restrictions.
+    pub fn migrate_to_v1() -> Weight {
+        if Self::on_chain_storage_version() >= STORAGE_VERSION {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let blacklist = storage_key_iter::<T::AccountId, bool, Blake2_128Concat>(
+            Self::name().as_bytes(),
+            b"Blacklist",
+        )
+        .drain()
+        .collect::<Vec<_>>();
+
+        let count = blacklist.len() as Weight;
+        for (who, blocked) in blacklist {
+            if blocked {
+                Restrictions::<T>::insert(
+                    who,
+                    Restriction {
+                        flags: AccountRestrictions::TRANSACT,
+                        reason: BoundedVec::try_from(b"blacklist".to_vec())
+                            .expect("the reason is shorter than MAX_REASON_LENGTH; qed"),
+                        expiry: None,
+                    },
+                );
+            }
+        }
+        STORAGE_VERSION.put::<Self>();
+
+        T::DbWeight::get().reads_writes(1 + count, 1 + 2 * count)
+    }
+}
+
+impl<T: Config> AccountRestriction<T::AccountId> for Pallet<T> {
+    fn is_restricted(who: &T::AccountId, action: RestrictedAction) -> bool {
+        let flag = match action {
+            RestrictedAction::MoveAsset => AccountRestrictions::MOVE_ASSET,
+            RestrictedAction::Withdraw => AccountRestrictions::WITHDRAW,
+            RestrictedAction::Trade => AccountRestrictions::TRADE,
+        };
+        Self::restrictions_of(who).contains(flag)
+    }
+}
+
+impl<T: Config> AssetPause<AssetId> for Pallet<T> {
+    fn is_paused(asset_id: &AssetId) -> bool {
+        Self::is_asset_paused(*asset_id)
+    }
 }
@@ -0,0 +1,279 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::CallMetadata,
+    storage::migration::{get_storage_value, put_storage_value},
+    traits::{GetStorageVersion, StorageVersion},
+    Blake2_128Concat, StorageHasher,
+};
+use sp_runtime::DispatchError;
+
+use xpallet_support::traits::{AccountRestriction, AssetPause, RestrictedAction};
+
+use crate::mock::*;
+use crate::{
+    AccountRestrictions, EmergencyPauses, Error, Event, Pallet, PauseScope, PausesExpiringAt,
+    Restriction, MAX_REASON_LENGTH,
+};
+
+fn events() -> Vec<crate::mock::Event> {
+    frame_system::Pallet::<Test>::events()
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+fn last_event() -> crate::mock::Event {
+    events().pop().expect("Event expected")
+}
+
+fn transfer() -> CallMetadata {
+    CallMetadata {
+        function_name: "transfer",
+        pallet_name: "XAssets",
+    }
+}
+
+fn xassets() -> PauseScope {
+    PauseScope::Call(b"XAssets".to_vec(), None)
+}
+
+#[test]
+fn modify_paused_should_work() {
+    new_test_ext().execute_with(|| {
+        let call = Some(b"transfer".to_vec());
+        assert_noop!(
+            XSystem::modify_paused(Origin::signed(ALICE), b"XAssets".to_vec(), None, true),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(XSystem::modify_paused(
+            Origin::root(),
+            b"XAssets".to_vec(),
+            call.clone(),
+            true
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::Paused(
+                PauseScope::Call(b"XAssets".to_vec(), call.clone()),
+                None
+            ))
+        );
+        assert!(Pallet::<Test>::is_paused(transfer()));
+
+        assert_ok!(XSystem::modify_paused(
+            Origin::root(),
+            b"XAssets".to_vec(),
+            call.clone(),
+            false
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::Unpaused(PauseScope::Call(
+                b"XAssets".to_vec(),
+                call
+            )))
+        );
+        assert!(!Pallet::<Test>::is_paused(transfer()));
+    })
+}
+
+#[test]
+fn emergency_pause_should_expire() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            XSystem::emergency_pause(Origin::signed(ALICE), xassets(), 10),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XSystem::emergency_pause(Origin::root(), xassets(), 0),
+            Error::<Test>::InvalidPauseDuration
+        );
+        assert_noop!(
+            XSystem::emergency_pause(Origin::root(), xassets(), MAX_PAUSE_DURATION + 1),
+            Error::<Test>::InvalidPauseDuration
+        );
+
+        assert_ok!(XSystem::emergency_pause(Origin::root(), xassets(), 10));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::Paused(xassets(), Some(11)))
+        );
+        assert!(Pallet::<Test>::is_paused(transfer()));
+        assert_eq!(PausesExpiringAt::<Test>::get(11), vec![xassets()]);
+
+        run_to_block(10);
+        assert!(Pallet::<Test>::is_paused(transfer()));
+
+        run_to_block(11);
+        assert!(!Pallet::<Test>::is_paused(transfer()));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::PauseExpired(xassets()))
+        );
+        assert!(!EmergencyPauses::<Test>::contains_key(xassets()));
+        assert!(!PausesExpiringAt::<Test>::contains_key(11));
+    })
+}
+
+#[test]
+fn emergency_pause_should_reset_expiry() {
+    new_test_ext().execute_with(|| {
+        let asset = PauseScope::Asset(1);
+        assert_ok!(XSystem::emergency_pause(Origin::root(), asset.clone(), 10));
+        assert_ok!(XSystem::emergency_pause(Origin::root(), xassets(), 10));
+        assert!(<Pallet<Test> as AssetPause<_>>::is_paused(&1));
+        assert!(!<Pallet<Test> as AssetPause<_>>::is_paused(&2));
+
+        run_to_block(5);
+        assert_ok!(XSystem::emergency_pause(Origin::root(), asset.clone(), 20));
+        assert_eq!(PausesExpiringAt::<Test>::get(11), vec![xassets()]);
+        assert_eq!(PausesExpiringAt::<Test>::get(25), vec![asset.clone()]);
+
+        // only the pause of XAssets expires at the former expiry
+        run_to_block(11);
+        assert!(Pallet::<Test>::is_asset_paused(1));
+        assert!(
+            !events().contains(&crate::mock::Event::XSystem(Event::PauseExpired(
+                asset.clone()
+            )))
+        );
+
+        run_to_block(25);
+        assert!(!Pallet::<Test>::is_asset_paused(1));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::PauseExpired(asset))
+        );
+    })
+}
+
+#[test]
+fn emergency_unpause_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            XSystem::emergency_unpause(Origin::root(), xassets()),
+            Error::<Test>::NotPaused
+        );
+
+        assert_ok!(XSystem::emergency_pause(Origin::root(), xassets(), 10));
+        assert_noop!(
+            XSystem::emergency_unpause(Origin::signed(ALICE), xassets()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(XSystem::emergency_unpause(Origin::root(), xassets()));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::Unpaused(xassets()))
+        );
+        assert!(!Pallet::<Test>::is_paused(transfer()));
+        assert!(!PausesExpiringAt::<Test>::contains_key(11));
+
+        // the revoked pause does not expire
+        run_to_block(11);
+        assert!(!events().contains(&crate::mock::Event::XSystem(Event::PauseExpired(xassets()))));
+    })
+}
+
+#[test]
+fn restrictions_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            XSystem::set_restriction(
+                Origin::root(),
+                ALICE,
+                AccountRestrictions::empty(),
+                vec![],
+                None
+            ),
+            Error::<Test>::EmptyRestriction
+        );
+        assert_noop!(
+            XSystem::set_restriction(
+                Origin::root(),
+                ALICE,
+                AccountRestrictions::TRADE,
+                vec![0; MAX_REASON_LENGTH as usize + 1],
+                None
+            ),
+            Error::<Test>::ReasonTooLong
+        );
+        assert_noop!(
+            XSystem::remove_restriction(Origin::root(), ALICE),
+            Error::<Test>::NotRestricted
+        );
+
+        let flags = AccountRestrictions::MOVE_ASSET | AccountRestrictions::TRADE;
+        assert_ok!(XSystem::set_restriction(
+            Origin::root(),
+            ALICE,
+            flags,
+            b"sanction".to_vec(),
+            Some(10)
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::RestrictionSet(ALICE, flags, Some(10)))
+        );
+        assert!(Pallet::<Test>::is_restricted(
+            &ALICE,
+            RestrictedAction::MoveAsset
+        ));
+        assert!(!Pallet::<Test>::is_restricted(
+            &ALICE,
+            RestrictedAction::Withdraw
+        ));
+        assert_eq!(Pallet::<Test>::restricted_accounts().len(), 1);
+
+        // the restriction is lifted at the expiry
+        System::set_block_number(10);
+        assert_eq!(
+            Pallet::<Test>::restrictions_of(&ALICE),
+            AccountRestrictions::empty()
+        );
+        assert!(Pallet::<Test>::restricted_accounts().is_empty());
+
+        assert_ok!(XSystem::remove_restriction(Origin::root(), ALICE));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XSystem(Event::RestrictionRemoved(ALICE))
+        );
+        assert!(Pallet::<Test>::restrictions(ALICE).is_none());
+    })
+}
+
+#[test]
+fn migrate_to_v1_should_work() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<XSystem>();
+        let blacklist_key = |who: AccountId| Blake2_128Concat::hash(&who.encode());
+        put_storage_value(b"XSystem", b"Blacklist", &blacklist_key(ALICE), true);
+        put_storage_value(b"XSystem", b"Blacklist", &blacklist_key(2), false);
+
+        Pallet::<Test>::migrate_to_v1();
+        assert_eq!(XSystem::on_chain_storage_version(), StorageVersion::new(1));
+        assert_eq!(
+            Pallet::<Test>::restrictions(ALICE),
+            Some(Restriction {
+                flags: AccountRestrictions::TRANSACT,
+                reason: b"blacklist".to_vec().try_into().unwrap(),
+                expiry: None,
+            })
+        );
+        // the unblocked account is not restricted
+        assert!(Pallet::<Test>::restrictions(2).is_none());
+        // the legacy blacklist is removed
+        assert!(
+            get_storage_value::<bool>(b"XSystem", b"Blacklist", &blacklist_key(ALICE)).is_none()
+        );
+        assert!(get_storage_value::<bool>(b"XSystem", b"Blacklist", &blacklist_key(2)).is_none());
+
+        // the migration runs only once
+        put_storage_value(b"XSystem", b"Blacklist", &blacklist_key(3), true);
+        Pallet::<Test>::migrate_to_v1();
+        assert!(Pallet::<Test>::restrictions(3).is_none());
+    })
+}
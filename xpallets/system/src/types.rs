@@ -0,0 +1,62 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use bitflags::bitflags;
+use codec::{Decode, Encode};
+use frame_support::{traits::ConstU32, BoundedVec};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+use chainx_primitives::AssetId;
+
+/// The maximum length of the reason of a restriction.
+pub const MAX_REASON_LENGTH: u32 = 256;
+
+/// The bound of the reason of a restriction.
+pub type MaxReasonLength = ConstU32<MAX_REASON_LENGTH>;
+
+/// The scope of a pause.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug, TypeInfo)]
+pub enum PauseScope {
+    /// The given call of the pallet, or the whole pallet if the call is None.
+    Call(Vec<u8>, Option<Vec<u8>>),
+    /// The user operations moving the asset in XAssets, XGatewayCommon, XSpot and
+    /// XAssetsBridge, including their precompiles, which check it via `AssetPause`.
+    Asset(AssetId),
+}
+
+bitflags! {
+    /// Restrictions on the actions of an account.
+    #[derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub struct AccountRestrictions: u32 {
+        /// Every signed transaction is rejected, i.e. the former blacklist.
+        const TRANSACT      = 1 << 0;
+        const MOVE_ASSET    = 1 << 1;
+        const WITHDRAW      = 1 << 2;
+        const TRADE         = 1 << 3;
+    }
+}
+
+impl Default for AccountRestrictions {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// The restrictions on an account with the reason, which are lifted at `expiry` if any.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct Restriction<BlockNumber> {
+    pub flags: AccountRestrictions,
+    pub reason: BoundedVec<u8, MaxReasonLength>,
+    pub expiry: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd> Restriction<BlockNumber> {
+    /// Returns true if the restriction is still in force at the block `now`.
+    pub fn is_active(&self, now: &BlockNumber) -> bool {
+        self.expiry.as_ref().map_or(true, |expiry| now < expiry)
+    }
+}
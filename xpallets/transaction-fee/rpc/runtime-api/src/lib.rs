@@ -4,15 +4,40 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
 
-pub use xpallet_transaction_fee::{FeeDetails, InclusionFee};
+pub use xpallet_transaction_fee::{AssetFee, FeeDetails, InclusionFee};
+
+/// The fee details returned by the version 1 of the api, which has no asset fee.
+#[derive(Encode, Decode)]
+pub struct FeeDetailsV1<Balance> {
+    pub inclusion_fee: Option<InclusionFee<Balance>>,
+    pub tip: Balance,
+    pub extra_fee: Balance,
+    pub final_fee: Balance,
+}
+
+impl<Balance> From<FeeDetailsV1<Balance>> for FeeDetails<Balance> {
+    fn from(details: FeeDetailsV1<Balance>) -> Self {
+        Self {
+            inclusion_fee: details.inclusion_fee,
+            tip: details.tip,
+            extra_fee: details.extra_fee,
+            final_fee: details.final_fee,
+            asset_fee: None,
+        }
+    }
+}
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait XTransactionFeeApi<Balance> where
         Balance: Codec + MaybeDisplay + MaybeFromStr,
     {
+        #[changed_in(2)]
+        fn query_fee_details(uxt: Block::Extrinsic, len: u32) -> FeeDetailsV1<Balance>;
+
         fn query_fee_details(uxt: Block::Extrinsic, len: u32) -> FeeDetails<Balance>;
     }
 }
@@ -9,7 +9,7 @@ use codec::{Codec, Decode};
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
 use sp_runtime::{
@@ -20,7 +20,7 @@ use sp_runtime::{
 use pallet_transaction_payment_rpc::Error;
 
 use xp_rpc::RpcBalance;
-use xpallet_transaction_fee_rpc_runtime_api::{FeeDetails, InclusionFee};
+use xpallet_transaction_fee_rpc_runtime_api::{AssetFee, FeeDetails, InclusionFee};
 
 pub use xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi as XTransactionFeeRuntimeApi;
 
@@ -66,7 +66,19 @@ where
 
         let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(into_rpc_err)?;
 
-        api.query_fee_details(&at, uxt, encoded_len)
+        let api_version = api
+            .api_version::<dyn XTransactionFeeRuntimeApi<Block, Balance>>(&at)
+            .map_err(into_rpc_err)?
+            .ok_or_else(|| into_rpc_err("XTransactionFeeApi is not supported"))?;
+        let fee_details = if api_version < 2 {
+            #[allow(deprecated)]
+            api.query_fee_details_before_version_2(&at, uxt, encoded_len)
+                .map(Into::into)
+        } else {
+            api.query_fee_details(&at, uxt, encoded_len)
+        };
+
+        fee_details
             .map(|fee_details| FeeDetails {
                 inclusion_fee: fee_details.inclusion_fee.map(|fee| InclusionFee {
                     base_fee: fee.base_fee.into(),
@@ -76,6 +88,10 @@ where
                 tip: fee_details.tip.into(),
                 extra_fee: fee_details.extra_fee.into(),
                 final_fee: fee_details.final_fee.into(),
+                asset_fee: fee_details.asset_fee.map(|fee| AssetFee {
+                    asset_id: fee.asset_id,
+                    amount: fee.amount.into(),
+                }),
             })
             .map_err(into_rpc_err)
     }
@@ -0,0 +1,59 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+use sp_runtime::FixedU128;
+
+use super::*;
+use crate::Pallet as XTransactionFee;
+
+/// The extra fees of a pallet, which is far more than the calls of any pallet.
+const MAX_EXTRA_FEES: u32 = 64;
+
+benchmarks! {
+    set_extra_fee {
+        let pallet = b"XAssets".to_vec();
+        for i in 0..MAX_EXTRA_FEES {
+            XTransactionFee::<T>::set_extra_fee_inner(
+                pallet.clone(),
+                i.to_be_bytes().to_vec(),
+                Some(1u32.into()),
+            );
+        }
+        let call = b"transfer".to_vec();
+        let fee: BalanceOf<T> = 10u32.into();
+    }: _(RawOrigin::Root, pallet.clone(), call.clone(), Some(fee))
+    verify {
+        assert_eq!(XTransactionFee::<T>::extra_fees(&pallet).get(&call), Some(&fee));
+    }
+
+    set_fee_asset {
+        let rate = FeeRate::Fixed(FixedU128::saturating_from_integer(2));
+    }: _(RawOrigin::Root, 1, Some(rate))
+    verify {
+        assert_eq!(XTransactionFee::<T>::fee_assets(1), Some(rate));
+    }
+
+    set_asset_fee_margin {
+        let margin = Permill::from_percent(10);
+    }: _(RawOrigin::Root, margin)
+    verify {
+        assert_eq!(XTransactionFee::<T>::asset_fee_margin(), margin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Pallet::<Test>::test_benchmark_set_extra_fee());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_fee_asset());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_asset_fee_margin());
+        });
+    }
+}
@@ -4,12 +4,49 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 mod types;
+pub mod weights;
 
-pub use self::types::FeeDetails;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+use sp_runtime::{
+    traits::{One, Saturating, Zero},
+    FixedPointNumber, Permill,
+};
+
+use frame_support::{
+    dispatch::{CallMetadata, Weight},
+    traits::{Get, GetStorageVersion, StorageVersion},
+};
+
+use chainx_primitives::AssetId;
+
+pub use self::types::{AssetFee, FeeDetails, FeeRate};
+pub use self::weights::WeightInfo;
 pub use pallet_transaction_payment::InclusionFee;
 
-type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as pallet_transaction_payment::OnChargeTransaction<T>>::Balance;
+pub type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as pallet_transaction_payment::OnChargeTransaction<T>>::Balance;
+
+/// The current storage version.
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// The market price source of the assets accepted for the transaction fee.
+pub trait AssetPrice<Balance> {
+    /// Converts the `amount` of PCX into the asset, None if there is no price.
+    fn native_to_asset(asset_id: AssetId, amount: Balance) -> Option<Balance>;
+}
+
+impl<Balance> AssetPrice<Balance> for () {
+    fn native_to_asset(_asset_id: AssetId, _amount: Balance) -> Option<Balance> {
+        None
+    }
+}
 
 pub use pallet::*;
 
@@ -17,21 +54,196 @@ pub use pallet::*;
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
     #[pallet::config]
     pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The origin which can modify the extra fees and the fee assets.
+        type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The market price of the fee assets, used by `FeeRate::Spot`.
+        type AssetPrice: AssetPrice<BalanceOf<Self>>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Set the extra fee of the given pallet call, `None` removes the extra fee.
+        #[pallet::weight(T::WeightInfo::set_extra_fee())]
+        pub fn set_extra_fee(
+            origin: OriginFor<T>,
+            pallet: Vec<u8>,
+            call: Vec<u8>,
+            fee: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            Self::set_extra_fee_inner(pallet.clone(), call.clone(), fee);
+
+            Self::deposit_event(Event::<T>::ExtraFeeSet(pallet, call, fee));
+            Ok(())
+        }
+
+        /// Set the rate of the asset accepted for the transaction fee, `None` disables it.
+        #[pallet::weight(T::WeightInfo::set_fee_asset())]
+        pub fn set_fee_asset(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            rate: Option<FeeRate>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            match rate {
+                Some(rate) => FeeAssets::<T>::insert(asset_id, rate),
+                None => FeeAssets::<T>::remove(asset_id),
+            }
+
+            Self::deposit_event(Event::<T>::FeeAssetSet(asset_id, rate));
+            Ok(())
+        }
+
+        /// Set the safety margin added to the fee paid in the non-native assets.
+        #[pallet::weight(T::WeightInfo::set_asset_fee_margin())]
+        pub fn set_asset_fee_margin(origin: OriginFor<T>, margin: Permill) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            AssetFeeMargin::<T>::put(margin);
+
+            Self::deposit_event(Event::<T>::AssetFeeMarginSet(margin));
+            Ok(())
+        }
     }
 
     #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Transaction fee was paid to the block author and its reward pot in 1:9.
         /// [author, author_fee, reward_pot, reward_pot_fee]
         FeePaid(T::AccountId, BalanceOf<T>, T::AccountId, BalanceOf<T>),
+        /// The extra fee of the pallet call was changed. [pallet, call, fee]
+        ExtraFeeSet(Vec<u8>, Vec<u8>, Option<BalanceOf<T>>),
+        /// Extra fee was paid to the block author and its reward pot in 1:9.
+        /// [payer, author, author_fee, reward_pot, reward_pot_fee]
+        ExtraFeePaid(
+            T::AccountId,
+            T::AccountId,
+            BalanceOf<T>,
+            T::AccountId,
+            BalanceOf<T>,
+        ),
+        /// The rate of the fee asset was changed. [asset_id, rate]
+        FeeAssetSet(AssetId, Option<FeeRate>),
+        /// The safety margin of the asset fee was changed. [margin]
+        AssetFeeMarginSet(Permill),
+        /// Transaction fee was paid in the non-native asset, which is exchanged with the
+        /// treasury for the native fee. [payer, asset_id, asset_fee, native_fee]
+        AssetFeePaid(T::AccountId, AssetId, BalanceOf<T>, BalanceOf<T>),
+    }
+
+    /// The extra fees of pallet calls.
+    #[pallet::storage]
+    #[pallet::getter(fn extra_fees)]
+    pub type ExtraFees<T: Config> =
+        StorageMap<_, Twox64Concat, Vec<u8>, BTreeMap<Vec<u8>, BalanceOf<T>>, ValueQuery>;
+
+    /// The assets accepted for the transaction fee and their rates.
+    #[pallet::storage]
+    #[pallet::getter(fn fee_assets)]
+    pub type FeeAssets<T: Config> = StorageMap<_, Twox64Concat, AssetId, FeeRate, OptionQuery>;
+
+    /// The safety margin added to the fee paid in the non-native assets.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_fee_margin)]
+    pub type AssetFeeMargin<T: Config> = StorageValue<_, Permill, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// The initial extra fees, (pallet, call, fee).
+        pub extra_fees: Vec<(Vec<u8>, Vec<u8>, BalanceOf<T>)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                extra_fees: Default::default(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for (pallet, call, fee) in &self.extra_fees {
+                Pallet::<T>::set_extra_fee_inner(pallet.clone(), call.clone(), Some(*fee));
+            }
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the extra fee of the given pallet call if any.
+    pub fn extra_fee(metadata: CallMetadata) -> Option<BalanceOf<T>> {
+        Self::extra_fees(metadata.pallet_name.as_bytes())
+            .get(metadata.function_name.as_bytes())
+            .copied()
+    }
+
+    /// Converts the `native_fee` into the fee asset, including the safety margin.
+    ///
+    /// Returns None if the asset is not accepted for the fee or has no price.
+    pub fn native_to_asset_fee(
+        asset_id: AssetId,
+        native_fee: BalanceOf<T>,
+    ) -> Option<BalanceOf<T>> {
+        let amount = match Self::fee_assets(asset_id)? {
+            FeeRate::Spot => T::AssetPrice::native_to_asset(asset_id, native_fee)?,
+            FeeRate::Fixed(rate) => rate.saturating_mul_int(native_fee),
+        };
+        if native_fee.is_zero() {
+            return Some(amount);
+        }
+        let margin = Self::asset_fee_margin().mul_ceil(amount);
+        // Charge at least one unit of the asset for the non-zero fee.
+        Some(amount.saturating_add(margin).max(One::one()))
+    }
+
+    fn set_extra_fee_inner(pallet: Vec<u8>, call: Vec<u8>, fee: Option<BalanceOf<T>>) {
+        let mut fees = Self::extra_fees(&pallet);
+        match fee {
+            Some(fee) => fees.insert(call, fee),
+            None => fees.remove(&call),
+        };
+
+        if fees.is_empty() {
+            ExtraFees::<T>::remove(&pallet);
+        } else {
+            ExtraFees::<T>::insert(pallet, fees);
+        }
+    }
+
+    /// Moves the extra fees hard-coded in the runtime into the storage.
+    pub fn migrate_to_v1(extra_fees: Vec<(Vec<u8>, Vec<u8>, BalanceOf<T>)>) -> Weight {
+        if Self::on_chain_storage_version() >= STORAGE_VERSION {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let count = extra_fees.len() as Weight;
+        for (pallet, call, fee) in extra_fees {
+            Self::set_extra_fee_inner(pallet, call, Some(fee));
+        }
+        STORAGE_VERSION.put::<Self>();
+
+        T::DbWeight::get().reads_writes(1 + count, 1 + count)
     }
 }
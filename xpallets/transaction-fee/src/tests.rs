@@ -0,0 +1,183 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok, dispatch::CallMetadata};
+use sp_runtime::{DispatchError, FixedPointNumber, FixedU128, Permill};
+
+use crate::mock::*;
+use crate::{Event, FeeRate, Pallet};
+
+fn last_event() -> crate::mock::Event {
+    frame_system::Pallet::<Test>::events()
+        .pop()
+        .expect("Event expected")
+        .event
+}
+
+fn metadata(pallet_name: &'static str, function_name: &'static str) -> CallMetadata {
+    CallMetadata {
+        function_name,
+        pallet_name,
+    }
+}
+
+#[test]
+fn set_extra_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        let transfer = metadata("XAssets", "transfer");
+        assert_noop!(
+            XTransactionFee::set_extra_fee(
+                Origin::signed(ALICE),
+                b"XAssets".to_vec(),
+                b"transfer".to_vec(),
+                Some(10)
+            ),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(XTransactionFee::set_extra_fee(
+            Origin::root(),
+            b"XAssets".to_vec(),
+            b"transfer".to_vec(),
+            Some(10)
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XTransactionFee(Event::ExtraFeeSet(
+                b"XAssets".to_vec(),
+                b"transfer".to_vec(),
+                Some(10)
+            ))
+        );
+        assert_eq!(Pallet::<Test>::extra_fee(transfer.clone()), Some(10));
+        assert_eq!(
+            Pallet::<Test>::extra_fee(metadata("XAssets", "force_transfer")),
+            None
+        );
+
+        assert_ok!(XTransactionFee::set_extra_fee(
+            Origin::root(),
+            b"XAssets".to_vec(),
+            b"transfer".to_vec(),
+            None
+        ));
+        assert_eq!(Pallet::<Test>::extra_fee(transfer), None);
+        // the empty fees of the pallet are removed
+        assert!(!crate::ExtraFees::<Test>::contains_key(b"XAssets".to_vec()));
+    })
+}
+
+#[test]
+fn set_fee_asset_should_work() {
+    new_test_ext().execute_with(|| {
+        let rate = FeeRate::Fixed(FixedU128::saturating_from_integer(2));
+        assert_noop!(
+            XTransactionFee::set_fee_asset(Origin::signed(ALICE), PRICED_ASSET, Some(rate)),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(XTransactionFee::set_fee_asset(
+            Origin::root(),
+            PRICED_ASSET,
+            Some(rate)
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XTransactionFee(Event::FeeAssetSet(PRICED_ASSET, Some(rate)))
+        );
+        assert_eq!(Pallet::<Test>::fee_assets(PRICED_ASSET), Some(rate));
+
+        assert_ok!(XTransactionFee::set_fee_asset(
+            Origin::root(),
+            PRICED_ASSET,
+            None
+        ));
+        assert_eq!(Pallet::<Test>::fee_assets(PRICED_ASSET), None);
+    })
+}
+
+#[test]
+fn set_asset_fee_margin_should_work() {
+    new_test_ext().execute_with(|| {
+        let margin = Permill::from_percent(5);
+        assert_noop!(
+            XTransactionFee::set_asset_fee_margin(Origin::signed(ALICE), margin),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(XTransactionFee::set_asset_fee_margin(
+            Origin::root(),
+            margin
+        ));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::XTransactionFee(Event::AssetFeeMarginSet(margin))
+        );
+        assert_eq!(Pallet::<Test>::asset_fee_margin(), margin);
+    })
+}
+
+#[test]
+fn native_to_asset_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        // not accepted for the fee
+        assert_eq!(Pallet::<Test>::native_to_asset_fee(PRICED_ASSET, 100), None);
+
+        assert_ok!(XTransactionFee::set_fee_asset(
+            Origin::root(),
+            PRICED_ASSET,
+            Some(FeeRate::Spot)
+        ));
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(PRICED_ASSET, 100),
+            Some(300)
+        );
+        // accepted, but without a price
+        assert_ok!(XTransactionFee::set_fee_asset(
+            Origin::root(),
+            UNPRICED_ASSET,
+            Some(FeeRate::Spot)
+        ));
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(UNPRICED_ASSET, 100),
+            None
+        );
+
+        // 1 PCX for 2 units of the asset
+        assert_ok!(XTransactionFee::set_fee_asset(
+            Origin::root(),
+            UNPRICED_ASSET,
+            Some(FeeRate::Fixed(FixedU128::saturating_from_rational(1, 2)))
+        ));
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(UNPRICED_ASSET, 100),
+            Some(50)
+        );
+        // at least one unit is charged for the non-zero fee
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(UNPRICED_ASSET, 1),
+            Some(1)
+        );
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(UNPRICED_ASSET, 0),
+            Some(0)
+        );
+
+        // the margin is rounded up
+        assert_ok!(XTransactionFee::set_asset_fee_margin(
+            Origin::root(),
+            Permill::from_percent(10)
+        ));
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(PRICED_ASSET, 100),
+            Some(330)
+        );
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(UNPRICED_ASSET, 30),
+            Some(17)
+        );
+        assert_eq!(
+            Pallet::<Test>::native_to_asset_fee(PRICED_ASSET, 0),
+            Some(0)
+        );
+    })
+}
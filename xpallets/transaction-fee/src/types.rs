@@ -5,10 +5,30 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::{traits::AtLeast32BitUnsigned, RuntimeDebug};
+use sp_runtime::{traits::AtLeast32BitUnsigned, FixedU128, RuntimeDebug};
 
+use chainx_primitives::AssetId;
 use pallet_transaction_payment::InclusionFee;
 
+/// The price of an asset accepted for the transaction fee.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum FeeRate {
+    /// Use the time-weighted average price of the XSpot trading pair `PCX/asset`.
+    Spot,
+    /// The council-set amount of the asset for one PCX, both in the smallest unit.
+    Fixed(FixedU128),
+}
+
+/// The transaction fee paid in a non-native asset.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetFee<Balance> {
+    pub asset_id: AssetId,
+    /// The amount of the asset which is charged instead of the `final_fee`.
+    pub amount: Balance,
+}
+
 /// The `final_fee` is composed of:
 ///   - (Optional) `inclusion_fee`: Only the `Pays::Yes` transaction can have the inclusion fee.
 ///   - (Optional) `tip`: If included in the transaction, the tip will be added on top. Only
@@ -29,6 +49,9 @@ pub struct FeeDetails<Balance> {
     /// Additional fee for some ChainX specific calls.
     pub extra_fee: Balance,
     pub final_fee: Balance,
+    /// The asset and amount charged instead if the fee is paid in a non-native asset,
+    /// the `extra_fee` is always paid in PCX.
+    pub asset_fee: Option<AssetFee<Balance>>,
 }
 
 impl<Balance: AtLeast32BitUnsigned + Copy> FeeDetails<Balance> {
@@ -45,6 +68,11 @@ impl<Balance: AtLeast32BitUnsigned + Copy> FeeDetails<Balance> {
             None => base.into(),
         }
     }
+
+    /// Sets the fee charged in the non-native asset.
+    pub fn with_asset_fee(self, asset_fee: Option<AssetFee<Balance>>) -> Self {
+        Self { asset_fee, ..self }
+    }
 }
 
 impl<Balance: AtLeast32BitUnsigned + Copy> From<pallet_transaction_payment::FeeDetails<Balance>>
@@ -57,6 +85,7 @@ impl<Balance: AtLeast32BitUnsigned + Copy> From<pallet_transaction_payment::FeeD
             tip: details.tip,
             extra_fee: 0u32.into(),
             final_fee,
+            asset_fee: None,
         }
     }
 }
@@ -0,0 +1,68 @@
+// Copyright 2019-2022 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Weights for xpallet_transaction_fee
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2022-06-20, STEPS: 50, REPEAT: 20, LOW RANGE: [], HIGH RANGE: []
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("benchmarks"), DB CACHE: 1024
+
+// Executed Command:
+// ./target/release/chainx
+// benchmark
+// --chain=benchmarks
+// --steps=50
+// --repeat=20
+// --pallet=xpallet_transaction_fee
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./xpallets/transaction-fee/src/weights.rs
+// --template=./scripts/xpallet-weight-template.hbs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpallet_transaction_fee.
+pub trait WeightInfo {
+    fn set_extra_fee() -> Weight;
+    fn set_fee_asset() -> Weight;
+    fn set_asset_fee_margin() -> Weight;
+}
+
+/// Weights for xpallet_transaction_fee using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn set_extra_fee() -> Weight {
+        (38_512_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_fee_asset() -> Weight {
+        (22_863_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_asset_fee_margin() -> Weight {
+        (20_415_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn set_extra_fee() -> Weight {
+        (38_512_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_fee_asset() -> Weight {
+        (22_863_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_asset_fee_margin() -> Weight {
+        (20_415_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}